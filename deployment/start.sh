@@ -83,6 +83,12 @@ if [[ -n "$ACCOUNTS_FILE" ]]; then
     RUN_ARGS+=(--accounts-file "$ACCOUNTS_FILE")
 fi
 
+echo "Running Preflight Checks..."
+if ! "$BINARY" --check "${RUN_ARGS[@]}"; then
+    echo "Preflight checks failed. Aborting live deployment."
+    exit 1
+fi
+
 echo "Running Dry Run Simulation..."
 if ! "$BINARY" --dry-run "${RUN_ARGS[@]}"; then
     echo "Dry run failed. Aborting live deployment."
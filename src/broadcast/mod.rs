@@ -1,4 +1,6 @@
+mod rest;
 pub mod server;
+pub mod sink;
 pub mod types;
 
 pub use server::StatusBroadcaster;
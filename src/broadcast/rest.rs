@@ -0,0 +1,217 @@
+//! Read-only HTTP endpoints served on the WebSocket port.
+//!
+//! Cached events are returned exactly as the WebSocket sends them, without
+//! `seq`. Every endpoint except `/health` needs the WebSocket token when one
+//! is configured. On a hub, `?bot=<name>` selects one of the upstream bots;
+//! `/orders` only knows this process's resting orders and rejects it.
+
+use super::server::{is_authorized, query_param, BotCache, BroadcastState};
+use crate::broadcast::types::{FillRecord, HealthStatus, WSEvent};
+use serde::Serialize;
+use serde_json::json;
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use tokio_tungstenite::tungstenite::http::{Method, StatusCode};
+
+/// Full HTTP response for `request`, which is `None` when the head could not be parsed.
+pub(super) fn respond(
+    request: Option<&Request>,
+    state: &BroadcastState,
+    auth_token: Option<&str>,
+) -> Vec<u8> {
+    let (status, body) = match request {
+        Some(request) => route(request, state, auth_token),
+        None => error(StatusCode::BAD_REQUEST, "Malformed request"),
+    };
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default(),
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+fn route(
+    request: &Request,
+    state: &BroadcastState,
+    auth_token: Option<&str>,
+) -> (StatusCode, String) {
+    if request.method() != Method::GET {
+        return error(StatusCode::METHOD_NOT_ALLOWED, "Only GET is supported");
+    }
+    let path = request.uri().path();
+    // Uptime checkers do not need the token
+    if path == "/health" {
+        return ok(&HealthStatus {
+            status: "ok".to_string(),
+            uptime_secs: state.started.elapsed().as_secs(),
+            seq: state.head,
+        });
+    }
+    if auth_token.is_some_and(|token| !is_authorized(request, token)) {
+        return error(StatusCode::UNAUTHORIZED, "Unauthorized");
+    }
+    let bot = query_param(request, "bot");
+    let empty = BotCache::default();
+    let cache = match (state.cache(bot), bot) {
+        (Some(cache), _) => cache,
+        (None, None) => &empty,
+        (None, Some(_)) => return error(StatusCode::NOT_FOUND, "Unknown bot"),
+    };
+    match path {
+        "/status" => cached(&cache.last_summary, "No summary yet"),
+        "/grid" => cached(&cache.last_grid_state, "No grid state yet"),
+        "/config" => cached(&cache.last_config, "No config yet"),
+        "/history" => cached(&cache.last_history, "No history yet"),
+        // Upstream bots do not report their resting orders
+        "/orders" if bot.is_some() => error(
+            StatusCode::BAD_REQUEST,
+            "Open orders are only available for this process",
+        ),
+        "/orders" => ok(&state.open_orders),
+        "/fills" => {
+            let since = match query_param(request, "since").map(str::parse::<u64>) {
+                None => 0,
+                Some(Ok(since)) => since,
+                Some(Err(_)) => return error(StatusCode::BAD_REQUEST, "Invalid since"),
+            };
+            let fills: Vec<&FillRecord> = state
+                .fills
+                .iter()
+                .filter(|fill| fill.timestamp_ms >= since)
+                .filter(|fill| bot.is_none() || fill.bot.as_deref() == bot)
+                .collect();
+            ok(&fills)
+        }
+        _ => error(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+fn cached(event: &Option<WSEvent>, missing: &str) -> (StatusCode, String) {
+    match event {
+        Some(event) => ok(event),
+        None => error(StatusCode::SERVICE_UNAVAILABLE, missing),
+    }
+}
+
+fn ok(value: &impl Serialize) -> (StatusCode, String) {
+    match serde_json::to_string(value) {
+        Ok(body) => (StatusCode::OK, body),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> (StatusCode, String) {
+    (status, json!({ "error": message }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::types::{MarketEvent, OrderEvent};
+
+    fn get(uri: &str) -> Request {
+        Request::builder().uri(uri).body(()).unwrap()
+    }
+
+    fn order(oid: u64) -> OrderEvent {
+        OrderEvent {
+            oid,
+            cloid: None,
+            side: "Buy".to_string(),
+            price: 10.0,
+            size: 1.0,
+            status: "FILLED".to_string(),
+            fee: 0.01,
+            is_taker: false,
+        }
+    }
+
+    fn fill(oid: u64) -> WSEvent {
+        WSEvent::OrderUpdate(order(oid))
+    }
+
+    #[test]
+    fn test_routes() {
+        let mut state = BroadcastState::new(10);
+        let (status, _) = route(&get("/status"), &state, None);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        state.record(None, WSEvent::Config(json!({"symbol": "BTC"})));
+        state.record(None, WSEvent::MarketUpdate(MarketEvent { price: 1.0 }));
+        state.record(None, fill(7));
+
+        let (status, body) = route(&get("/health"), &state, None);
+        assert_eq!(status, StatusCode::OK);
+        let health: HealthStatus = serde_json::from_str(&body).unwrap();
+        assert_eq!(health.seq, 3);
+
+        let (_, body) = route(&get("/config"), &state, None);
+        assert_eq!(
+            body,
+            r#"{"event_type":"config","data":{"symbol":"BTC"}}"#.to_string()
+        );
+
+        let (_, body) = route(&get("/fills?since=0"), &state, None);
+        let fills: Vec<FillRecord> = serde_json::from_str(&body).unwrap();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order.oid, 7);
+        let (_, body) = route(&get("/fills?since=99999999999999"), &state, None);
+        assert_eq!(body, "[]");
+        let (status, _) = route(&get("/fills?since=yesterday"), &state, None);
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (_, body) = route(&get("/orders"), &state, None);
+        assert_eq!(body, "[]");
+        let (status, _) = route(&get("/nope"), &state, None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_orders_reject_bot_filter() {
+        let mut state = BroadcastState::new(10);
+        state.open_orders = vec![OrderEvent {
+            status: "OPEN".to_string(),
+            ..order(3)
+        }];
+        state.record(Some("alpha".to_string()), fill(7));
+
+        let (status, body) = route(&get("/orders"), &state, None);
+        assert_eq!(status, StatusCode::OK);
+        let orders: Vec<OrderEvent> = serde_json::from_str(&body).unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].oid, 3);
+
+        let (status, body) = route(&get("/orders?bot=alpha"), &state, None);
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(!body.contains("\"oid\""));
+        let (status, _) = route(&get("/orders?bot=ghost"), &state, None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_token_required_except_health() {
+        let state = BroadcastState::new(10);
+        let token = Some("s3cret");
+        assert_eq!(route(&get("/health"), &state, token).0, StatusCode::OK);
+        assert_eq!(
+            route(&get("/orders"), &state, token).0,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            route(&get("/orders?token=s3cret"), &state, token).0,
+            StatusCode::OK
+        );
+
+        let post = Request::builder()
+            .method("POST")
+            .uri("/health")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            route(&post, &state, token).0,
+            StatusCode::METHOD_NOT_ALLOWED
+        );
+    }
+}
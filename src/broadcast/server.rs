@@ -1,160 +1,506 @@
-use crate::broadcast::types::WSEvent;
-use crate::config::broadcast::WebsocketConfig;
-use futures_util::{SinkExt, StreamExt};
+use crate::broadcast::rest;
+use crate::broadcast::sink::{EventSink, WebSocketSink};
+use crate::broadcast::types::{
+    CommandResult, ConnectionCommand, ConnectionRequest, ControlRequest, FillRecord, History,
+    OrderEvent, Resync, ResyncReason, SequencedEvent, Topic, WSEvent,
+};
+use crate::config::broadcast::{TopicRates, WebsocketConfig, DEFAULT_REPLAY_BUFFER};
+use anyhow::anyhow;
+use futures_util::{Sink, SinkExt, StreamExt};
 use log::{error, info, warn};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::broadcast;
-use tokio_tungstenite::accept_async;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, Semaphore};
+use tokio::time::{Duration, Instant};
+use tokio_rustls::{rustls, TlsAcceptor};
+use tokio_tungstenite::accept_hdr_async;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
 use tokio_tungstenite::tungstenite::Message;
 
+const ORDER_HISTORY_SIZE: usize = 50;
+const FILL_HISTORY_SIZE: usize = 1000;
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// Time a new connection gets to finish TLS and send its request, so idle
+/// sockets cannot hold the `max_connections` slots.
+#[cfg(not(test))]
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+#[cfg(test)]
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(300);
+
 #[derive(Clone)]
 pub struct StatusBroadcaster {
-    sender: broadcast::Sender<WSEvent>,
-    last_config: Arc<Mutex<Option<WSEvent>>>,
-    last_info: Arc<Mutex<Option<WSEvent>>>,
-    last_summary: Arc<Mutex<Option<WSEvent>>>,
-    last_grid_state: Arc<Mutex<Option<WSEvent>>>,
-    last_market_update: Arc<Mutex<Option<WSEvent>>>,
-    order_history: Arc<Mutex<VecDeque<WSEvent>>>,
+    sender: broadcast::Sender<SequencedEvent>,
+    /// The WebSocket sink first, then any configured file or stdout sinks.
+    sinks: Arc<Vec<Box<dyn EventSink>>>,
+    state: Arc<Mutex<BroadcastState>>,
+    control_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<ControlRequest>>>>,
+}
+
+/// Sequence counter, replay buffer and the cached state sent to new clients
+/// and served over REST.
+pub(super) struct BroadcastState {
+    pub(super) started: Instant,
+    /// Sequence number of the last broadcast event, 0 before the first one.
+    pub(super) head: u64,
+    replay: VecDeque<SequencedEvent>,
+    replay_capacity: usize,
+    /// Cached state per bot. `None` is this process; a hub adds one entry per
+    /// upstream bot.
+    caches: BTreeMap<Option<String>, BotCache>,
+    /// Resting orders as last reported by the engine.
+    pub(super) open_orders: Vec<OrderEvent>,
+    pub(super) fills: VecDeque<FillRecord>,
+}
+
+/// Latest stateful events of one bot.
+#[derive(Default)]
+pub(super) struct BotCache {
+    pub(super) last_config: Option<WSEvent>,
+    last_info: Option<WSEvent>,
+    pub(super) last_summary: Option<WSEvent>,
+    pub(super) last_grid_state: Option<WSEvent>,
+    last_market_update: Option<WSEvent>,
+    pub(super) last_history: Option<WSEvent>,
+    order_history: VecDeque<WSEvent>,
+}
+
+impl BroadcastState {
+    pub(super) fn new(replay_capacity: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            head: 0,
+            replay: VecDeque::with_capacity(replay_capacity),
+            replay_capacity,
+            caches: BTreeMap::new(),
+            open_orders: Vec::new(),
+            fills: VecDeque::new(),
+        }
+    }
+
+    /// Cached state of `bot`, or of this process for `None`.
+    pub(super) fn cache(&self, bot: Option<&str>) -> Option<&BotCache> {
+        self.caches
+            .iter()
+            .find(|(name, _)| name.as_deref() == bot)
+            .map(|(_, cache)| cache)
+    }
+
+    /// Caches stateful events, then numbers the event and keeps it for replay.
+    pub(super) fn record(&mut self, bot: Option<String>, event: WSEvent) -> SequencedEvent {
+        let cache = self.caches.entry(bot.clone()).or_default();
+        match &event {
+            WSEvent::Config(_) => cache.last_config = Some(event.clone()),
+            WSEvent::Info(_) => cache.last_info = Some(event.clone()),
+            // Cache strategy summaries (whichever strategy is running)
+            WSEvent::SpotGridSummary(_)
+            | WSEvent::PerpGridSummary(_)
+            | WSEvent::TwapSummary(_)
+            | WSEvent::RebalanceSummary(_)
+            | WSEvent::PortfolioSummary(_) => cache.last_summary = Some(event.clone()),
+            // Cache grid state for new connections
+            WSEvent::GridState(_) => cache.last_grid_state = Some(event.clone()),
+            // Cache recent market update for new connections (so UI has price immediately)
+            WSEvent::MarketUpdate(_) => cache.last_market_update = Some(event.clone()),
+            WSEvent::History(_) => cache.last_history = Some(event.clone()),
+            // A bot behind a hub resynced: its snapshot follows
+            WSEvent::Resync(_) => *cache = BotCache::default(),
+            // Cache recent order updates
+            WSEvent::OrderUpdate(order) => {
+                if cache.order_history.len() >= ORDER_HISTORY_SIZE {
+                    cache.order_history.pop_front();
+                }
+                cache.order_history.push_back(event.clone());
+                if order.status == "FILLED" {
+                    if self.fills.len() >= FILL_HISTORY_SIZE {
+                        self.fills.pop_front();
+                    }
+                    self.fills.push_back(FillRecord {
+                        timestamp_ms: now_ms(),
+                        bot: bot.clone(),
+                        order: order.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        self.head += 1;
+        let sequenced = SequencedEvent {
+            seq: self.head,
+            bot,
+            event,
+        };
+        if self.replay.len() >= self.replay_capacity {
+            self.replay.pop_front();
+        }
+        self.replay.push_back(sequenced.clone());
+        sequenced
+    }
+
+    /// Events a client that has seen everything up to `after` needs to reach
+    /// `head`. Falls back to a resync snapshot when the replay buffer no longer
+    /// reaches back that far, or when `after` is `None`.
+    fn catch_up(&self, after: Option<u64>, reason: ResyncReason) -> Vec<SequencedEvent> {
+        if let Some(after) = after {
+            let oldest = self.replay.front().map_or(self.head + 1, |e| e.seq);
+            if after <= self.head && after + 1 >= oldest {
+                return self
+                    .replay
+                    .iter()
+                    .filter(|e| e.seq > after)
+                    .cloned()
+                    .collect();
+            }
+        }
+        self.snapshot(reason)
+    }
+
+    /// `resync` followed by the cached state of every bot, all numbered `head`.
+    fn snapshot(&self, reason: ResyncReason) -> Vec<SequencedEvent> {
+        let resync = SequencedEvent {
+            seq: self.head,
+            bot: None,
+            event: WSEvent::Resync(Resync { reason }),
+        };
+        let cached = self.caches.iter().flat_map(|(bot, cache)| {
+            let latest = [
+                &cache.last_config,
+                &cache.last_info,
+                &cache.last_summary,
+                &cache.last_grid_state,
+                &cache.last_market_update,
+                &cache.last_history,
+            ];
+            latest
+                .into_iter()
+                .flatten()
+                .chain(cache.order_history.iter())
+                .map(|event| SequencedEvent {
+                    seq: self.head,
+                    bot: bot.clone(),
+                    event: event.clone(),
+                })
+        });
+        std::iter::once(resync).chain(cached).collect()
+    }
+
+    /// The cached `history` event of every bot.
+    fn histories(&self) -> Vec<(Option<String>, WSEvent)> {
+        self.caches
+            .iter()
+            .filter_map(|(bot, cache)| Some((bot.clone(), cache.last_history.clone()?)))
+            .collect()
+    }
+}
+
+/// Topic filter and per-topic rate limits of one client. On a hub the
+/// limits apply to each bot separately.
+struct ClientFeed {
+    topics: HashSet<Topic>,
+    rates: TopicRates,
+    last_sent: HashMap<FeedKey, Instant>,
+    /// Events held back by the rate limit, oldest first. Conflated topics
+    /// hold at most the latest one.
+    pending: HashMap<FeedKey, VecDeque<SequencedEvent>>,
+}
+
+/// Bot and topic an event is throttled under.
+type FeedKey = (Option<String>, Topic);
+
+impl ClientFeed {
+    fn new(topics: HashSet<Topic>, rates: TopicRates) -> Self {
+        Self {
+            topics,
+            rates,
+            last_sent: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    fn wants(&self, event: &WSEvent) -> bool {
+        event
+            .topic()
+            .is_none_or(|topic| self.topics.contains(&topic))
+    }
+
+    fn set_topics(&mut self, topics: HashSet<Topic>) {
+        self.pending.retain(|(_, topic), _| topics.contains(topic));
+        self.topics = topics;
+    }
+
+    fn min_interval(&self, topic: Topic) -> Option<Duration> {
+        self.rates
+            .get(topic)
+            .map(|rate| Duration::from_secs_f64(1.0 / rate))
+    }
+
+    /// Returns the event if it may go out now. Otherwise holds it until
+    /// `next_due`, replacing any held event of a conflated topic.
+    fn offer(&mut self, event: SequencedEvent, now: Instant) -> Option<SequencedEvent> {
+        if !self.wants(&event.event) {
+            return None;
+        }
+        let Some(topic) = event.event.topic() else {
+            return Some(event);
+        };
+        let Some(interval) = self.min_interval(topic) else {
+            return Some(event);
+        };
+        let key = (event.bot.clone(), topic);
+        let ready = self.pending.get(&key).is_none_or(VecDeque::is_empty)
+            && self
+                .last_sent
+                .get(&key)
+                .is_none_or(|sent| now >= *sent + interval);
+        if ready {
+            self.last_sent.insert(key, now);
+            return Some(event);
+        }
+        let pending = self.pending.entry(key).or_default();
+        if topic.is_conflated() {
+            pending.clear();
+        }
+        pending.push_back(event);
+        None
+    }
+
+    /// When the next held event may go out.
+    fn next_due(&self) -> Option<Instant> {
+        self.pending
+            .iter()
+            .filter(|(_, held)| !held.is_empty())
+            .filter_map(|(key, _)| Some(*self.last_sent.get(key)? + self.min_interval(key.1)?))
+            .min()
+    }
+
+    /// Releases one held event for every bot and topic whose interval has passed.
+    fn take_due(&mut self, now: Instant) -> Vec<SequencedEvent> {
+        let mut due = Vec::new();
+        for (key, held) in self.pending.iter_mut() {
+            let (Some(sent), Some(interval)) = (
+                self.last_sent.get(key).copied(),
+                self.rates
+                    .get(key.1)
+                    .map(|rate| Duration::from_secs_f64(1.0 / rate)),
+            ) else {
+                continue;
+            };
+            if now < sent + interval {
+                continue;
+            }
+            if let Some(event) = held.pop_front() {
+                self.last_sent.insert(key.clone(), now);
+                due.push(event);
+            }
+        }
+        due.sort_by_key(|event| event.seq);
+        due
+    }
+
+    /// Filters a catch-up batch. A resync snapshot replaces anything held back
+    /// and skips the rate limits; replayed events go through `offer`.
+    fn catch_up(&mut self, events: Vec<SequencedEvent>, now: Instant) -> Vec<SequencedEvent> {
+        if events
+            .first()
+            .is_some_and(|e| matches!(e.event, WSEvent::Resync(_)) && e.bot.is_none())
+        {
+            self.pending.clear();
+            let snapshot: Vec<SequencedEvent> = events
+                .into_iter()
+                .filter(|e| self.wants(&e.event))
+                .collect();
+            for event in &snapshot {
+                if let Some(topic) = event.event.topic() {
+                    self.last_sent.insert((event.bot.clone(), topic), now);
+                }
+            }
+            return snapshot;
+        }
+        events
+            .into_iter()
+            .filter_map(|event| self.offer(event, now))
+            .collect()
+    }
 }
 
 impl StatusBroadcaster {
-    pub fn new(config: Option<WebsocketConfig>) -> Self {
+    /// Starts the server when `config` is set. Fails if the TLS files cannot be loaded.
+    pub fn new(config: Option<WebsocketConfig>) -> anyhow::Result<Self> {
+        Self::with_sinks(config, Vec::new())
+    }
+
+    /// Like `new`, also writing every event to `sinks`.
+    pub fn with_sinks(
+        config: Option<WebsocketConfig>,
+        sinks: Vec<Box<dyn EventSink>>,
+    ) -> anyhow::Result<Self> {
         let (sender, _) = broadcast::channel(100);
-        let last_config = Arc::new(Mutex::new(None));
-        let last_info = Arc::new(Mutex::new(None));
-        let last_summary = Arc::new(Mutex::new(None));
-        let last_grid_state = Arc::new(Mutex::new(None));
-        let last_market_update: Arc<Mutex<Option<WSEvent>>> = Arc::new(Mutex::new(None));
-        let order_history = Arc::new(Mutex::new(VecDeque::with_capacity(50)));
+        let sinks: Vec<Box<dyn EventSink>> =
+            std::iter::once(Box::new(WebSocketSink::new(sender.clone())) as Box<dyn EventSink>)
+                .chain(sinks)
+                .collect();
+        let replay_capacity = config
+            .as_ref()
+            .map_or(DEFAULT_REPLAY_BUFFER, |conf| conf.replay_buffer);
+        let state = Arc::new(Mutex::new(BroadcastState::new(replay_capacity)));
+        let (control_sender, control_receiver) = mpsc::unbounded_channel();
 
         if let Some(conf) = config {
+            let tls = match (&conf.tls_cert, &conf.tls_key) {
+                (Some(cert), Some(key)) => Some(tls_acceptor(cert, key)?),
+                _ => None,
+            };
             let sender_clone = sender.clone();
-            let config_clone = last_config.clone();
-            let info_clone = last_info.clone();
-            let summary_clone = last_summary.clone();
-            let grid_state_clone = last_grid_state.clone();
-            let market_update_clone = last_market_update.clone();
-            let history_clone = order_history.clone();
+            let state_clone = state.clone();
 
             tokio::spawn(async move {
-                if let Err(e) = run_server(
-                    conf.host,
-                    conf.port,
-                    sender_clone,
-                    config_clone,
-                    info_clone,
-                    summary_clone,
-                    grid_state_clone,
-                    market_update_clone,
-                    history_clone,
-                )
-                .await
+                if let Err(e) =
+                    run_server(conf, tls, sender_clone, state_clone, control_sender).await
                 {
                     error!("WebSocket Server failed: {}", e);
                 }
             });
         }
 
-        Self {
+        Ok(Self {
             sender,
-            last_config,
-            last_info,
-            last_summary,
-            last_grid_state,
-            last_market_update,
-            order_history,
-        }
+            sinks: Arc::new(sinks),
+            state,
+            control_receiver: Arc::new(Mutex::new(Some(control_receiver))),
+        })
     }
 
     pub fn send(&self, event: WSEvent) {
-        // Cache stateful events
-        match &event {
-            WSEvent::Config(_) => {
-                let mut lock = self.last_config.lock().unwrap();
-                *lock = Some(event.clone());
-            }
-            WSEvent::Info(_) => {
-                let mut lock = self.last_info.lock().unwrap();
-                *lock = Some(event.clone());
-            }
-            // Cache strategy summaries (either spot or perp)
-            WSEvent::SpotGridSummary(_) | WSEvent::PerpGridSummary(_) => {
-                let mut lock = self.last_summary.lock().unwrap();
-                *lock = Some(event.clone());
-            }
-            // Cache grid state for new connections
-            WSEvent::GridState(_) => {
-                let mut lock = self.last_grid_state.lock().unwrap();
-                *lock = Some(event.clone());
-            }
-            // Cache recent market update for new connections (so UI has price immediately)
-            WSEvent::MarketUpdate(_) => {
-                let mut lock = self.last_market_update.lock().unwrap();
-                *lock = Some(event.clone());
-            }
-            // Cache recent order updates
-            WSEvent::OrderUpdate(_) => {
-                let mut lock = self.order_history.lock().unwrap();
-                if lock.len() >= 50 {
-                    lock.pop_front();
-                }
-                lock.push_back(event.clone());
-            }
-            _ => {}
+        self.publish(None, event);
+    }
+
+    /// Broadcasts an event of one of the bots behind a hub, tagged with its name.
+    pub fn send_from(&self, bot: &str, event: WSEvent) {
+        self.publish(Some(bot.to_string()), event);
+    }
+
+    fn publish(&self, bot: Option<String>, event: WSEvent) {
+        let mut state = self.state.lock().unwrap();
+        let sequenced = state.record(bot, event);
+        // Sent under the lock so a client catching up from the replay buffer
+        // never sees a gap or a duplicate, and files see events in order.
+        for sink in self.sinks.iter() {
+            sink.send(&sequenced);
         }
+    }
 
-        // We ignore "channel closed" errors as we might not have any subscribers
-        let _ = self.sender.send(event);
+    /// Equity series sent to new clients and on `request_history`. Not broadcast.
+    pub fn set_history(&self, history: History) {
+        self.state
+            .lock()
+            .unwrap()
+            .caches
+            .entry(None)
+            .or_default()
+            .last_history = Some(WSEvent::History(history));
     }
 
-    pub fn subscribe(&self) -> broadcast::Receiver<WSEvent> {
+    /// Resting orders served at `GET /orders`. Not broadcast.
+    pub fn set_open_orders(&self, orders: Vec<OrderEvent>) {
+        self.state.lock().unwrap().open_orders = orders;
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SequencedEvent> {
         self.sender.subscribe()
     }
+
+    /// Control commands sent by clients. Only the first caller gets the receiver.
+    pub fn take_control_receiver(&self) -> Option<mpsc::UnboundedReceiver<ControlRequest>> {
+        self.control_receiver.lock().unwrap().take()
+    }
 }
 
-#[allow(clippy::too_many_arguments)]
 async fn run_server(
-    host: String,
-    port: u16,
-    sender: broadcast::Sender<WSEvent>,
-    last_config: Arc<Mutex<Option<WSEvent>>>,
-    last_info: Arc<Mutex<Option<WSEvent>>>,
-    last_summary: Arc<Mutex<Option<WSEvent>>>,
-    last_grid_state: Arc<Mutex<Option<WSEvent>>>,
-    last_market_update: Arc<Mutex<Option<WSEvent>>>,
-    order_history: Arc<Mutex<VecDeque<WSEvent>>>,
+    conf: WebsocketConfig,
+    tls: Option<TlsAcceptor>,
+    sender: broadcast::Sender<SequencedEvent>,
+    state: Arc<Mutex<BroadcastState>>,
+    control_sender: mpsc::UnboundedSender<ControlRequest>,
 ) -> anyhow::Result<()> {
-    let addr = format!("{}:{}", host, port);
+    let addr = format!("{}:{}", conf.host, conf.port);
     let listener = TcpListener::bind(&addr).await?;
-    info!("WebSocket Status Server listening on: ws://{}", addr);
+    let scheme = if tls.is_some() { "wss" } else { "ws" };
+    info!(
+        "WebSocket Status Server listening on: {}://{} (auth {}, max {} clients)",
+        scheme,
+        addr,
+        if conf.auth_token.is_some() {
+            "on"
+        } else {
+            "off"
+        },
+        conf.max_connections
+    );
+    let slots = Arc::new(Semaphore::new(conf.max_connections));
+    // Anyone who can reach a public port could stop the bot or close positions
+    let control_allowed = conf.auth_token.is_some() || !conf.is_public();
+    let auth_token = conf.auth_token.map(Arc::new);
 
     while let Ok((stream, peer_addr)) = listener.accept().await {
+        let Ok(permit) = slots.clone().try_acquire_owned() else {
+            warn!(
+                "Rejecting {}: max_connections ({}) reached",
+                peer_addr, conf.max_connections
+            );
+            continue;
+        };
+        let tls = tls.clone();
+        let auth_clone = auth_token.clone();
         let sender_clone = sender.clone();
-        let config_clone = last_config.clone();
-        let info_clone = last_info.clone();
-        let summary_clone = last_summary.clone();
-        let grid_state_clone = last_grid_state.clone();
-        let market_update_clone = last_market_update.clone();
-        let history_clone = order_history.clone();
+        let state_clone = state.clone();
+        let rates_clone = conf.max_rate.clone();
+        let control_clone = Some(control_sender.clone());
 
         tokio::spawn(async move {
-            if let Err(e) = handle_connection(
-                stream,
-                peer_addr,
-                sender_clone,
-                config_clone,
-                info_clone,
-                summary_clone,
-                grid_state_clone,
-                market_update_clone,
-                history_clone,
-            )
-            .await
-            {
+            // Held until the client disconnects
+            let _permit = permit;
+            let result = match tls {
+                Some(acceptor) => {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            handle_connection(
+                                stream,
+                                peer_addr,
+                                auth_clone,
+                                sender_clone,
+                                state_clone,
+                                rates_clone,
+                                control_clone.filter(|_| control_allowed),
+                            )
+                            .await
+                        }
+                        Ok(Err(e)) => Err(anyhow!("TLS handshake failed: {}", e)),
+                        Err(_) => Err(anyhow!("TLS handshake timed out")),
+                    }
+                }
+                None => {
+                    handle_connection(
+                        stream,
+                        peer_addr,
+                        auth_clone,
+                        sender_clone,
+                        state_clone,
+                        rates_clone,
+                        control_clone.filter(|_| control_allowed),
+                    )
+                    .await
+                }
+            };
+            if let Err(e) = result {
                 warn!("Error handling connection from {}: {}", peer_addr, e);
             }
         });
@@ -163,85 +509,136 @@ async fn run_server(
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn handle_connection(
-    stream: TcpStream,
+async fn handle_connection<S>(
+    stream: S,
     peer_addr: SocketAddr,
-    sender: broadcast::Sender<WSEvent>,
-    last_config: Arc<Mutex<Option<WSEvent>>>,
-    last_info: Arc<Mutex<Option<WSEvent>>>,
-    last_summary: Arc<Mutex<Option<WSEvent>>>,
-    last_grid_state: Arc<Mutex<Option<WSEvent>>>,
-    last_market_update: Arc<Mutex<Option<WSEvent>>>,
-    order_history: Arc<Mutex<VecDeque<WSEvent>>>,
-) -> anyhow::Result<()> {
-    info!("New WebSocket connection: {}", peer_addr);
-
-    // Accept the websocket handshake
-    let ws_stream = accept_async(stream).await?;
-    let (mut ws_sender, mut ws_receiver) = ws_stream.split();
-
-    // Subscribe to broadcast channel
-    let mut rx = sender.subscribe();
-
-    // Send Initial State (Config, Summary, and Grid State)
-    {
-        let config_opt = last_config.lock().unwrap().clone();
-        if let Some(event) = config_opt {
-            let json_str = serde_json::to_string(&event)?;
-            ws_sender.send(Message::Text(json_str)).await?;
+    auth_token: Option<Arc<String>>,
+    sender: broadcast::Sender<SequencedEvent>,
+    state: Arc<Mutex<BroadcastState>>,
+    rates: TopicRates,
+    // `None` when control commands are refused
+    control_sender: Option<mpsc::UnboundedSender<ControlRequest>>,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // Plain HTTP requests share the port with the WebSocket
+    let (head, mut stream) = tokio::time::timeout(HANDSHAKE_TIMEOUT, read_request_head(stream))
+        .await
+        .map_err(|_| anyhow!("no request within {:?}", HANDSHAKE_TIMEOUT))??;
+    match parse_request_head(&head) {
+        Some(request) if is_websocket_upgrade(&request) => {}
+        request => {
+            let response = rest::respond(
+                request.as_ref(),
+                &state.lock().unwrap(),
+                auth_token.as_deref().map(String::as_str),
+            );
+            stream.write_all(&response).await?;
+            stream.shutdown().await?;
+            return Ok(());
         }
+    }
+    let stream = Prefixed::new(head, stream);
 
-        let info_opt = last_info.lock().unwrap().clone();
-        if let Some(event) = info_opt {
-            let json_str = serde_json::to_string(&event)?;
-            ws_sender.send(Message::Text(json_str)).await?;
-        }
+    info!("New WebSocket connection: {}", peer_addr);
 
-        let summary_opt = last_summary.lock().unwrap().clone();
-        if let Some(event) = summary_opt {
-            let json_str = serde_json::to_string(&event)?;
-            ws_sender.send(Message::Text(json_str)).await?;
+    // Accept the websocket handshake, checking the token and reading
+    // `resume_from` and `topics`
+    let mut resume_from = None;
+    let mut topics: HashSet<Topic> = Topic::ALL.into_iter().collect();
+    #[allow(clippy::result_large_err)] // Callback signature is fixed by tungstenite
+    let check_request = |request: &Request, response: Response| {
+        if let Some(token) = &auth_token {
+            if !is_authorized(request, token) {
+                warn!("Rejected unauthenticated WebSocket client {}", peer_addr);
+                let mut denied = ErrorResponse::new(Some("Unauthorized".to_string()));
+                *denied.status_mut() = StatusCode::UNAUTHORIZED;
+                return Err(denied);
+            }
         }
-
-        let grid_state_opt = last_grid_state.lock().unwrap().clone();
-        if let Some(event) = grid_state_opt {
-            let json_str = serde_json::to_string(&event)?;
-            ws_sender.send(Message::Text(json_str)).await?;
+        let bad_request = |message: String| {
+            let mut invalid = ErrorResponse::new(Some(message));
+            *invalid.status_mut() = StatusCode::BAD_REQUEST;
+            invalid
+        };
+        if let Some(value) = query_param(request, "resume_from") {
+            resume_from = Some(
+                value
+                    .parse::<u64>()
+                    .map_err(|_| bad_request("Invalid resume_from".to_string()))?,
+            );
         }
-
-        let market_update_opt = last_market_update.lock().unwrap().clone();
-        if let Some(event) = market_update_opt {
-            let json_str = serde_json::to_string(&event)?;
-            ws_sender.send(Message::Text(json_str)).await?;
+        if let Some(value) = query_param(request, "topics") {
+            topics = parse_topics(value).map_err(|e| bad_request(e.to_string()))?;
         }
+        Ok(response)
+    };
+    let ws_stream =
+        tokio::time::timeout(HANDSHAKE_TIMEOUT, accept_hdr_async(stream, check_request))
+            .await
+            .map_err(|_| anyhow!("WebSocket handshake timed out"))??;
+    let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
-        // Send cached order history
-        let history_events: Vec<WSEvent> = {
-            let history = order_history.lock().unwrap();
-            history.iter().cloned().collect()
-        };
-
-        for event in history_events {
-            let json_str = serde_json::to_string(&event)?;
-            ws_sender.send(Message::Text(json_str)).await?;
-        }
+    // Subscribe and collect the catch-up under the same lock, so the live
+    // stream continues exactly where the catch-up ends
+    let reason = if resume_from.is_some() {
+        ResyncReason::ResumeUnavailable
+    } else {
+        ResyncReason::Connect
+    };
+    let (mut rx, catch_up, mut last_seq) = {
+        let state = state.lock().unwrap();
+        (
+            sender.subscribe(),
+            state.catch_up(resume_from, reason),
+            state.head,
+        )
+    };
+    let resumed = !catch_up
+        .first()
+        .is_some_and(|e| matches!(e.event, WSEvent::Resync(_)) && e.bot.is_none());
+    if resume_from.is_some() && resumed {
+        info!(
+            "Client {} resumed, replaying {} events",
+            peer_addr,
+            catch_up.len()
+        );
+    }
+    let mut feed = ClientFeed::new(topics, rates);
+    for event in feed.catch_up(catch_up, Instant::now()) {
+        send_event(&mut ws_sender, &event).await?;
     }
 
-    // Since we only BROADCAST to clients, we can ignore incoming messages mostly,
-    // but we need to keep the connection alive. We can select! loop.
+    // Broadcast events to the client and forward its control commands to the engine.
 
     loop {
+        let next_due = feed.next_due();
         tokio::select! {
             // Receive Message from Channel
             msg_res = rx.recv() => {
                 match msg_res {
                     Ok(event) => {
-                         let json_str = serde_json::to_string(&event)?;
-                         ws_sender.send(Message::Text(json_str)).await?;
+                        if event.seq <= last_seq {
+                            continue;
+                        }
+                        last_seq = event.seq;
+                        if let Some(event) = feed.offer(event, Instant::now()) {
+                            send_event(&mut ws_sender, &event).await?;
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(count)) => {
-                        warn!("Client {} lagged by {} messages", peer_addr, count);
+                        warn!("Client {} lagged by {} messages, catching up", peer_addr, count);
+                        let catch_up = {
+                            let state = state.lock().unwrap();
+                            rx = rx.resubscribe();
+                            let events = state.catch_up(Some(last_seq), ResyncReason::Lagged);
+                            last_seq = state.head;
+                            events
+                        };
+                        for event in feed.catch_up(catch_up, Instant::now()) {
+                            send_event(&mut ws_sender, &event).await?;
+                        }
                     }
                     Err(broadcast::error::RecvError::Closed) => {
                         break;
@@ -249,13 +646,104 @@ async fn handle_connection(
                 }
             }
 
-            // Receive Message from Client (Heartbeats usually)
+            // Release events held back by the rate limits
+            _ = tokio::time::sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                for event in feed.take_due(Instant::now()) {
+                    send_event(&mut ws_sender, &event).await?;
+                }
+            }
+
+            // Receive Message from Client (subscriptions and control commands)
             client_msg = ws_receiver.next() => {
                 match client_msg {
                     Some(Ok(Message::Close(_))) => {
                         info!("Client {} disconnected", peer_addr);
                         break;
                     }
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(request) = serde_json::from_str::<ConnectionRequest>(&text) {
+                            let (command, success, message, histories) = match request.command {
+                                ConnectionCommand::Subscribe { topics } => {
+                                    let topics: HashSet<Topic> = topics.into_iter().collect();
+                                    let mut names: Vec<&str> = Topic::ALL
+                                        .into_iter()
+                                        .filter(|topic| topics.contains(topic))
+                                        .map(Topic::name)
+                                        .collect();
+                                    if names.is_empty() {
+                                        names.push("none");
+                                    }
+                                    feed.set_topics(topics);
+                                    ("subscribe", true, format!("Subscribed to {}", names.join(", ")), Vec::new())
+                                }
+                                ConnectionCommand::RequestHistory => {
+                                    let histories = state.lock().unwrap().histories();
+                                    if histories.is_empty() {
+                                        ("request_history", false, "No history yet".to_string(), histories)
+                                    } else {
+                                        ("request_history", true, "History sent".to_string(), histories)
+                                    }
+                                }
+                            };
+                            // Answer only the sender, reusing the last sequence number
+                            let result = WSEvent::CommandResult(CommandResult {
+                                id: request.id,
+                                command: command.to_string(),
+                                success,
+                                message,
+                            });
+                            for (bot, event) in histories.into_iter().chain([(None, result)]) {
+                                send_event(&mut ws_sender, &SequencedEvent { seq: last_seq, bot, event }).await?;
+                            }
+                            continue;
+                        }
+                        match serde_json::from_str::<ControlRequest>(&text) {
+                            Ok(request) => {
+                                let Some(control_sender) = &control_sender else {
+                                    warn!(
+                                        "[CONTROL] Refused {} from {}: no auth_token on a public bind",
+                                        request.command.name(),
+                                        peer_addr
+                                    );
+                                    let result = SequencedEvent {
+                                        seq: last_seq,
+                                        bot: None,
+                                        event: WSEvent::CommandResult(CommandResult {
+                                            id: request.id,
+                                            command: request.command.name().to_string(),
+                                            success: false,
+                                            message: "Control commands need websocket.auth_token when the server binds to a public address".to_string(),
+                                        }),
+                                    };
+                                    send_event(&mut ws_sender, &result).await?;
+                                    continue;
+                                };
+                                info!(
+                                    "[CONTROL] {} from {}",
+                                    request.command.name(),
+                                    peer_addr
+                                );
+                                if control_sender.send(request).is_err() {
+                                    warn!("Control command from {} dropped: engine not running", peer_addr);
+                                }
+                            }
+                            Err(e) => {
+                                // Answer only the sender; other clients did not ask.
+                                // Not broadcast, so it reuses the last sequence number.
+                                let result = SequencedEvent {
+                                    seq: last_seq,
+                                    bot: None,
+                                    event: WSEvent::CommandResult(CommandResult {
+                                        id: None,
+                                        command: "unknown".to_string(),
+                                        success: false,
+                                        message: format!("Invalid command: {}", e),
+                                    }),
+                                };
+                                send_event(&mut ws_sender, &result).await?;
+                            }
+                        }
+                    }
                     Some(Ok(_)) => {
                         // Ignore other messages (ping/pong handled by library/browser)
                     }
@@ -273,3 +761,722 @@ async fn handle_connection(
 
     Ok(())
 }
+
+async fn send_event<W>(ws_sender: &mut W, event: &SequencedEvent) -> anyhow::Result<()>
+where
+    W: Sink<Message> + Unpin,
+    W::Error: std::error::Error + Send + Sync + 'static,
+{
+    let json_str = serde_json::to_string(event)?;
+    ws_sender.send(Message::Text(json_str)).await?;
+    Ok(())
+}
+
+/// Reads until the end of the HTTP request head.
+async fn read_request_head<S>(mut stream: S) -> anyhow::Result<(Vec<u8>, S)>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok((buf, stream))
+}
+
+fn parse_request_head(buf: &[u8]) -> Option<Request> {
+    let end = buf.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&buf[..end]).ok()?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let mut builder = Request::builder()
+        .method(request_line.next()?)
+        .uri(request_line.next()?);
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        builder = builder.header(name.trim(), value.trim());
+    }
+    builder.body(()).ok()
+}
+
+fn is_websocket_upgrade(request: &Request) -> bool {
+    request
+        .headers()
+        .get("upgrade")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+/// Replays bytes already read from `inner` before reading from it again, so
+/// the WebSocket handshake sees the request head we inspected.
+struct Prefixed<S> {
+    prefix: Vec<u8>,
+    pos: usize,
+    inner: S,
+}
+
+impl<S> Prefixed<S> {
+    fn new(prefix: Vec<u8>, inner: S) -> Self {
+        Self {
+            prefix,
+            pos: 0,
+            inner,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Prefixed<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if self.pos < self.prefix.len() {
+            let n = buf.remaining().min(self.prefix.len() - self.pos);
+            let start = self.pos;
+            buf.put_slice(&self.prefix[start..start + n]);
+            self.pos += n;
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Prefixed<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Comma-separated topic names, e.g. `market,orders`.
+fn parse_topics(value: &str) -> anyhow::Result<HashSet<Topic>> {
+    value
+        .split(',')
+        .filter(|name| !name.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+pub(super) fn query_param<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.uri().query().and_then(|query| {
+        query.split('&').find_map(|pair| {
+            pair.strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('='))
+        })
+    })
+}
+
+/// Accepts `Authorization: Bearer <token>`, or a `token` query parameter for
+/// browsers, which cannot set headers on WebSocket requests.
+pub(super) fn is_authorized(request: &Request, token: &str) -> bool {
+    let header = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    header
+        .or(query_param(request, "token"))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn tls_acceptor(cert_path: &str, key_path: &str) -> anyhow::Result<TlsAcceptor> {
+    let open = |path: &str| {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| anyhow!("Failed to open {}: {}", path, e))
+    };
+    let certs: Vec<rustls::Certificate> = rustls_pemfile::certs(&mut open(cert_path)?)?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    if certs.is_empty() {
+        return Err(anyhow!("No certificates found in {}", cert_path));
+    }
+    let key = rustls_pemfile::read_all(&mut open(key_path)?)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("No private key found in {}", key_path))?;
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| anyhow!("Invalid TLS certificate or key: {}", e))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::types::ControlCommand;
+    use std::time::Duration;
+    use tokio_tungstenite::connect_async;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    /// Starts a server on a free local port and waits until it accepts connections.
+    async fn start_server(config: impl FnOnce(u16) -> WebsocketConfig) -> (StatusBroadcaster, u16) {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let broadcaster = StatusBroadcaster::new(Some(config(port))).unwrap();
+        while tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_err()
+        {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        (broadcaster, port)
+    }
+
+    fn market(price: f64) -> WSEvent {
+        WSEvent::MarketUpdate(crate::broadcast::types::MarketEvent { price })
+    }
+
+    async fn next_event<S>(client: &mut S) -> SequencedEvent
+    where
+        S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>>
+            + Unpin,
+    {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(&message.into_text().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_control_commands_are_forwarded() {
+        let (broadcaster, port) =
+            start_server(|port| WebsocketConfig::new("127.0.0.1", port)).await;
+        let mut control = broadcaster.take_control_receiver().unwrap();
+        assert!(broadcaster.take_control_receiver().is_none());
+
+        let (mut client, _) = connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        let resync = next_event(&mut client).await;
+        assert!(matches!(resync.event, WSEvent::Resync(_)));
+
+        client
+            .send(Message::Text(
+                r#"{"id": "7", "command": "set_range", "low": 10.0, "high": 20.0}"#.to_string(),
+            ))
+            .await
+            .unwrap();
+        let request = tokio::time::timeout(Duration::from_secs(5), control.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.id.as_deref(), Some("7"));
+        assert_eq!(
+            request.command,
+            ControlCommand::SetRange {
+                low: 10.0,
+                high: 20.0
+            }
+        );
+
+        // Malformed commands are answered on the same socket
+        client
+            .send(Message::Text(r#"{"command": "launch"}"#.to_string()))
+            .await
+            .unwrap();
+        let reply = next_event(&mut client).await;
+        let WSEvent::CommandResult(result) = reply.event else {
+            panic!("Expected command_result, got {:?}", reply.event);
+        };
+        assert!(!result.success);
+        assert!(result.message.starts_with("Invalid command"));
+    }
+
+    #[tokio::test]
+    async fn test_control_commands_need_a_token_on_public_bind() {
+        let (broadcaster, port) = start_server(|port| WebsocketConfig::new("0.0.0.0", port)).await;
+        let mut control = broadcaster.take_control_receiver().unwrap();
+        let (mut client, _) = connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        next_event(&mut client).await;
+
+        client
+            .send(Message::Text(
+                r#"{"id": "1", "command": "shutdown"}"#.to_string(),
+            ))
+            .await
+            .unwrap();
+        let reply = next_event(&mut client).await;
+        let WSEvent::CommandResult(result) = reply.event else {
+            panic!("Expected command_result, got {:?}", reply.event);
+        };
+        assert_eq!(result.id.as_deref(), Some("1"));
+        assert_eq!(result.command, "shutdown");
+        assert!(!result.success);
+        assert!(control.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_and_connection_limit() {
+        let (_broadcaster, port) = start_server(|port| WebsocketConfig {
+            auth_token: Some("s3cret".to_string()),
+            max_connections: 1,
+            ..WebsocketConfig::new("127.0.0.1", port)
+        })
+        .await;
+        let url = format!("ws://127.0.0.1:{}", port);
+
+        assert!(connect_async(&url).await.is_err());
+        assert!(connect_async(format!("{}/?token=wrong", url))
+            .await
+            .is_err());
+
+        let mut request = url.as_str().into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("Authorization", "Bearer s3cret".parse().unwrap());
+        let (mut first, _) = connect_async(request).await.unwrap();
+
+        // The only slot is taken
+        let with_query = format!("{}/?token=s3cret", url);
+        assert!(connect_async(&with_query).await.is_err());
+
+        first.close(None).await.unwrap();
+        let mut connected = false;
+        for _ in 0..50 {
+            if connect_async(&with_query).await.is_ok() {
+                connected = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(connected);
+    }
+
+    #[tokio::test]
+    async fn test_idle_sockets_release_their_slot() {
+        let (_broadcaster, port) = start_server(|port| WebsocketConfig {
+            max_connections: 1,
+            ..WebsocketConfig::new("127.0.0.1", port)
+        })
+        .await;
+        let url = format!("ws://127.0.0.1:{}", port);
+        // Let the startup probe release its slot
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // A socket that never sends a request holds the only slot for a while
+        let _idle = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(connect_async(&url).await.is_err());
+
+        tokio::time::sleep(HANDSHAKE_TIMEOUT * 2).await;
+        assert!(connect_async(&url).await.is_ok());
+    }
+
+    #[test]
+    fn test_catch_up_replays_or_resyncs() {
+        let mut state = BroadcastState::new(3);
+        state.record(None, WSEvent::Config(serde_json::json!({"symbol": "BTC"})));
+        for price in [1.0, 2.0, 3.0, 4.0] {
+            state.record(None, market(price));
+        }
+        assert_eq!(state.head, 5);
+        let seqs = |events: Vec<SequencedEvent>| events.iter().map(|e| e.seq).collect::<Vec<_>>();
+
+        // Buffer holds 3..=5
+        assert_eq!(
+            seqs(state.catch_up(Some(2), ResyncReason::Lagged)),
+            [3, 4, 5]
+        );
+        assert_eq!(seqs(state.catch_up(Some(4), ResyncReason::Lagged)), [5]);
+        assert!(state.catch_up(Some(5), ResyncReason::Lagged).is_empty());
+
+        // Too old, or ahead of this run: config and latest price, numbered head
+        for after in [Some(1), Some(9), None] {
+            let snapshot = state.catch_up(after, ResyncReason::ResumeUnavailable);
+            assert!(matches!(
+                snapshot[0].event,
+                WSEvent::Resync(Resync {
+                    reason: ResyncReason::ResumeUnavailable
+                })
+            ));
+            assert!(matches!(snapshot[1].event, WSEvent::Config(_)));
+            assert!(matches!(snapshot[2].event, WSEvent::MarketUpdate(_)));
+            assert_eq!(snapshot.len(), 3);
+            assert!(snapshot.iter().all(|e| e.seq == 5));
+        }
+    }
+
+    #[test]
+    fn test_sequenced_event_wire_format() {
+        let event = SequencedEvent {
+            seq: 42,
+            bot: None,
+            event: market(3542.75),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"seq": 42, "event_type": "market_update", "data": {"price": 3542.75}})
+        );
+        let parsed: SequencedEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.seq, 42);
+        assert!(matches!(parsed.event, WSEvent::MarketUpdate(_)));
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_replays_missed_events() {
+        let (broadcaster, port) = start_server(|port| WebsocketConfig {
+            replay_buffer: 5,
+            max_rate: TopicRates::default(),
+            ..WebsocketConfig::new("127.0.0.1", port)
+        })
+        .await;
+        let url = format!("ws://127.0.0.1:{}", port);
+        for price in 1..=8 {
+            broadcaster.send(market(price as f64));
+        }
+
+        // Fresh client: resync, then the latest price
+        let (mut client, _) = connect_async(&url).await.unwrap();
+        let resync = next_event(&mut client).await;
+        assert_eq!(resync.seq, 8);
+        assert!(matches!(resync.event, WSEvent::Resync(_)));
+        assert_eq!(next_event(&mut client).await.seq, 8);
+        broadcaster.send(market(9.0));
+        assert_eq!(next_event(&mut client).await.seq, 9);
+        drop(client);
+
+        // Missed 7..=9, all still buffered
+        let (mut client, _) = connect_async(format!("{}/?resume_from=6", url))
+            .await
+            .unwrap();
+        for seq in 7..=9 {
+            let event = next_event(&mut client).await;
+            assert_eq!(event.seq, seq);
+            assert!(matches!(event.event, WSEvent::MarketUpdate(_)));
+        }
+        broadcaster.send(market(10.0));
+        assert_eq!(next_event(&mut client).await.seq, 10);
+
+        // 2 was evicted
+        let (mut client, _) = connect_async(format!("{}/?resume_from=2", url))
+            .await
+            .unwrap();
+        let resync = next_event(&mut client).await;
+        let WSEvent::Resync(Resync { reason }) = resync.event else {
+            panic!("Expected resync, got {:?}", resync.event);
+        };
+        assert_eq!(reason, ResyncReason::ResumeUnavailable);
+        assert_eq!(resync.seq, 10);
+
+        assert!(connect_async(format!("{}/?resume_from=abc", url))
+            .await
+            .is_err());
+    }
+
+    fn order(seq: u64) -> SequencedEvent {
+        SequencedEvent {
+            seq,
+            bot: None,
+            event: WSEvent::OrderUpdate(crate::broadcast::types::OrderEvent {
+                oid: seq,
+                cloid: None,
+                side: "buy".to_string(),
+                price: 1.0,
+                size: 1.0,
+                status: "filled".to_string(),
+                fee: 0.0,
+                is_taker: false,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_client_feed_filters_conflates_and_queues() {
+        let rates = TopicRates {
+            market: Some(2.0),
+            orders: Some(2.0),
+            ..Default::default()
+        };
+        let mut feed = ClientFeed::new([Topic::Market, Topic::Orders].into(), rates);
+        let start = Instant::now();
+        let tick = |seq, price| SequencedEvent {
+            seq,
+            bot: None,
+            event: market(price),
+        };
+
+        // Unsubscribed topics are dropped, topic-less events always pass
+        let error = WSEvent::Error("boom".to_string());
+        assert!(feed
+            .offer(
+                SequencedEvent {
+                    seq: 1,
+                    bot: None,
+                    event: error
+                },
+                start
+            )
+            .is_none());
+        let info = WSEvent::Info(crate::broadcast::types::SystemInfo {
+            network: "testnet".to_string(),
+            exchange: "hyperliquid".to_string(),
+        });
+        assert!(feed
+            .offer(
+                SequencedEvent {
+                    seq: 2,
+                    bot: None,
+                    event: info
+                },
+                start
+            )
+            .is_some());
+
+        // Market prices inside the interval are conflated to the latest
+        assert_eq!(feed.offer(tick(3, 1.0), start).unwrap().seq, 3);
+        assert!(feed.offer(tick(4, 2.0), start).is_none());
+        assert!(feed.offer(tick(5, 3.0), start).is_none());
+        // Orders are queued, not dropped
+        assert_eq!(feed.offer(order(6), start).unwrap().seq, 6);
+        assert!(feed.offer(order(7), start).is_none());
+        assert!(feed.offer(order(8), start).is_none());
+
+        let due = start + Duration::from_millis(500);
+        assert_eq!(feed.next_due(), Some(due));
+        assert!(feed.take_due(start).is_empty());
+        let released: Vec<u64> = feed.take_due(due).iter().map(|e| e.seq).collect();
+        assert_eq!(released, [5, 7]);
+        let released: Vec<u64> = feed
+            .take_due(due + Duration::from_millis(500))
+            .iter()
+            .map(|e| e.seq)
+            .collect();
+        assert_eq!(released, [8]);
+        assert_eq!(feed.next_due(), None);
+
+        // Unsubscribing drops held events
+        assert!(feed.offer(order(9), due).is_none());
+        feed.set_topics([Topic::Market].into());
+        assert_eq!(feed.next_due(), None);
+        assert!(feed
+            .offer(order(10), due + Duration::from_secs(5))
+            .is_none());
+    }
+
+    #[test]
+    fn test_bots_are_cached_and_throttled_separately() {
+        let mut state = BroadcastState::new(10);
+        state.record(Some("a".to_string()), market(1.0));
+        state.record(Some("b".to_string()), market(2.0));
+        state.record(Some("a".to_string()), market(3.0));
+
+        // One cached price per bot, tagged
+        let snapshot = state.catch_up(None, ResyncReason::Connect);
+        let tagged: Vec<Option<&str>> = snapshot.iter().map(|e| e.bot.as_deref()).collect();
+        assert_eq!(tagged, [None, Some("a"), Some("b")]);
+
+        // A bot's resync clears only its own cache
+        state.record(
+            Some("a".to_string()),
+            WSEvent::Resync(Resync {
+                reason: ResyncReason::ResumeUnavailable,
+            }),
+        );
+        assert_eq!(state.catch_up(None, ResyncReason::Connect).len(), 2);
+
+        // Conflation keeps the latest price of each bot
+        let rates = TopicRates {
+            market: Some(1.0),
+            ..Default::default()
+        };
+        let mut feed = ClientFeed::new(Topic::ALL.into_iter().collect(), rates);
+        let start = Instant::now();
+        let tick = |seq, bot: &str| SequencedEvent {
+            seq,
+            bot: Some(bot.to_string()),
+            event: market(seq as f64),
+        };
+        assert!(feed.offer(tick(1, "a"), start).is_some());
+        assert!(feed.offer(tick(2, "b"), start).is_some());
+        assert!(feed.offer(tick(3, "a"), start).is_none());
+        assert!(feed.offer(tick(4, "b"), start).is_none());
+        let released: Vec<u64> = feed
+            .take_due(start + Duration::from_secs(1))
+            .iter()
+            .map(|e| e.seq)
+            .collect();
+        assert_eq!(released, [3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_topics_from_handshake_and_subscribe() {
+        let (broadcaster, port) =
+            start_server(|port| WebsocketConfig::new("127.0.0.1", port)).await;
+        let url = format!("ws://127.0.0.1:{}", port);
+        assert!(connect_async(format!("{}/?topics=market,prices", url))
+            .await
+            .is_err());
+
+        let (mut client, _) = connect_async(format!("{}/?topics=orders", url))
+            .await
+            .unwrap();
+        assert!(matches!(
+            next_event(&mut client).await.event,
+            WSEvent::Resync(_)
+        ));
+        broadcaster.send(market(1.0));
+        broadcaster.send(order(0).event);
+        let event = next_event(&mut client).await;
+        assert!(matches!(event.event, WSEvent::OrderUpdate(_)));
+        assert_eq!(event.seq, 2);
+
+        client
+            .send(Message::Text(
+                r#"{"id": "s1", "command": "subscribe", "topics": ["market"]}"#.to_string(),
+            ))
+            .await
+            .unwrap();
+        let reply = next_event(&mut client).await;
+        let WSEvent::CommandResult(result) = reply.event else {
+            panic!("Expected command_result, got {:?}", reply.event);
+        };
+        assert_eq!(result.id.as_deref(), Some("s1"));
+        assert_eq!(result.message, "Subscribed to market");
+
+        broadcaster.send(order(0).event);
+        broadcaster.send(market(2.0));
+        let event = next_event(&mut client).await;
+        assert!(matches!(event.event, WSEvent::MarketUpdate(_)));
+        assert_eq!(event.seq, 4);
+    }
+
+    #[tokio::test]
+    async fn test_http_requests_share_the_websocket_port() {
+        let (broadcaster, port) =
+            start_server(|port| WebsocketConfig::new("127.0.0.1", port)).await;
+        broadcaster.send(market(1.0));
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#""seq":1}"#));
+
+        // The WebSocket still works on the same port
+        let (mut client, _) = connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        assert!(matches!(
+            next_event(&mut client).await.event,
+            WSEvent::Resync(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_history_on_connect_and_request() {
+        let (broadcaster, port) =
+            start_server(|port| WebsocketConfig::new("127.0.0.1", port)).await;
+        let (mut client, _) = connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        assert!(matches!(
+            next_event(&mut client).await.event,
+            WSEvent::Resync(_)
+        ));
+        let request = Message::Text(r#"{"command": "request_history"}"#.to_string());
+        client.send(request.clone()).await.unwrap();
+        let WSEvent::CommandResult(result) = next_event(&mut client).await.event else {
+            panic!("Expected command_result");
+        };
+        assert!(!result.success);
+
+        let history = History {
+            minute: vec![crate::broadcast::types::HistoryPoint {
+                timestamp_ms: 60_000,
+                equity: Some(1000.0),
+                matched_profit: None,
+                unrealized_pnl: None,
+                position_size: None,
+                price: Some(10.0),
+            }],
+            ..Default::default()
+        };
+        broadcaster.set_history(history.clone());
+        client.send(request).await.unwrap();
+        let WSEvent::History(sent) = next_event(&mut client).await.event else {
+            panic!("Expected history");
+        };
+        assert_eq!(sent, history);
+        assert!(matches!(
+            next_event(&mut client).await.event,
+            WSEvent::CommandResult(CommandResult { success: true, .. })
+        ));
+
+        // New clients get it in the snapshot
+        let (mut client, _) = connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        assert!(matches!(
+            next_event(&mut client).await.event,
+            WSEvent::Resync(_)
+        ));
+        assert!(matches!(
+            next_event(&mut client).await.event,
+            WSEvent::History(_)
+        ));
+    }
+
+    #[test]
+    fn test_missing_tls_files_fail_startup() {
+        let config = WebsocketConfig {
+            tls_cert: Some("/nonexistent/cert.pem".to_string()),
+            tls_key: Some("/nonexistent/key.pem".to_string()),
+            ..WebsocketConfig::new("127.0.0.1", 0)
+        };
+        let err = StatusBroadcaster::new(Some(config)).err().unwrap();
+        assert!(err.to_string().contains("/nonexistent/cert.pem"));
+    }
+}
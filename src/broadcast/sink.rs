@@ -0,0 +1,222 @@
+//! Destinations of the broadcast event stream.
+//!
+//! The [`StatusBroadcaster`](super::StatusBroadcaster) numbers every event and
+//! hands it to each sink in `seq` order. The WebSocket sink feeds the client
+//! channel; the JSONL and stdout sinks write one [`RecordedEvent`] per line so
+//! a session can be inspected or replayed offline.
+
+use crate::broadcast::types::{RecordedEvent, SequencedEvent};
+use crate::config::sink::SinkConfig;
+use anyhow::{Context, Result};
+use log::warn;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+/// Receives every broadcast event. Called while the broadcaster holds its
+/// lock, so implementations must not block for long.
+pub trait EventSink: Send + Sync {
+    fn send(&self, event: &SequencedEvent);
+}
+
+/// Feeds the channel WebSocket clients read from.
+pub struct WebSocketSink {
+    sender: broadcast::Sender<SequencedEvent>,
+}
+
+impl WebSocketSink {
+    pub fn new(sender: broadcast::Sender<SequencedEvent>) -> Self {
+        Self { sender }
+    }
+}
+
+impl EventSink for WebSocketSink {
+    fn send(&self, event: &SequencedEvent) {
+        // We ignore "channel closed" errors as we might not have any subscribers
+        let _ = self.sender.send(event.clone());
+    }
+}
+
+/// Appends events to a JSON lines file, rotating it by size.
+pub struct JsonlSink {
+    file: Mutex<RotatingFile>,
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    writer: BufWriter<File>,
+    written: u64,
+}
+
+impl JsonlSink {
+    /// Appends to `path`, creating it and its directory if needed.
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = open_append(&path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self {
+            file: Mutex::new(RotatingFile {
+                path,
+                max_bytes,
+                max_files,
+                writer: BufWriter::new(file),
+                written,
+            }),
+        })
+    }
+}
+
+impl EventSink for JsonlSink {
+    fn send(&self, event: &SequencedEvent) {
+        let Ok(line) = record_line(event) else {
+            return;
+        };
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = file.write_line(&line) {
+            warn!("Failed to write event to {}: {}", file.path.display(), e);
+        }
+    }
+}
+
+impl RotatingFile {
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.writer, "{}", line)?;
+        // Flushed per event so a crash loses nothing already broadcast
+        self.writer.flush()?;
+        self.written += len;
+        Ok(())
+    }
+
+    /// `path` becomes `path.1`, `path.1` becomes `path.2`, and so on; the
+    /// oldest file beyond `max_files` is overwritten.
+    fn rotate(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        for i in (1..self.max_files).rev() {
+            let from = self.rotated(i);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated(i + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated(1))?;
+        self.writer = BufWriter::new(open_append(&self.path)?);
+        self.written = 0;
+        Ok(())
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        name.into()
+    }
+}
+
+fn open_append(path: &PathBuf) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Writes events to standard output, one JSON line each.
+pub struct StdoutSink;
+
+impl EventSink for StdoutSink {
+    fn send(&self, event: &SequencedEvent) {
+        if let Ok(line) = record_line(event) {
+            // Ignore closed pipes: the bot keeps running when the reader goes away
+            let _ = writeln!(std::io::stdout().lock(), "{}", line);
+        }
+    }
+}
+
+fn record_line(event: &SequencedEvent) -> serde_json::Result<String> {
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    serde_json::to_string(&RecordedEvent {
+        timestamp_ms,
+        event: event.clone(),
+    })
+}
+
+/// Opens the file and stdout sinks of `configs`. The WebSocket sink belongs
+/// to the broadcaster and is skipped here.
+pub fn open_sinks(configs: &[SinkConfig], bot_name: &str) -> Result<Vec<Box<dyn EventSink>>> {
+    let mut sinks: Vec<Box<dyn EventSink>> = Vec::new();
+    for config in configs {
+        match config {
+            SinkConfig::Websocket => {}
+            SinkConfig::Jsonl {
+                path,
+                max_bytes,
+                max_files,
+            } => {
+                let path = SinkConfig::jsonl_path_for(path.as_deref(), bot_name);
+                sinks.push(Box::new(JsonlSink::open(path, *max_bytes, *max_files)?));
+            }
+            SinkConfig::Stdout => sinks.push(Box::new(StdoutSink)),
+        }
+    }
+    Ok(sinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::types::{MarketEvent, WSEvent};
+
+    fn market(seq: u64) -> SequencedEvent {
+        SequencedEvent {
+            seq,
+            bot: None,
+            event: WSEvent::MarketUpdate(MarketEvent { price: seq as f64 }),
+        }
+    }
+
+    #[test]
+    fn test_jsonl_sink_records_and_rotates() {
+        let dir = std::env::temp_dir().join(format!("hlbot-sink-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("events.jsonl");
+
+        // Each line is under 100 bytes, so every third event rotates
+        let sink = JsonlSink::open(&path, 200, 2).unwrap();
+        for seq in 1..=7 {
+            sink.send(&market(seq));
+        }
+
+        let seqs = |path: PathBuf| -> Vec<u64> {
+            std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<RecordedEvent>(line).unwrap())
+                .map(|recorded| recorded.event.seq)
+                .collect()
+        };
+        assert_eq!(seqs(path.clone()), [7]);
+        assert_eq!(seqs(dir.join("events.jsonl.1")), [5, 6]);
+        assert_eq!(seqs(dir.join("events.jsonl.2")), [3, 4]);
+        assert!(!dir.join("events.jsonl.3").exists());
+
+        // Reopening appends
+        drop(sink);
+        JsonlSink::open(&path, 200, 2).unwrap().send(&market(8));
+        assert_eq!(seqs(path), [7, 8]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
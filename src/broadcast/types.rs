@@ -1,4 +1,7 @@
+use crate::config::reload::ConfigChange;
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 // ============================================================
 // WebSocket Event Types
@@ -23,6 +26,14 @@ pub enum WSEvent {
     #[serde(rename = "perp_grid_summary")]
     PerpGridSummary(PerpGridSummary),
 
+    /// TWAP execution summary (progress and execution quality)
+    #[serde(rename = "twap_summary")]
+    TwapSummary(TwapSummary),
+
+    /// Portfolio rebalance summary (allocations and drift)
+    #[serde(rename = "rebalance_summary")]
+    RebalanceSummary(RebalanceSummary),
+
     /// Grid zone state for dashboard CLOB visualization
     #[serde(rename = "grid_state")]
     GridState(GridState),
@@ -38,6 +49,119 @@ pub enum WSEvent {
     /// Error notification
     #[serde(rename = "error")]
     Error(String),
+
+    /// Outcome of a control command sent by a client
+    #[serde(rename = "command_result")]
+    CommandResult(CommandResult),
+
+    /// Client must drop its state; a full snapshot follows
+    #[serde(rename = "resync")]
+    Resync(Resync),
+
+    /// Equity and PnL time series (sent on connect and on `request_history`)
+    #[serde(rename = "history")]
+    History(History),
+
+    /// Totals across the bots behind a hub
+    #[serde(rename = "portfolio_summary")]
+    PortfolioSummary(PortfolioSummary),
+
+    /// Changes and order actions of a config reload, sent before it is applied
+    #[serde(rename = "reload_plan")]
+    ReloadPlan(ReloadPlan),
+}
+
+impl WSEvent {
+    /// Topic the event belongs to. `None` for events every client receives
+    /// (config, info, command results, resync).
+    pub fn topic(&self) -> Option<Topic> {
+        match self {
+            WSEvent::MarketUpdate(_) => Some(Topic::Market),
+            WSEvent::OrderUpdate(_) => Some(Topic::Orders),
+            WSEvent::SpotGridSummary(_)
+            | WSEvent::PerpGridSummary(_)
+            | WSEvent::TwapSummary(_)
+            | WSEvent::RebalanceSummary(_)
+            | WSEvent::PortfolioSummary(_) => Some(Topic::Summary),
+            WSEvent::GridState(_) => Some(Topic::Grid),
+            WSEvent::Error(_) => Some(Topic::Errors),
+            WSEvent::Config(_)
+            | WSEvent::Info(_)
+            | WSEvent::CommandResult(_)
+            | WSEvent::Resync(_)
+            | WSEvent::History(_)
+            | WSEvent::ReloadPlan(_) => None,
+        }
+    }
+}
+
+/// Event groups a client can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    Market,
+    Orders,
+    Summary,
+    Grid,
+    Errors,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 5] = [
+        Topic::Market,
+        Topic::Orders,
+        Topic::Summary,
+        Topic::Grid,
+        Topic::Errors,
+    ];
+
+    /// Only the latest event matters, so throttled events are replaced
+    /// rather than queued.
+    pub fn is_conflated(self) -> bool {
+        matches!(self, Topic::Market | Topic::Summary | Topic::Grid)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Topic::Market => "market",
+            Topic::Orders => "orders",
+            Topic::Summary => "summary",
+            Topic::Grid => "grid",
+            Topic::Errors => "errors",
+        }
+    }
+}
+
+impl FromStr for Topic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Topic::ALL
+            .into_iter()
+            .find(|topic| topic.name() == s)
+            .ok_or_else(|| anyhow!("Unknown topic '{}'", s))
+    }
+}
+
+/// Wire format of every message: the event plus its sequence number,
+/// e.g. `{"seq": 42, "event_type": "market_update", "data": {...}}`.
+/// A hub adds the name of the bot the event came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot: Option<String>,
+    #[serde(flatten)]
+    pub event: WSEvent,
+}
+
+/// An event as written by the JSONL and stdout sinks: the wire message plus
+/// the time it was broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub timestamp_ms: u64,
+    #[serde(flatten)]
+    pub event: SequencedEvent,
 }
 
 /// System information
@@ -111,6 +235,57 @@ pub struct PerpGridSummary {
     pub initial_entry_price: Option<f64>,
 }
 
+/// TWAP execution summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapSummary {
+    pub symbol: String,
+    pub state: String, // "Initializing", "Running", "Completed"
+    pub uptime: String,
+    pub side: String, // "Buy" or "Sell"
+
+    // Progress
+    pub target_size: f64,
+    pub filled_size: f64,
+    pub remaining_size: f64,
+    pub progress_pct: f64,
+    pub slices_completed: u32,
+    pub slice_count: u32,
+
+    // Execution quality
+    pub avg_fill_price: Option<f64>,
+    pub arrival_price: Option<f64>,
+    pub slippage_bps: Option<f64>, // Positive = worse than arrival mid
+    pub total_fees: f64,
+}
+
+/// Portfolio rebalance summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceSummary {
+    pub quote_asset: String,
+    pub state: String, // "Initializing", "Monitoring", "Selling", "Buying", "Cooldown"
+    pub uptime: String,
+
+    pub portfolio_value: f64, // Valued in quote asset at live mids
+    pub max_drift_pct: f64,   // Largest |current - target| weight, in percentage points
+    pub rebalance_count: u32,
+    pub traded_notional: f64,
+    pub total_fees: f64,
+    pub open_orders: u32,
+
+    pub allocations: Vec<AssetAllocation>,
+}
+
+/// Current vs target allocation of a single asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetAllocation {
+    pub asset: String,
+    pub balance: f64,
+    pub price: f64,
+    pub value: f64,
+    pub current_weight_pct: f64,
+    pub target_weight_pct: f64,
+}
+
 // ============================================================
 // Grid State (Zone data for dashboard CLOB visualization)
 // ============================================================
@@ -119,7 +294,7 @@ pub struct PerpGridSummary {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GridState {
     pub symbol: String,
-    pub strategy_type: String,     // "spot_grid" or "perp_grid"
+    pub strategy_type: String, // "spot_grid", "perp_grid", "twap" or "rebalance"
     pub grid_bias: Option<String>, // None for spot, "Long"/"Short"/"Neutral" for perp
     pub zones: Vec<ZoneInfo>,
 }
@@ -140,6 +315,10 @@ pub struct ZoneInfo {
     // Metrics
     pub entry_price: f64,
     pub roundtrip_count: u32,
+
+    /// Sub-range the zone belongs to, for grids built from `ranges`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_index: Option<usize>,
 }
 
 // ============================================================
@@ -163,6 +342,229 @@ pub struct MarketEvent {
     pub price: f64,
 }
 
+// ============================================================
+// Equity History
+// ============================================================
+
+/// One sample of the strategy's equity and PnL. Fields a strategy does not
+/// report are omitted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    pub timestamp_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_profit: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unrealized_pnl: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position_size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+}
+
+/// Equity time series at three resolutions, oldest first. Each point is the
+/// latest sample in its minute, hour or UTC day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    #[serde(rename = "1m", default)]
+    pub minute: Vec<HistoryPoint>,
+    #[serde(rename = "1h", default)]
+    pub hour: Vec<HistoryPoint>,
+    #[serde(rename = "1d", default)]
+    pub day: Vec<HistoryPoint>,
+}
+
+// ============================================================
+// Control Commands (client -> bot)
+// ============================================================
+
+/// Command sent by a client over the WebSocket, e.g. `{"id": "1", "command": "pause"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlRequest {
+    /// Echoed back in the matching `command_result`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub command: ControlCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Stop calling the strategy. Open orders stay on the book.
+    Pause,
+    Resume,
+    /// Cancel every open order and pause.
+    CancelAll,
+    /// Cancel every open order, pause, and close the perp position.
+    ClosePosition,
+    /// Move the grid range.
+    SetRange {
+        low: f64,
+        high: f64,
+    },
+    /// Broadcast the current summary and grid state now.
+    RequestSnapshot,
+    /// Re-read the config file and apply its live-safe changes. With
+    /// `dry_run` only the plan is broadcast.
+    Reload {
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// Cancel open orders and stop the bot.
+    Shutdown,
+}
+
+impl ControlCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlCommand::Pause => "pause",
+            ControlCommand::Resume => "resume",
+            ControlCommand::CancelAll => "cancel_all",
+            ControlCommand::ClosePosition => "close_position",
+            ControlCommand::SetRange { .. } => "set_range",
+            ControlCommand::RequestSnapshot => "request_snapshot",
+            ControlCommand::Reload { .. } => "reload",
+            ControlCommand::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Command handled by the connection itself rather than the engine,
+/// e.g. `{"id": "2", "command": "subscribe", "topics": ["market", "orders"]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub command: ConnectionCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ConnectionCommand {
+    /// Replaces the client's topics.
+    Subscribe { topics: Vec<Topic> },
+    /// Sends the latest `history` to this client.
+    RequestHistory,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandResult {
+    pub id: Option<String>,
+    pub command: String,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Why the server is resending its full state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResyncReason {
+    /// New client without `resume_from`
+    Connect,
+    /// `resume_from` is older than the replay buffer, or from an earlier run
+    ResumeUnavailable,
+    /// The client fell behind by more than the replay buffer
+    Lagged,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resync {
+    pub reason: ResyncReason,
+}
+
+// ============================================================
+// Hub
+// ============================================================
+
+/// Portfolio totals across the bots behind a hub, in quote (USDC).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioSummary {
+    /// Sum of the equity of the bots that report one.
+    pub total_equity: f64,
+    /// Sum of `total_profit` of the bots that report one.
+    pub total_pnl: f64,
+    pub bots: Vec<BotStatus>,
+    /// Net position per coin, largest notional first.
+    pub exposure: Vec<CoinExposure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotStatus {
+    pub bot: String,
+    pub connected: bool,
+    /// Strategy type, e.g. `perp_grid`. Missing until the bot sends a summary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pnl: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoinExposure {
+    pub coin: String,
+    /// Signed size in the coin: positive long, negative short.
+    pub size: f64,
+    /// `size` at the latest known price. Missing until a price is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notional: Option<f64>,
+}
+
+// ============================================================
+// Config Reload
+// ============================================================
+
+/// What a config reload changes. Broadcast before the changes are applied, and
+/// on its own for a `dry_run` or a config file edit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReloadPlan {
+    pub changes: Vec<ConfigChange>,
+    /// Cloids of the open orders the reload cancels.
+    pub cancels: Vec<String>,
+    /// Orders placed in their place. A grid that has not started is re-planned
+    /// on the next tick instead, so this may be empty.
+    pub orders: Vec<PlannedOrder>,
+    /// True when the plan is only a preview.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedOrder {
+    pub side: String,
+    /// Limit price. Missing for a market order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    pub size: f64,
+}
+
+// ============================================================
+// REST API (GET on the WebSocket port)
+// ============================================================
+
+/// `GET /health`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub status: String,
+    pub uptime_secs: u64,
+    /// Sequence number of the last broadcast event.
+    pub seq: u64,
+}
+
+/// A fill as returned by `GET /fills`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillRecord {
+    pub timestamp_ms: u64,
+    /// Bot that reported the fill, on a hub.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot: Option<String>,
+    #[serde(flatten)]
+    pub order: OrderEvent,
+}
+
 // ============================================================
 // Strategy Summary Enum (for trait return type)
 // ============================================================
@@ -173,6 +575,8 @@ pub struct MarketEvent {
 pub enum StrategySummary {
     SpotGrid(SpotGridSummary),
     PerpGrid(PerpGridSummary),
+    Twap(TwapSummary),
+    Rebalance(RebalanceSummary),
 }
 
 #[cfg(test)]
@@ -311,6 +715,7 @@ mod schema_tests {
                     is_reduce_only: false,
                     entry_price: 20.25,
                     roundtrip_count: 1,
+                    range_index: None,
                 },
                 ZoneInfo {
                     index: 1,
@@ -322,6 +727,7 @@ mod schema_tests {
                     is_reduce_only: true,
                     entry_price: 20.75,
                     roundtrip_count: 0,
+                    range_index: None,
                 },
             ],
         });
@@ -0,0 +1,453 @@
+//! The `accounts` subcommand's edits of `accounts.toml`.
+//!
+//! Profiles are added and removed through `toml_edit`, so comments and the
+//! layout of hand-written profiles survive. Addresses, networks and key
+//! sources go through the same checks as `load_exchange_config`, so a
+//! profile that was added here also loads at startup.
+
+use crate::config::exchange::{
+    validate_address, validate_network, AccountProfile, AccountsConfig, KeySource,
+};
+use crate::config::signer::SignerEndpoint;
+use anyhow::{anyhow, Context, Result};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Password, Select};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::H160;
+use ethers::utils::to_checksum;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+/// One row of `accounts list`.
+#[derive(Debug, Clone)]
+pub struct AccountSummary {
+    pub name: String,
+    pub network: String,
+    pub master_account_address: String,
+    pub sub_account_address: Option<String>,
+    /// Where the key comes from, with plaintext keys redacted.
+    pub key_source: String,
+    /// API wallet address, when it is known without prompting for a
+    /// passphrase or running a command.
+    pub api_wallet: Option<String>,
+    /// Why the profile would fail to load.
+    pub problem: Option<String>,
+}
+
+/// Profiles of the accounts file at `path`, sorted by name.
+pub fn list_accounts(path: &Path) -> Result<Vec<AccountSummary>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read accounts file at {}", path.display()))?;
+    let config: AccountsConfig =
+        toml::from_str(&content).with_context(|| "Failed to parse accounts TOML")?;
+    let mut names: Vec<&String> = config.accounts.keys().collect();
+    names.sort();
+    Ok(names
+        .into_iter()
+        .map(|name| summarize(name, &config.accounts[name]))
+        .collect())
+}
+
+fn summarize(name: &str, profile: &AccountProfile) -> AccountSummary {
+    let source = profile.key_source(name);
+    let problem = validate_profile(name, profile).err().map(|e| e.to_string());
+    AccountSummary {
+        name: name.to_string(),
+        network: profile.network.clone(),
+        master_account_address: profile.master_account_address.clone(),
+        sub_account_address: profile.sub_account_address.clone(),
+        key_source: source
+            .as_ref()
+            .map(describe_key_source)
+            .unwrap_or_else(|_| "-".to_string()),
+        api_wallet: source.ok().and_then(|s| known_api_wallet(&s)).map(checksum),
+        problem,
+    }
+}
+
+/// The checks `load_exchange_config` runs before it loads the key.
+fn validate_profile(name: &str, profile: &AccountProfile) -> Result<()> {
+    validate_network(&profile.network)?;
+    validate_address("master_account_address", &profile.master_account_address)?;
+    if let Some(sub_account) = &profile.sub_account_address {
+        validate_address("sub_account_address", sub_account)?;
+        if sub_account.eq_ignore_ascii_case(&profile.master_account_address) {
+            return Err(anyhow!(
+                "sub_account_address of account '{}' is the master account itself",
+                name
+            ));
+        }
+    }
+    if let KeySource::Signer(endpoint) = profile.key_source(name)? {
+        SignerEndpoint::parse(endpoint)?;
+    }
+    Ok(())
+}
+
+fn describe_key_source(source: &KeySource<'_>) -> String {
+    match source {
+        KeySource::Plaintext(key) => format!("plaintext {}", redact(key)),
+        KeySource::Env(var) => format!("env ${}", var),
+        KeySource::Command(command) => format!("command `{}`", command.join(" ")),
+        KeySource::Keystore { path, .. } => format!("keystore {}", path),
+        KeySource::Signer(endpoint) => format!("signer {}", endpoint),
+    }
+}
+
+/// First and last four hex digits of a key.
+fn redact(key: &str) -> String {
+    let digits = key.trim().trim_start_matches("0x");
+    if digits.len() < 16 {
+        return "****".to_string();
+    }
+    format!("0x{}…{}", &digits[..4], &digits[digits.len() - 4..])
+}
+
+/// Address of the key without side effects: keystores record it in the
+/// clear, commands and signers would have to be run.
+fn known_api_wallet(source: &KeySource<'_>) -> Option<H160> {
+    match source {
+        KeySource::Plaintext(key) => key.trim().parse::<LocalWallet>().ok().map(|w| w.address()),
+        KeySource::Env(var) => std::env::var(var)
+            .ok()?
+            .trim()
+            .parse::<LocalWallet>()
+            .ok()
+            .map(|w| w.address()),
+        KeySource::Keystore { path, .. } => {
+            let keystore: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+            let address = keystore["address"].as_str()?;
+            H160::from_str(address.trim_start_matches("0x")).ok()
+        }
+        KeySource::Command(_) | KeySource::Signer(_) => None,
+    }
+}
+
+fn checksum(address: H160) -> String {
+    to_checksum(&address, None)
+}
+
+/// Adds `profile` as `[accounts.<name>]`.
+fn insert_account(doc: &mut DocumentMut, name: &str, profile: &AccountProfile) -> Result<()> {
+    validate_profile(name, profile)?;
+    let accounts = doc
+        .entry("accounts")
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("'accounts' in the accounts file is not a table"))?;
+    if accounts.contains_key(name) {
+        return Err(anyhow!("Account profile '{}' already exists", name));
+    }
+
+    let mut table = Table::new();
+    table["network"] = value(&profile.network);
+    table["master_account_address"] = value(&profile.master_account_address);
+    if let Some(sub_account) = &profile.sub_account_address {
+        table["sub_account_address"] = value(sub_account);
+    }
+    if let Some(key) = &profile.api_wallet_private_key {
+        table["api_wallet_private_key"] = value(key);
+    }
+    if let Some(var) = &profile.api_wallet_private_key_env {
+        table["api_wallet_private_key_env"] = value(var);
+    }
+    if let Some(command) = &profile.api_wallet_private_key_command {
+        table["api_wallet_private_key_command"] =
+            value(command.iter().map(String::as_str).collect::<Array>());
+    }
+    if let Some(path) = &profile.api_wallet_keystore {
+        table["api_wallet_keystore"] = value(path);
+    }
+    if let Some(fd) = profile.keystore_passphrase_fd {
+        table["keystore_passphrase_fd"] = value(fd as i64);
+    }
+    if let Some(endpoint) = &profile.api_wallet_signer {
+        table["api_wallet_signer"] = value(endpoint);
+    }
+    accounts.insert(name, Item::Table(table));
+    Ok(())
+}
+
+fn remove_account(doc: &mut DocumentMut, name: &str) -> Result<()> {
+    doc.get_mut("accounts")
+        .and_then(Item::as_table_mut)
+        .and_then(|accounts| accounts.remove(name))
+        .map(|_| ())
+        .ok_or_else(|| anyhow!("Account profile '{}' not found", name))
+}
+
+fn read_document(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    fs::read_to_string(path)
+        .with_context(|| format!("Failed to read accounts file at {}", path.display()))?
+        .parse()
+        .with_context(|| "Failed to parse accounts TOML")
+}
+
+/// Writes `doc` back; a new file is created readable by its owner only.
+fn write_document(path: &Path, doc: &DocumentMut) -> Result<()> {
+    if path.exists() {
+        return fs::write(path, doc.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()));
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(doc.to_string().as_bytes())?;
+    Ok(())
+}
+
+/// Removes the profile `name`, asking first unless `confirmed`.
+pub fn remove_account_from_file(path: &Path, name: &str, confirmed: bool) -> Result<bool> {
+    let mut doc = read_document(path)?;
+    remove_account(&mut doc, name)?;
+    let confirmed = confirmed
+        || Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Remove account '{}' from {}?",
+                name,
+                path.display()
+            ))
+            .default(false)
+            .interact()?;
+    if confirmed {
+        write_document(path, &doc)?;
+    }
+    Ok(confirmed)
+}
+
+/// Prompts for a new profile and appends it to the accounts file at `path`.
+/// Returns the new profile's name.
+pub fn add_account_interactive(path: &Path) -> Result<String> {
+    let theme = ColorfulTheme::default();
+    let mut doc = read_document(path)?;
+    let existing: Vec<String> = doc
+        .get("accounts")
+        .and_then(Item::as_table)
+        .map(|accounts| accounts.iter().map(|(name, _)| name.to_string()).collect())
+        .unwrap_or_default();
+
+    let name: String = Input::with_theme(&theme)
+        .with_prompt("Account profile name")
+        .validate_with(|name: &String| -> Result<()> {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(anyhow!("Use letters, digits, '_' and '-'"));
+            }
+            if existing.contains(name) {
+                return Err(anyhow!("Account profile '{}' already exists", name));
+            }
+            Ok(())
+        })
+        .interact_text()?;
+
+    let networks = ["mainnet", "testnet"];
+    let network = networks[Select::with_theme(&theme)
+        .with_prompt("Network")
+        .default(0)
+        .items(networks)
+        .interact()?];
+
+    let master_account_address: String = Input::with_theme(&theme)
+        .with_prompt("Master account address")
+        .validate_with(|address: &String| validate_address("master_account_address", address))
+        .interact_text()?;
+
+    let sub_account_raw: String = Input::with_theme(&theme)
+        .with_prompt("Sub-account address (blank to trade the master account)")
+        .allow_empty(true)
+        .validate_with(|address: &String| -> Result<()> {
+            if address.is_empty() {
+                return Ok(());
+            }
+            validate_address("sub_account_address", address)?;
+            if address.eq_ignore_ascii_case(&master_account_address) {
+                return Err(anyhow!("This is the master account address"));
+            }
+            Ok(())
+        })
+        .interact_text()?;
+
+    let mut profile = AccountProfile {
+        network: network.to_string(),
+        master_account_address: master_account_address.clone(),
+        sub_account_address: (!sub_account_raw.is_empty()).then_some(sub_account_raw),
+        ..Default::default()
+    };
+
+    let sources = [
+        "Encrypted keystore file",
+        "Password manager command",
+        "Environment variable",
+        "Remote signer",
+        "Plaintext key in this file (not recommended)",
+    ];
+    match Select::with_theme(&theme)
+        .with_prompt("Where is the API wallet key?")
+        .default(0)
+        .items(sources)
+        .interact()?
+    {
+        0 => {
+            let path: String = Input::with_theme(&theme)
+                .with_prompt("Keystore path")
+                .validate_with(|path: &String| -> Result<()> {
+                    if Path::new(path).is_file() {
+                        Ok(())
+                    } else {
+                        Err(anyhow!("No file at {}", path))
+                    }
+                })
+                .interact_text()?;
+            profile.api_wallet_keystore = Some(path);
+        }
+        1 => {
+            let command: String = Input::with_theme(&theme)
+                .with_prompt("Command printing the key (e.g. pass show hyperliquid/api)")
+                .interact_text()?;
+            profile.api_wallet_private_key_command =
+                Some(command.split_whitespace().map(str::to_string).collect());
+        }
+        2 => {
+            let var: String = Input::with_theme(&theme)
+                .with_prompt("Environment variable")
+                .interact_text()?;
+            profile.api_wallet_private_key_env = Some(var);
+        }
+        3 => {
+            let endpoint: String = Input::with_theme(&theme)
+                .with_prompt(
+                    "Signer endpoint (unix:/path/to/signer.sock or http://127.0.0.1:<port>)",
+                )
+                .validate_with(|endpoint: &String| SignerEndpoint::parse(endpoint).map(|_| ()))
+                .interact_text()?;
+            profile.api_wallet_signer = Some(endpoint);
+        }
+        _ => {
+            let key = Password::with_theme(&theme)
+                .with_prompt("API wallet private key")
+                .validate_with(|key: &String| -> Result<()> {
+                    key.trim()
+                        .parse::<LocalWallet>()
+                        .map(|_| ())
+                        .map_err(|e| anyhow!("Invalid private key: {}", e))
+                })
+                .interact()?;
+            profile.api_wallet_private_key = Some(key.trim().to_string());
+        }
+    }
+
+    if let Some(api_wallet) = profile
+        .key_source(&name)
+        .ok()
+        .and_then(|source| known_api_wallet(&source))
+    {
+        println!("API wallet address: {}", checksum(api_wallet));
+        if format!("{:?}", api_wallet).eq_ignore_ascii_case(&master_account_address) {
+            println!("This is the master account's own key. Prefer an approved API wallet.");
+        }
+    }
+
+    insert_account(&mut doc, &name, &profile)?;
+    write_document(path, &doc)?;
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER: &str = "0x1111111111111111111111111111111111111111";
+    const SUB: &str = "0x2222222222222222222222222222222222222222";
+    const KEY: &str = "0xe908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+
+    #[test]
+    fn test_add_list_and_remove_keep_hand_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("accounts.toml");
+        fs::write(
+            &path,
+            format!("# Trading accounts\n[accounts.spot]\nnetwork = \"mainnet\"\nmaster_account_address = \"{MASTER}\"\n# keep me\napi_wallet_private_key_env = \"HL_SPOT_KEY\"\n"),
+        )
+        .unwrap();
+
+        let mut doc = read_document(&path).unwrap();
+        let profile = AccountProfile {
+            network: "testnet".to_string(),
+            master_account_address: MASTER.to_string(),
+            sub_account_address: Some(SUB.to_string()),
+            api_wallet_private_key: Some(KEY.to_string()),
+            ..Default::default()
+        };
+        insert_account(&mut doc, "perp", &profile).unwrap();
+        assert!(insert_account(&mut doc, "perp", &profile).is_err());
+        write_document(&path, &doc).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Trading accounts\n"));
+        assert!(content.contains("# keep me"));
+
+        let accounts = list_accounts(&path).unwrap();
+        assert_eq!(accounts.len(), 2);
+        let perp = &accounts[0];
+        assert_eq!(perp.name, "perp");
+        assert_eq!(perp.key_source, "plaintext 0xe908…688e");
+        assert!(perp.api_wallet.is_some());
+        assert!(perp.problem.is_none());
+        assert_eq!(accounts[1].key_source, "env $HL_SPOT_KEY");
+
+        let mut doc = read_document(&path).unwrap();
+        remove_account(&mut doc, "perp").unwrap();
+        assert!(remove_account(&mut doc, "perp").is_err());
+        assert!(!doc.to_string().contains(KEY));
+        assert!(doc.to_string().contains("# keep me"));
+    }
+
+    #[test]
+    fn test_profiles_are_validated() {
+        let mut doc = DocumentMut::new();
+        let profile = AccountProfile {
+            network: "mainnet".to_string(),
+            // Checksum broken by a single mistyped character
+            master_account_address: "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAee".to_string(),
+            api_wallet_private_key_env: Some("HL_KEY".to_string()),
+            ..Default::default()
+        };
+        assert!(insert_account(&mut doc, "typo", &profile).is_err());
+
+        let same_as_master = AccountProfile {
+            master_account_address: MASTER.to_string(),
+            sub_account_address: Some(MASTER.to_string()),
+            ..profile.clone()
+        };
+        assert!(insert_account(&mut doc, "same", &same_as_master).is_err());
+
+        let no_key = AccountProfile {
+            master_account_address: MASTER.to_string(),
+            api_wallet_private_key_env: None,
+            ..profile
+        };
+        let summary = summarize("no_key", &no_key);
+        assert!(summary.problem.unwrap().contains("no API wallet key"));
+        assert!(doc.get("accounts").is_none());
+    }
+}
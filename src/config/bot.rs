@@ -1,4 +1,8 @@
+use crate::config::broadcast::WebsocketSettings;
+use crate::config::history::HistoryConfig;
+use crate::config::notification::NotificationConfig;
 use crate::config::simulation::SimulationConfig;
+use crate::config::sink::{validate_sinks, SinkConfig};
 use crate::config::strategy::StrategyConfig;
 use serde::{Deserialize, Serialize};
 
@@ -8,8 +12,21 @@ pub struct BotConfig {
     pub account: String,
     #[serde(default)]
     pub websocket_port: Option<u16>,
+    /// Bind address, authentication and TLS for the status servers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket: Option<WebsocketSettings>,
+    /// Serves Prometheus metrics on this port when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_port: Option<u16>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub simulation: Option<SimulationConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<NotificationConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryConfig>,
+    /// Where broadcast events go. Defaults to the WebSocket server only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sinks: Option<Vec<SinkConfig>>,
     pub strategy: StrategyConfig,
 }
 
@@ -21,9 +38,26 @@ impl BotConfig {
         if self.account.trim().is_empty() {
             return Err(anyhow::anyhow!("Account profile must not be empty."));
         }
+        if self.metrics_port.is_some() && self.metrics_port == Some(self.websocket_port()) {
+            return Err(anyhow::anyhow!(
+                "metrics_port must differ from the WebSocket port."
+            ));
+        }
+        if let Some(websocket) = &self.websocket {
+            websocket.validate()?;
+        }
         if let Some(simulation) = &self.simulation {
             simulation.validate()?;
         }
+        if let Some(notifications) = &self.notifications {
+            notifications.validate()?;
+        }
+        if let Some(history) = &self.history {
+            history.validate()?;
+        }
+        if let Some(sinks) = &self.sinks {
+            validate_sinks(sinks)?;
+        }
         self.strategy.validate()
     }
 
@@ -32,14 +66,33 @@ impl BotConfig {
             .unwrap_or_else(|| self.strategy.default_websocket_port())
     }
 
+    pub fn websocket_settings(&self) -> WebsocketSettings {
+        self.websocket.clone().unwrap_or_default()
+    }
+
     pub fn simulation_config(&self) -> SimulationConfig {
         self.simulation.clone().unwrap_or_default()
     }
+
+    pub fn notification_config(&self) -> NotificationConfig {
+        self.notifications.clone().unwrap_or_default()
+    }
+
+    pub fn history_config(&self) -> HistoryConfig {
+        self.history.clone().unwrap_or_default()
+    }
+
+    pub fn sink_configs(&self) -> Vec<SinkConfig> {
+        self.sinks
+            .clone()
+            .unwrap_or_else(|| vec![SinkConfig::Websocket])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::strategy::SizeDistribution;
 
     #[test]
     fn test_validate_requires_name_and_account() {
@@ -47,7 +100,12 @@ mod tests {
             name: "".to_string(),
             account: "account1".to_string(),
             websocket_port: None,
+            websocket: None,
+            metrics_port: None,
             simulation: None,
+            notifications: None,
+            history: None,
+            sinks: None,
             strategy: StrategyConfig::SpotGrid(crate::config::strategy::SpotGridConfig {
                 symbol: "BTC/USDC".to_string(),
                 grid_range_high: 2000.0,
@@ -55,6 +113,12 @@ mod tests {
                 grid_type: crate::config::strategy::GridType::Arithmetic,
                 grid_count: Some(10),
                 spread_bips: None,
+                spacing_mode: crate::config::strategy::SpacingMode::Fixed,
+                atr: crate::config::strategy::AtrSpacingConfig::default(),
+                levels: None,
+                ranges: None,
+                size_distribution: SizeDistribution::default(),
+                compound: None,
                 total_investment: 1000.0,
                 trigger_price: None,
             }),
@@ -70,7 +134,12 @@ mod tests {
             name: "btc-perp-grid".to_string(),
             account: "account1".to_string(),
             websocket_port: Some(9001),
+            websocket: None,
+            metrics_port: None,
             simulation: None,
+            notifications: None,
+            history: None,
+            sinks: None,
             strategy: StrategyConfig::PerpGrid(crate::config::strategy::PerpGridConfig {
                 symbol: "BTC".to_string(),
                 leverage: 10,
@@ -80,6 +149,12 @@ mod tests {
                 grid_type: crate::config::strategy::GridType::Geometric,
                 grid_count: Some(20),
                 spread_bips: None,
+                spacing_mode: crate::config::strategy::SpacingMode::Fixed,
+                atr: crate::config::strategy::AtrSpacingConfig::default(),
+                levels: None,
+                ranges: None,
+                size_distribution: SizeDistribution::default(),
+                compound: None,
                 total_investment: 8000.0,
                 grid_bias: crate::config::strategy::GridBias::Short,
                 trigger_price: None,
@@ -125,7 +200,12 @@ total_investment = 985.0
             name: "spot-bot".to_string(),
             account: "account1".to_string(),
             websocket_port: None,
+            websocket: None,
+            metrics_port: None,
             simulation: None,
+            notifications: None,
+            history: None,
+            sinks: None,
             strategy: StrategyConfig::SpotGrid(crate::config::strategy::SpotGridConfig {
                 symbol: "BTC/USDC".to_string(),
                 grid_range_high: 2000.0,
@@ -133,6 +213,12 @@ total_investment = 985.0
                 grid_type: crate::config::strategy::GridType::Arithmetic,
                 grid_count: Some(10),
                 spread_bips: None,
+                spacing_mode: crate::config::strategy::SpacingMode::Fixed,
+                atr: crate::config::strategy::AtrSpacingConfig::default(),
+                levels: None,
+                ranges: None,
+                size_distribution: SizeDistribution::default(),
+                compound: None,
                 total_investment: 1000.0,
                 trigger_price: None,
             }),
@@ -147,7 +233,12 @@ total_investment = 985.0
             name: "perp-bot".to_string(),
             account: "account1".to_string(),
             websocket_port: None,
+            websocket: None,
+            metrics_port: None,
             simulation: None,
+            notifications: None,
+            history: None,
+            sinks: None,
             strategy: StrategyConfig::PerpGrid(crate::config::strategy::PerpGridConfig {
                 symbol: "BTC".to_string(),
                 leverage: 10,
@@ -157,6 +248,12 @@ total_investment = 985.0
                 grid_type: crate::config::strategy::GridType::Geometric,
                 grid_count: Some(20),
                 spread_bips: None,
+                spacing_mode: crate::config::strategy::SpacingMode::Fixed,
+                atr: crate::config::strategy::AtrSpacingConfig::default(),
+                levels: None,
+                ranges: None,
+                size_distribution: SizeDistribution::default(),
+                compound: None,
                 total_investment: 8000.0,
                 grid_bias: crate::config::strategy::GridBias::Short,
                 trigger_price: None,
@@ -165,4 +262,30 @@ total_investment = 985.0
 
         assert_eq!(config.websocket_port(), 8001);
     }
+
+    #[test]
+    fn test_metrics_port_is_optional_and_distinct() {
+        let toml = r#"
+name = "btc-perp"
+account = "perp_account"
+metrics_port = 9101
+
+[strategy]
+type = "perp_grid"
+symbol = "BTC"
+leverage = 5
+grid_range_high = 95000.0
+grid_range_low = 85000.0
+grid_count = 10
+total_investment = 1000.0
+grid_bias = "long"
+"#;
+        let mut config: BotConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.metrics_port, Some(9101));
+        assert!(config.validate().is_ok());
+
+        config.metrics_port = Some(config.websocket_port());
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(err, "metrics_port must differ from the WebSocket port.");
+    }
 }
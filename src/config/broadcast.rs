@@ -1,21 +1,347 @@
+use crate::broadcast::types::Topic;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+pub const DEFAULT_MAX_CONNECTIONS: usize = 32;
+pub const DEFAULT_REPLAY_BUFFER: usize = 1000;
+pub const DEFAULT_MARKET_RATE: f64 = 4.0;
+
+/// Security settings for the status servers.
+///
+/// This block lives inside the main bot TOML under `[websocket]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebsocketSettings {
+    /// Address the WebSocket and metrics servers bind to. Defaults to `127.0.0.1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind: Option<String>,
+    /// Token clients must present when connecting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// Environment variable holding the token, instead of `auth_token`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token_env: Option<String>,
+    /// PEM certificate chain. Serves `wss://` together with `tls_key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<String>,
+    /// PEM private key (PKCS#8 or RSA).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<String>,
+    /// Maximum concurrent clients. Defaults to 32.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_connections: Option<usize>,
+    /// Recent events kept for reconnecting clients (`resume_from`). Defaults to 1000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_buffer: Option<usize>,
+    /// Per-client limits for each topic, under `[websocket.max_rate]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rate: Option<TopicRates>,
+}
+
+/// Messages per second each client may receive on a topic. Unset means
+/// unlimited, except `market`, which defaults to 4.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicRates {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub market: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orders: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grid: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<f64>,
+}
+
+impl TopicRates {
+    pub fn get(&self, topic: Topic) -> Option<f64> {
+        match topic {
+            Topic::Market => self.market,
+            Topic::Orders => self.orders,
+            Topic::Summary => self.summary,
+            Topic::Grid => self.grid,
+            Topic::Errors => self.errors,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for topic in Topic::ALL {
+            if self
+                .get(topic)
+                .is_some_and(|rate| !(rate.is_finite() && rate > 0.0))
+            {
+                return Err(anyhow!(
+                    "websocket.max_rate.{} must be greater than 0.",
+                    topic.name()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills in the default market rate.
+    fn with_defaults(&self) -> Self {
+        Self {
+            market: self.market.or(Some(DEFAULT_MARKET_RATE)),
+            ..self.clone()
+        }
+    }
+}
+
+impl WebsocketSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.bind.as_deref().is_some_and(|b| b.trim().is_empty()) {
+            return Err(anyhow!("websocket.bind must not be empty."));
+        }
+        if self.auth_token.is_some() && self.auth_token_env.is_some() {
+            return Err(anyhow!(
+                "Set only one of websocket.auth_token and websocket.auth_token_env."
+            ));
+        }
+        if self.auth_token.as_deref().is_some_and(str::is_empty) {
+            return Err(anyhow!("websocket.auth_token must not be empty."));
+        }
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            return Err(anyhow!(
+                "websocket.tls_cert and websocket.tls_key must be set together."
+            ));
+        }
+        if self.max_connections == Some(0) {
+            return Err(anyhow!("websocket.max_connections must be greater than 0."));
+        }
+        if self.replay_buffer == Some(0) {
+            return Err(anyhow!("websocket.replay_buffer must be greater than 0."));
+        }
+        if let Some(max_rate) = &self.max_rate {
+            max_rate.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Token from `auth_token` or the `auth_token_env` variable.
+    pub fn resolve_auth_token(&self) -> Result<Option<String>> {
+        if let Some(var) = &self.auth_token_env {
+            let token = std::env::var(var).map_err(|_| {
+                anyhow!(
+                    "Environment variable {} for websocket.auth_token_env is not set.",
+                    var
+                )
+            })?;
+            if token.is_empty() {
+                return Err(anyhow!("Environment variable {} is empty.", var));
+            }
+            return Ok(Some(token));
+        }
+        Ok(self.auth_token.clone())
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebsocketConfig {
     pub port: u16,
     pub host: String,
+    #[serde(default, skip_serializing)]
+    pub auth_token: Option<String>,
+    #[serde(default)]
+    pub tls_cert: Option<String>,
+    #[serde(default)]
+    pub tls_key: Option<String>,
+    pub max_connections: usize,
+    pub replay_buffer: usize,
+    #[serde(default)]
+    pub max_rate: TopicRates,
+}
+
+impl WebsocketConfig {
+    /// Plain, unauthenticated server on `host:port`.
+    pub fn new(host: &str, port: u16) -> Self {
+        Self {
+            port,
+            host: host.to_string(),
+            auth_token: None,
+            tls_cert: None,
+            tls_key: None,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            replay_buffer: DEFAULT_REPLAY_BUFFER,
+            max_rate: TopicRates::default().with_defaults(),
+        }
+    }
+
+    /// Whether clients on other machines can reach the server.
+    pub fn is_public(&self) -> bool {
+        self.host != "localhost"
+            && self
+                .host
+                .parse::<IpAddr>()
+                .map_or(true, |ip| !ip.is_loopback())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub port: u16,
+    pub host: String,
+    /// The WebSocket token, required from scrapers as well.
+    #[serde(default, skip_serializing)]
+    pub auth_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BroadcastConfig {
     pub websocket: WebsocketConfig,
+    pub metrics: Option<MetricsConfig>,
 }
 
-pub fn load_broadcast_config(websocket_port: u16) -> BroadcastConfig {
-    BroadcastConfig {
+/// Fails for a public bind without a token: the feed, the REST endpoints and
+/// the metrics would be open to every host that reaches the port.
+pub fn load_broadcast_config(
+    websocket_port: u16,
+    metrics_port: Option<u16>,
+    settings: &WebsocketSettings,
+) -> Result<BroadcastConfig> {
+    let host = settings
+        .bind
+        .clone()
+        .unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string());
+    let auth_token = settings.resolve_auth_token()?;
+    if auth_token.is_none() && WebsocketConfig::new(&host, websocket_port).is_public() {
+        return Err(anyhow!(
+            "websocket.bind = \"{}\" exposes the feed, REST endpoints and metrics to other hosts. Set websocket.auth_token_env or auth_token, or bind to 127.0.0.1.",
+            host
+        ));
+    }
+    Ok(BroadcastConfig {
         websocket: WebsocketConfig {
-            port: websocket_port,
-            host: "0.0.0.0".to_string(),
+            auth_token: auth_token.clone(),
+            tls_cert: settings.tls_cert.clone(),
+            tls_key: settings.tls_key.clone(),
+            max_connections: settings.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS),
+            replay_buffer: settings.replay_buffer.unwrap_or(DEFAULT_REPLAY_BUFFER),
+            max_rate: settings
+                .max_rate
+                .clone()
+                .unwrap_or_default()
+                .with_defaults(),
+            ..WebsocketConfig::new(&host, websocket_port)
         },
+        metrics: metrics_port.map(|port| MetricsConfig {
+            port,
+            host,
+            auth_token,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_settings() {
+        let settings: WebsocketSettings = toml::from_str(
+            r#"
+bind = "127.0.0.1"
+auth_token_env = "HLBOT_TEST_WS_TOKEN"
+max_connections = 4
+replay_buffer = 200
+
+[max_rate]
+summary = 0.5
+"#,
+        )
+        .unwrap();
+        assert!(settings.validate().is_ok());
+        assert!(load_broadcast_config(8000, None, &settings).is_err());
+
+        std::env::set_var("HLBOT_TEST_WS_TOKEN", "s3cret");
+        let config = load_broadcast_config(8000, Some(9100), &settings).unwrap();
+        assert_eq!(config.websocket.host, "127.0.0.1");
+        assert_eq!(config.websocket.auth_token.as_deref(), Some("s3cret"));
+        assert_eq!(config.websocket.max_connections, 4);
+        assert_eq!(config.websocket.replay_buffer, 200);
+        assert_eq!(config.websocket.max_rate.get(Topic::Summary), Some(0.5));
+        assert_eq!(
+            config.websocket.max_rate.get(Topic::Market),
+            Some(DEFAULT_MARKET_RATE)
+        );
+        assert_eq!(config.websocket.max_rate.get(Topic::Orders), None);
+        assert!(!config.websocket.is_public());
+        assert_eq!(config.metrics.unwrap().host, "127.0.0.1");
+
+        let defaults = load_broadcast_config(8000, None, &WebsocketSettings::default()).unwrap();
+        assert_eq!(defaults.websocket.host, "127.0.0.1");
+        assert!(!defaults.websocket.is_public());
+        for (host, public) in [
+            ("0.0.0.0", true),
+            ("::", true),
+            ("::1", false),
+            ("localhost", false),
+        ] {
+            assert_eq!(
+                WebsocketConfig::new(host, 8000).is_public(),
+                public,
+                "{}",
+                host
+            );
+        }
+
+        // A public bind needs a token, which the metrics endpoint then checks too
+        let public = WebsocketSettings {
+            bind: Some("0.0.0.0".to_string()),
+            ..Default::default()
+        };
+        let err = load_broadcast_config(8000, Some(9100), &public).unwrap_err();
+        assert!(err.to_string().contains("exposes the feed"));
+        let public = WebsocketSettings {
+            auth_token: Some("s3cret".to_string()),
+            ..public
+        };
+        let config = load_broadcast_config(8000, Some(9100), &public).unwrap();
+        assert_eq!(
+            config.metrics.unwrap().auth_token.as_deref(),
+            Some("s3cret")
+        );
+        assert_eq!(defaults.websocket.max_connections, DEFAULT_MAX_CONNECTIONS);
+        assert_eq!(defaults.websocket.replay_buffer, DEFAULT_REPLAY_BUFFER);
+    }
+
+    #[test]
+    fn test_websocket_settings_validation() {
+        let half_tls = WebsocketSettings {
+            tls_cert: Some("cert.pem".to_string()),
+            ..Default::default()
+        };
+        assert!(half_tls.validate().is_err());
+
+        let two_tokens = WebsocketSettings {
+            auth_token: Some("a".to_string()),
+            auth_token_env: Some("B".to_string()),
+            ..Default::default()
+        };
+        assert!(two_tokens.validate().is_err());
+
+        let no_clients = WebsocketSettings {
+            max_connections: Some(0),
+            ..Default::default()
+        };
+        assert!(no_clients.validate().is_err());
+
+        let no_replay = WebsocketSettings {
+            replay_buffer: Some(0),
+            ..Default::default()
+        };
+        assert!(no_replay.validate().is_err());
+
+        let zero_rate = WebsocketSettings {
+            max_rate: Some(TopicRates {
+                grid: Some(0.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let err = zero_rate.validate().unwrap_err().to_string();
+        assert_eq!(err, "websocket.max_rate.grid must be greater than 0.");
     }
 }
@@ -1,5 +1,9 @@
 use crate::config::bot::BotConfig;
-use crate::config::strategy::{GridBias, GridType, PerpGridConfig, SpotGridConfig, StrategyConfig};
+use crate::config::strategy::{
+    AtrSpacingConfig, CompoundAllocation, CompoundConfig, DistributionShape, GridBias, GridType,
+    OrderSide, PerpGridConfig, SizeDistribution, SpacingMode, SpotGridConfig, StrategyConfig,
+    TwapConfig,
+};
 use anyhow::Result;
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use std::fs;
@@ -7,17 +11,17 @@ use std::fs;
 pub fn create_config() -> Result<()> {
     let theme = ColorfulTheme::default();
 
-    let strategy_types = vec!["Spot Grid", "Perp Grid"];
+    let strategy_types = vec!["Spot Grid", "Perp Grid", "TWAP"];
     let selection = Select::with_theme(&theme)
         .with_prompt("Select Strategy Type")
         .default(0)
         .items(&strategy_types)
         .interact()?;
 
-    let strategy = if selection == 0 {
-        create_spot_grid(&theme)?
-    } else {
-        create_perp_grid(&theme)?
+    let strategy = match selection {
+        0 => create_spot_grid(&theme)?,
+        1 => create_perp_grid(&theme)?,
+        _ => create_twap(&theme)?,
     };
 
     let name: String = Input::with_theme(&theme)
@@ -46,7 +50,12 @@ pub fn create_config() -> Result<()> {
         name,
         account,
         websocket_port,
+        websocket: None,
+        metrics_port: None,
         simulation: None,
+        notifications: None,
+        history: None,
+        sinks: None,
         strategy,
     };
     config.validate()?;
@@ -112,7 +121,7 @@ fn create_spot_grid(theme: &ColorfulTheme) -> Result<StrategyConfig> {
         })
         .interact_text()?;
 
-    let (grid_type, grid_count, spread_bips) = prompt_grid_spacing(theme)?;
+    let spacing = prompt_grid_spacing(theme)?;
 
     let total_investment: f64 = Input::with_theme(theme)
         .with_prompt("Total Investment (USDC)")
@@ -133,13 +142,22 @@ fn create_spot_grid(theme: &ColorfulTheme) -> Result<StrategyConfig> {
         None
     };
 
+    let size_distribution = prompt_size_distribution(theme)?;
+    let compound = prompt_compound(theme)?;
+
     Ok(StrategyConfig::SpotGrid(SpotGridConfig {
         symbol,
         grid_range_high,
         grid_range_low,
-        grid_type,
-        grid_count,
-        spread_bips,
+        grid_type: spacing.grid_type,
+        grid_count: spacing.grid_count,
+        spread_bips: spacing.spread_bips,
+        spacing_mode: spacing.spacing_mode,
+        atr: spacing.atr,
+        levels: None,
+        ranges: None,
+        size_distribution,
+        compound,
         total_investment,
         trigger_price,
     }))
@@ -181,7 +199,7 @@ fn create_perp_grid(theme: &ColorfulTheme) -> Result<StrategyConfig> {
         })
         .interact_text()?;
 
-    let (grid_type, grid_count, spread_bips) = prompt_grid_spacing(theme)?;
+    let spacing = prompt_grid_spacing(theme)?;
 
     let total_investment: f64 = Input::with_theme(theme)
         .with_prompt("Total Investment (USDC)")
@@ -215,23 +233,134 @@ fn create_perp_grid(theme: &ColorfulTheme) -> Result<StrategyConfig> {
         None
     };
 
+    let size_distribution = prompt_size_distribution(theme)?;
+    let compound = prompt_compound(theme)?;
+
     Ok(StrategyConfig::PerpGrid(PerpGridConfig {
         symbol,
         leverage,
         is_isolated,
         grid_range_low,
         grid_range_high,
-        grid_type,
-        grid_count,
-        spread_bips,
+        grid_type: spacing.grid_type,
+        grid_count: spacing.grid_count,
+        spread_bips: spacing.spread_bips,
+        spacing_mode: spacing.spacing_mode,
+        atr: spacing.atr,
+        levels: None,
+        ranges: None,
+        size_distribution,
+        compound,
         total_investment,
         grid_bias,
         trigger_price,
     }))
 }
 
-fn prompt_grid_spacing(theme: &ColorfulTheme) -> Result<(GridType, Option<u32>, Option<f64>)> {
-    let spacing_modes = vec!["Grid Count", "Spread (bips)"];
+fn create_twap(theme: &ColorfulTheme) -> Result<StrategyConfig> {
+    let symbol: String = Input::with_theme(theme)
+        .with_prompt("Symbol (e.g., HYPE/USDC for spot, BTC for perp)")
+        .interact_text()?;
+
+    let sides = vec!["Buy", "Sell"];
+    let side_sel = Select::with_theme(theme)
+        .with_prompt("Side")
+        .default(0)
+        .items(&sides)
+        .interact()?;
+    let side = if side_sel == 0 {
+        OrderSide::Buy
+    } else {
+        OrderSide::Sell
+    };
+
+    let target_size: f64 = Input::with_theme(theme)
+        .with_prompt("Target Size (base asset)")
+        .validate_with(|input: &f64| -> Result<(), &str> {
+            if *input > 0.0 {
+                Ok(())
+            } else {
+                Err("Target size must be greater than 0")
+            }
+        })
+        .interact_text()?;
+
+    let duration_secs: u64 = Input::with_theme(theme)
+        .with_prompt("Duration (seconds)")
+        .interact_text()?;
+
+    let slice_count: u32 = Input::with_theme(theme)
+        .with_prompt("Number of Slices")
+        .interact_text()?;
+
+    let randomize_pct: f64 = Input::with_theme(theme)
+        .with_prompt("Timing Jitter (%)")
+        .default(0.0)
+        .interact_text()?;
+
+    let has_limit = Confirm::with_theme(theme)
+        .with_prompt("Set a Price Limit?")
+        .default(false)
+        .interact()?;
+
+    let price_limit = if has_limit {
+        Some(
+            Input::with_theme(theme)
+                .with_prompt("Price Limit")
+                .interact_text()?,
+        )
+    } else {
+        None
+    };
+
+    let has_participation = Confirm::with_theme(theme)
+        .with_prompt("Cap participation in market volume?")
+        .default(false)
+        .interact()?;
+
+    let max_participation_pct = if has_participation {
+        Some(
+            Input::with_theme(theme)
+                .with_prompt("Max Participation (%)")
+                .interact_text()?,
+        )
+    } else {
+        None
+    };
+
+    let reduce_only = if symbol.contains('/') {
+        false
+    } else {
+        Confirm::with_theme(theme)
+            .with_prompt("Reduce Only?")
+            .default(false)
+            .interact()?
+    };
+
+    Ok(StrategyConfig::Twap(TwapConfig {
+        symbol,
+        side,
+        target_size,
+        duration_secs: Some(duration_secs),
+        slice_count: Some(slice_count),
+        randomize_pct,
+        price_limit,
+        max_participation_pct,
+        slice_timeout_secs: None,
+        reduce_only,
+    }))
+}
+
+struct GridSpacing {
+    grid_type: GridType,
+    grid_count: Option<u32>,
+    spread_bips: Option<f64>,
+    spacing_mode: SpacingMode,
+    atr: AtrSpacingConfig,
+}
+
+fn prompt_grid_spacing(theme: &ColorfulTheme) -> Result<GridSpacing> {
+    let spacing_modes = vec!["Grid Count", "Spread (bips)", "ATR (volatility)"];
     let spacing_sel = Select::with_theme(theme)
         .with_prompt("Grid Spacing Mode")
         .default(0)
@@ -256,8 +385,14 @@ fn prompt_grid_spacing(theme: &ColorfulTheme) -> Result<(GridType, Option<u32>,
             .with_prompt("Grid Count")
             .interact_text()?;
 
-        Ok((grid_type, Some(grid_count), None))
-    } else {
+        Ok(GridSpacing {
+            grid_type,
+            grid_count: Some(grid_count),
+            spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+        })
+    } else if spacing_sel == 1 {
         let spread_bips: f64 = Input::with_theme(theme)
             .with_prompt("Spread (bips)")
             .validate_with(|input: &f64| -> Result<(), &str> {
@@ -269,10 +404,132 @@ fn prompt_grid_spacing(theme: &ColorfulTheme) -> Result<(GridType, Option<u32>,
             })
             .interact_text()?;
 
-        Ok((GridType::Geometric, None, Some(spread_bips)))
+        Ok(GridSpacing {
+            grid_type: GridType::Geometric,
+            grid_count: None,
+            spread_bips: Some(spread_bips),
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+        })
+    } else {
+        let mut atr = AtrSpacingConfig::default();
+        atr.candle_interval = Input::with_theme(theme)
+            .with_prompt("Candle Interval (e.g. 15m, 1h, 1d)")
+            .default(atr.candle_interval)
+            .validate_with(|input: &String| -> Result<(), &str> {
+                let probe = AtrSpacingConfig {
+                    candle_interval: input.clone(),
+                    ..AtrSpacingConfig::default()
+                };
+                probe
+                    .candle_duration()
+                    .map(|_| ())
+                    .ok_or("Unsupported candle interval")
+            })
+            .interact_text()?;
+        atr.lookback = Input::with_theme(theme)
+            .with_prompt("ATR Lookback (candles)")
+            .default(atr.lookback)
+            .interact_text()?;
+        atr.multiplier = Input::with_theme(theme)
+            .with_prompt("Spacing (x ATR)")
+            .default(atr.multiplier)
+            .interact_text()?;
+
+        let recompute = Confirm::with_theme(theme)
+            .with_prompt("Re-space idle zones as volatility changes?")
+            .default(false)
+            .interact()?;
+        if recompute {
+            atr.recompute_secs = Some(
+                Input::with_theme(theme)
+                    .with_prompt("Recompute Interval (seconds)")
+                    .default(3600u64)
+                    .interact_text()?,
+            );
+        }
+
+        Ok(GridSpacing {
+            grid_type: GridType::Geometric,
+            grid_count: None,
+            spread_bips: None,
+            spacing_mode: SpacingMode::Atr,
+            atr,
+        })
     }
 }
 
+/// Preset shapes only; explicit per-zone weights are set in the TOML file.
+fn prompt_size_distribution(theme: &ColorfulTheme) -> Result<SizeDistribution> {
+    let shapes = vec![
+        "Flat (equal size per zone)",
+        "Linear (grows away from entry)",
+        "Geometric (grows away from entry)",
+        "Pyramid (grows away from entry)",
+    ];
+    let shape_sel = Select::with_theme(theme)
+        .with_prompt("Size Distribution")
+        .default(0)
+        .items(&shapes)
+        .interact()?;
+
+    let shape = match shape_sel {
+        1 => DistributionShape::Linear,
+        2 => DistributionShape::Geometric,
+        3 => DistributionShape::Pyramid,
+        _ => DistributionShape::Flat,
+    };
+    Ok(SizeDistribution::Shape(shape))
+}
+
+fn prompt_compound(theme: &ColorfulTheme) -> Result<Option<CompoundConfig>> {
+    let modes = vec![
+        "Off",
+        "Proportional (all waiting zones)",
+        "Nearest zones to price",
+    ];
+    let mode_sel = Select::with_theme(theme)
+        .with_prompt("Compound Profits")
+        .default(0)
+        .items(&modes)
+        .interact()?;
+
+    let mut compound = CompoundConfig::default();
+    match mode_sel {
+        1 => compound.allocation = CompoundAllocation::Proportional,
+        2 => {
+            compound.allocation = CompoundAllocation::Nearest;
+            compound.nearest_zones = Input::with_theme(theme)
+                .with_prompt("Zones to Grow")
+                .default(compound.nearest_zones)
+                .validate_with(|input: &usize| -> Result<(), &str> {
+                    if *input > 0 {
+                        Ok(())
+                    } else {
+                        Err("At least one zone")
+                    }
+                })
+                .interact_text()?;
+        }
+        _ => return Ok(None),
+    }
+
+    let scheduled = Confirm::with_theme(theme)
+        .with_prompt("Reinvest on a schedule instead of every roundtrip?")
+        .default(false)
+        .interact()?;
+    if scheduled {
+        compound.interval_secs = Some(
+            Input::with_theme(theme)
+                .with_prompt("Reinvest Interval (seconds)")
+                .default(3600u64)
+                .interact_text()?,
+        );
+    }
+
+    Ok(Some(compound))
+}
+
 fn generate_default_filename(strategy: &StrategyConfig) -> String {
     match strategy {
         StrategyConfig::SpotGrid(SpotGridConfig {
@@ -281,6 +538,7 @@ fn generate_default_filename(strategy: &StrategyConfig) -> String {
             grid_range_low,
             grid_type,
             spread_bips,
+            spacing_mode,
             ..
         }) => {
             // Extract asset name (e.g., "ETH" from "ETH/USDC")
@@ -288,7 +546,9 @@ fn generate_default_filename(strategy: &StrategyConfig) -> String {
             format!(
                 "{}_Spot_{}_{}_{}.toml",
                 asset,
-                if spread_bips.is_some() {
+                if *spacing_mode == SpacingMode::Atr {
+                    "ATR".to_string()
+                } else if spread_bips.is_some() {
                     "Spread".to_string()
                 } else {
                     format!("{:?}", grid_type)
@@ -310,5 +570,25 @@ fn generate_default_filename(strategy: &StrategyConfig) -> String {
                 symbol, grid_bias, leverage, grid_range_low, grid_range_high
             )
         }
+        StrategyConfig::Twap(TwapConfig {
+            symbol,
+            side,
+            target_size,
+            ..
+        }) => {
+            let asset = symbol.split('/').next().unwrap_or(symbol);
+            format!("{}_TWAP_{}_{}.toml", asset, side, target_size)
+        }
+        StrategyConfig::Rebalance(c) => {
+            let assets: Vec<&str> = c.targets.keys().map(String::as_str).collect();
+            format!("Rebalance_{}.toml", assets.join("_"))
+        }
+        StrategyConfig::InfinityGrid(c) => {
+            let asset = c.symbol.split('/').next().unwrap_or(&c.symbol);
+            format!(
+                "{}_Infinity_{}_{}bips.toml",
+                asset, c.grid_range_low, c.spread_bips
+            )
+        }
     }
 }
@@ -1,10 +1,17 @@
+use crate::config::signer::SignerEndpoint;
 use anyhow::{anyhow, Context, Result};
+use dialoguer::{theme::ColorfulTheme, Password};
+use ethers::signers::LocalWallet;
 use ethers::types::H160;
+use ethers::utils::{hex, to_checksum};
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::str::FromStr;
 
 const DEFAULT_ACCOUNTS_DIR: &str = "hyperliquid";
@@ -15,12 +22,180 @@ pub struct AccountsConfig {
     pub accounts: HashMap<String, AccountProfile>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// One account in `accounts.toml`. The API wallet key comes from exactly one
+/// of `api_wallet_private_key`, `api_wallet_private_key_env`,
+/// `api_wallet_private_key_command` and `api_wallet_keystore`, or stays with
+/// the remote signer named by `api_wallet_signer`.
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct AccountProfile {
     pub network: String,
     pub master_account_address: String,
     pub sub_account_address: Option<String>,
-    pub api_wallet_private_key: String,
+    /// Plaintext key. Prefer one of the other sources on shared hosts.
+    pub api_wallet_private_key: Option<String>,
+    /// Environment variable holding the key.
+    pub api_wallet_private_key_env: Option<String>,
+    /// Command printing the key as the first line of its output,
+    /// e.g. `["pass", "show", "hyperliquid/api"]`.
+    pub api_wallet_private_key_command: Option<Vec<String>>,
+    /// Encrypted JSON keystore (Web3 Secret Storage) holding the key.
+    pub api_wallet_keystore: Option<String>,
+    /// File descriptor the keystore passphrase is read from, e.g. `3` with
+    /// `3< passphrase.txt`. Without it the passphrase is prompted for on the terminal.
+    pub keystore_passphrase_fd: Option<u32>,
+    /// Remote signer holding the key, `unix:/path/to/signer.sock` or
+    /// `http://127.0.0.1:<port>`. The bot then never loads the key.
+    pub api_wallet_signer: Option<String>,
+}
+
+/// Where an account's API wallet key is loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum KeySource<'a> {
+    Plaintext(&'a str),
+    Env(&'a str),
+    Command(&'a [String]),
+    Keystore {
+        path: &'a str,
+        passphrase_fd: Option<u32>,
+    },
+    Signer(&'a str),
+}
+
+impl AccountProfile {
+    pub(crate) fn key_source(&self, account_name: &str) -> Result<KeySource<'_>> {
+        let mut sources = Vec::new();
+        if let Some(key) = &self.api_wallet_private_key {
+            sources.push(KeySource::Plaintext(key));
+        }
+        if let Some(var) = &self.api_wallet_private_key_env {
+            sources.push(KeySource::Env(var));
+        }
+        if let Some(command) = &self.api_wallet_private_key_command {
+            sources.push(KeySource::Command(command));
+        }
+        if let Some(path) = &self.api_wallet_keystore {
+            sources.push(KeySource::Keystore {
+                path,
+                passphrase_fd: self.keystore_passphrase_fd,
+            });
+        }
+        if let Some(endpoint) = &self.api_wallet_signer {
+            sources.push(KeySource::Signer(endpoint));
+        }
+        if self.keystore_passphrase_fd.is_some() && self.api_wallet_keystore.is_none() {
+            return Err(anyhow!(
+                "keystore_passphrase_fd for account '{}' needs api_wallet_keystore",
+                account_name
+            ));
+        }
+        match sources.len() {
+            1 => Ok(sources.remove(0)),
+            0 => Err(anyhow!(
+                "Account '{}' has no API wallet key. Set api_wallet_keystore, api_wallet_private_key_command, api_wallet_private_key_env, api_wallet_private_key or api_wallet_signer.",
+                account_name
+            )),
+            _ => Err(anyhow!(
+                "Set only one of api_wallet_private_key, api_wallet_private_key_env, api_wallet_private_key_command, api_wallet_keystore and api_wallet_signer for account '{}'",
+                account_name
+            )),
+        }
+    }
+}
+
+impl KeySource<'_> {
+    /// Loads the key as a hex string.
+    fn resolve(&self, account_name: &str) -> Result<String> {
+        let key = match self {
+            KeySource::Plaintext(key) => {
+                warn!(
+                    "Account '{}' stores its API wallet key in plaintext. Consider api_wallet_keystore, api_wallet_private_key_command or api_wallet_private_key_env.",
+                    account_name
+                );
+                key.to_string()
+            }
+            KeySource::Env(var) => env::var(var).map_err(|_| {
+                anyhow!(
+                    "Environment variable {} for api_wallet_private_key_env is not set",
+                    var
+                )
+            })?,
+            KeySource::Command(command) => run_key_command(command)?,
+            KeySource::Keystore {
+                path,
+                passphrase_fd,
+            } => {
+                let passphrase = match passphrase_fd {
+                    Some(fd) => read_passphrase_fd(*fd)?,
+                    None => prompt_passphrase(&format!(
+                        "Passphrase for keystore {} (account '{}')",
+                        path, account_name
+                    ))?,
+                };
+                let wallet = LocalWallet::decrypt_keystore(path, passphrase)
+                    .map_err(|e| anyhow!("Failed to decrypt keystore {}: {}", path, e))?;
+                format!("0x{}", hex::encode(wallet.signer().to_bytes()))
+            }
+            KeySource::Signer(_) => {
+                return Err(anyhow!(
+                    "Account '{}' keeps its API wallet key in a remote signer",
+                    account_name
+                ))
+            }
+        };
+        let key = key.trim().to_string();
+        if key.is_empty() {
+            return Err(anyhow!(
+                "API wallet key for account '{}' is empty",
+                account_name
+            ));
+        }
+        Ok(key)
+    }
+}
+
+/// Runs `command` and returns the first line of its output, the convention of
+/// `pass` and `gopass`.
+fn run_key_command(command: &[String]) -> Result<String> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow!("api_wallet_private_key_command must not be empty"))?;
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run key command '{}'", program))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Key command '{}' failed with {}",
+            program,
+            output.status
+        ));
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow!("Key command '{}' printed invalid UTF-8", program))?;
+    Ok(stdout.lines().next().unwrap_or_default().to_string())
+}
+
+/// First line read from an inherited file descriptor.
+fn read_passphrase_fd(fd: u32) -> Result<String> {
+    let path = format!("/dev/fd/{}", fd);
+    let file = File::open(&path)
+        .with_context(|| format!("Failed to open keystore passphrase fd {}", fd))?;
+    let mut line = String::new();
+    BufReader::new(file)
+        .read_line(&mut line)
+        .with_context(|| format!("Failed to read keystore passphrase from fd {}", fd))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Reads a passphrase from the terminal without echoing it.
+fn prompt_passphrase(prompt: &str) -> Result<String> {
+    Password::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty_password(true)
+        .interact()
+        .context("No terminal to prompt for the keystore passphrase. Set keystore_passphrase_fd.")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,7 +204,10 @@ pub struct ExchangeConfig {
     pub network: String,
     pub master_account_address: String,
     pub sub_account_address: Option<String>,
-    pub api_wallet_private_key: String,
+    /// Hex key of the API wallet, unless a remote signer holds it.
+    pub api_wallet_private_key: Option<String>,
+    /// Endpoint of the `signer` process holding the key.
+    pub api_wallet_signer: Option<String>,
 }
 
 impl ExchangeConfig {
@@ -44,12 +222,25 @@ impl ExchangeConfig {
     }
 }
 
-fn validate_address(label: &str, address: &str) -> Result<()> {
-    H160::from_str(address).map_err(|e| anyhow!("Invalid {} '{}': {}", label, address, e))?;
+/// Parses `address` and, when it is written in mixed case, checks its EIP-55
+/// checksum, which catches most mistyped characters.
+pub(crate) fn validate_address(label: &str, address: &str) -> Result<()> {
+    let parsed =
+        H160::from_str(address).map_err(|e| anyhow!("Invalid {} '{}': {}", label, address, e))?;
+    let digits = address.trim_start_matches("0x");
+    let mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+        && digits.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && to_checksum(&parsed, None)[2..] != *digits {
+        return Err(anyhow!(
+            "Invalid {} '{}': checksum mismatch, the address has a typo",
+            label,
+            address
+        ));
+    }
     Ok(())
 }
 
-fn validate_network(network: &str) -> Result<String> {
+pub(crate) fn validate_network(network: &str) -> Result<String> {
     let normalized = network.trim().to_lowercase();
     match normalized.as_str() {
         "mainnet" | "testnet" => Ok(normalized),
@@ -103,14 +294,13 @@ pub fn load_exchange_config(
         validate_address("sub_account_address", sub_account)?;
     }
 
-    let api_wallet_private_key = profile.api_wallet_private_key.trim().to_string();
-
-    if api_wallet_private_key.is_empty() {
-        return Err(anyhow!(
-            "api_wallet_private_key for account '{}' is empty",
-            account_name
-        ));
-    }
+    let (api_wallet_private_key, api_wallet_signer) = match profile.key_source(account_name)? {
+        KeySource::Signer(endpoint) => {
+            SignerEndpoint::parse(endpoint)?;
+            (None, Some(endpoint.to_string()))
+        }
+        source => (Some(source.resolve(account_name)?), None),
+    };
 
     Ok(ExchangeConfig {
         account_name: account_name.to_string(),
@@ -118,6 +308,7 @@ pub fn load_exchange_config(
         master_account_address: profile.master_account_address.clone(),
         sub_account_address: profile.sub_account_address.clone(),
         api_wallet_private_key,
+        api_wallet_signer,
     })
 }
 
@@ -157,6 +348,128 @@ api_wallet_private_key = "0xmainnet-secret"
             cfg.vault_address(),
             Some("0x2222222222222222222222222222222222222222")
         );
-        assert_eq!(cfg.api_wallet_private_key, "0xmainnet-secret");
+        assert_eq!(
+            cfg.api_wallet_private_key.as_deref(),
+            Some("0xmainnet-secret")
+        );
+    }
+
+    #[test]
+    fn validate_address_checks_checksum() {
+        let address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(validate_address("master_account_address", address).is_ok());
+        assert!(validate_address("master_account_address", &address.to_lowercase()).is_ok());
+        // One character changed keeps the address well-formed but breaks its checksum
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAee";
+        let err = validate_address("master_account_address", typo).unwrap_err();
+        assert!(err.to_string().contains("checksum"));
+        assert!(validate_address("master_account_address", "0x5aAeb6053F").is_err());
+    }
+
+    fn write_accounts(dir: &std::path::Path, key_lines: &str) -> String {
+        let accounts_path = dir.join("accounts.toml");
+        let accounts_toml = format!(
+            "[accounts.account1]\nnetwork = \"testnet\"\nmaster_account_address = \"0x1111111111111111111111111111111111111111\"\n{}\n",
+            key_lines
+        );
+        fs::write(&accounts_path, accounts_toml).unwrap();
+        accounts_path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn load_exchange_config_resolves_key_sources() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        let dir = tempdir().unwrap();
+
+        env::set_var("HLBOT_TEST_API_KEY", " 0xfrom-env\n");
+        let path = write_accounts(
+            dir.path(),
+            "api_wallet_private_key_env = \"HLBOT_TEST_API_KEY\"",
+        );
+        let cfg = load_exchange_config("account1", Some(&path)).unwrap();
+        assert_eq!(cfg.api_wallet_private_key.as_deref(), Some("0xfrom-env"));
+        env::remove_var("HLBOT_TEST_API_KEY");
+        assert!(load_exchange_config("account1", Some(&path)).is_err());
+
+        // Only the first line counts, like `pass show`
+        let path = write_accounts(
+            dir.path(),
+            "api_wallet_private_key_command = [\"sh\", \"-c\", \"echo 0xfrom-command; echo user: bot\"]",
+        );
+        let cfg = load_exchange_config("account1", Some(&path)).unwrap();
+        assert_eq!(
+            cfg.api_wallet_private_key.as_deref(),
+            Some("0xfrom-command")
+        );
+
+        let path = write_accounts(dir.path(), "api_wallet_private_key_command = [\"false\"]");
+        assert!(load_exchange_config("account1", Some(&path)).is_err());
+
+        let path = write_accounts(
+            dir.path(),
+            "api_wallet_private_key = \"0xplain\"\napi_wallet_private_key_env = \"HLBOT_TEST_API_KEY\"",
+        );
+        let err = load_exchange_config("account1", Some(&path)).unwrap_err();
+        assert!(err.to_string().contains("Set only one of"));
+
+        let path = write_accounts(dir.path(), "");
+        let err = load_exchange_config("account1", Some(&path)).unwrap_err();
+        assert!(err.to_string().contains("has no API wallet key"));
+
+        // A remote signer keeps the key out of the bot entirely
+        let path = write_accounts(
+            dir.path(),
+            "api_wallet_signer = \"unix:/run/hlbot/signer.sock\"",
+        );
+        let cfg = load_exchange_config("account1", Some(&path)).unwrap();
+        assert_eq!(cfg.api_wallet_private_key, None);
+        assert_eq!(
+            cfg.api_wallet_signer.as_deref(),
+            Some("unix:/run/hlbot/signer.sock")
+        );
+        let path = write_accounts(dir.path(), "api_wallet_signer = \"http://10.0.0.5:8300\"");
+        assert!(load_exchange_config("account1", Some(&path)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_exchange_config_decrypts_keystore() {
+        use std::os::unix::io::AsRawFd;
+
+        let dir = tempdir().unwrap();
+        let secret = [7u8; 32];
+        let mut rng = ethers::core::rand::thread_rng();
+        LocalWallet::encrypt_keystore(dir.path(), &mut rng, secret, "hunter2", Some("api.json"))
+            .unwrap();
+        let passphrase_path = dir.path().join("passphrase");
+        fs::write(&passphrase_path, "hunter2\n").unwrap();
+        let passphrase = File::open(&passphrase_path).unwrap();
+
+        let path = write_accounts(
+            dir.path(),
+            &format!(
+                "api_wallet_keystore = \"{}\"\nkeystore_passphrase_fd = {}",
+                dir.path().join("api.json").display(),
+                passphrase.as_raw_fd()
+            ),
+        );
+        let cfg = load_exchange_config("account1", Some(&path)).unwrap();
+        assert_eq!(
+            cfg.api_wallet_private_key,
+            Some(format!("0x{}", hex::encode(secret)))
+        );
+
+        fs::write(&passphrase_path, "wrong\n").unwrap();
+        let passphrase = File::open(&passphrase_path).unwrap();
+        let path = write_accounts(
+            dir.path(),
+            &format!(
+                "api_wallet_keystore = \"{}\"\nkeystore_passphrase_fd = {}",
+                dir.path().join("api.json").display(),
+                passphrase.as_raw_fd()
+            ),
+        );
+        let err = load_exchange_config("account1", Some(&path)).unwrap_err();
+        assert!(err.to_string().contains("Failed to decrypt keystore"));
     }
 }
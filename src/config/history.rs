@@ -0,0 +1,80 @@
+use crate::config::file_stem;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Directory for history files when `file` is not set.
+pub const DEFAULT_HISTORY_DIR: &str = "data/history";
+
+/// Equity history sampling.
+///
+/// This block lives inside the main bot TOML under `[history]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Seconds between samples.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// JSON file the series is saved to. Defaults to `data/history/<bot name>.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_secs: default_interval_secs(),
+            file: None,
+        }
+    }
+}
+
+impl HistoryConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_secs == 0 {
+            return Err(anyhow!("history.interval_secs must be greater than 0."));
+        }
+        if self.file.as_deref().is_some_and(|f| f.trim().is_empty()) {
+            return Err(anyhow!("history.file must not be empty."));
+        }
+        Ok(())
+    }
+
+    /// `file`, or a file named after the bot in `data/history`.
+    pub fn file_for(&self, bot_name: &str) -> String {
+        self.file
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}.json", DEFAULT_HISTORY_DIR, file_stem(bot_name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_file_name() {
+        let config: HistoryConfig = toml::from_str("interval_secs = 15").unwrap();
+        assert!(config.enabled);
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.file_for("hype grid/1"),
+            "data/history/hype_grid_1.json"
+        );
+
+        let zero = HistoryConfig {
+            interval_secs: 0,
+            ..Default::default()
+        };
+        assert!(zero.validate().is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::broadcast::WebsocketSettings;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+pub const DEFAULT_HUB_PORT: u16 = 8100;
+
+/// Dashboard hub: the bots to follow and the server to re-serve them on.
+///
+/// Loaded from its own TOML file by `hub <file>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubConfig {
+    /// Port of the hub's WebSocket server. Defaults to 8100.
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Bind address, token, TLS and limits of the hub's own server, as in a bot config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket: Option<WebsocketSettings>,
+    pub bots: Vec<HubBotConfig>,
+}
+
+/// One upstream bot, under `[[bots]]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubBotConfig {
+    /// Identifier put on every event from this bot.
+    pub name: String,
+    /// WebSocket URL of the bot, e.g. `ws://127.0.0.1:8001`.
+    pub url: String,
+    /// Token of the bot's `[websocket]` server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// Environment variable holding the token, instead of `auth_token`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token_env: Option<String>,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_HUB_PORT
+}
+
+impl HubConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.bots.is_empty() {
+            return Err(anyhow!("A hub needs at least one [[bots]] entry."));
+        }
+        let mut names = HashSet::new();
+        for bot in &self.bots {
+            bot.validate()?;
+            if !names.insert(bot.name.as_str()) {
+                return Err(anyhow!("Duplicate bot name '{}'.", bot.name));
+            }
+        }
+        if let Some(websocket) = &self.websocket {
+            websocket.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn websocket_settings(&self) -> WebsocketSettings {
+        self.websocket.clone().unwrap_or_default()
+    }
+}
+
+impl HubBotConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("bots.name must not be empty."));
+        }
+        if !self.url.starts_with("ws://") {
+            return Err(anyhow!(
+                "bots.url of '{}' must start with ws:// (wss:// upstreams are not supported).",
+                self.name
+            ));
+        }
+        if self.auth_token.is_some() && self.auth_token_env.is_some() {
+            return Err(anyhow!(
+                "Set only one of auth_token and auth_token_env for bot '{}'.",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Token from `auth_token` or the `auth_token_env` variable.
+    pub fn resolve_auth_token(&self) -> Result<Option<String>> {
+        if let Some(var) = &self.auth_token_env {
+            let token = std::env::var(var).map_err(|_| {
+                anyhow!(
+                    "Environment variable {} for bot '{}' is not set.",
+                    var,
+                    self.name
+                )
+            })?;
+            return Ok(Some(token));
+        }
+        Ok(self.auth_token.clone())
+    }
+}
+
+pub fn load_hub_config(path: &str) -> Result<HubConfig> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let config: HubConfig =
+        toml::from_str(&content).with_context(|| format!("Invalid hub config {}", path))?;
+    config.validate()?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hub_config() {
+        let config: HubConfig = toml::from_str(
+            r#"
+[websocket]
+bind = "127.0.0.1"
+
+[[bots]]
+name = "btc-perp"
+url = "ws://127.0.0.1:8001"
+auth_token = "s3cret"
+
+[[bots]]
+name = "hype-spot"
+url = "ws://127.0.0.1:8000"
+"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.port, DEFAULT_HUB_PORT);
+        assert_eq!(
+            config.websocket_settings().bind.as_deref(),
+            Some("127.0.0.1")
+        );
+        assert_eq!(
+            config.bots[0].resolve_auth_token().unwrap().as_deref(),
+            Some("s3cret")
+        );
+
+        let mut duplicate = config.clone();
+        duplicate.bots[1].name = "btc-perp".to_string();
+        assert!(duplicate.validate().is_err());
+
+        let mut secure = config.clone();
+        secure.bots[0].url = "wss://bots.example.com".to_string();
+        assert!(secure.validate().is_err());
+
+        let empty = HubConfig {
+            bots: Vec::new(),
+            ..config
+        };
+        assert!(empty.validate().is_err());
+    }
+}
@@ -2,11 +2,18 @@ use self::bot::BotConfig;
 use crate::error::BotError;
 use std::fs;
 
+pub mod accounts;
 pub mod bot;
 pub mod broadcast;
 pub mod creator;
 pub mod exchange;
+pub mod history;
+pub mod hub;
+pub mod notification;
+pub mod reload;
+pub mod signer;
 pub mod simulation;
+pub mod sink;
 pub mod strategy;
 
 pub fn load_bot_config(path: &str) -> Result<BotConfig, BotError> {
@@ -17,3 +24,17 @@ pub fn load_bot_config(path: &str) -> Result<BotConfig, BotError> {
         .map_err(|e| BotError::ValidationError(e.to_string()))?;
     Ok(config)
 }
+
+/// Bot name reduced to characters that are safe in a file name.
+pub(crate) fn file_stem(bot_name: &str) -> String {
+    bot_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
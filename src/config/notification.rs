@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Alert types a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    Fill,
+    Error,
+    CircuitBreak,
+    RangeExit,
+    DailySummary,
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertKind::Fill => write!(f, "fill"),
+            AlertKind::Error => write!(f, "error"),
+            AlertKind::CircuitBreak => write!(f, "circuit_break"),
+            AlertKind::RangeExit => write!(f, "range_exit"),
+            AlertKind::DailySummary => write!(f, "daily_summary"),
+        }
+    }
+}
+
+/// Payload shape sent to a webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{"bot", "event", "symbol", "title", "message", "timestamp_ms"}`
+    #[default]
+    Generic,
+    /// `{"text"}` for Slack incoming webhooks
+    Slack,
+    /// `{"content"}` for Discord webhooks
+    Discord,
+    /// `{"chat_id", "text"}` for the Telegram Bot API `sendMessage` endpoint
+    Telegram,
+}
+
+/// One webhook target.
+///
+/// Lives in the bot TOML as `[[notifications.webhooks]]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Chat to post to, required for `format = "telegram"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_id: Option<String>,
+    /// Custom JSON body. Overrides `format`. Placeholders: `{{bot}}`, `{{event}}`,
+    /// `{{symbol}}`, `{{title}}`, `{{message}}`, `{{text}}`, `{{timestamp_ms}}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Alert types routed to this webhook. Empty means all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<AlertKind>,
+    /// Maximum alerts per minute for each alert type. Extra alerts are dropped.
+    #[serde(default = "default_max_per_minute")]
+    pub max_per_minute: u32,
+}
+
+fn default_max_per_minute() -> u32 {
+    10
+}
+
+impl WebhookConfig {
+    pub fn accepts(&self, kind: AlertKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            return Err(anyhow!(
+                "Webhook url '{}' must start with http:// or https://.",
+                self.url
+            ));
+        }
+        if self.max_per_minute == 0 {
+            return Err(anyhow!("Webhook max_per_minute must be greater than 0."));
+        }
+        if self.template.is_none()
+            && self.format == WebhookFormat::Telegram
+            && self.chat_id.is_none()
+        {
+            return Err(anyhow!("Telegram webhooks require chat_id."));
+        }
+        Ok(())
+    }
+}
+
+/// Outbound alert settings.
+///
+/// This block lives inside the main bot TOML under `[notifications]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+impl NotificationConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.webhooks.iter().try_for_each(WebhookConfig::validate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_webhooks() {
+        let toml = r#"
+[[webhooks]]
+url = "https://hooks.slack.com/services/T000/B000/XXX"
+format = "slack"
+events = ["error", "circuit_break"]
+
+[[webhooks]]
+url = "https://api.telegram.org/bot123:abc/sendMessage"
+format = "telegram"
+chat_id = "-1001234"
+max_per_minute = 2
+"#;
+        let config: NotificationConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+
+        let slack = &config.webhooks[0];
+        assert_eq!(slack.format, WebhookFormat::Slack);
+        assert_eq!(slack.max_per_minute, 10);
+        assert!(slack.accepts(AlertKind::Error));
+        assert!(!slack.accepts(AlertKind::Fill));
+        assert!(config.webhooks[1].accepts(AlertKind::DailySummary));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_webhooks() {
+        let webhook = WebhookConfig {
+            url: "https://example.com/hook".to_string(),
+            format: WebhookFormat::Telegram,
+            chat_id: None,
+            template: None,
+            events: Vec::new(),
+            max_per_minute: 10,
+        };
+        let err = webhook.validate().unwrap_err().to_string();
+        assert_eq!(err, "Telegram webhooks require chat_id.");
+
+        let bad_url = WebhookConfig {
+            url: "example.com".to_string(),
+            format: WebhookFormat::Generic,
+            ..webhook.clone()
+        };
+        assert!(bad_url.validate().is_err());
+
+        let no_rate = WebhookConfig {
+            format: WebhookFormat::Generic,
+            max_per_minute: 0,
+            ..webhook.clone()
+        };
+        assert!(no_rate.validate().is_err());
+
+        let no_chat_id_with_template = WebhookConfig {
+            template: Some("{\"text\": \"{{text}}\"}".to_string()),
+            ..webhook
+        };
+        assert!(no_chat_id_with_template.validate().is_ok());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::config::strategy::StrategyConfig;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// One strategy field that differs between the running and the reloaded config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Lists the fields `new` changes relative to the running `old` config.
+///
+/// Only changes a strategy can apply without restarting are accepted: extending
+/// a grid range, moving the trigger price, topping up `total_investment` and
+/// the TWAP risk limits. Anything else, including a different strategy type or
+/// symbol, is rejected with every offending field named.
+pub fn diff_strategy(old: &StrategyConfig, new: &StrategyConfig) -> Result<Vec<ConfigChange>> {
+    if std::mem::discriminant(old) != std::mem::discriminant(new) {
+        return Err(anyhow!(
+            "Strategy type changed from {} to {}; restart the bot to switch strategies",
+            old.type_name(),
+            new.type_name()
+        ));
+    }
+    if old.markets() != new.markets() {
+        return Err(anyhow!(
+            "Markets changed from {} to {}; restart the bot to trade other markets",
+            old.markets().join(", "),
+            new.markets().join(", ")
+        ));
+    }
+
+    let old_value = serde_json::to_value(old)?;
+    let new_value = serde_json::to_value(new)?;
+    let kind = old_value["type"].as_str().unwrap_or_default();
+    let fields: BTreeSet<&String> = [&old_value, &new_value]
+        .into_iter()
+        .filter_map(Value::as_object)
+        .flat_map(|fields| fields.keys())
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut rejected = Vec::new();
+    for field in fields {
+        let old = old_value.get(field).cloned().unwrap_or(Value::Null);
+        let new = new_value.get(field).cloned().unwrap_or(Value::Null);
+        if old == new {
+            continue;
+        }
+        let change = ConfigChange {
+            field: field.clone(),
+            old,
+            new,
+        };
+        if let Some(reason) = rejection(kind, &change) {
+            rejected.push(format!("{} ({})", field, reason));
+        }
+        changes.push(change);
+    }
+
+    if !rejected.is_empty() {
+        return Err(anyhow!(
+            "Cannot reload without a restart: {}",
+            rejected.join(", ")
+        ));
+    }
+    Ok(changes)
+}
+
+/// Why `change` cannot be applied live, or `None` when it can.
+fn rejection(kind: &str, change: &ConfigChange) -> Option<&'static str> {
+    let grid = matches!(kind, "spot_grid" | "perp_grid");
+    let (old, new) = (change.old.as_f64(), change.new.as_f64());
+    match (change.field.as_str(), old, new) {
+        ("grid_range_low", Some(old), Some(new)) if grid => {
+            (new > old).then_some("the range can only be extended live")
+        }
+        ("grid_range_high", Some(old), Some(new)) if grid => {
+            (new < old).then_some("the range can only be extended live")
+        }
+        ("total_investment", Some(old), Some(new)) if grid => {
+            (new < old).then_some("the investment can only be topped up live")
+        }
+        ("trigger_price", ..) if grid => None,
+        ("price_limit" | "max_participation_pct", ..) if kind == "twap" => None,
+        _ => Some("needs a restart"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> StrategyConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn spot_grid(low: f64, high: f64, investment: f64, grid_count: u32) -> StrategyConfig {
+        parse(&format!(
+            r#"
+type = "spot_grid"
+symbol = "HYPE/USDC"
+grid_range_low = {low:?}
+grid_range_high = {high:?}
+grid_count = {grid_count}
+total_investment = {investment:?}
+"#
+        ))
+    }
+
+    #[test]
+    fn test_diff_accepts_safe_changes() {
+        let running = spot_grid(90.0, 110.0, 1000.0, 5);
+        assert!(diff_strategy(&running, &running).unwrap().is_empty());
+
+        let changes = diff_strategy(&running, &spot_grid(80.0, 120.0, 1500.0, 5)).unwrap();
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["grid_range_high", "grid_range_low", "total_investment"]
+        );
+        assert_eq!(changes[1].old, serde_json::json!(90.0));
+        assert_eq!(changes[1].new, serde_json::json!(80.0));
+
+        let twap = |limit: f64| {
+            parse(&format!(
+                "type = \"twap\"\nsymbol = \"HYPE/USDC\"\nside = \"buy\"\ntarget_size = 10.0\nduration_secs = 600\nprice_limit = {limit:?}\n"
+            ))
+        };
+        assert_eq!(diff_strategy(&twap(30.0), &twap(31.0)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_diff_rejects_unsafe_changes() {
+        let running = spot_grid(90.0, 110.0, 1000.0, 5);
+
+        let err = diff_strategy(&running, &spot_grid(95.0, 110.0, 800.0, 6)).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("grid_count (needs a restart)"));
+        assert!(message.contains("grid_range_low (the range can only be extended live)"));
+        assert!(message.contains("total_investment (the investment can only be topped up live)"));
+
+        let other_symbol = parse(
+            "type = \"spot_grid\"\nsymbol = \"PURR/USDC\"\ngrid_range_low = 90.0\ngrid_range_high = 110.0\ngrid_count = 5\ntotal_investment = 1000.0\n",
+        );
+        assert!(diff_strategy(&running, &other_symbol)
+            .unwrap_err()
+            .to_string()
+            .contains("Markets changed"));
+
+        let perp = parse(
+            "type = \"perp_grid\"\nsymbol = \"HYPE/USDC\"\nleverage = 2\ngrid_range_low = 90.0\ngrid_range_high = 110.0\ngrid_count = 5\ntotal_investment = 1000.0\ngrid_bias = \"long\"\n",
+        );
+        assert!(diff_strategy(&running, &perp)
+            .unwrap_err()
+            .to_string()
+            .contains("Strategy type changed"));
+    }
+}
@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// Remote signer process: the account whose key it holds, where it listens
+/// and what it agrees to sign.
+///
+/// Loaded from its own TOML file by `signer <file>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerConfig {
+    /// Account in `accounts.toml` holding the API wallet key.
+    pub account: String,
+    /// `unix:/path/to/signer.sock` or `http://127.0.0.1:<port>`.
+    pub listen: String,
+    pub policy: SignerPolicy,
+}
+
+/// Limits checked before every signature. Anything not covered is refused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerPolicy {
+    /// Markets orders and cancels may touch, as symbols (e.g. "HYPE/USDC" or "BTC").
+    pub allowed_assets: Vec<String>,
+    /// Largest notional of a single order, in quote currency. Each order is
+    /// valued at the higher of its limit price and the market's mid.
+    pub max_order_notional: f64,
+    /// Largest size of a single order per market, in base units.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub max_order_size: HashMap<String, f64>,
+    /// Furthest a sell may be priced below the mid, or a buy above it, in basis points.
+    #[serde(default = "default_max_price_deviation_bps")]
+    pub max_price_deviation_bps: f64,
+    /// Most notional signed across all orders within `notional_window_secs`.
+    pub max_window_notional: f64,
+    /// Length of the rolling window `max_window_notional` is counted over.
+    #[serde(default = "default_notional_window_secs")]
+    pub notional_window_secs: u64,
+    /// Highest leverage `update_leverage` may set. Leverage changes are refused without it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_leverage: Option<u32>,
+}
+
+fn default_max_price_deviation_bps() -> f64 {
+    100.0
+}
+
+fn default_notional_window_secs() -> u64 {
+    3600
+}
+
+/// Address of a signer, as written in `listen` and `api_wallet_signer`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignerEndpoint {
+    Unix(PathBuf),
+    Http(SocketAddr),
+}
+
+impl SignerEndpoint {
+    pub fn parse(value: &str) -> Result<Self> {
+        if let Some(path) = value.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(anyhow!("Signer endpoint '{}' has no socket path.", value));
+            }
+            return Ok(SignerEndpoint::Unix(PathBuf::from(path)));
+        }
+        if let Some(addr) = value.strip_prefix("http://") {
+            let addr: SocketAddr = addr.trim_end_matches('/').parse().map_err(|_| {
+                anyhow!(
+                    "Signer endpoint '{}' must be http://<ip>:<port>, e.g. http://127.0.0.1:8300.",
+                    value
+                )
+            })?;
+            // The protocol has no authentication or TLS of its own
+            if !addr.ip().is_loopback() {
+                return Err(anyhow!(
+                    "Signer endpoint '{}' must be a loopback address.",
+                    value
+                ));
+            }
+            return Ok(SignerEndpoint::Http(addr));
+        }
+        Err(anyhow!(
+            "Invalid signer endpoint '{}'. Expected unix:/path/to/socket or http://127.0.0.1:<port>.",
+            value
+        ))
+    }
+}
+
+impl SignerConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.account.trim().is_empty() {
+            return Err(anyhow!("account must not be empty."));
+        }
+        SignerEndpoint::parse(&self.listen)?;
+        self.policy.validate()
+    }
+}
+
+impl SignerPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if self.allowed_assets.is_empty() {
+            return Err(anyhow!(
+                "policy.allowed_assets must list at least one market."
+            ));
+        }
+        if !self.max_order_notional.is_finite() || self.max_order_notional <= 0.0 {
+            return Err(anyhow!("policy.max_order_notional must be greater than 0."));
+        }
+        if !self.max_price_deviation_bps.is_finite() || self.max_price_deviation_bps <= 0.0 {
+            return Err(anyhow!(
+                "policy.max_price_deviation_bps must be greater than 0."
+            ));
+        }
+        if !self.max_window_notional.is_finite()
+            || self.max_window_notional < self.max_order_notional
+        {
+            return Err(anyhow!(
+                "policy.max_window_notional must be at least max_order_notional."
+            ));
+        }
+        if self.notional_window_secs == 0 {
+            return Err(anyhow!("policy.notional_window_secs must be at least 1."));
+        }
+        for (symbol, size) in &self.max_order_size {
+            if !self.allowed_assets.contains(symbol) {
+                return Err(anyhow!(
+                    "policy.max_order_size: '{}' is not in allowed_assets.",
+                    symbol
+                ));
+            }
+            if !size.is_finite() || *size <= 0.0 {
+                return Err(anyhow!(
+                    "policy.max_order_size for '{}' must be greater than 0.",
+                    symbol
+                ));
+            }
+        }
+        if self.max_leverage == Some(0) {
+            return Err(anyhow!("policy.max_leverage must be at least 1."));
+        }
+        Ok(())
+    }
+}
+
+pub fn load_signer_config(path: &str) -> Result<SignerConfig> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let config: SignerConfig =
+        toml::from_str(&content).with_context(|| format!("Invalid signer config {}", path))?;
+    config.validate()?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signer_config() {
+        let config: SignerConfig = toml::from_str(
+            r#"
+account = "perp_account"
+listen = "unix:/run/hlbot/signer.sock"
+
+[policy]
+allowed_assets = ["BTC", "HYPE/USDC"]
+max_order_notional = 5000.0
+max_window_notional = 20000.0
+max_leverage = 5
+
+[policy.max_order_size]
+BTC = 0.1
+"HYPE/USDC" = 500.0
+"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            SignerEndpoint::parse(&config.listen).unwrap(),
+            SignerEndpoint::Unix(PathBuf::from("/run/hlbot/signer.sock"))
+        );
+        assert_eq!(
+            SignerEndpoint::parse("http://127.0.0.1:8300").unwrap(),
+            SignerEndpoint::Http("127.0.0.1:8300".parse().unwrap())
+        );
+        assert!(SignerEndpoint::parse("http://0.0.0.0:8300").is_err());
+        assert!(SignerEndpoint::parse("https://127.0.0.1:8300").is_err());
+        assert!(SignerEndpoint::parse("unix:").is_err());
+
+        assert_eq!(config.policy.max_order_size["HYPE/USDC"], 500.0);
+        assert_eq!(config.policy.max_price_deviation_bps, 100.0);
+        assert_eq!(config.policy.notional_window_secs, 3600);
+
+        let mut small_window = config.clone();
+        small_window.policy.max_window_notional = 1000.0;
+        assert!(small_window.validate().is_err());
+
+        let mut open = config.clone();
+        open.policy.allowed_assets.clear();
+        assert!(open.validate().is_err());
+
+        let mut unknown = config.clone();
+        unknown.policy.max_order_size.insert("ETH".to_string(), 1.0);
+        assert!(unknown.validate().is_err());
+        let mut zero = config;
+        zero.policy.max_order_size.insert("BTC".to_string(), 0.0);
+        assert!(zero.validate().is_err());
+    }
+}
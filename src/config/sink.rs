@@ -0,0 +1,139 @@
+use crate::config::file_stem;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Directory for event files when `path` is not set.
+pub const DEFAULT_EVENTS_DIR: &str = "data/events";
+pub const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+/// One destination of the broadcast event stream.
+///
+/// These live inside the main bot TOML as `[[sinks]]` entries. Without any,
+/// events only go to the WebSocket server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// The WebSocket and REST server on `websocket_port`.
+    Websocket,
+    /// Append-only JSON lines file, rotated by size.
+    Jsonl {
+        /// Defaults to `data/events/<bot name>.jsonl`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Size at which the file is rotated. Defaults to 100 MiB.
+        #[serde(default = "default_max_bytes")]
+        max_bytes: u64,
+        /// Rotated files kept as `<path>.1` (newest) to `<path>.<max_files>`. Defaults to 5.
+        #[serde(default = "default_max_files")]
+        max_files: usize,
+    },
+    /// One JSON line per event on standard output.
+    Stdout,
+}
+
+fn default_max_bytes() -> u64 {
+    DEFAULT_MAX_BYTES
+}
+
+fn default_max_files() -> usize {
+    DEFAULT_MAX_FILES
+}
+
+impl SinkConfig {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SinkConfig::Websocket => "websocket",
+            SinkConfig::Jsonl { .. } => "jsonl",
+            SinkConfig::Stdout => "stdout",
+        }
+    }
+
+    /// `path` of a `jsonl` sink, or a file named after the bot in `data/events`.
+    pub fn jsonl_path_for(path: Option<&str>, bot_name: &str) -> String {
+        path.map(str::to_string)
+            .unwrap_or_else(|| format!("{}/{}.jsonl", DEFAULT_EVENTS_DIR, file_stem(bot_name)))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let SinkConfig::Jsonl {
+            path,
+            max_bytes,
+            max_files,
+        } = self
+        {
+            if path.as_deref().is_some_and(|p| p.trim().is_empty()) {
+                return Err(anyhow!("sinks.path must not be empty."));
+            }
+            if *max_bytes == 0 {
+                return Err(anyhow!("sinks.max_bytes must be greater than 0."));
+            }
+            if *max_files == 0 {
+                return Err(anyhow!("sinks.max_files must be greater than 0."));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks every sink and rejects a second `websocket` or `stdout` sink.
+pub fn validate_sinks(sinks: &[SinkConfig]) -> Result<()> {
+    for (i, sink) in sinks.iter().enumerate() {
+        sink.validate()?;
+        let single = matches!(sink, SinkConfig::Websocket | SinkConfig::Stdout);
+        if single && sinks[..i].contains(sink) {
+            return Err(anyhow!("Only one {} sink is allowed.", sink.name()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Sinks {
+        sinks: Vec<SinkConfig>,
+    }
+
+    #[test]
+    fn test_sink_configs() {
+        let parsed: Sinks = toml::from_str(
+            r#"
+[[sinks]]
+type = "websocket"
+
+[[sinks]]
+type = "jsonl"
+max_files = 2
+
+[[sinks]]
+type = "stdout"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.sinks[1],
+            SinkConfig::Jsonl {
+                path: None,
+                max_bytes: DEFAULT_MAX_BYTES,
+                max_files: 2,
+            }
+        );
+        assert!(validate_sinks(&parsed.sinks).is_ok());
+        assert_eq!(
+            SinkConfig::jsonl_path_for(None, "btc perp"),
+            "data/events/btc_perp.jsonl"
+        );
+
+        let twice = [SinkConfig::Stdout, SinkConfig::Stdout];
+        assert!(validate_sinks(&twice).is_err());
+        let no_rotation = [SinkConfig::Jsonl {
+            path: None,
+            max_bytes: 1024,
+            max_files: 0,
+        }];
+        assert!(validate_sinks(&no_rotation).is_err());
+    }
+}
@@ -1,6 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-pub use crate::strategy::types::{GridBias, GridType};
+use crate::constants::{
+    DEFAULT_TWAP_SLICE_INTERVAL, INVESTMENT_BUFFER_SPOT, MAX_FEE_SHARE_OF_SPACING,
+};
+use crate::engine::context::MIN_NOTIONAL_VALUE;
+use crate::model::FeeRates;
+pub use crate::model::OrderSide;
+use crate::strategy::common::{
+    calculate_grid_spacing_pct, calculate_infinity_grid_prices, calculate_ladder_spacing_pct,
+    calculate_layered_grid_prices,
+};
+pub use crate::strategy::types::{
+    CompoundAllocation, DistributionShape, GridBias, GridType, SizeDistribution, SpacingMode,
+};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type")]
@@ -9,6 +23,12 @@ pub enum StrategyConfig {
     SpotGrid(SpotGridConfig),
     #[serde(rename = "perp_grid")]
     PerpGrid(PerpGridConfig),
+    #[serde(rename = "twap")]
+    Twap(TwapConfig),
+    #[serde(rename = "rebalance")]
+    Rebalance(RebalanceConfig),
+    #[serde(rename = "infinity_grid")]
+    InfinityGrid(InfinityGridConfig),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -25,6 +45,24 @@ pub struct SpotGridConfig {
     /// Spread in basis points between levels. Implies geometric spacing.
     #[serde(default)]
     pub spread_bips: Option<f64>,
+    /// Explicit ascending price levels, instead of a generated ladder.
+    #[serde(default)]
+    pub levels: Option<Vec<f64>>,
+    /// Adjacent sub-ranges, each with its own spacing and investment.
+    #[serde(default)]
+    pub ranges: Option<Vec<GridRangeConfig>>,
+    /// `fixed` (default) or `atr` for spacing derived from candle history.
+    #[serde(default)]
+    pub spacing_mode: SpacingMode,
+    /// ATR settings, used when `spacing_mode = "atr"`.
+    #[serde(default)]
+    pub atr: AtrSpacingConfig,
+    /// How `total_investment` is split across zones. Defaults to `flat`.
+    #[serde(default)]
+    pub size_distribution: SizeDistribution,
+    /// Reinvest realized profit into zone sizes.
+    #[serde(default)]
+    pub compound: Option<CompoundConfig>,
     pub total_investment: f64,
     #[serde(default)]
     pub trigger_price: Option<f64>,
@@ -47,12 +85,215 @@ pub struct PerpGridConfig {
     /// Spread in basis points between levels. Implies geometric spacing.
     #[serde(default)]
     pub spread_bips: Option<f64>,
+    /// Explicit ascending price levels, instead of a generated ladder.
+    #[serde(default)]
+    pub levels: Option<Vec<f64>>,
+    /// Adjacent sub-ranges, each with its own spacing and investment.
+    #[serde(default)]
+    pub ranges: Option<Vec<GridRangeConfig>>,
+    /// `fixed` (default) or `atr` for spacing derived from candle history.
+    #[serde(default)]
+    pub spacing_mode: SpacingMode,
+    /// ATR settings, used when `spacing_mode = "atr"`.
+    #[serde(default)]
+    pub atr: AtrSpacingConfig,
+    /// How `total_investment` is split across zones. Defaults to `flat`.
+    #[serde(default)]
+    pub size_distribution: SizeDistribution,
+    /// Reinvest realized profit into zone sizes.
+    #[serde(default)]
+    pub compound: Option<CompoundConfig>,
     pub total_investment: f64,
     pub grid_bias: GridBias,
     #[serde(default)]
     pub trigger_price: Option<f64>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AtrSpacingConfig {
+    /// Candle interval, e.g. "15m", "1h", "1d".
+    #[serde(default = "default_atr_candle_interval")]
+    pub candle_interval: String,
+    /// Number of candles averaged into the ATR.
+    #[serde(default = "default_atr_lookback")]
+    pub lookback: u32,
+    /// Level spacing as a multiple of ATR (as a fraction of the last close).
+    #[serde(default = "default_atr_multiplier")]
+    pub multiplier: f64,
+    /// Lower bound for the derived spacing, in basis points.
+    #[serde(default)]
+    pub min_spread_bips: Option<f64>,
+    /// Upper bound for the derived spacing, in basis points.
+    #[serde(default)]
+    pub max_spread_bips: Option<f64>,
+    /// Refresh candles and re-space idle zones at this interval, in seconds.
+    #[serde(default)]
+    pub recompute_secs: Option<u64>,
+}
+
+impl Default for AtrSpacingConfig {
+    fn default() -> Self {
+        Self {
+            candle_interval: default_atr_candle_interval(),
+            lookback: default_atr_lookback(),
+            multiplier: default_atr_multiplier(),
+            min_spread_bips: None,
+            max_spread_bips: None,
+            recompute_secs: None,
+        }
+    }
+}
+
+/// One layer of a multi-range grid.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GridRangeConfig {
+    pub low: f64,
+    pub high: f64,
+    /// Grid type. Defaults to `geometric` when omitted.
+    #[serde(default)]
+    pub grid_type: GridType,
+    /// Number of levels in this range, both bounds included.
+    #[serde(default)]
+    pub grid_count: Option<u32>,
+    /// Spread in basis points, fitted so the levels end exactly at `high`.
+    #[serde(default)]
+    pub spread_bips: Option<f64>,
+    /// Part of `total_investment` placed in this range.
+    pub investment: f64,
+}
+
+/// Reinvestment of realized grid profit into zone sizes.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CompoundConfig {
+    /// `proportional` (default) or `nearest`.
+    #[serde(default)]
+    pub allocation: CompoundAllocation,
+    /// Zones grown per reinvestment with `nearest` allocation.
+    #[serde(default = "default_compound_nearest_zones")]
+    pub nearest_zones: usize,
+    /// Reinvest on this schedule, in seconds. Reinvests after every roundtrip when unset.
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+}
+
+impl Default for CompoundConfig {
+    fn default() -> Self {
+        Self {
+            allocation: CompoundAllocation::default(),
+            nearest_zones: default_compound_nearest_zones(),
+            interval_secs: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TwapConfig {
+    /// Spot pair ("HYPE/USDC") or perp coin ("BTC").
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Total size to execute, in base asset units.
+    pub target_size: f64,
+    /// Total execution window in seconds.
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+    /// Number of slices. Slices are spread over `duration_secs` when both are set.
+    #[serde(default)]
+    pub slice_count: Option<u32>,
+    /// Random jitter applied to each slice interval, as a percentage (0-90).
+    #[serde(default)]
+    pub randomize_pct: f64,
+    /// Worst acceptable price: maximum for buys, minimum for sells.
+    #[serde(default)]
+    pub price_limit: Option<f64>,
+    /// Maximum share of observed market volume per slice, as a percentage.
+    #[serde(default)]
+    pub max_participation_pct: Option<f64>,
+    /// Seconds a passive slice rests before the remainder is sent as IOC.
+    /// Defaults to half the slice interval.
+    #[serde(default)]
+    pub slice_timeout_secs: Option<u64>,
+    /// Perp only: send slices as reduce-only (for unwinding a position).
+    #[serde(default)]
+    pub reduce_only: bool,
+}
+
+/// Spot grid with a lower bound only. Levels are added above the ladder as
+/// price makes new highs.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct InfinityGridConfig {
+    pub symbol: String,
+    pub grid_range_low: f64,
+    /// Geometric spacing between levels, in basis points.
+    pub spread_bips: f64,
+    /// Quote value held by each zone.
+    pub level_value: f64,
+    /// Zones kept above price, holding base to sell.
+    #[serde(default = "default_infinity_levels_above")]
+    pub levels_above: u32,
+    /// Most quote the ladder may hold across all its zones.
+    pub total_investment: f64,
+    /// Stop adding levels once the ladder has this many zones. Defaults to
+    /// what `total_investment` covers at `level_value` per zone.
+    #[serde(default)]
+    pub max_levels: Option<u32>,
+    #[serde(default)]
+    pub trigger_price: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RebalanceConfig {
+    /// Asset used to value the portfolio and to trade against.
+    #[serde(default = "default_quote_asset")]
+    pub quote_asset: String,
+    /// Target weight per asset (including the quote asset). Weights are relative
+    /// and normalized by their sum.
+    pub targets: BTreeMap<String, f64>,
+    /// Rebalance when any asset's weight drifts this many percentage points from target.
+    #[serde(default)]
+    pub drift_band_pct: Option<f64>,
+    /// Rebalance on a fixed schedule, in seconds.
+    #[serde(default)]
+    pub rebalance_interval_secs: Option<u64>,
+    /// Distance from mid for post-only pricing, in basis points.
+    #[serde(default = "default_post_only_offset_bips")]
+    pub post_only_offset_bips: f64,
+    /// Seconds before an unfilled rebalance order is cancelled.
+    #[serde(default = "default_rebalance_order_timeout_secs")]
+    pub order_timeout_secs: u64,
+}
+
+fn default_atr_candle_interval() -> String {
+    "1h".to_string()
+}
+
+fn default_atr_lookback() -> u32 {
+    14
+}
+
+fn default_atr_multiplier() -> f64 {
+    1.0
+}
+
+fn default_compound_nearest_zones() -> usize {
+    3
+}
+
+fn default_quote_asset() -> String {
+    "USDC".to_string()
+}
+
+fn default_post_only_offset_bips() -> f64 {
+    5.0
+}
+
+fn default_rebalance_order_timeout_secs() -> u64 {
+    300
+}
+
+fn default_infinity_levels_above() -> u32 {
+    5
+}
+
 fn default_is_isolated() -> bool {
     false // Default to cross margin (more capital efficient for grid strategies)
 }
@@ -62,13 +303,28 @@ impl StrategyConfig {
         match self {
             StrategyConfig::SpotGrid(_) => "Spot Grid",
             StrategyConfig::PerpGrid(_) => "Perp Grid",
+            StrategyConfig::Twap(_) => "TWAP",
+            StrategyConfig::Rebalance(_) => "Rebalance",
+            StrategyConfig::InfinityGrid(_) => "Infinity Grid",
         }
     }
 
+    /// Display symbol. Multi-market strategies report their quote asset.
     pub fn symbol(&self) -> &str {
         match self {
             StrategyConfig::SpotGrid(c) => &c.symbol,
             StrategyConfig::PerpGrid(c) => &c.symbol,
+            StrategyConfig::Twap(c) => &c.symbol,
+            StrategyConfig::Rebalance(c) => &c.quote_asset,
+            StrategyConfig::InfinityGrid(c) => &c.symbol,
+        }
+    }
+
+    /// All markets the strategy trades. The first entry drives the price tick.
+    pub fn markets(&self) -> Vec<String> {
+        match self {
+            StrategyConfig::Rebalance(c) => c.markets(),
+            _ => vec![self.symbol().to_string()],
         }
     }
 
@@ -76,6 +332,9 @@ impl StrategyConfig {
         match self {
             StrategyConfig::SpotGrid(_) => 8000,
             StrategyConfig::PerpGrid(_) => 8001,
+            StrategyConfig::Twap(_) => 8002,
+            StrategyConfig::Rebalance(_) => 8003,
+            StrategyConfig::InfinityGrid(_) => 8004,
         }
     }
 
@@ -83,15 +342,187 @@ impl StrategyConfig {
         match self {
             StrategyConfig::SpotGrid(c) => c.validate(),
             StrategyConfig::PerpGrid(c) => c.validate(),
+            StrategyConfig::Twap(c) => c.validate(),
+            StrategyConfig::Rebalance(c) => c.validate(),
+            StrategyConfig::InfinityGrid(c) => c.validate(),
+        }
+    }
+
+    /// Symbol and ATR settings when the strategy sizes its grid from candle history.
+    pub fn candle_feed(&self) -> Option<(&str, &AtrSpacingConfig)> {
+        match self {
+            StrategyConfig::SpotGrid(c) if c.spacing_mode == SpacingMode::Atr => {
+                Some((&c.symbol, &c.atr))
+            }
+            StrategyConfig::PerpGrid(c) if c.spacing_mode == SpacingMode::Atr => {
+                Some((&c.symbol, &c.atr))
+            }
+            _ => None,
+        }
+    }
+
+    /// Price range (low, high) a grid strategy trades in. Infinity grids have no upper bound.
+    pub fn grid_range(&self) -> Option<(f64, f64)> {
+        match self {
+            StrategyConfig::SpotGrid(c) => Some((c.grid_range_low, c.grid_range_high)),
+            StrategyConfig::PerpGrid(c) => Some((c.grid_range_low, c.grid_range_high)),
+            StrategyConfig::InfinityGrid(c) => Some((c.grid_range_low, f64::INFINITY)),
+            StrategyConfig::Twap(_) | StrategyConfig::Rebalance(_) => None,
+        }
+    }
+
+    /// Smallest zone spacing of a grid strategy, in percent of price.
+    ///
+    /// ATR grids only have a known floor when `min_spread_bips` is set.
+    pub fn min_grid_spacing_pct(&self) -> Option<f64> {
+        match self {
+            StrategyConfig::SpotGrid(c) => match c.custom_levels() {
+                Some(levels) => Some(calculate_ladder_spacing_pct(&levels).0),
+                None => min_spacing_pct(
+                    c.grid_type,
+                    c.grid_range_low,
+                    c.grid_range_high,
+                    c.grid_count,
+                    c.spread_bips,
+                    c.spacing_mode,
+                    &c.atr,
+                ),
+            },
+            StrategyConfig::PerpGrid(c) => match c.custom_levels() {
+                Some(levels) => Some(calculate_ladder_spacing_pct(&levels).0),
+                None => min_spacing_pct(
+                    c.grid_type,
+                    c.grid_range_low,
+                    c.grid_range_high,
+                    c.grid_count,
+                    c.spread_bips,
+                    c.spacing_mode,
+                    &c.atr,
+                ),
+            },
+            StrategyConfig::InfinityGrid(c) => Some(c.spread_bips / 100.0),
+            _ => None,
+        }
+    }
+
+    /// Compare the grid's tightest zone against round-trip maker fees.
+    pub fn fee_check(&self, fees: &FeeRates) -> Option<GridFeeCheck> {
+        self.min_grid_spacing_pct().map(|spacing_pct| GridFeeCheck {
+            spacing_pct,
+            roundtrip_fee_pct: fees.roundtrip_maker_pct(),
+        })
+    }
+
+    /// Whether the strategy trades spot markets (and pays spot fees).
+    pub fn is_spot(&self) -> bool {
+        match self {
+            StrategyConfig::SpotGrid(_)
+            | StrategyConfig::Rebalance(_)
+            | StrategyConfig::InfinityGrid(_) => true,
+            StrategyConfig::PerpGrid(_) => false,
+            StrategyConfig::Twap(c) => c.symbol.contains('/'),
+        }
+    }
+
+    /// Whether the engine should subscribe to the public trade feed for the symbol.
+    pub fn requires_trade_feed(&self) -> bool {
+        match self {
+            StrategyConfig::Twap(c) => c.max_participation_pct.is_some(),
+            _ => false,
         }
     }
 }
 
+/// Net result of one buy/sell roundtrip in the tightest zone of a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridFeeCheck {
+    /// Smallest zone spacing, in percent of price.
+    pub spacing_pct: f64,
+    /// Maker fees for one buy and one sell, in percent of notional.
+    pub roundtrip_fee_pct: f64,
+}
+
+impl GridFeeCheck {
+    /// Profit per roundtrip after fees, in percent of notional.
+    pub fn net_pct(&self) -> f64 {
+        self.spacing_pct - self.roundtrip_fee_pct
+    }
+
+    /// Share of the gross spacing consumed by fees.
+    pub fn fee_share(&self) -> f64 {
+        self.roundtrip_fee_pct / self.spacing_pct
+    }
+
+    /// Fees take a large share of each roundtrip, but it is still profitable.
+    pub fn is_marginal(&self) -> bool {
+        self.fee_share() > MAX_FEE_SHARE_OF_SPACING
+    }
+
+    /// Fails when a roundtrip in the tightest zone cannot cover its fees.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.net_pct() <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Grid spacing {:.4}% does not cover round-trip maker fees of {:.4}%. Every roundtrip would lose money. Widen the spacing (fewer levels or larger spread_bips) or run with --recommend.",
+                self.spacing_pct,
+                self.roundtrip_fee_pct
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn min_spacing_pct(
+    grid_type: GridType,
+    grid_range_low: f64,
+    grid_range_high: f64,
+    grid_count: Option<u32>,
+    spread_bips: Option<f64>,
+    spacing_mode: SpacingMode,
+    atr: &AtrSpacingConfig,
+) -> Option<f64> {
+    if spacing_mode == SpacingMode::Atr {
+        return atr.min_spread_bips.map(|bips| bips / 100.0);
+    }
+    if let Some(bips) = spread_bips {
+        return Some(bips / 100.0);
+    }
+    let count = grid_count?;
+    let (min_pct, _) =
+        calculate_grid_spacing_pct(&grid_type, grid_range_low, grid_range_high, count - 1);
+    Some(min_pct)
+}
+
 fn validate_grid_spacing_fields(
     grid_type: GridType,
     grid_count: Option<u32>,
     spread_bips: Option<f64>,
+    spacing_mode: SpacingMode,
+    atr: &AtrSpacingConfig,
+    custom_ladder: bool,
 ) -> anyhow::Result<()> {
+    if custom_ladder {
+        if spacing_mode == SpacingMode::Atr || grid_count.is_some() || spread_bips.is_some() {
+            return Err(anyhow::anyhow!(
+                "levels and ranges cannot be combined with grid_count, spread_bips or spacing_mode = \"atr\"."
+            ));
+        }
+        return Ok(());
+    }
+
+    if spacing_mode == SpacingMode::Atr {
+        if grid_count.is_some() || spread_bips.is_some() {
+            return Err(anyhow::anyhow!(
+                "grid_count and spread_bips cannot be used with spacing_mode = \"atr\"."
+            ));
+        }
+        if grid_type != GridType::Geometric {
+            return Err(anyhow::anyhow!(
+                "grid_type must be geometric when spacing_mode is atr."
+            ));
+        }
+        return atr.validate();
+    }
+
     match (grid_count, spread_bips) {
         (Some(count), None) => {
             if count <= 2 {
@@ -118,7 +549,7 @@ fn validate_grid_spacing_fields(
         }
         (None, None) => {
             return Err(anyhow::anyhow!(
-                "Either grid_count or spread_bips must be specified."
+                "Either grid_count, spread_bips, levels or ranges must be specified."
             ));
         }
     }
@@ -126,9 +557,151 @@ fn validate_grid_spacing_fields(
     Ok(())
 }
 
+fn validate_size_distribution(
+    distribution: &SizeDistribution,
+    zone_count: Option<usize>,
+    spacing_mode: SpacingMode,
+    layered: bool,
+) -> anyhow::Result<()> {
+    let SizeDistribution::Weights(weights) = distribution else {
+        return Ok(());
+    };
+    if layered {
+        return Err(anyhow::anyhow!(
+            "size_distribution weights cannot be used with ranges; each range sets its own investment."
+        ));
+    }
+    if spacing_mode == SpacingMode::Atr {
+        return Err(anyhow::anyhow!(
+            "size_distribution weights cannot be used with spacing_mode = \"atr\"; the zone count changes with volatility."
+        ));
+    }
+    if weights.iter().any(|w| !w.is_finite() || *w <= 0.0) {
+        return Err(anyhow::anyhow!(
+            "size_distribution weights must all be positive."
+        ));
+    }
+    if let Some(count) = zone_count {
+        if weights.len() != count {
+            return Err(anyhow::anyhow!(
+                "size_distribution has {} weights but the grid has {} zones.",
+                weights.len(),
+                count
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_grid_ladder(
+    levels: Option<&[f64]>,
+    ranges: Option<&[GridRangeConfig]>,
+    grid_range_low: f64,
+    grid_range_high: f64,
+    total_investment: f64,
+) -> anyhow::Result<()> {
+    match (levels, ranges) {
+        (Some(_), Some(_)) => Err(anyhow::anyhow!(
+            "Only one of levels or ranges can be specified, not both."
+        )),
+        (Some(levels), None) => {
+            if levels.len() < 3 {
+                return Err(anyhow::anyhow!("levels must contain at least 3 prices."));
+            }
+            if levels.iter().any(|p| !p.is_finite() || *p <= 0.0) {
+                return Err(anyhow::anyhow!("levels must all be positive."));
+            }
+            if levels.windows(2).any(|pair| pair[1] <= pair[0]) {
+                return Err(anyhow::anyhow!("levels must be strictly ascending."));
+            }
+            if levels[0] < grid_range_low || levels[levels.len() - 1] > grid_range_high {
+                return Err(anyhow::anyhow!(
+                    "levels must lie within the grid range [{}, {}].",
+                    grid_range_low,
+                    grid_range_high
+                ));
+            }
+            Ok(())
+        }
+        (None, Some(ranges)) => {
+            if ranges.is_empty() {
+                return Err(anyhow::anyhow!("ranges must contain at least one range."));
+            }
+            for range in ranges {
+                range.validate()?;
+            }
+            for pair in ranges.windows(2) {
+                if pair[1].low != pair[0].high {
+                    return Err(anyhow::anyhow!(
+                        "ranges must be ascending and adjacent: range ending at {} is followed by one starting at {}.",
+                        pair[0].high,
+                        pair[1].low
+                    ));
+                }
+            }
+            if ranges[0].low < grid_range_low || ranges[ranges.len() - 1].high > grid_range_high {
+                return Err(anyhow::anyhow!(
+                    "ranges must lie within the grid range [{}, {}].",
+                    grid_range_low,
+                    grid_range_high
+                ));
+            }
+            let invested: f64 = ranges.iter().map(|r| r.investment).sum();
+            if (invested - total_investment).abs() > 1e-6 * total_investment {
+                return Err(anyhow::anyhow!(
+                    "Range investments sum to {} but total_investment is {}.",
+                    invested,
+                    total_investment
+                ));
+            }
+            Ok(())
+        }
+        (None, None) => Ok(()),
+    }
+}
+
+/// Levels from `levels` or `ranges`, when the grid uses an explicit ladder.
+fn custom_grid_levels(
+    levels: Option<&[f64]>,
+    ranges: Option<&[GridRangeConfig]>,
+) -> Option<Vec<f64>> {
+    levels
+        .map(|levels| levels.to_vec())
+        .or_else(|| ranges.map(calculate_layered_grid_prices))
+}
+
 impl SpotGridConfig {
     pub fn validate(&self) -> anyhow::Result<()> {
-        validate_grid_spacing_fields(self.grid_type, self.grid_count, self.spread_bips)?;
+        validate_grid_spacing_fields(
+            self.grid_type,
+            self.grid_count,
+            self.spread_bips,
+            self.spacing_mode,
+            &self.atr,
+            self.levels.is_some() || self.ranges.is_some(),
+        )?;
+        validate_grid_ladder(
+            self.levels.as_deref(),
+            self.ranges.as_deref(),
+            self.grid_range_low,
+            self.grid_range_high,
+            self.total_investment,
+        )?;
+        let zone_count = self
+            .custom_levels()
+            .map(|levels| levels.len().saturating_sub(1))
+            .or(self
+                .grid_count
+                .map(|count| count.saturating_sub(1) as usize));
+        validate_size_distribution(
+            &self.size_distribution,
+            zone_count,
+            self.spacing_mode,
+            self.ranges.is_some(),
+        )?;
+        if let Some(compound) = &self.compound {
+            compound.validate()?;
+        }
 
         if self.grid_range_high <= self.grid_range_low {
             return Err(anyhow::anyhow!(
@@ -163,6 +736,11 @@ impl SpotGridConfig {
         Ok(())
     }
 
+    /// Levels from `levels` or `ranges`, when the grid uses an explicit ladder.
+    pub fn custom_levels(&self) -> Option<Vec<f64>> {
+        custom_grid_levels(self.levels.as_deref(), self.ranges.as_deref())
+    }
+
     /// Get the effective grid count, either from config or calculated from spread_bips.
     /// This should only be called after zones have been generated.
     pub fn get_grid_count(&self) -> u32 {
@@ -172,7 +750,36 @@ impl SpotGridConfig {
 
 impl PerpGridConfig {
     pub fn validate(&self) -> anyhow::Result<()> {
-        validate_grid_spacing_fields(self.grid_type, self.grid_count, self.spread_bips)?;
+        validate_grid_spacing_fields(
+            self.grid_type,
+            self.grid_count,
+            self.spread_bips,
+            self.spacing_mode,
+            &self.atr,
+            self.levels.is_some() || self.ranges.is_some(),
+        )?;
+        validate_grid_ladder(
+            self.levels.as_deref(),
+            self.ranges.as_deref(),
+            self.grid_range_low,
+            self.grid_range_high,
+            self.total_investment,
+        )?;
+        let zone_count = self
+            .custom_levels()
+            .map(|levels| levels.len().saturating_sub(1))
+            .or(self
+                .grid_count
+                .map(|count| count.saturating_sub(1) as usize));
+        validate_size_distribution(
+            &self.size_distribution,
+            zone_count,
+            self.spacing_mode,
+            self.ranges.is_some(),
+        )?;
+        if let Some(compound) = &self.compound {
+            compound.validate()?;
+        }
 
         if self.grid_range_high <= self.grid_range_low {
             return Err(anyhow::anyhow!(
@@ -204,6 +811,384 @@ impl PerpGridConfig {
         }
         Ok(())
     }
+
+    /// Levels from `levels` or `ranges`, when the grid uses an explicit ladder.
+    pub fn custom_levels(&self) -> Option<Vec<f64>> {
+        custom_grid_levels(self.levels.as_deref(), self.ranges.as_deref())
+    }
+}
+
+impl GridRangeConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.low <= 0.0 || self.high <= self.low {
+            return Err(anyhow::anyhow!(
+                "Range {}-{}: high must be greater than a positive low.",
+                self.low,
+                self.high
+            ));
+        }
+        match (self.grid_count, self.spread_bips) {
+            (Some(count), None) if count < 2 => Err(anyhow::anyhow!(
+                "Range {}-{}: grid_count must be at least 2.",
+                self.low,
+                self.high
+            )),
+            (None, Some(bips)) if bips <= 0.0 => Err(anyhow::anyhow!(
+                "Range {}-{}: spread_bips must be positive.",
+                self.low,
+                self.high
+            )),
+            (None, Some(_)) if self.grid_type != GridType::Geometric => Err(anyhow::anyhow!(
+                "Range {}-{}: grid_type must be geometric when spread_bips is used.",
+                self.low,
+                self.high
+            )),
+            (Some(_), Some(_)) | (None, None) => Err(anyhow::anyhow!(
+                "Range {}-{}: exactly one of grid_count or spread_bips must be specified.",
+                self.low,
+                self.high
+            )),
+            _ if self.investment <= 0.0 => Err(anyhow::anyhow!(
+                "Range {}-{}: investment must be positive.",
+                self.low,
+                self.high
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl CompoundConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.nearest_zones == 0 {
+            return Err(anyhow::anyhow!(
+                "compound.nearest_zones must be at least 1."
+            ));
+        }
+        if self.interval_secs == Some(0) {
+            return Err(anyhow::anyhow!("compound.interval_secs must be positive."));
+        }
+        Ok(())
+    }
+
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval_secs.map(Duration::from_secs)
+    }
+}
+
+impl AtrSpacingConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.candle_duration().is_none() {
+            return Err(anyhow::anyhow!(
+                "Unsupported atr.candle_interval '{}'. Use e.g. 15m, 1h, 4h or 1d.",
+                self.candle_interval
+            ));
+        }
+        if self.lookback < 2 {
+            return Err(anyhow::anyhow!(
+                "atr.lookback {} must be at least 2.",
+                self.lookback
+            ));
+        }
+        if self.multiplier <= 0.0 {
+            return Err(anyhow::anyhow!("atr.multiplier must be positive."));
+        }
+        if let (Some(min), Some(max)) = (self.min_spread_bips, self.max_spread_bips) {
+            if min > max {
+                return Err(anyhow::anyhow!(
+                    "atr.min_spread_bips {} must not exceed atr.max_spread_bips {}.",
+                    min,
+                    max
+                ));
+            }
+        }
+        if [self.min_spread_bips, self.max_spread_bips]
+            .iter()
+            .flatten()
+            .any(|bips| *bips <= 0.0)
+        {
+            return Err(anyhow::anyhow!("atr spread bounds must be positive."));
+        }
+        if self.recompute_secs == Some(0) {
+            return Err(anyhow::anyhow!("atr.recompute_secs must be positive."));
+        }
+        Ok(())
+    }
+
+    /// Length of one candle, for the intervals the exchange serves.
+    pub fn candle_duration(&self) -> Option<Duration> {
+        let secs = match self.candle_interval.as_str() {
+            "1m" => 60,
+            "3m" => 3 * 60,
+            "5m" => 5 * 60,
+            "15m" => 15 * 60,
+            "30m" => 30 * 60,
+            "1h" => 3600,
+            "2h" => 2 * 3600,
+            "4h" => 4 * 3600,
+            "8h" => 8 * 3600,
+            "12h" => 12 * 3600,
+            "1d" => 86400,
+            "3d" => 3 * 86400,
+            "1w" => 7 * 86400,
+            _ => return None,
+        };
+        Some(Duration::from_secs(secs))
+    }
+
+    pub fn recompute_interval(&self) -> Option<Duration> {
+        self.recompute_secs.map(Duration::from_secs)
+    }
+}
+
+impl TwapConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.target_size <= 0.0 {
+            return Err(anyhow::anyhow!("Target size must be positive."));
+        }
+        match (self.duration_secs, self.slice_count) {
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "Either duration_secs or slice_count must be specified."
+                ));
+            }
+            (Some(0), _) => {
+                return Err(anyhow::anyhow!("duration_secs must be positive."));
+            }
+            (_, Some(0)) => {
+                return Err(anyhow::anyhow!("slice_count must be positive."));
+            }
+            _ => {}
+        }
+        if !(0.0..=90.0).contains(&self.randomize_pct) {
+            return Err(anyhow::anyhow!(
+                "randomize_pct {} must be between 0 and 90.",
+                self.randomize_pct
+            ));
+        }
+        if let Some(limit) = self.price_limit {
+            if limit <= 0.0 {
+                return Err(anyhow::anyhow!("Price limit must be positive."));
+            }
+        }
+        if let Some(pct) = self.max_participation_pct {
+            if pct <= 0.0 || pct > 100.0 {
+                return Err(anyhow::anyhow!(
+                    "max_participation_pct {} must be in (0, 100].",
+                    pct
+                ));
+            }
+        }
+        if let Some(timeout) = self.slice_timeout_secs {
+            if Duration::from_secs(timeout) >= self.slice_interval() {
+                return Err(anyhow::anyhow!(
+                    "slice_timeout_secs {} must be shorter than the slice interval ({}s).",
+                    timeout,
+                    self.slice_interval().as_secs_f64()
+                ));
+            }
+        }
+        if self.reduce_only && self.symbol.contains('/') {
+            return Err(anyhow::anyhow!(
+                "reduce_only is only supported for perp symbols."
+            ));
+        }
+        Ok(())
+    }
+
+    /// Effective number of slices.
+    /// Derived from `duration_secs` at one slice per default interval when not set.
+    pub fn effective_slice_count(&self) -> u32 {
+        match (self.slice_count, self.duration_secs) {
+            (Some(count), _) => count.max(1),
+            (None, Some(duration)) => {
+                let interval = DEFAULT_TWAP_SLICE_INTERVAL.as_secs().max(1);
+                duration.div_ceil(interval).max(1) as u32
+            }
+            (None, None) => 1,
+        }
+    }
+
+    /// Nominal time between slice starts (before jitter).
+    pub fn slice_interval(&self) -> Duration {
+        match self.duration_secs {
+            Some(duration) => {
+                Duration::from_secs_f64(duration as f64 / self.effective_slice_count() as f64)
+            }
+            None => DEFAULT_TWAP_SLICE_INTERVAL,
+        }
+    }
+
+    /// How long a passive slice rests before escalating to IOC.
+    pub fn slice_timeout(&self) -> Duration {
+        self.slice_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| self.slice_interval() / 2)
+    }
+}
+
+impl InfinityGridConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.symbol.contains('/') || self.symbol.len() < 3 {
+            return Err(anyhow::anyhow!(
+                "Spot symbol must be in 'Base/Quote' format"
+            ));
+        }
+        if self.grid_range_low <= 0.0 {
+            return Err(anyhow::anyhow!("grid_range_low must be positive."));
+        }
+        if self.spread_bips <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "spread_bips {} must be positive.",
+                self.spread_bips
+            ));
+        }
+        if INVESTMENT_BUFFER_SPOT.markdown(self.level_value) < MIN_NOTIONAL_VALUE {
+            return Err(anyhow::anyhow!(
+                "level_value {} is below the minimum order value ({}) after the investment buffer.",
+                self.level_value,
+                MIN_NOTIONAL_VALUE
+            ));
+        }
+        if self.levels_above == 0 {
+            return Err(anyhow::anyhow!("levels_above must be at least 1."));
+        }
+        if self.max_levels == Some(0) {
+            return Err(anyhow::anyhow!("max_levels must be positive."));
+        }
+        if let Some(max) = self.max_levels {
+            if max as f64 * self.level_value > self.total_investment {
+                return Err(anyhow::anyhow!(
+                    "max_levels {} x level_value {} exceeds total_investment {}.",
+                    max,
+                    self.level_value,
+                    self.total_investment
+                ));
+            }
+        }
+        // The smallest ladder has two zones
+        if self.level_cap() < 2 {
+            return Err(anyhow::anyhow!(
+                "total_investment {} covers fewer than 2 zones of level_value {}.",
+                self.total_investment,
+                self.level_value
+            ));
+        }
+        if let Some(trigger) = self.trigger_price {
+            if trigger < self.grid_range_low {
+                return Err(anyhow::anyhow!(
+                    "Trigger price {} is below grid_range_low {}.",
+                    trigger,
+                    self.grid_range_low
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Most zones the ladder may grow to: `max_levels`, or as many as
+    /// `total_investment` covers.
+    pub fn level_cap(&self) -> u32 {
+        self.max_levels
+            .unwrap_or((self.total_investment / self.level_value).floor() as u32)
+    }
+
+    /// Bounded spot grid for the ladder built around `entry_price`, with
+    /// `level_value` of investment per zone.
+    pub fn ladder_config(&self, entry_price: f64) -> SpotGridConfig {
+        let levels = calculate_infinity_grid_prices(
+            self.grid_range_low,
+            self.spread_bips,
+            entry_price,
+            self.levels_above,
+        );
+        let zones = levels.len().saturating_sub(1);
+        SpotGridConfig {
+            symbol: self.symbol.clone(),
+            grid_range_high: levels.last().copied().unwrap_or(self.grid_range_low),
+            grid_range_low: self.grid_range_low,
+            grid_type: GridType::Geometric,
+            grid_count: None,
+            spread_bips: None,
+            levels: Some(levels),
+            ranges: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            size_distribution: SizeDistribution::default(),
+            compound: None,
+            total_investment: self.level_value * zones as f64,
+            trigger_price: self.trigger_price,
+        }
+    }
+}
+
+impl RebalanceConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.quote_asset.trim().is_empty() {
+            return Err(anyhow::anyhow!("quote_asset must not be empty."));
+        }
+        if let Some((asset, weight)) = self.targets.iter().find(|(_, w)| **w < 0.0) {
+            return Err(anyhow::anyhow!(
+                "Target weight for {} must not be negative (got {}).",
+                asset,
+                weight
+            ));
+        }
+        if self.targets.values().sum::<f64>() <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Target weights must sum to a positive value."
+            ));
+        }
+        if self.markets().is_empty() {
+            return Err(anyhow::anyhow!(
+                "targets must include at least one asset other than {}.",
+                self.quote_asset
+            ));
+        }
+        if self.drift_band_pct.is_none() && self.rebalance_interval_secs.is_none() {
+            return Err(anyhow::anyhow!(
+                "Either drift_band_pct or rebalance_interval_secs must be specified."
+            ));
+        }
+        if let Some(band) = self.drift_band_pct {
+            if band <= 0.0 || band >= 100.0 {
+                return Err(anyhow::anyhow!(
+                    "drift_band_pct {} must be in (0, 100).",
+                    band
+                ));
+            }
+        }
+        if self.rebalance_interval_secs == Some(0) {
+            return Err(anyhow::anyhow!("rebalance_interval_secs must be positive."));
+        }
+        if self.post_only_offset_bips < 0.0 {
+            return Err(anyhow::anyhow!(
+                "post_only_offset_bips must not be negative."
+            ));
+        }
+        if self.order_timeout_secs == 0 {
+            return Err(anyhow::anyhow!("order_timeout_secs must be positive."));
+        }
+        Ok(())
+    }
+
+    /// Spot pairs ("BASE/QUOTE") for every non-quote target asset.
+    pub fn markets(&self) -> Vec<String> {
+        self.targets
+            .keys()
+            .filter(|asset| **asset != self.quote_asset)
+            .map(|asset| format!("{}/{}", asset, self.quote_asset))
+            .collect()
+    }
+
+    /// Target weights normalized to sum to 1.0.
+    pub fn normalized_targets(&self) -> BTreeMap<String, f64> {
+        let total: f64 = self.targets.values().sum();
+        self.targets
+            .iter()
+            .map(|(asset, weight)| (asset.clone(), weight / total))
+            .collect()
+    }
 }
 
 pub fn print_strategy_help() {
@@ -218,6 +1203,12 @@ pub fn print_strategy_help() {
     println!("     - grid_type (String): 'geometric' (default) or 'arithmetic'.");
     println!("     - grid_count (u32): fixed-count grid.");
     println!("     - spread_bips (f64): geometric spacing in basis points.");
+    println!("     - spacing_mode (String): 'fixed' (default) or 'atr' (spacing from candles).");
+    println!("     - atr (table): candle_interval, lookback, multiplier, min/max_spread_bips, recompute_secs.");
+    println!("     - levels ([f64], Optional): explicit ascending price levels instead of grid_count/spread_bips.");
+    println!("     - ranges (array of tables, Optional): adjacent sub-ranges with low, high, grid_count or spread_bips, investment.");
+    println!("     - size_distribution (String or [f64], Optional): 'flat' (default), 'linear', 'geometric', 'pyramid', or one weight per zone.");
+    println!("     - compound (table, Optional): allocation ('proportional' or 'nearest'), nearest_zones, interval_secs.");
     println!("     - total_investment (f64): Total base asset value to invest.");
     println!("     - trigger_price (Option<f64>): Price to trigger strategy start (optional).");
     println!();
@@ -233,12 +1224,65 @@ pub fn print_strategy_help() {
     println!("     - grid_type (String): 'geometric' (default) or 'arithmetic'.");
     println!("     - grid_count (u32): fixed-count grid.");
     println!("     - spread_bips (f64): geometric spacing in basis points.");
+    println!("     - spacing_mode (String): 'fixed' (default) or 'atr' (spacing from candles).");
+    println!("     - atr (table): candle_interval, lookback, multiplier, min/max_spread_bips, recompute_secs.");
+    println!("     - levels ([f64], Optional): explicit ascending price levels instead of grid_count/spread_bips.");
+    println!("     - ranges (array of tables, Optional): adjacent sub-ranges with low, high, grid_count or spread_bips, investment.");
+    println!("     - size_distribution (String or [f64], Optional): 'flat' (default), 'linear', 'geometric', 'pyramid', or one weight per zone.");
+    println!("     - compound (table, Optional): allocation ('proportional' or 'nearest'), nearest_zones, interval_secs.");
     println!(
         "     - total_investment (f64): Total cost basis in USDC.
      - grid_bias (String): 'long' or 'short'.
      - trigger_price (Option<f64>): Price to trigger strategy start (optional)."
     );
     println!();
+
+    println!("3. TWAP Strategy (type = 'twap')");
+    println!("   Description: Executes a target size gradually in time-sliced orders.");
+    println!("   Parameters:");
+    println!("     - symbol (String): Spot pair (e.g., 'HYPE/USDC') or perp coin (e.g., 'BTC').");
+    println!("     - side (String): 'buy' or 'sell'.");
+    println!("     - target_size (f64): Total base size to execute.");
+    println!("     - duration_secs (u64): Execution window in seconds.");
+    println!("     - slice_count (u32): Number of slices (one per minute if no duration).");
+    println!("     - randomize_pct (f64): Slice timing jitter in percent (default: 0).");
+    println!("     - price_limit (Option<f64>): Max price for buys / min price for sells.");
+    println!("     - max_participation_pct (Option<f64>): Cap slice size to % of traded volume.");
+    println!("     - slice_timeout_secs (Option<u64>): Passive rest time before IOC.");
+    println!("     - reduce_only (bool): Perp only, for unwinding a position (default: false).");
+    println!();
+
+    println!("4. Rebalance Strategy (type = 'rebalance')");
+    println!("   Description: Keeps a basket of spot tokens at target weights.");
+    println!("   Parameters:");
+    println!("     - quote_asset (String): Valuation and trading asset (default: 'USDC').");
+    println!("     - targets (table): Relative target weight per asset, e.g. {{ HYPE = 60, USDC = 40 }}.");
+    println!(
+        "     - drift_band_pct (Option<f64>): Rebalance when a weight drifts this many points."
+    );
+    println!("     - rebalance_interval_secs (Option<u64>): Rebalance on a fixed schedule.");
+    println!(
+        "     - post_only_offset_bips (f64): Distance from mid for resting orders (default: 5)."
+    );
+    println!(
+        "     - order_timeout_secs (u64): Cancel unfilled orders after this long (default: 300)."
+    );
+    println!();
+
+    println!("5. Infinity Grid Strategy (type = 'infinity_grid')");
+    println!(
+        "   Description: Spot grid with a lower bound only; adds levels as price makes new highs."
+    );
+    println!("   Parameters:");
+    println!("     - symbol (String): The trading pair symbol (e.g., 'HYPE/USDC').");
+    println!("     - grid_range_low (f64): The lowest level of the ladder.");
+    println!("     - spread_bips (f64): Geometric spacing between levels in basis points.");
+    println!("     - level_value (f64): Quote value held by each zone.");
+    println!("     - levels_above (u32): Zones kept above price (default: 5).");
+    println!("     - total_investment (f64): Most quote the ladder may hold.");
+    println!("     - max_levels (Option<u32>): Stop adding levels at this many zones (default: total_investment / level_value).");
+    println!("     - trigger_price (Option<f64>): Price to trigger strategy start (optional).");
+    println!();
 }
 
 #[cfg(test)]
@@ -254,6 +1298,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(10),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: None,
         });
@@ -274,6 +1324,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(10),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: Some(3000.0),
         });
@@ -291,6 +1347,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(2),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: None,
         });
@@ -311,6 +1373,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(5),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: None,
         });
@@ -326,6 +1394,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(5),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: -100.0,
             trigger_price: None,
         });
@@ -344,6 +1418,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(5),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             grid_bias: GridBias::Long,
             trigger_price: None,
@@ -360,6 +1440,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(5),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             grid_bias: GridBias::Long,
             trigger_price: None,
@@ -376,6 +1462,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(10),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: None,
         });
@@ -389,6 +1481,12 @@ mod tests {
             grid_type: GridType::default(),
             grid_count: None,
             spread_bips: Some(100.0), // 1%
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: None,
         });
@@ -403,6 +1501,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(10),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             grid_bias: GridBias::Long,
             trigger_price: None,
@@ -410,6 +1514,108 @@ mod tests {
         assert!(perp.validate().is_ok());
     }
 
+    fn twap_config() -> TwapConfig {
+        TwapConfig {
+            symbol: "HYPE/USDC".to_string(),
+            side: OrderSide::Buy,
+            target_size: 100.0,
+            duration_secs: Some(600),
+            slice_count: None,
+            randomize_pct: 0.0,
+            price_limit: None,
+            max_participation_pct: None,
+            slice_timeout_secs: None,
+            reduce_only: false,
+        }
+    }
+
+    #[test]
+    fn test_twap_slice_schedule_derivation() {
+        let by_duration = twap_config();
+        assert_eq!(by_duration.effective_slice_count(), 10);
+        assert_eq!(by_duration.slice_interval(), Duration::from_secs(60));
+        assert_eq!(by_duration.slice_timeout(), Duration::from_secs(30));
+
+        let both = TwapConfig {
+            slice_count: Some(4),
+            ..twap_config()
+        };
+        assert_eq!(both.slice_interval(), Duration::from_secs(150));
+
+        let by_count = TwapConfig {
+            duration_secs: None,
+            slice_count: Some(5),
+            ..twap_config()
+        };
+        assert_eq!(by_count.slice_interval(), DEFAULT_TWAP_SLICE_INTERVAL);
+    }
+
+    #[test]
+    fn test_twap_validation() {
+        assert!(StrategyConfig::Twap(twap_config()).validate().is_ok());
+
+        let no_schedule = TwapConfig {
+            duration_secs: None,
+            ..twap_config()
+        };
+        assert_eq!(
+            no_schedule.validate().unwrap_err().to_string(),
+            "Either duration_secs or slice_count must be specified."
+        );
+
+        let spot_reduce_only = TwapConfig {
+            reduce_only: true,
+            ..twap_config()
+        };
+        assert!(spot_reduce_only.validate().is_err());
+
+        let long_timeout = TwapConfig {
+            slice_timeout_secs: Some(60),
+            ..twap_config()
+        };
+        assert!(long_timeout.validate().is_err());
+    }
+
+    #[test]
+    fn test_rebalance_markets_and_validation() {
+        let toml = r#"
+type = "rebalance"
+drift_band_pct = 5.0
+
+[targets]
+HYPE = 50
+PURR = 20
+USDC = 30
+"#;
+        let config: StrategyConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.symbol(), "USDC");
+        assert_eq!(
+            config.markets(),
+            vec!["HYPE/USDC".to_string(), "PURR/USDC".to_string()]
+        );
+
+        if let StrategyConfig::Rebalance(c) = &config {
+            let weights = c.normalized_targets();
+            assert!((weights["HYPE"] - 0.5).abs() < 1e-12);
+            assert!((weights["USDC"] - 0.3).abs() < 1e-12);
+
+            let quote_only = RebalanceConfig {
+                targets: BTreeMap::from([("USDC".to_string(), 1.0)]),
+                ..c.clone()
+            };
+            assert!(quote_only.validate().is_err());
+
+            let no_trigger = RebalanceConfig {
+                drift_band_pct: None,
+                ..c.clone()
+            };
+            assert!(no_trigger.validate().is_err());
+        } else {
+            panic!("Expected rebalance config");
+        }
+    }
+
     #[test]
     fn test_validation_rejects_arithmetic_with_spread_bips() {
         let spot_bips = StrategyConfig::SpotGrid(SpotGridConfig {
@@ -419,6 +1625,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: None,
             spread_bips: Some(100.0),
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price: None,
         });
@@ -426,4 +1638,410 @@ mod tests {
         let err = spot_bips.validate().unwrap_err().to_string();
         assert_eq!(err, "grid_type must be geometric when spread_bips is used.");
     }
+
+    #[test]
+    fn test_atr_spacing_parsing_and_validation() {
+        let toml = r#"
+type = "perp_grid"
+symbol = "HYPE"
+leverage = 5
+grid_range_low = 20.0
+grid_range_high = 30.0
+grid_type = "geometric"
+spacing_mode = "atr"
+total_investment = 500.0
+grid_bias = "long"
+
+[atr]
+candle_interval = "4h"
+lookback = 20
+multiplier = 1.5
+min_spread_bips = 25.0
+recompute_secs = 3600
+"#;
+        let config: StrategyConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+        let (symbol, atr) = config.candle_feed().unwrap();
+        assert_eq!(symbol, "HYPE");
+        assert_eq!(atr.lookback, 20);
+        assert_eq!(atr.candle_duration().unwrap().as_secs(), 4 * 3600);
+        assert_eq!(atr.recompute_interval().unwrap().as_secs(), 3600);
+
+        let StrategyConfig::PerpGrid(perp) = config else {
+            panic!("Expected perp grid config");
+        };
+        let with_count = PerpGridConfig {
+            grid_count: Some(10),
+            ..perp.clone()
+        };
+        assert!(StrategyConfig::PerpGrid(with_count).validate().is_err());
+
+        let arithmetic = PerpGridConfig {
+            grid_type: GridType::Arithmetic,
+            ..perp.clone()
+        };
+        assert!(StrategyConfig::PerpGrid(arithmetic).validate().is_err());
+
+        let bad_interval = PerpGridConfig {
+            atr: AtrSpacingConfig {
+                candle_interval: "7m".to_string(),
+                ..perp.atr.clone()
+            },
+            ..perp.clone()
+        };
+        assert!(StrategyConfig::PerpGrid(bad_interval).validate().is_err());
+
+        let fixed = PerpGridConfig {
+            spacing_mode: SpacingMode::Fixed,
+            grid_count: Some(10),
+            grid_type: GridType::Arithmetic,
+            ..perp
+        };
+        assert!(StrategyConfig::PerpGrid(fixed.clone()).validate().is_ok());
+        assert!(StrategyConfig::PerpGrid(fixed).candle_feed().is_none());
+    }
+
+    #[test]
+    fn test_grid_fee_check() {
+        let fees = FeeRates {
+            maker: 0.0004,
+            taker: 0.0007,
+        };
+        let spot = SpotGridConfig {
+            symbol: "HYPE/USDC".to_string(),
+            grid_range_high: 101.0,
+            grid_range_low: 100.0,
+            grid_type: GridType::Geometric,
+            grid_count: Some(21),
+            spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
+            total_investment: 1000.0,
+            trigger_price: None,
+        };
+
+        // 20 zones over 1% -> ~0.05% spacing, below the 0.08% round trip
+        let tight = StrategyConfig::SpotGrid(spot.clone())
+            .fee_check(&fees)
+            .unwrap();
+        assert!((tight.roundtrip_fee_pct - 0.08).abs() < 1e-12);
+        assert!(tight.net_pct() < 0.0);
+        assert!(tight.validate().is_err());
+
+        // 0.12% spacing is profitable but fees take two thirds of it
+        let marginal = StrategyConfig::SpotGrid(SpotGridConfig {
+            grid_count: None,
+            spread_bips: Some(12.0),
+            ..spot.clone()
+        })
+        .fee_check(&fees)
+        .unwrap();
+        assert!(marginal.validate().is_ok());
+        assert!(marginal.is_marginal());
+
+        let wide = StrategyConfig::SpotGrid(SpotGridConfig {
+            grid_count: Some(3),
+            ..spot.clone()
+        })
+        .fee_check(&fees)
+        .unwrap();
+        assert!(wide.validate().is_ok());
+        assert!(!wide.is_marginal());
+
+        // ATR grids are only checked against their configured floor
+        let atr = SpotGridConfig {
+            grid_count: None,
+            spacing_mode: SpacingMode::Atr,
+            ..spot
+        };
+        assert!(StrategyConfig::SpotGrid(atr.clone())
+            .fee_check(&fees)
+            .is_none());
+        let floored = SpotGridConfig {
+            atr: AtrSpacingConfig {
+                min_spread_bips: Some(5.0),
+                ..AtrSpacingConfig::default()
+            },
+            ..atr
+        };
+        assert!(StrategyConfig::SpotGrid(floored)
+            .fee_check(&fees)
+            .unwrap()
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_compound_config_parsing_and_validation() {
+        let toml = r#"
+type = "spot_grid"
+symbol = "HYPE/USDC"
+grid_range_low = 20.0
+grid_range_high = 30.0
+grid_count = 11
+total_investment = 500.0
+
+[compound]
+allocation = "nearest"
+nearest_zones = 2
+interval_secs = 600
+"#;
+        let config: StrategyConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+        let StrategyConfig::SpotGrid(spot) = config else {
+            panic!("Expected spot grid config");
+        };
+        let compound = spot.compound.clone().unwrap();
+        assert_eq!(compound.allocation, CompoundAllocation::Nearest);
+        assert_eq!(compound.nearest_zones, 2);
+        assert_eq!(compound.interval().unwrap().as_secs(), 600);
+
+        let no_zones = SpotGridConfig {
+            compound: Some(CompoundConfig {
+                nearest_zones: 0,
+                ..compound.clone()
+            }),
+            ..spot.clone()
+        };
+        assert!(no_zones.validate().is_err());
+
+        let zero_interval = SpotGridConfig {
+            compound: Some(CompoundConfig {
+                interval_secs: Some(0),
+                ..compound
+            }),
+            ..spot
+        };
+        assert!(zero_interval.validate().is_err());
+    }
+
+    #[test]
+    fn test_size_distribution_parsing_and_validation() {
+        let base = r#"
+type = "perp_grid"
+symbol = "HYPE"
+leverage = 5
+grid_range_low = 20.0
+grid_range_high = 30.0
+grid_count = 5
+total_investment = 500.0
+grid_bias = "long"
+"#;
+        let config: StrategyConfig = toml::from_str(base).unwrap();
+        let StrategyConfig::PerpGrid(perp) = config else {
+            panic!("Expected perp grid config");
+        };
+        assert!(perp.size_distribution.is_flat());
+
+        let shaped: StrategyConfig =
+            toml::from_str(&format!("{}size_distribution = \"pyramid\"\n", base)).unwrap();
+        let StrategyConfig::PerpGrid(shaped) = shaped else {
+            panic!("Expected perp grid config");
+        };
+        assert_eq!(
+            shaped.size_distribution,
+            SizeDistribution::Shape(DistributionShape::Pyramid)
+        );
+
+        let weighted: StrategyConfig = toml::from_str(&format!(
+            "{}size_distribution = [1.0, 2.0, 3.0, 4.0]\n",
+            base
+        ))
+        .unwrap();
+        assert!(weighted.validate().is_ok());
+
+        let wrong_len = PerpGridConfig {
+            size_distribution: SizeDistribution::Weights(vec![1.0, 2.0]),
+            ..perp.clone()
+        };
+        assert!(wrong_len.validate().is_err());
+
+        let negative = PerpGridConfig {
+            size_distribution: SizeDistribution::Weights(vec![1.0, -1.0, 1.0, 1.0]),
+            ..perp.clone()
+        };
+        assert!(negative.validate().is_err());
+
+        let atr = PerpGridConfig {
+            grid_count: None,
+            spacing_mode: SpacingMode::Atr,
+            size_distribution: SizeDistribution::Weights(vec![1.0, 1.0, 1.0, 1.0]),
+            ..perp
+        };
+        assert!(atr.validate().is_err());
+
+        assert!(toml::from_str::<StrategyConfig>(&format!(
+            "{}size_distribution = \"steep\"\n",
+            base
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_levels_and_ranges_parsing_and_validation() {
+        let toml = r#"
+type = "spot_grid"
+symbol = "HYPE/USDC"
+grid_range_low = 20.0
+grid_range_high = 30.0
+levels = [20.0, 21.0, 21.5, 22.0, 25.0, 30.0]
+total_investment = 500.0
+"#;
+        let config: StrategyConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+        // Tightest zone is 21.5 -> 22.0
+        let min_pct = config.min_grid_spacing_pct().unwrap();
+        assert!((min_pct - 0.5 / 21.5 * 100.0).abs() < 1e-9);
+        let StrategyConfig::SpotGrid(spot) = config else {
+            panic!("Expected spot grid config");
+        };
+
+        let unsorted = SpotGridConfig {
+            levels: Some(vec![20.0, 22.0, 21.0]),
+            ..spot.clone()
+        };
+        assert!(unsorted.validate().is_err());
+        let outside = SpotGridConfig {
+            levels: Some(vec![20.0, 25.0, 31.0]),
+            ..spot.clone()
+        };
+        assert!(outside.validate().is_err());
+        let with_count = SpotGridConfig {
+            grid_count: Some(5),
+            ..spot.clone()
+        };
+        assert!(with_count.validate().is_err());
+
+        let toml = r#"
+type = "perp_grid"
+symbol = "HYPE"
+leverage = 5
+grid_range_low = 20.0
+grid_range_high = 30.0
+total_investment = 500.0
+grid_bias = "long"
+
+[[ranges]]
+low = 20.0
+high = 22.0
+grid_count = 9
+investment = 300.0
+
+[[ranges]]
+low = 22.0
+high = 30.0
+spread_bips = 400.0
+investment = 200.0
+"#;
+        let config: StrategyConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+        let StrategyConfig::PerpGrid(perp) = config else {
+            panic!("Expected perp grid config");
+        };
+        let levels = perp.custom_levels().unwrap();
+        assert_eq!(levels.first(), Some(&20.0));
+        assert_eq!(levels.last(), Some(&30.0));
+        assert!(levels.contains(&22.0));
+
+        let ranges = perp.ranges.clone().unwrap();
+        let gap = PerpGridConfig {
+            ranges: Some(vec![
+                ranges[0].clone(),
+                GridRangeConfig {
+                    low: 23.0,
+                    ..ranges[1].clone()
+                },
+            ]),
+            ..perp.clone()
+        };
+        assert!(gap.validate().is_err());
+
+        let underfunded = PerpGridConfig {
+            total_investment: 600.0,
+            ..perp.clone()
+        };
+        assert!(underfunded.validate().is_err());
+
+        let both_spacings = PerpGridConfig {
+            ranges: Some(vec![GridRangeConfig {
+                grid_count: Some(3),
+                ..ranges[1].clone()
+            }]),
+            total_investment: 200.0,
+            ..perp.clone()
+        };
+        assert!(both_spacings.validate().is_err());
+
+        let weighted = PerpGridConfig {
+            size_distribution: SizeDistribution::Weights(vec![1.0; 10]),
+            ..perp
+        };
+        assert!(weighted.validate().is_err());
+    }
+
+    #[test]
+    fn test_infinity_grid_config() {
+        let toml = r#"
+type = "infinity_grid"
+symbol = "HYPE/USDC"
+grid_range_low = 20.0
+spread_bips = 150.0
+level_value = 25.0
+total_investment = 1000.0
+"#;
+        let config: StrategyConfig = toml::from_str(toml).unwrap();
+        assert!(config.validate().is_ok());
+        assert!(config.is_spot());
+        assert_eq!(config.default_websocket_port(), 8004);
+        assert!((config.min_grid_spacing_pct().unwrap() - 1.5).abs() < 1e-12);
+        assert_eq!(config.grid_range(), Some((20.0, f64::INFINITY)));
+        let StrategyConfig::InfinityGrid(infinity) = config else {
+            panic!("Expected infinity grid config");
+        };
+        assert_eq!(infinity.levels_above, 5);
+        assert_eq!(infinity.level_cap(), 40);
+
+        let ladder = infinity.ladder_config(30.0);
+        assert!(ladder.validate().is_ok());
+        let levels = ladder.levels.clone().unwrap();
+        assert_eq!(ladder.grid_range_high, *levels.last().unwrap());
+        assert_eq!(levels.iter().filter(|&&p| p > 30.0).count(), 6);
+        assert!((ladder.total_investment - 25.0 * (levels.len() - 1) as f64).abs() < 1e-9);
+
+        let tiny = InfinityGridConfig {
+            level_value: 11.0,
+            ..infinity.clone()
+        };
+        assert!(tiny.validate().is_err());
+        let no_levels = InfinityGridConfig {
+            levels_above: 0,
+            ..infinity.clone()
+        };
+        assert!(no_levels.validate().is_err());
+        let over_budget = InfinityGridConfig {
+            max_levels: Some(41),
+            ..infinity.clone()
+        };
+        assert!(over_budget.validate().is_err());
+        let capped = InfinityGridConfig {
+            max_levels: Some(30),
+            ..infinity.clone()
+        };
+        assert!(capped.validate().is_ok());
+        assert_eq!(capped.level_cap(), 30);
+        let one_zone = InfinityGridConfig {
+            total_investment: 40.0,
+            ..infinity.clone()
+        };
+        assert!(one_zone.validate().is_err());
+        let low_trigger = InfinityGridConfig {
+            trigger_price: Some(19.0),
+            ..infinity
+        };
+        assert!(low_trigger.validate().is_err());
+    }
 }
@@ -7,7 +7,7 @@
 // STRATEGY CONSTANTS
 // =============================================================================
 
-use crate::model::Spread;
+use crate::model::{FeeRates, Spread};
 
 // =============================================================================
 // STRATEGY CONSTANTS
@@ -29,17 +29,68 @@ pub const INVESTMENT_BUFFER_SPOT: Spread = Spread::new(0.1);
 /// 0.05% fee buffer for spot
 pub const FEE_BUFFER: Spread = Spread::new(0.05);
 
+/// 0.5% crossing allowance for TWAP IOC slices (bounded by the price limit)
+pub const TWAP_IOC_SLIPPAGE: Spread = Spread::new(0.5);
+
+/// Relative change in ATR spacing required before idle zones are rebuilt (10%)
+pub const ATR_RESPACE_MIN_CHANGE: f64 = 0.1;
+
+/// Per-level growth of zone size with `size_distribution = "geometric"`
+pub const GEOMETRIC_SIZE_RATIO: f64 = 1.25;
+
+/// Warn when round-trip maker fees take more than this share of a zone's spacing (50%)
+pub const MAX_FEE_SHARE_OF_SPACING: f64 = 0.5;
+
+/// Base-tier perp fees, used when the account's fee tier cannot be fetched
+pub const DEFAULT_PERP_FEES: FeeRates = FeeRates {
+    maker: 0.00015,
+    taker: 0.00045,
+};
+
+/// Base-tier spot fees, used when the account's fee tier cannot be fetched
+pub const DEFAULT_SPOT_FEES: FeeRates = FeeRates {
+    maker: 0.0004,
+    taker: 0.0007,
+};
+
 // =============================================================================
 // ENGINE TIMER INTERVALS
 // =============================================================================
 
 use std::time::Duration;
 
+/// Default time between TWAP slices when only one of duration/slice count is set (1 minute)
+pub const DEFAULT_TWAP_SLICE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Pause after a rebalance so balances refresh before drift is measured again (1 minute)
+pub const REBALANCE_COOLDOWN: Duration = Duration::from_secs(60);
+
 /// Interval for refreshing account balances (30 seconds)
 pub const BALANCE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
 
 /// Interval for broadcasting status summary updates (5 seconds)
 pub const STATUS_SUMMARY_INTERVAL: Duration = Duration::from_secs(5);
 
+/// Interval for checking the config file for edits (5 seconds)
+pub const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Interval for order reconciliation checks (2 minutes)
 pub const RECONCILIATION_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// Warn in preflight when the API wallet approval expires within this (7 days)
+pub const AGENT_EXPIRY_WARNING: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Requests preflight expects to be left beyond the opening orders, for cancels and re-placements
+pub const PREFLIGHT_REQUEST_RESERVE: u64 = 200;
+
+/// Time allowed for a remote signer to answer one request (5 seconds)
+pub const SIGNER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest difference between a nonce and the signer's clock it will sign (1 minute)
+pub const MAX_SIGNER_NONCE_SKEW: Duration = Duration::from_secs(60);
+
+/// How often the signer refreshes the mids it values orders at (5 seconds)
+pub const SIGNER_MID_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Oldest mid the signer values an order at; without a newer one it refuses (30 seconds)
+pub const MAX_SIGNER_MID_AGE: Duration = Duration::from_secs(30);
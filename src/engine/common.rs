@@ -1,11 +1,16 @@
 //! Common engine utilities shared between live and simulation engines.
 
+use crate::config::strategy::{AtrSpacingConfig, StrategyConfig};
+use crate::constants::{DEFAULT_PERP_FEES, DEFAULT_SPOT_FEES, MAX_FEE_SHARE_OF_SPACING};
 use crate::engine::context::{MarketInfo, StrategyContext};
+use crate::model::{Candle, FeeRates};
 use anyhow::{anyhow, Result};
 use ethers::types::H160;
 use hyperliquid_rust_sdk::{BaseUrl, InfoClient};
+use serde::Deserialize;
 use std::collections::HashMap;
-use tracing::{error, info};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
 
 /// Create an InfoClient based on network configuration.
 pub async fn setup_info_client(network: &str) -> Result<InfoClient> {
@@ -119,3 +124,188 @@ pub async fn fetch_balances(
         Err(e) => error!("{}Failed to fetch perp balances: {}", log_prefix, e),
     }
 }
+
+/// Fetch the closed candles needed for ATR spacing and store them in the context.
+pub async fn fetch_candles(
+    info_client: &InfoClient,
+    market: &MarketInfo,
+    atr: &AtrSpacingConfig,
+    ctx: &mut StrategyContext,
+    log_prefix: &str,
+) {
+    let Some(interval) = atr.candle_duration() else {
+        return;
+    };
+    let interval_ms = interval.as_millis() as u64;
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    // One spare candle for the previous close, one for the candle still forming
+    let start_ms = now_ms.saturating_sub(interval_ms * (atr.lookback as u64 + 2));
+
+    match info_client
+        .candles_snapshot(
+            market.coin.clone(),
+            atr.candle_interval.clone(),
+            start_ms,
+            now_ms,
+        )
+        .await
+    {
+        Ok(snapshot) => {
+            let candles: Vec<Candle> = snapshot
+                .iter()
+                .filter(|c| c.time_open + interval_ms <= now_ms)
+                .map(|c| Candle {
+                    open_time: c.time_open,
+                    open: c.open.parse().unwrap_or(0.0),
+                    high: c.high.parse().unwrap_or(0.0),
+                    low: c.low.parse().unwrap_or(0.0),
+                    close: c.close.parse().unwrap_or(0.0),
+                })
+                .collect();
+            if candles.len() <= atr.lookback as usize {
+                warn!(
+                    "{}Only {} {} candles available for {} (ATR needs {})",
+                    log_prefix,
+                    candles.len(),
+                    atr.candle_interval,
+                    market.symbol,
+                    atr.lookback + 1
+                );
+            } else {
+                info!(
+                    "{}Loaded {} {} candles for {}",
+                    log_prefix,
+                    candles.len(),
+                    atr.candle_interval,
+                    market.symbol
+                );
+            }
+            ctx.set_candles(&market.symbol, candles);
+        }
+        Err(e) => error!(
+            "{}Failed to fetch candles for {}: {}",
+            log_prefix, market.symbol, e
+        ),
+    }
+}
+
+/// Fee fields of the `userFees` info response. The SDK response type omits the spot rates.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserFeeRates {
+    user_add_rate: String,
+    user_cross_rate: String,
+    user_spot_add_rate: Option<String>,
+    user_spot_cross_rate: Option<String>,
+}
+
+/// Fetch the account's current maker/taker rates for spot or perp markets.
+pub async fn fetch_fee_rates(
+    info_client: &InfoClient,
+    user_address: H160,
+    spot: bool,
+) -> Result<FeeRates> {
+    let request = serde_json::json!({ "type": "userFees", "user": user_address });
+    let response = info_client
+        .http_client
+        .post("/info", request.to_string())
+        .await
+        .map_err(|e| anyhow!("userFees request failed: {}", e))?;
+    let rates: UserFeeRates = serde_json::from_str(&response)?;
+
+    let (add, cross) = if spot {
+        (
+            rates
+                .user_spot_add_rate
+                .ok_or_else(|| anyhow!("userFees response has no spot rates"))?,
+            rates
+                .user_spot_cross_rate
+                .ok_or_else(|| anyhow!("userFees response has no spot rates"))?,
+        )
+    } else {
+        (rates.user_add_rate, rates.user_cross_rate)
+    };
+    Ok(FeeRates {
+        maker: add.parse()?,
+        taker: cross.parse()?,
+    })
+}
+
+/// Fee rates for the strategy's market type, falling back to base-tier rates.
+pub async fn fee_rates_or_default(
+    info_client: &InfoClient,
+    user_address: H160,
+    config: &StrategyConfig,
+    log_prefix: &str,
+) -> FeeRates {
+    let spot = config.is_spot();
+    match fetch_fee_rates(info_client, user_address, spot).await {
+        Ok(fees) => {
+            info!(
+                "{}Account fee tier: maker {:.4}% taker {:.4}%",
+                log_prefix,
+                fees.maker * 100.0,
+                fees.taker * 100.0
+            );
+            fees
+        }
+        Err(e) => {
+            let fees = if spot {
+                DEFAULT_SPOT_FEES
+            } else {
+                DEFAULT_PERP_FEES
+            };
+            warn!(
+                "{}Failed to fetch fee tier ({}). Assuming base-tier maker fee {:.4}%",
+                log_prefix,
+                e,
+                fees.maker * 100.0
+            );
+            fees
+        }
+    }
+}
+
+/// Check grid spacing against the account's maker fees.
+///
+/// Fails when a roundtrip in the tightest zone cannot cover its fees and warns when
+/// fees take more than `MAX_FEE_SHARE_OF_SPACING` of it.
+pub async fn validate_grid_fees(
+    info_client: &InfoClient,
+    user_address: H160,
+    config: &StrategyConfig,
+    log_prefix: &str,
+) -> Result<()> {
+    if config.min_grid_spacing_pct().is_none() {
+        return Ok(());
+    }
+    let fees = fee_rates_or_default(info_client, user_address, config, log_prefix).await;
+    let Some(check) = config.fee_check(&fees) else {
+        return Ok(());
+    };
+
+    check.validate()?;
+    if check.is_marginal() {
+        warn!(
+            "{}Round-trip fees {:.4}% take {:.0}% of the {:.4}% grid spacing (limit {:.0}%). Net per roundtrip: {:.4}%",
+            log_prefix,
+            check.roundtrip_fee_pct,
+            check.fee_share() * 100.0,
+            check.spacing_pct,
+            MAX_FEE_SHARE_OF_SPACING * 100.0,
+            check.net_pct()
+        );
+    } else {
+        info!(
+            "{}Grid spacing {:.4}% nets {:.4}% per roundtrip after {:.4}% fees",
+            log_prefix,
+            check.spacing_pct,
+            check.net_pct(),
+            check.roundtrip_fee_pct
+        );
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use crate::model::{Cloid, OrderRequest};
+use crate::model::{Candle, Cloid, OrderRequest};
+use crate::notify::Alert;
 use std::collections::HashMap;
 
 pub const MIN_NOTIONAL_VALUE: f64 = 11.0;
@@ -72,12 +73,21 @@ pub struct Balance {
     pub available: f64,
 }
 
+#[derive(Clone)]
 pub struct StrategyContext {
     pub markets: HashMap<String, MarketInfo>,
     pub spot_balances: HashMap<String, Balance>,
     pub perp_balances: HashMap<String, Balance>,
     pub order_queue: Vec<OrderRequest>,
     pub cancellation_queue: Vec<Cloid>,
+    /// Cumulative traded volume (base units) observed on the public trade feed, by symbol.
+    pub traded_volume: HashMap<String, f64>,
+    /// Latest mid price for every market the strategy trades, by symbol.
+    pub mids: HashMap<String, f64>,
+    /// Recent candles (oldest first) for strategies that size levels from volatility, by symbol.
+    pub candles: HashMap<String, Vec<Candle>>,
+    /// Alerts raised by the strategy, drained by the engine into the notifier.
+    pub alerts: Vec<Alert>,
 }
 
 impl StrategyContext {
@@ -88,6 +98,10 @@ impl StrategyContext {
             perp_balances: HashMap::new(),
             order_queue: Vec::new(),
             cancellation_queue: Vec::new(),
+            traded_volume: HashMap::new(),
+            mids: HashMap::new(),
+            candles: HashMap::new(),
+            alerts: Vec::new(),
         }
     }
 
@@ -115,6 +129,36 @@ impl StrategyContext {
         self.cancellation_queue.push(cloid);
     }
 
+    pub fn raise_alert(&mut self, alert: Alert) {
+        self.alerts.push(alert);
+    }
+
+    // --- Market Activity ---
+
+    pub fn record_trade_volume(&mut self, symbol: &str, size: f64) {
+        *self.traded_volume.entry(symbol.to_string()).or_insert(0.0) += size;
+    }
+
+    pub fn get_traded_volume(&self, symbol: &str) -> f64 {
+        self.traded_volume.get(symbol).copied().unwrap_or(0.0)
+    }
+
+    pub fn update_mid(&mut self, symbol: &str, price: f64) {
+        self.mids.insert(symbol.to_string(), price);
+    }
+
+    pub fn get_mid(&self, symbol: &str) -> Option<f64> {
+        self.mids.get(symbol).copied()
+    }
+
+    pub fn set_candles(&mut self, symbol: &str, candles: Vec<Candle>) {
+        self.candles.insert(symbol.to_string(), candles);
+    }
+
+    pub fn get_candles(&self, symbol: &str) -> &[Candle] {
+        self.candles.get(symbol).map(Vec::as_slice).unwrap_or(&[])
+    }
+
     // --- Balance Accessors ---
 
     pub fn update_spot_balance(&mut self, asset: String, total: f64, available: f64) {
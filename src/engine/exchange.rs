@@ -0,0 +1,242 @@
+//! Exchange calls of the live engine, signed locally or by a remote signer.
+//!
+//! With a local key the SDK's `ExchangeClient` builds, signs and posts each
+//! action. With a remote signer the client only supplies metadata and the
+//! HTTP connection: actions are built here in the SDK's wire format, signed
+//! by the `signer` process and posted as the SDK would.
+
+use crate::signer::RemoteSigner;
+use anyhow::{anyhow, Context, Result};
+use hyperliquid_rust_sdk::{
+    Actions, ClientCancelRequestCloid, ClientOrder, ClientOrderRequest, ExchangeClient,
+    ExchangeResponseStatus,
+};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// Decimals the exchange hashes prices and sizes with.
+const WIRE_DECIMALS: usize = 8;
+
+/// Last nonce handed out; nonces must be unique per signer.
+static LAST_NONCE: AtomicU64 = AtomicU64::new(0);
+
+pub struct TradingClient {
+    client: ExchangeClient,
+    signer: Option<RemoteSigner>,
+}
+
+impl TradingClient {
+    /// Signs with the wallet `client` was created with.
+    pub fn local(client: ExchangeClient) -> Self {
+        Self {
+            client,
+            signer: None,
+        }
+    }
+
+    /// Signs through `signer`; the wallet of `client` is never used.
+    pub fn remote(client: ExchangeClient, signer: RemoteSigner) -> Self {
+        Self {
+            client,
+            signer: Some(signer),
+        }
+    }
+
+    pub async fn bulk_order(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+    ) -> Result<ExchangeResponseStatus> {
+        if self.signer.is_none() {
+            return Ok(self.client.bulk_order(orders, None).await?);
+        }
+        let orders = orders
+            .into_iter()
+            .map(|order| Ok(order_wire(self.asset(&order.asset)?, order)))
+            .collect::<Result<Vec<_>>>()?;
+        self.submit(json!({"type": "order", "orders": orders, "grouping": "na"}))
+            .await
+    }
+
+    pub async fn bulk_cancel_by_cloid(
+        &self,
+        cancels: Vec<ClientCancelRequestCloid>,
+    ) -> Result<ExchangeResponseStatus> {
+        if self.signer.is_none() {
+            return Ok(self.client.bulk_cancel_by_cloid(cancels, None).await?);
+        }
+        let cancels = cancels
+            .into_iter()
+            .map(|cancel| {
+                Ok(json!({
+                    "asset": self.asset(&cancel.asset)?,
+                    "cloid": cloid_wire(cancel.cloid),
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.submit(json!({"type": "cancelByCloid", "cancels": cancels}))
+            .await
+    }
+
+    pub async fn update_leverage(
+        &self,
+        leverage: u32,
+        coin: &str,
+        is_cross: bool,
+    ) -> Result<ExchangeResponseStatus> {
+        if self.signer.is_none() {
+            return Ok(self
+                .client
+                .update_leverage(leverage, coin, is_cross, None)
+                .await?);
+        }
+        self.submit(json!({
+            "type": "updateLeverage",
+            "asset": self.asset(coin)?,
+            "isCross": is_cross,
+            "leverage": leverage,
+        }))
+        .await
+    }
+
+    fn asset(&self, coin: &str) -> Result<u32> {
+        self.client
+            .coin_to_asset
+            .get(coin)
+            .copied()
+            .ok_or_else(|| anyhow!("Unknown asset '{}'", coin))
+    }
+
+    /// Has `action` signed remotely and posts it to `/exchange`.
+    async fn submit(&self, action: Value) -> Result<ExchangeResponseStatus> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| anyhow!("No remote signer configured"))?;
+        let action: Actions = serde_json::from_value(action)?;
+        let nonce = next_nonce();
+        let signature = signer.sign(&action, nonce).await?;
+        let payload = json!({
+            "action": action,
+            "signature": signature,
+            "nonce": nonce,
+            "vaultAddress": self.client.vault_address,
+        });
+        let response = self
+            .client
+            .http_client
+            .post("/exchange", payload.to_string())
+            .await
+            .map_err(|e| anyhow!("Exchange request failed: {}", e))?;
+        serde_json::from_str(&response)
+            .with_context(|| format!("Invalid exchange response: {}", response))
+    }
+}
+
+/// One order as the SDK's `ClientOrderRequest::convert` writes it.
+fn order_wire(asset: u32, order: ClientOrderRequest) -> Value {
+    let order_type = match order.order_type {
+        ClientOrder::Limit(limit) => json!({"limit": {"tif": limit.tif}}),
+        ClientOrder::Trigger(trigger) => json!({"trigger": {
+            "isMarket": trigger.is_market,
+            "triggerPx": float_wire(trigger.trigger_px),
+            "tpsl": trigger.tpsl,
+        }}),
+    };
+    let mut wire = json!({
+        "a": asset,
+        "b": order.is_buy,
+        "p": float_wire(order.limit_px),
+        "s": float_wire(order.sz),
+        "r": order.reduce_only,
+        "t": order_type,
+    });
+    if let Some(cloid) = order.cloid {
+        wire["c"] = json!(cloid_wire(cloid));
+    }
+    wire
+}
+
+/// Millisecond timestamp, bumped past the previous nonce when called twice
+/// in the same millisecond.
+fn next_nonce() -> u64 {
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let previous = LAST_NONCE
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(now_ms.max(last + 1))
+        })
+        .unwrap_or(0);
+    now_ms.max(previous + 1)
+}
+
+/// Price or size as the exchange hashes it: 8 decimals, trailing zeros trimmed.
+fn float_wire(value: f64) -> String {
+    let mut wire = format!("{:.*}", WIRE_DECIMALS, value);
+    while wire.ends_with('0') {
+        wire.pop();
+    }
+    if wire.ends_with('.') {
+        wire.pop();
+    }
+    if wire == "-0" {
+        "0".to_string()
+    } else {
+        wire
+    }
+}
+
+fn cloid_wire(cloid: Uuid) -> String {
+    format!("0x{}", cloid.simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperliquid_rust_sdk::ClientLimit;
+
+    #[test]
+    fn test_wire_format_matches_sdk() {
+        // Cases from the SDK's float_to_string_for_hashing tests
+        assert_eq!(float_wire(0.0), "0");
+        assert_eq!(float_wire(-0.0), "0");
+        assert_eq!(float_wire(0.00076), "0.00076");
+        assert_eq!(float_wire(0.00000001), "0.00000001");
+        assert_eq!(float_wire(0.12345678), "0.12345678");
+        assert_eq!(float_wire(87654321.12345678), "87654321.12345678");
+        assert_eq!(float_wire(987654321.0), "987654321");
+        assert_eq!(float_wire(0.000000001), "0");
+
+        let cloid = Uuid::parse_str("1e60610f-0b3d-4205-97c8-c7c4a6f3c1d1").unwrap();
+        assert_eq!(cloid_wire(cloid), "0x1e60610f0b3d420597c8c7c4a6f3c1d1");
+
+        let first = next_nonce();
+        assert!(next_nonce() > first);
+
+        // The built action parses into the SDK's own types and back unchanged
+        let order = ClientOrderRequest {
+            asset: "BTC".to_string(),
+            is_buy: false,
+            limit_px: 65000.5,
+            sz: 0.0100,
+            reduce_only: true,
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: "Alo".to_string(),
+            }),
+            cloid: Some(cloid),
+        };
+        let action = json!({"type": "order", "orders": [order_wire(3, order)], "grouping": "na"});
+        let parsed: Actions = serde_json::from_value(action.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), action);
+        assert_eq!(action["orders"][0]["p"], "65000.5");
+        assert_eq!(action["orders"][0]["s"], "0.01");
+
+        let leverage =
+            json!({"type": "updateLeverage", "asset": 3, "isCross": true, "leverage": 5});
+        let parsed: Actions = serde_json::from_value(leverage.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), leverage);
+    }
+}
@@ -3,15 +3,27 @@
 //! This engine connects to the exchange via WebSocket, subscribes to market
 //! data and user events, and executes orders in real-time.
 
-use crate::broadcast::types::StrategySummary;
+use crate::broadcast::types::{
+    CommandResult, ControlCommand, ControlRequest, PlannedOrder, ReloadPlan, StrategySummary,
+};
 use crate::broadcast::{MarketEvent, OrderEvent, StatusBroadcaster, WSEvent};
+use crate::config::load_bot_config;
+use crate::config::notification::AlertKind;
+use crate::config::reload;
 use crate::config::strategy::StrategyConfig;
 use crate::constants::{
-    BALANCE_REFRESH_INTERVAL, RECONCILIATION_INTERVAL, STATUS_SUMMARY_INTERVAL,
+    BALANCE_REFRESH_INTERVAL, CONFIG_WATCH_INTERVAL, RECONCILIATION_INTERVAL,
+    STATUS_SUMMARY_INTERVAL, TWAP_IOC_SLIPPAGE,
 };
 use crate::engine::common;
 use crate::engine::context::{MarketInfo, StrategyContext};
-use crate::model::{Cloid, OrderFill, OrderSide};
+use crate::engine::exchange::TradingClient;
+use crate::history::{self, EquityHistory};
+use crate::journal::{Journal, JournalEntry};
+use crate::metrics::EngineMetrics;
+use crate::model::{Cloid, OrderFill, OrderRequest, OrderSide};
+use crate::notify::{Alert, Notifier};
+use crate::signer::RemoteSigner;
 use crate::strategy::Strategy;
 use anyhow::{anyhow, Result};
 use ethers::signers::{LocalWallet, Signer};
@@ -21,9 +33,14 @@ use hyperliquid_rust_sdk::{
 };
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, warn};
 
 struct PendingOrder {
+    coin: String,
+    side: OrderSide,
+    limit_px: f64,
     target_size: f64,
     filled_size: f64,
     weighted_avg_px: f64,
@@ -32,20 +49,73 @@ struct PendingOrder {
     oid: Option<u64>,
 }
 
+/// What the exchange did with one order sent by `process_bulk_orders`.
+#[derive(Debug, Clone, PartialEq)]
+enum OrderOutcome {
+    Resting,
+    Filled { size: f64, price: f64 },
+    Rejected(String),
+}
+
 struct EngineRuntime {
     pub ctx: StrategyContext,
     pub pending_orders: HashMap<Cloid, PendingOrder>,
     pub completed_cloids: HashSet<Cloid>,
+    /// Exchange coin -> symbol for every market the strategy trades.
+    pub traded_coins: HashMap<String, String>,
+    /// Set by the `pause`, `cancel_all` and `close_position` commands.
+    pub paused: bool,
+    /// The position was closed by command; the strategy's view of it is stale.
+    pub position_closed: bool,
+    /// Grid range for range exit alerts, moved by `set_range`.
+    pub grid_range: Option<(f64, f64)>,
+    /// Whether the last tick was outside the grid range.
+    pub outside_range: bool,
+    /// PnL at the start of the current UTC day, for the daily summary alert.
+    pub daily_baseline: Option<DailyBaseline>,
+    /// Strategy config applied by the last `reload`; the engine's own config until then.
+    pub reloaded_config: Option<StrategyConfig>,
+    /// Modification time of the config file when it was last checked.
+    pub config_modified: Option<SystemTime>,
+}
+
+struct DailyBaseline {
+    day: u64,
+    matched_profit: f64,
+    total_fees: f64,
 }
 
 impl EngineRuntime {
-    fn new(ctx: StrategyContext) -> Self {
+    fn new(ctx: StrategyContext, traded_coins: HashMap<String, String>) -> Self {
         Self {
             ctx,
             pending_orders: HashMap::new(),
             completed_cloids: HashSet::new(),
+            traded_coins,
+            paused: false,
+            position_closed: false,
+            grid_range: None,
+            outside_range: false,
+            daily_baseline: None,
+            reloaded_config: None,
+            config_modified: None,
         }
     }
+
+    /// Pairs each cloid with the coin its order was placed on.
+    fn cancel_targets(&self, cloids: Vec<Cloid>, default_coin: &str) -> Vec<(Cloid, String)> {
+        cloids
+            .into_iter()
+            .map(|cloid| {
+                let coin = self
+                    .pending_orders
+                    .get(&cloid)
+                    .map(|p| p.coin.clone())
+                    .unwrap_or_else(|| default_coin.to_string());
+                (cloid, coin)
+            })
+            .collect()
+    }
 }
 
 /// Live trading engine for real order execution.
@@ -53,6 +123,13 @@ pub struct Engine {
     config: StrategyConfig,
     exchange_config: crate::config::exchange::ExchangeConfig,
     broadcaster: StatusBroadcaster,
+    bot_name: String,
+    journal: Option<Journal>,
+    metrics: EngineMetrics,
+    notifier: Notifier,
+    history: Option<Mutex<EquityHistory>>,
+    history_interval: Duration,
+    config_file: Option<String>,
 }
 
 impl Engine {
@@ -61,33 +138,117 @@ impl Engine {
         exchange_config: crate::config::exchange::ExchangeConfig,
         broadcaster: StatusBroadcaster,
     ) -> Self {
+        let metrics = EngineMetrics::new("", config.symbol());
         Self {
             config,
             exchange_config,
             broadcaster,
+            bot_name: String::new(),
+            journal: None,
+            metrics,
+            notifier: Notifier::default(),
+            history: None,
+            history_interval: Duration::from_secs(60),
+            config_file: None,
         }
     }
 
+    /// Records order activity to `journal` under `bot_name`.
+    pub fn with_journal(mut self, bot_name: &str, journal: Journal) -> Self {
+        self.bot_name = bot_name.to_string();
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Reports engine and strategy health to `metrics`.
+    pub fn with_metrics(mut self, metrics: EngineMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Sends fill, error, range and daily summary alerts to `notifier`.
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Samples equity into `history` every `interval` and saves it.
+    pub fn with_history(mut self, history: EquityHistory, interval: Duration) -> Self {
+        self.history = Some(Mutex::new(history));
+        self.history_interval = interval;
+        self
+    }
+
+    /// Watches `path` for edits and reloads the strategy from it on `reload`.
+    pub fn with_config_file(mut self, path: &str) -> Self {
+        self.config_file = Some(path.to_string());
+        self
+    }
+
     async fn setup_info_client(&self) -> Result<InfoClient> {
         info!("Connecting to InfoClient...");
         common::setup_info_client(&self.exchange_config.network).await
     }
 
-    async fn setup_exchange_client(
-        &self,
-        wallet: LocalWallet,
-        vault_address: Option<H160>,
-    ) -> Result<ExchangeClient> {
+    /// Connects the exchange client and the signer it uses: the local API
+    /// wallet, or the remote signer of `api_wallet_signer`.
+    async fn setup_exchange_client(&self, vault_address: Option<H160>) -> Result<TradingClient> {
         let base_url = if self.exchange_config.network == "mainnet" {
             BaseUrl::Mainnet
         } else {
             BaseUrl::Testnet
         };
+        let (wallet, remote) = match (
+            &self.exchange_config.api_wallet_private_key,
+            &self.exchange_config.api_wallet_signer,
+        ) {
+            (Some(key), _) => {
+                let wallet: LocalWallet = key
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid API wallet private key: {}", e))?;
+                (wallet, None)
+            }
+            (None, Some(endpoint)) => {
+                info!("Connecting to remote signer at {}...", endpoint);
+                let signer = RemoteSigner::new(endpoint)?;
+                let identity = signer.identity().await?;
+                if identity.network != self.exchange_config.network {
+                    return Err(anyhow!(
+                        "Remote signer signs for {} but account '{}' is on {}",
+                        identity.network,
+                        self.exchange_config.account_name,
+                        self.exchange_config.network
+                    ));
+                }
+                if identity.vault_address != vault_address {
+                    return Err(anyhow!(
+                        "Remote signer signs for vault {:?} but account '{}' trades {:?}",
+                        identity.vault_address,
+                        self.exchange_config.account_name,
+                        vault_address
+                    ));
+                }
+                // The SDK client needs a wallet; this one never signs anything
+                let placeholder = LocalWallet::new(&mut rand::thread_rng());
+                (placeholder, Some((signer, identity.address)))
+            }
+            (None, None) => {
+                return Err(anyhow!(
+                    "Account '{}' has no API wallet key or signer",
+                    self.exchange_config.account_name
+                ))
+            }
+        };
+        let api_wallet = remote
+            .as_ref()
+            .map_or(wallet.address(), |(_, address)| *address);
+
         info!("Connecting to ExchangeClient...");
         info!(
-            "Using account profile '{}' | API wallet {} | master account {} | trading account {} | vault routing {}",
+            "Using account profile '{}' | API wallet {}{} | master account {} | trading account {} | vault routing {}",
             self.exchange_config.account_name,
-            wallet.address(),
+            api_wallet,
+            if remote.is_some() { " (remote signer)" } else { "" },
             self.exchange_config.master_account_address,
             self.exchange_config.trading_account_address(),
             self.exchange_config
@@ -95,9 +256,13 @@ impl Engine {
                 .unwrap_or("none (master account mode)")
         );
 
-        ExchangeClient::new(None, wallet, Some(base_url), None, vault_address)
+        let client = ExchangeClient::new(None, wallet, Some(base_url), None, vault_address)
             .await
-            .map_err(|e| anyhow!("Failed to connect ExchangeClient: {}", e))
+            .map_err(|e| anyhow!("Failed to connect ExchangeClient: {}", e))?;
+        Ok(match remote {
+            Some((signer, _)) => TradingClient::remote(client, signer),
+            None => TradingClient::local(client),
+        })
     }
 
     async fn load_metadata(
@@ -119,11 +284,6 @@ impl Engine {
     pub async fn run(&self, mut strategy: Box<dyn Strategy>) -> Result<()> {
         info!("Engine started for {}.", self.config.symbol());
 
-        let api_wallet_private_key = &self.exchange_config.api_wallet_private_key;
-        let wallet: LocalWallet = api_wallet_private_key
-            .parse()
-            .map_err(|e| anyhow!("Invalid API wallet private key: {}", e))?;
-
         let user_address = H160::from_str(self.exchange_config.trading_account_address())
             .map_err(|e| anyhow!("Invalid trading account address: {}", e))?;
         let vault_address = self
@@ -136,20 +296,29 @@ impl Engine {
 
         // 1. Setup Clients
         let mut info_client = self.setup_info_client().await?;
-        let exchange_client = self.setup_exchange_client(wallet, vault_address).await?;
+        let exchange_client = self.setup_exchange_client(vault_address).await?;
 
         // 2. Load Metadata
         let markets = self.load_metadata(&mut info_client).await?;
 
-        let target_symbol = self.config.symbol();
-        if !markets.contains_key(target_symbol) {
-            return Err(anyhow!(
-                "Critical Error: Metadata for symbol '{}' not found. Please check your configuration.",
-                target_symbol
-            ));
-        } else {
-            info!("Metadata loaded for {}.", target_symbol);
+        let traded_markets = self.config.markets();
+        for symbol in &traded_markets {
+            if !markets.contains_key(symbol) {
+                return Err(anyhow!(
+                    "Critical Error: Metadata for symbol '{}' not found. Please check your configuration.",
+                    symbol
+                ));
+            }
+            info!("Metadata loaded for {}.", symbol);
         }
+        let target_symbol = traded_markets[0].as_str();
+        let traded_coins: HashMap<String, String> = traded_markets
+            .iter()
+            .map(|symbol| (markets[symbol].coin.clone(), symbol.clone()))
+            .collect();
+
+        // Reject grids whose spacing cannot cover the account's maker fees
+        common::validate_grid_fees(&info_client, user_address, &self.config, "").await?;
 
         // 3. Init State
         let mut ctx = StrategyContext::new(markets);
@@ -161,6 +330,14 @@ impl Engine {
 
         self.log_balances(&ctx);
 
+        // Candle history for ATR spacing
+        if let Some((symbol, atr)) = self.config.candle_feed() {
+            let market = ctx.market_info(symbol).cloned();
+            if let Some(market) = market {
+                common::fetch_candles(&info_client, &market, atr, &mut ctx, "").await;
+            }
+        }
+
         // 5. Setup Leverage/Margin for Perp strategies
         if let StrategyConfig::PerpGrid(crate::config::strategy::PerpGridConfig {
             leverage,
@@ -176,7 +353,7 @@ impl Engine {
             );
 
             match exchange_client
-                .update_leverage(*leverage, target_symbol, is_cross, None)
+                .update_leverage(*leverage, target_symbol, is_cross)
                 .await
             {
                 Ok(response) => {
@@ -209,29 +386,56 @@ impl Engine {
         info_client
             .subscribe(
                 hyperliquid_rust_sdk::Subscription::UserEvents { user: user_address },
-                sender,
+                sender.clone(),
             )
             .await
             .map_err(|e| anyhow!("Failed to subscribe to UserEvents: {}", e))?;
         info!("Subscribed to UserEvents for {:?}.", user_address);
 
-        let mut runtime = EngineRuntime::new(ctx);
+        if self.config.requires_trade_feed() {
+            info_client
+                .subscribe(
+                    hyperliquid_rust_sdk::Subscription::Trades {
+                        coin: string_coin.clone(),
+                    },
+                    sender.clone(),
+                )
+                .await
+                .map_err(|e| anyhow!("Failed to subscribe to Trades: {}", e))?;
+            info!("Subscribed to Trades for {}.", string_coin);
+        }
+
+        let mut runtime = EngineRuntime::new(ctx, traded_coins);
+        runtime.grid_range = self.config.grid_range();
+        let mut control_receiver = self
+            .broadcaster
+            .take_control_receiver()
+            .unwrap_or_else(|| tokio::sync::mpsc::unbounded_channel().1);
 
         let mut balance_refresh_timer = tokio::time::interval(BALANCE_REFRESH_INTERVAL);
         let mut status_summary_timer = tokio::time::interval(STATUS_SUMMARY_INTERVAL);
         let mut reconciliation_timer = tokio::time::interval(RECONCILIATION_INTERVAL);
+        let mut history_timer = tokio::time::interval(self.history_interval);
+        if let Some(history) = &self.history {
+            // Clients connecting before the first sample still get the saved series
+            self.broadcaster
+                .set_history(history.lock().unwrap().snapshot());
+        }
+        let candle_refresh = self
+            .config
+            .candle_feed()
+            .and_then(|(_, atr)| atr.recompute_interval());
+        let candle_period = candle_refresh.unwrap_or(BALANCE_REFRESH_INTERVAL);
+        let mut candle_refresh_timer =
+            tokio::time::interval_at(tokio::time::Instant::now() + candle_period, candle_period);
+
+        let mut config_watch_timer = tokio::time::interval(CONFIG_WATCH_INTERVAL);
+        // Only edits made from now on count
+        self.config_file_changed(&mut runtime);
 
         // Broadcast Config
-        let mut config_json = serde_json::to_value(&self.config).unwrap_or(serde_json::Value::Null);
-        if let Some(info) = runtime.ctx.market_info(target_symbol) {
-            if let Some(obj) = config_json.as_object_mut() {
-                obj.insert(
-                    "sz_decimals".to_string(),
-                    serde_json::json!(info.sz_decimals),
-                );
-            }
-        }
-        self.broadcaster.send(WSEvent::Config(config_json));
+        self.broadcaster
+            .send(Self::config_event(&self.config, &runtime.ctx));
         self.broadcaster
             .send(WSEvent::Info(crate::broadcast::types::SystemInfo {
                 network: self.exchange_config.network.clone(),
@@ -247,18 +451,10 @@ impl Engine {
                  _ = status_summary_timer.tick() => {
                     // Periodic Summary Broadcast
                     let summary = strategy.get_summary(&runtime.ctx);
-                    match summary {
-                        StrategySummary::SpotGrid(s) => {
-                            self.broadcaster.send(WSEvent::SpotGridSummary(s));
-                        }
-                        StrategySummary::PerpGrid(s) => {
-                            self.broadcaster.send(WSEvent::PerpGridSummary(s));
-                        }
-                    }
-
-                    // Also broadcast grid state periodically (ensures cache is populated)
-                    let grid_state = strategy.get_grid_state(&runtime.ctx);
-                    self.broadcaster.send(WSEvent::GridState(grid_state));
+                    self.metrics.observe_summary(&summary, &runtime.ctx);
+                    self.metrics.set_pending_orders(runtime.pending_orders.len());
+                    self.check_daily_summary(&summary, &mut runtime);
+                    self.broadcast_status(summary, strategy.as_ref(), &runtime.ctx);
                  }
                  _ = tokio::signal::ctrl_c() => {
                     info!("Shutdown signal received. Stopping Engine...");
@@ -266,18 +462,486 @@ impl Engine {
                         .await;
                     break;
                  }
+                 Some(request) = control_receiver.recv() => {
+                     let shutdown = self
+                         .handle_control(request, &mut runtime, &mut strategy, &exchange_client, &info_client, user_address, &string_coin)
+                         .await;
+                     if shutdown {
+                         self.cancel_pending_orders_on_shutdown(&runtime, &exchange_client, &string_coin)
+                             .await;
+                         break;
+                     }
+                 }
                  Some(message) = receiver.recv() => {
                      self.handle_message(message, &mut runtime, &mut strategy, &exchange_client, &string_coin).await?;
                  }
+                 _ = history_timer.tick(), if self.history.is_some() => {
+                    self.record_history(strategy.as_ref(), &runtime.ctx);
+                 }
+                 _ = config_watch_timer.tick(), if self.config_file.is_some() => {
+                     if self.config_file_changed(&mut runtime) {
+                         // Edits are only previewed; the reload command applies them
+                         info!("[RELOAD] Config file changed. Previewing reload...");
+                         let outcome = self.reload(&mut runtime, &mut strategy, true);
+                         self.send_command_result(None, "reload", outcome);
+                     }
+                 }
                  _ = reconciliation_timer.tick() => {
                      self.reconcile_orders(&mut info_client, user_address, &mut runtime, &mut strategy).await;
                  }
+                 _ = candle_refresh_timer.tick(), if candle_refresh.is_some() => {
+                     if let Some((symbol, atr)) = self.config.candle_feed() {
+                         if let Some(market) = runtime.ctx.market_info(symbol).cloned() {
+                             common::fetch_candles(&info_client, &market, atr, &mut runtime.ctx, "Periodic: ").await;
+                         }
+                     }
+                 }
             }
+            for alert in runtime.ctx.alerts.drain(..) {
+                self.notifier.notify(alert);
+            }
+            self.broadcaster
+                .set_open_orders(Self::open_orders(&runtime));
         }
         info!("Engine stopped gracefully.");
         Ok(())
     }
 
+    /// Adds a history sample, saves the series and hands it to the broadcaster.
+    fn record_history(&self, strategy: &dyn Strategy, ctx: &StrategyContext) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let summary = strategy.get_summary(ctx);
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let point = history::sample(&summary, ctx, self.config.symbol(), timestamp_ms);
+        let mut history = history.lock().unwrap();
+        history.record(point);
+        if let Err(e) = history.save() {
+            warn!("Failed to save equity history: {:#}", e);
+        }
+        self.broadcaster.set_history(history.snapshot());
+    }
+
+    /// Broadcasts the strategy summary and grid state.
+    fn broadcast_status(
+        &self,
+        summary: StrategySummary,
+        strategy: &dyn Strategy,
+        ctx: &StrategyContext,
+    ) {
+        match summary {
+            StrategySummary::SpotGrid(s) => {
+                self.broadcaster.send(WSEvent::SpotGridSummary(s));
+            }
+            StrategySummary::PerpGrid(s) => {
+                self.broadcaster.send(WSEvent::PerpGridSummary(s));
+            }
+            StrategySummary::Twap(s) => {
+                self.broadcaster.send(WSEvent::TwapSummary(s));
+            }
+            StrategySummary::Rebalance(s) => {
+                self.broadcaster.send(WSEvent::RebalanceSummary(s));
+            }
+        }
+
+        // Also broadcast grid state periodically (ensures cache is populated)
+        self.broadcaster
+            .send(WSEvent::GridState(strategy.get_grid_state(ctx)));
+    }
+
+    /// Runs an operator command from a WebSocket client and broadcasts the result.
+    /// Returns true when the engine should shut down.
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_control(
+        &self,
+        request: ControlRequest,
+        runtime: &mut EngineRuntime,
+        strategy: &mut Box<dyn Strategy>,
+        exchange_client: &TradingClient,
+        info_client: &InfoClient,
+        user_address: H160,
+        coin: &str,
+    ) -> bool {
+        let outcome = match &request.command {
+            ControlCommand::Pause => {
+                runtime.paused = true;
+                Ok("Strategy paused. Open orders stay on the book.".to_string())
+            }
+            ControlCommand::Resume => {
+                if runtime.position_closed {
+                    Err(anyhow!(
+                        "The position was closed by close_position. Restart the bot to trade again."
+                    ))
+                } else {
+                    runtime.paused = false;
+                    Ok("Strategy resumed.".to_string())
+                }
+            }
+            ControlCommand::CancelAll => {
+                runtime.paused = true;
+                let canceled = self
+                    .cancel_all_orders(runtime, strategy, exchange_client, coin)
+                    .await;
+                Ok(format!("Canceled {} orders. Strategy paused.", canceled))
+            }
+            ControlCommand::ClosePosition => {
+                self.close_position(
+                    runtime,
+                    strategy,
+                    exchange_client,
+                    info_client,
+                    user_address,
+                    coin,
+                )
+                .await
+            }
+            ControlCommand::SetRange { low, high } => {
+                strategy.set_range(*low, *high, &mut runtime.ctx).map(|()| {
+                    runtime.grid_range = Some((*low, *high));
+                    runtime.outside_range = false;
+                    self.broadcaster
+                        .send(WSEvent::GridState(strategy.get_grid_state(&runtime.ctx)));
+                    format!("Grid range set to {} - {}.", low, high)
+                })
+            }
+            ControlCommand::RequestSnapshot => {
+                let summary = strategy.get_summary(&runtime.ctx);
+                self.broadcast_status(summary, strategy.as_ref(), &runtime.ctx);
+                Ok("Snapshot sent.".to_string())
+            }
+            ControlCommand::Reload { dry_run } => self.reload(runtime, strategy, *dry_run),
+            ControlCommand::Shutdown => Ok("Shutting down.".to_string()),
+        };
+
+        self.send_command_result(request.id, request.command.name(), outcome);
+        request.command == ControlCommand::Shutdown
+    }
+
+    /// Logs the outcome of a command and broadcasts it as a `command_result`.
+    fn send_command_result(&self, id: Option<String>, command: &str, outcome: Result<String>) {
+        let result = match outcome {
+            Ok(message) => {
+                info!("[CONTROL] {} ok: {}", command, message);
+                CommandResult {
+                    id,
+                    command: command.to_string(),
+                    success: true,
+                    message,
+                }
+            }
+            Err(e) => {
+                warn!("[CONTROL] {} failed: {}", command, e);
+                CommandResult {
+                    id,
+                    command: command.to_string(),
+                    success: false,
+                    message: e.to_string(),
+                }
+            }
+        };
+        self.broadcaster.send(WSEvent::CommandResult(result));
+    }
+
+    /// Re-reads the config file and applies its live-safe strategy changes.
+    /// The plan is worked out on a copy of the strategy and broadcast before
+    /// the running strategy is touched.
+    fn reload(
+        &self,
+        runtime: &mut EngineRuntime,
+        strategy: &mut Box<dyn Strategy>,
+        dry_run: bool,
+    ) -> Result<String> {
+        let path = self
+            .config_file
+            .as_deref()
+            .ok_or_else(|| anyhow!("The bot was not started from a config file"))?;
+        let config = load_bot_config(path)
+            .map_err(|e| anyhow!("Failed to load {}: {}", path, e))?
+            .strategy;
+        let running = runtime.reloaded_config.as_ref().unwrap_or(&self.config);
+        let changes = reload::diff_strategy(running, &config)?;
+        if changes.is_empty() {
+            return Ok("The config file has no strategy changes.".to_string());
+        }
+
+        let mut preview_ctx = runtime.ctx.clone();
+        preview_ctx.order_queue.clear();
+        preview_ctx.cancellation_queue.clear();
+        let mut preview = strategy.clone_box();
+        preview.reconfigure(&config, &mut preview_ctx)?;
+        let plan = Self::reload_plan(changes, &preview_ctx, dry_run);
+
+        let fields: Vec<String> = plan
+            .changes
+            .iter()
+            .map(|c| format!("{} {} -> {}", c.field, c.old, c.new))
+            .collect();
+        let summary = format!(
+            "{} ({} cancels, {} new orders)",
+            fields.join(", "),
+            plan.cancels.len(),
+            plan.orders.len()
+        );
+        for order in &plan.orders {
+            info!(
+                "[RELOAD] Plan: {} {} @ {:?}",
+                order.side, order.size, order.price
+            );
+        }
+        self.broadcaster.send(WSEvent::ReloadPlan(plan));
+        if dry_run {
+            return Ok(format!(
+                "Reload preview: {}. Send reload to apply.",
+                summary
+            ));
+        }
+
+        strategy.reconfigure(&config, &mut runtime.ctx)?;
+        runtime.grid_range = config.grid_range();
+        runtime.outside_range = false;
+        self.broadcaster
+            .send(Self::config_event(&config, &runtime.ctx));
+        self.broadcaster
+            .send(WSEvent::GridState(strategy.get_grid_state(&runtime.ctx)));
+        runtime.reloaded_config = Some(config);
+        Ok(format!("Reloaded: {}.", summary))
+    }
+
+    /// The cancels and orders a reconfigure queued on `ctx`.
+    fn reload_plan(
+        changes: Vec<reload::ConfigChange>,
+        ctx: &StrategyContext,
+        dry_run: bool,
+    ) -> ReloadPlan {
+        let orders = ctx
+            .order_queue
+            .iter()
+            .filter_map(|order| match order {
+                OrderRequest::Limit {
+                    side, price, sz, ..
+                } => Some(PlannedOrder {
+                    side: side.to_string(),
+                    price: Some(*price),
+                    size: *sz,
+                }),
+                OrderRequest::Market { side, sz, .. } => Some(PlannedOrder {
+                    side: side.to_string(),
+                    price: None,
+                    size: *sz,
+                }),
+                OrderRequest::Cancel { .. } => None,
+            })
+            .collect();
+        ReloadPlan {
+            changes,
+            cancels: ctx
+                .cancellation_queue
+                .iter()
+                .map(|cloid| cloid.to_string())
+                .collect(),
+            orders,
+            dry_run,
+        }
+    }
+
+    /// True when the config file was modified since the last check. The
+    /// first check only records the modification time.
+    fn config_file_changed(&self, runtime: &mut EngineRuntime) -> bool {
+        let Some(path) = &self.config_file else {
+            return false;
+        };
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == runtime.config_modified {
+            return false;
+        }
+        let seen_before = runtime.config_modified.is_some();
+        runtime.config_modified = modified;
+        seen_before
+    }
+
+    /// `config` event: the strategy config plus the traded market's size decimals.
+    fn config_event(config: &StrategyConfig, ctx: &StrategyContext) -> WSEvent {
+        let mut config_json = serde_json::to_value(config).unwrap_or(serde_json::Value::Null);
+        if let Some(info) = config.markets().first().and_then(|s| ctx.market_info(s)) {
+            if let Some(obj) = config_json.as_object_mut() {
+                obj.insert(
+                    "sz_decimals".to_string(),
+                    serde_json::json!(info.sz_decimals),
+                );
+            }
+        }
+        WSEvent::Config(config_json)
+    }
+
+    /// Cancels every tracked order and returns how many the exchange confirmed.
+    async fn cancel_all_orders(
+        &self,
+        runtime: &mut EngineRuntime,
+        strategy: &mut Box<dyn Strategy>,
+        exchange_client: &TradingClient,
+        coin: &str,
+    ) -> usize {
+        let cloids = Self::collect_shutdown_cancel_cloids(runtime);
+        if cloids.is_empty() {
+            return 0;
+        }
+        let canceled = self
+            .process_bulk_cancels(runtime.cancel_targets(cloids, coin), exchange_client)
+            .await;
+        let count = canceled.len();
+        self.finalize_canceled_orders(canceled, runtime, strategy, true);
+        count
+    }
+
+    /// Cancels all orders, pauses, and closes the perp position with a reduce-only IOC order.
+    async fn close_position(
+        &self,
+        runtime: &mut EngineRuntime,
+        strategy: &mut Box<dyn Strategy>,
+        exchange_client: &TradingClient,
+        info_client: &InfoClient,
+        user_address: H160,
+        coin: &str,
+    ) -> Result<String> {
+        if self.config.is_spot() {
+            return Err(anyhow!("close_position is only available for perp markets"));
+        }
+        runtime.paused = true;
+        let canceled = self
+            .cancel_all_orders(runtime, strategy, exchange_client, coin)
+            .await;
+
+        let symbol = self.config.symbol();
+        let user_state = info_client
+            .user_state(user_address)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch position: {}", e))?;
+        let size = user_state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == coin)
+            .and_then(|p| p.position.szi.parse::<f64>().ok())
+            .unwrap_or(0.0);
+        if size == 0.0 {
+            return Ok(format!(
+                "Canceled {} orders. No open {} position. Strategy paused.",
+                canceled, symbol
+            ));
+        }
+
+        let mid_price = runtime
+            .ctx
+            .get_mid(symbol)
+            .ok_or_else(|| anyhow!("No mid price for {}", symbol))?;
+        let market_info = runtime
+            .ctx
+            .market_info(symbol)
+            .ok_or_else(|| anyhow!("No market info for {}", symbol))?;
+        let side = if size > 0.0 {
+            OrderSide::Sell
+        } else {
+            OrderSide::Buy
+        };
+        // An IOC at mid rests on neither side of the book, so cross the spread
+        let limit_px = market_info.round_price(match side {
+            OrderSide::Buy => TWAP_IOC_SLIPPAGE.markup(mid_price),
+            OrderSide::Sell => TWAP_IOC_SLIPPAGE.markdown(mid_price),
+        });
+        let cloid = Cloid::new();
+        let outcomes = self
+            .process_bulk_orders(
+                vec![OrderRequest::Market {
+                    symbol: symbol.to_string(),
+                    side,
+                    sz: size.abs(),
+                    reduce_only: true,
+                    limit_px: Some(limit_px),
+                    cloid: Some(cloid),
+                }],
+                runtime,
+                strategy,
+                exchange_client,
+                mid_price,
+            )
+            .await;
+        let outcome = outcomes.get(&cloid);
+        if matches!(outcome, Some(OrderOutcome::Filled { .. })) {
+            runtime.position_closed = true;
+        }
+        Self::close_position_result(canceled, side, size.abs(), symbol, outcome)
+    }
+
+    /// The `close_position` reply for what the exchange did with the close order.
+    fn close_position_result(
+        canceled: usize,
+        side: OrderSide,
+        size: f64,
+        symbol: &str,
+        outcome: Option<&OrderOutcome>,
+    ) -> Result<String> {
+        match outcome {
+            Some(OrderOutcome::Filled {
+                size: filled,
+                price,
+            }) if size - filled > 1e-9 => Err(anyhow!(
+                "Canceled {} orders. The reduce-only {} filled {} of {} {} @ {}; {} is still open. Strategy paused; send close_position again.",
+                canceled,
+                side,
+                filled,
+                size,
+                symbol,
+                price,
+                size - filled
+            )),
+            Some(OrderOutcome::Filled {
+                size: filled,
+                price,
+            }) => Ok(format!(
+                "Canceled {} orders and closed {} {} with a {} @ {}. Strategy paused; restart the bot to trade again.",
+                canceled, filled, symbol, side, price
+            )),
+            Some(OrderOutcome::Rejected(reason)) => Err(anyhow!(
+                "Canceled {} orders. The reduce-only {} {} {} was rejected: {}. Strategy paused.",
+                canceled,
+                side,
+                size,
+                symbol,
+                reason
+            )),
+            Some(OrderOutcome::Resting) | None => Err(anyhow!(
+                "Canceled {} orders. The reduce-only {} {} {} did not fill. Strategy paused.",
+                canceled,
+                side,
+                size,
+                symbol
+            )),
+        }
+    }
+
+    /// Resting orders for `GET /orders`, sized by what is left to fill.
+    fn open_orders(runtime: &EngineRuntime) -> Vec<OrderEvent> {
+        let mut orders: Vec<OrderEvent> = runtime
+            .pending_orders
+            .iter()
+            .map(|(cloid, pending)| OrderEvent {
+                oid: pending.oid.unwrap_or(0),
+                cloid: Some(cloid.to_string()),
+                side: pending.side.to_string(),
+                price: pending.limit_px,
+                size: pending.target_size - pending.filled_size,
+                status: "OPEN".to_string(),
+                fee: pending.accumulated_fees,
+                is_taker: false,
+            })
+            .collect();
+        orders.sort_by(|a, b| a.cloid.cmp(&b.cloid));
+        orders
+    }
+
     fn collect_shutdown_cancel_cloids(runtime: &EngineRuntime) -> Vec<Cloid> {
         let mut cloids: Vec<_> = runtime.pending_orders.keys().copied().collect();
         cloids.sort_by_key(|cloid| cloid.to_string());
@@ -287,7 +951,7 @@ impl Engine {
     async fn cancel_pending_orders_on_shutdown(
         &self,
         runtime: &EngineRuntime,
-        exchange_client: &ExchangeClient,
+        exchange_client: &TradingClient,
         coin: &str,
     ) {
         let cloids = Self::collect_shutdown_cancel_cloids(runtime);
@@ -300,7 +964,7 @@ impl Engine {
             "Shutdown canceling {} pending orders before exit.",
             cloids.len()
         );
-        self.process_bulk_cancels(cloids, exchange_client, coin)
+        self.process_bulk_cancels(runtime.cancel_targets(cloids, coin), exchange_client)
             .await;
     }
 
@@ -324,6 +988,14 @@ impl Engine {
                 .map(|value| value.to_string())
                 .unwrap_or_else(|| "none".to_string())
         );
+        self.journal(JournalEntry {
+            side: Some(side),
+            price: Some(price),
+            size: Some(size),
+            reduce_only: Some(reduce_only),
+            cloid: cloid.map(|c| c.to_string()),
+            ..JournalEntry::new("req", symbol)
+        });
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -336,6 +1008,7 @@ impl Engine {
         reduce_only: bool,
         cloid: Option<Cloid>,
         fee: f64,
+        fee_token: Option<&str>,
         oid: u64,
         is_taker: bool,
         raw_dir: Option<&str>,
@@ -355,6 +1028,24 @@ impl Engine {
             is_taker,
             raw_dir.unwrap_or("none")
         );
+        self.journal(JournalEntry {
+            side: Some(side),
+            price: Some(price),
+            size: Some(size),
+            reduce_only: Some(reduce_only),
+            cloid: cloid.map(|c| c.to_string()),
+            oid: Some(oid),
+            fee: Some(fee),
+            is_taker: Some(is_taker),
+            raw_dir: raw_dir.map(str::to_string),
+            fee_token: fee_token.map(str::to_string),
+            ..JournalEntry::new("fill", symbol)
+        });
+        self.notifier.notify(Alert::new(
+            AlertKind::Fill,
+            format!("{} filled", side),
+            format!("{} {} @ {} (fee {})", side, size, price, fee),
+        ));
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -380,6 +1071,15 @@ impl Engine {
                 .unwrap_or_else(|| "none".to_string()),
             oid
         );
+        self.journal(JournalEntry {
+            side: Some(side),
+            price: Some(price),
+            size: Some(size),
+            reduce_only: Some(reduce_only),
+            cloid: cloid.map(|c| c.to_string()),
+            oid: Some(oid),
+            ..JournalEntry::new("open", symbol)
+        });
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -405,6 +1105,20 @@ impl Engine {
                 .unwrap_or_else(|| "none".to_string()),
             reason
         );
+        self.journal(JournalEntry {
+            side: Some(side),
+            price: Some(price),
+            size: Some(size),
+            reduce_only: Some(reduce_only),
+            cloid: cloid.map(|c| c.to_string()),
+            details: Some(reason.to_string()),
+            ..JournalEntry::new("reject", symbol)
+        });
+        self.notifier.notify(Alert::new(
+            AlertKind::Error,
+            "Order rejected",
+            format!("{} {} @ {}: {}", side, size, price, reason),
+        ));
     }
 
     fn log_cancel_request(&self, cloid: Cloid, coin: &str) {
@@ -414,6 +1128,11 @@ impl Engine {
             coin,
             cloid
         );
+        self.journal(JournalEntry {
+            cloid: Some(cloid.to_string()),
+            details: Some(format!("asset={}", coin)),
+            ..JournalEntry::new("cancel_request", self.config.symbol())
+        });
     }
 
     fn log_cancel_result(
@@ -444,9 +1163,19 @@ impl Engine {
                 result
             ),
         }
+        self.journal(JournalEntry {
+            cloid: cloid.map(|c| c.to_string()),
+            details: Some(match details {
+                Some(details) => format!("asset={} result={} details={}", coin, result, details),
+                None => format!("asset={} result={}", coin, result),
+            }),
+            ..JournalEntry::new("cancel_result", self.config.symbol())
+        });
     }
 
     fn log_reconcile_event(&self, event: &str, cloid: Cloid, oid: u64, details: &str) {
+        self.metrics
+            .record_reconciliation(event.strip_prefix("reconcile_").unwrap_or(event));
         info!(
             "[ORDER_AUDIT] event={} symbol={} cloid={} oid={} {}",
             event,
@@ -455,6 +1184,93 @@ impl Engine {
             oid,
             details
         );
+        self.journal(JournalEntry {
+            cloid: Some(cloid.to_string()),
+            oid: Some(oid),
+            details: Some(details.to_string()),
+            ..JournalEntry::new(event, self.config.symbol())
+        });
+        if event == "reconcile_missing" {
+            self.notifier.notify(Alert::new(
+                AlertKind::Error,
+                "Order missing on exchange",
+                format!("cloid {} oid {}: {}", cloid, oid, details),
+            ));
+        }
+    }
+
+    /// Alerts once each time price leaves the grid range.
+    fn check_grid_range(&self, mid_price: f64, runtime: &mut EngineRuntime) {
+        let Some((low, high)) = runtime.grid_range else {
+            return;
+        };
+        let outside = mid_price < low || mid_price > high;
+        if outside && !runtime.outside_range {
+            let bound = if mid_price < low {
+                format!("below grid low {}", low)
+            } else {
+                format!("above grid high {}", high)
+            };
+            self.notifier.notify(Alert::new(
+                AlertKind::RangeExit,
+                "Price left grid range",
+                format!("Price {} is {}", mid_price, bound),
+            ));
+        }
+        runtime.outside_range = outside;
+    }
+
+    /// Sends the previous day's PnL once the UTC date rolls over.
+    fn check_daily_summary(&self, summary: &StrategySummary, runtime: &mut EngineRuntime) {
+        let (matched_profit, total_profit, total_fees) = match summary {
+            StrategySummary::SpotGrid(s) => (s.matched_profit, Some(s.total_profit), s.total_fees),
+            StrategySummary::PerpGrid(s) => (s.matched_profit, Some(s.total_profit), s.total_fees),
+            StrategySummary::Twap(s) => (0.0, None, s.total_fees),
+            StrategySummary::Rebalance(s) => (0.0, None, s.total_fees),
+        };
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let today = now_ms / 86_400_000;
+
+        if let Some(baseline) = &runtime.daily_baseline {
+            if baseline.day == today {
+                return;
+            }
+            let mut message = format!(
+                "{}: matched profit {:.4}, fees {:.4}",
+                crate::report::format_date(baseline.day * 86_400_000),
+                matched_profit - baseline.matched_profit,
+                total_fees - baseline.total_fees
+            );
+            if let Some(total) = total_profit {
+                message.push_str(&format!(", total profit to date {:.4}", total));
+            }
+            self.notifier.notify(Alert::new(
+                AlertKind::DailySummary,
+                "Daily PnL summary",
+                message,
+            ));
+        }
+        runtime.daily_baseline = Some(DailyBaseline {
+            day: today,
+            matched_profit,
+            total_fees,
+        });
+    }
+
+    fn journal(&self, entry: JournalEntry) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let entry = JournalEntry {
+            bot: self.bot_name.clone(),
+            ..entry
+        };
+        if let Err(e) = journal.record(&entry) {
+            warn!("Failed to write journal entry ({}): {}", entry.event, e);
+        }
     }
 
     fn log_balances(&self, ctx: &StrategyContext) {
@@ -508,11 +1324,22 @@ impl Engine {
         message: hyperliquid_rust_sdk::Message,
         runtime: &mut EngineRuntime,
         strategy: &mut Box<dyn Strategy>,
-        exchange_client: &ExchangeClient,
+        exchange_client: &TradingClient,
         coin: &str,
     ) -> Result<()> {
         match message {
             hyperliquid_rust_sdk::Message::AllMids(all_mids) => {
+                self.metrics.record_market_data();
+                for (traded_coin, symbol) in &runtime.traded_coins {
+                    if let Some(mid) = all_mids
+                        .data
+                        .mids
+                        .get(traded_coin)
+                        .and_then(|px| px.parse::<f64>().ok())
+                    {
+                        runtime.ctx.update_mid(symbol, mid);
+                    }
+                }
                 if let Some(price_str) = all_mids.data.mids.get(coin) {
                     let mid_price = price_str.parse::<f64>().unwrap_or(0.0);
                     if mid_price > 0.0 {
@@ -522,9 +1349,16 @@ impl Engine {
                 }
             }
             hyperliquid_rust_sdk::Message::User(user_events) => {
-                self.process_user_events(user_events.data, runtime, strategy, coin)
+                self.process_user_events(user_events.data, runtime, strategy)
                     .await;
             }
+            hyperliquid_rust_sdk::Message::Trades(trades) => {
+                let symbol = self.config.symbol();
+                for trade in trades.data.iter().filter(|t| t.coin == coin) {
+                    let size = trade.sz.parse::<f64>().unwrap_or(0.0);
+                    runtime.ctx.record_trade_volume(symbol, size);
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -535,15 +1369,20 @@ impl Engine {
         mid_price: f64,
         runtime: &mut EngineRuntime,
         strategy: &mut Box<dyn Strategy>,
-        exchange_client: &ExchangeClient,
+        exchange_client: &TradingClient,
         coin: &str,
     ) -> Result<()> {
-        // Broadcast Market Update (Real-time)
-        // Optimization: Could throttle this if it's too much data, but mid_price updates are usually manageable
+        // Broadcast Market Update (Real-time). Each client connection throttles
+        // and conflates these to its `[websocket.max_rate]` market rate.
         self.broadcaster
             .send(WSEvent::MarketUpdate(MarketEvent { price: mid_price }));
 
         // Call Strategy
+        self.metrics.record_tick();
+        self.check_grid_range(mid_price, runtime);
+        if runtime.paused {
+            return Ok(());
+        }
         strategy.on_tick(mid_price, &mut runtime.ctx)?;
 
         // Execute Order Queue & Cancellation Queue
@@ -564,8 +1403,9 @@ impl Engine {
         }
 
         if !cancels_to_process.is_empty() {
-            self.process_bulk_cancels(cancels_to_process, exchange_client, coin)
-                .await;
+            let cancels = runtime.cancel_targets(cancels_to_process, coin);
+            let canceled = self.process_bulk_cancels(cancels, exchange_client).await;
+            self.finalize_canceled_orders(canceled, runtime, strategy, false);
         }
 
         if !orders_to_place.is_empty() {
@@ -574,7 +1414,6 @@ impl Engine {
                 runtime,
                 strategy,
                 exchange_client,
-                coin,
                 mid_price,
             )
             .await;
@@ -583,16 +1422,17 @@ impl Engine {
         Ok(())
     }
 
+    /// Sends cancels for the given cloids and returns the ones the exchange confirmed.
     async fn process_bulk_cancels(
         &self,
-        cloids: Vec<Cloid>,
-        exchange_client: &ExchangeClient,
-        coin: &str,
-    ) {
-        info!("Processing Batch Cancellations: {} orders", cloids.len());
-
-        let mut cancel_reqs = Vec::with_capacity(cloids.len());
-        for cloid in &cloids {
+        cancels: Vec<(Cloid, String)>,
+        exchange_client: &TradingClient,
+    ) -> Vec<Cloid> {
+        info!("Processing Batch Cancellations: {} orders", cancels.len());
+        let mut canceled = Vec::new();
+
+        let mut cancel_reqs = Vec::with_capacity(cancels.len());
+        for (cloid, coin) in &cancels {
             self.log_cancel_request(*cloid, coin);
             // Broadcast Order Update (Cancel Sent)
             self.broadcaster.send(WSEvent::OrderUpdate(OrderEvent {
@@ -612,18 +1452,17 @@ impl Engine {
             });
         }
 
-        match exchange_client
-            .bulk_cancel_by_cloid(cancel_reqs, None)
-            .await
-        {
+        match exchange_client.bulk_cancel_by_cloid(cancel_reqs).await {
             Ok(hyperliquid_rust_sdk::ExchangeResponseStatus::Ok(exchange_res)) => {
                 if let Some(data) = &exchange_res.data {
                     for (i, status) in data.statuses.iter().enumerate() {
-                        let cloid = cloids.get(i);
+                        let cloid = cancels.get(i).map(|(cloid, _)| cloid);
+                        let coin = cancels.get(i).map_or("", |(_, coin)| coin.as_str());
                         match status {
                             hyperliquid_rust_sdk::ExchangeDataStatus::Success => {
                                 self.log_cancel_result(cloid.copied(), coin, "success", None);
                                 info!("Cancel successful for {:?}", cloid);
+                                canceled.extend(cloid.copied());
                             }
                             hyperliquid_rust_sdk::ExchangeDataStatus::Error(e) => {
                                 self.log_cancel_result(cloid.copied(), coin, "error", Some(e));
@@ -651,6 +1490,59 @@ impl Engine {
                 error!("Failed to execute bulk cancel: {:?}", e);
             }
         }
+        canceled
+    }
+
+    /// Resolves tracked orders whose cancel was confirmed.
+    /// Any partial fill is reported as a fill of the accumulated size. Otherwise the
+    /// order is failed, or reported as canceled when the engine cancelled it itself.
+    fn finalize_canceled_orders(
+        &self,
+        canceled: Vec<Cloid>,
+        runtime: &mut EngineRuntime,
+        strategy: &mut Box<dyn Strategy>,
+        by_engine: bool,
+    ) {
+        for cloid in canceled {
+            let Some(pending) = runtime.pending_orders.remove(&cloid) else {
+                continue;
+            };
+            runtime.completed_cloids.insert(cloid);
+
+            let result = if pending.filled_size > 0.0 {
+                info!(
+                    "[ORDER_CANCELED_PARTIAL] {} {} of {} @ {} (Fee: {})",
+                    pending.side,
+                    pending.filled_size,
+                    pending.target_size,
+                    pending.weighted_avg_px,
+                    pending.accumulated_fees
+                );
+                strategy.on_order_filled(
+                    &OrderFill {
+                        side: pending.side,
+                        size: pending.filled_size,
+                        price: pending.weighted_avg_px,
+                        fee: pending.accumulated_fees,
+                        cloid: Some(cloid),
+                        reduce_only: Some(pending.reduce_only),
+                        raw_dir: None,
+                    },
+                    &mut runtime.ctx,
+                )
+            } else if by_engine {
+                strategy.on_order_canceled(cloid, &mut runtime.ctx)
+            } else {
+                strategy.on_order_failed(cloid, &mut runtime.ctx)
+            };
+
+            if let Err(e) = result {
+                error!("Strategy cancel finalization error: {}", e);
+            } else {
+                let grid_state = strategy.get_grid_state(&runtime.ctx);
+                self.broadcaster.send(WSEvent::GridState(grid_state));
+            }
+        }
     }
 
     async fn process_bulk_orders(
@@ -658,13 +1550,12 @@ impl Engine {
         order_reqs: Vec<crate::model::OrderRequest>,
         runtime: &mut EngineRuntime,
         strategy: &mut Box<dyn Strategy>,
-        exchange_client: &ExchangeClient,
-        coin: &str,
+        exchange_client: &TradingClient,
         mid_price: f64,
-    ) {
+    ) -> HashMap<Cloid, OrderOutcome> {
         info!("[BULK_ORDER] {} orders", order_reqs.len());
+        let mut outcomes = HashMap::new();
 
-        let target_symbol = self.config.symbol();
         let mut sdk_reqs = Vec::with_capacity(order_reqs.len());
         let mut order_contexts = Vec::with_capacity(order_reqs.len());
 
@@ -686,11 +1577,31 @@ impl Engine {
                     if *reduce_only { " (RO)" } else { "" }
                 ),
                 crate::model::OrderRequest::Market {
-                    symbol, side, sz, ..
-                } => format!("MARKET {} {} {}", side, sz, symbol),
+                    symbol,
+                    side,
+                    sz,
+                    reduce_only,
+                    ..
+                } => format!(
+                    "MARKET {} {} {}{}",
+                    side,
+                    sz,
+                    symbol,
+                    if *reduce_only { " (RO)" } else { "" }
+                ),
                 _ => continue, // Cancels handled separately
             };
 
+            let Some(market_info) = order_req
+                .symbol()
+                .and_then(|symbol| runtime.ctx.market_info(symbol))
+                .cloned()
+            else {
+                error!("[ORDER_SKIPPED] No market info for ({})", req_summary);
+                continue;
+            };
+            let target_symbol = market_info.symbol.as_str();
+
             let (side, limit_px, sz, reduce_only, order_type, cloid, target_sz) = match order_req {
                 crate::model::OrderRequest::Limit {
                     symbol: _,
@@ -714,16 +1625,18 @@ impl Engine {
                     symbol: _,
                     side,
                     sz,
+                    reduce_only,
+                    limit_px,
                     cloid,
                 } => {
-                    let market_info = runtime.ctx.market_info(target_symbol).unwrap();
-                    let market_price = market_info.round_price(mid_price);
+                    let mid = runtime.ctx.get_mid(target_symbol).unwrap_or(mid_price);
+                    let market_price = limit_px.unwrap_or_else(|| market_info.round_price(mid));
 
                     (
                         side,
                         market_price,
                         sz,
-                        false,
+                        reduce_only,
                         ClientOrder::Limit(ClientLimit {
                             tif: "Ioc".to_string(),
                         }),
@@ -735,7 +1648,7 @@ impl Engine {
             };
 
             let sdk_req = ClientOrderRequest {
-                asset: coin.to_string(),
+                asset: market_info.coin.clone(),
                 is_buy: side.is_buy(),
                 limit_px,
                 sz,
@@ -749,25 +1662,36 @@ impl Engine {
             info!("[ORDER_SENT] Exchange ({})", req_summary);
 
             sdk_reqs.push(sdk_req);
-            order_contexts.push((cloid, side, target_sz, reduce_only, limit_px));
+            order_contexts.push((cloid, side, target_sz, reduce_only, limit_px, market_info));
         }
 
         if sdk_reqs.is_empty() {
-            return;
+            return outcomes;
         }
 
-        match exchange_client.bulk_order(sdk_reqs, None).await {
+        self.metrics.record_orders_sent(sdk_reqs.len());
+        let sent_at = std::time::Instant::now();
+        let response = exchange_client.bulk_order(sdk_reqs).await;
+        self.metrics.record_order_latency(sent_at.elapsed());
+
+        match response {
             Ok(hyperliquid_rust_sdk::ExchangeResponseStatus::Ok(exchange_res)) => {
                 if let Some(data) = &exchange_res.data {
                     for (i, status) in data.statuses.iter().enumerate() {
-                        let (cloid, side, target_sz, reduce_only, limit_px) = order_contexts[i];
+                        let (cloid, side, target_sz, reduce_only, limit_px, ref market_info) =
+                            order_contexts[i];
+                        let target_symbol = market_info.symbol.as_str();
 
                         match status {
                             hyperliquid_rust_sdk::ExchangeDataStatus::Resting(r) => {
                                 if let Some(c) = cloid {
+                                    outcomes.insert(c, OrderOutcome::Resting);
                                     runtime.pending_orders.insert(
                                         c,
                                         PendingOrder {
+                                            coin: market_info.coin.clone(),
+                                            side,
+                                            limit_px,
                                             target_size: target_sz,
                                             filled_size: 0.0,
                                             weighted_avg_px: 0.0,
@@ -802,6 +1726,7 @@ impl Engine {
                             hyperliquid_rust_sdk::ExchangeDataStatus::Filled(f) => {
                                 let amount: f64 = f.total_sz.parse().unwrap_or(0.0);
                                 let px: f64 = f.avg_px.parse().unwrap_or(0.0);
+                                self.metrics.record_order_filled("immediate");
                                 self.log_order_fill(
                                     target_symbol,
                                     side,
@@ -810,6 +1735,7 @@ impl Engine {
                                     reduce_only,
                                     cloid,
                                     0.0,
+                                    None,
                                     f.oid,
                                     true,
                                     None,
@@ -817,6 +1743,13 @@ impl Engine {
                                 info!("[ORDER_FILLED_MARKET] {} {} @ {}", side, amount, px);
 
                                 if let Some(c) = cloid {
+                                    outcomes.insert(
+                                        c,
+                                        OrderOutcome::Filled {
+                                            size: amount,
+                                            price: px,
+                                        },
+                                    );
                                     // Broadcast Filled
                                     self.broadcaster.send(WSEvent::OrderUpdate(OrderEvent {
                                         oid: f.oid,
@@ -850,6 +1783,7 @@ impl Engine {
                                 }
                             }
                             hyperliquid_rust_sdk::ExchangeDataStatus::Error(e) => {
+                                self.metrics.record_order_rejected("exchange");
                                 self.log_order_reject(
                                     target_symbol,
                                     side,
@@ -861,6 +1795,7 @@ impl Engine {
                                 );
                                 error!("Order Error for {:?}: {}", cloid, e);
                                 if let Some(c) = cloid {
+                                    outcomes.insert(c, OrderOutcome::Rejected(e.clone()));
                                     self.broadcaster.send(WSEvent::OrderUpdate(OrderEvent {
                                         oid: 0,
                                         cloid: Some(c.to_string()),
@@ -890,9 +1825,10 @@ impl Engine {
             Ok(hyperliquid_rust_sdk::ExchangeResponseStatus::Err(e)) => {
                 error!("Bulk order level error: {}", e);
                 // Fail all
-                for (cloid, side, target_sz, reduce_only, limit_px) in order_contexts {
+                for (cloid, side, target_sz, reduce_only, limit_px, market_info) in order_contexts {
+                    self.metrics.record_order_rejected("bulk_error");
                     self.log_order_reject(
-                        target_symbol,
+                        &market_info.symbol,
                         side,
                         limit_px,
                         target_sz,
@@ -901,6 +1837,7 @@ impl Engine {
                         &e,
                     );
                     if let Some(c) = cloid {
+                        outcomes.insert(c, OrderOutcome::Rejected(e.clone()));
                         if let Err(strategy_err) = strategy.on_order_failed(c, &mut runtime.ctx) {
                             error!("Strategy on_order_failed error: {}", strategy_err);
                         }
@@ -911,9 +1848,10 @@ impl Engine {
                 error!("Failed to place bulk orders: {:?}", e);
                 // Fail all
                 let reason = format!("{:?}", e);
-                for (cloid, side, target_sz, reduce_only, limit_px) in order_contexts {
+                for (cloid, side, target_sz, reduce_only, limit_px, market_info) in order_contexts {
+                    self.metrics.record_order_rejected("request_failed");
                     self.log_order_reject(
-                        target_symbol,
+                        &market_info.symbol,
                         side,
                         limit_px,
                         target_sz,
@@ -922,6 +1860,7 @@ impl Engine {
                         &reason,
                     );
                     if let Some(c) = cloid {
+                        outcomes.insert(c, OrderOutcome::Rejected(reason.clone()));
                         if let Err(strategy_err) = strategy.on_order_failed(c, &mut runtime.ctx) {
                             error!("Strategy on_order_failed error: {}", strategy_err);
                         }
@@ -929,6 +1868,7 @@ impl Engine {
                 }
             }
         }
+        outcomes
     }
 
     async fn process_user_events(
@@ -936,17 +1876,17 @@ impl Engine {
         user_events_data: UserData,
         runtime: &mut EngineRuntime,
         strategy: &mut Box<dyn Strategy>,
-        coin: &str,
     ) {
         if let UserData::Fills(fills) = user_events_data {
             for fill in fills {
-                if fill.coin != coin {
+                let Some(display_symbol) = runtime.traded_coins.get(&fill.coin).cloned() else {
                     debug!(
-                        "Ignoring fill for different coin: {} (expected: {})",
-                        fill.coin, coin
+                        "Ignoring fill for different coin: {} (expected: {:?})",
+                        fill.coin,
+                        runtime.traded_coins.keys().collect::<Vec<_>>()
                     );
                     continue;
-                }
+                };
 
                 let amount: f64 = fill.sz.parse().unwrap_or(0.0);
                 let px: f64 = fill.px.parse().unwrap_or(0.0);
@@ -968,20 +1908,16 @@ impl Engine {
                     .map(|p| p.reduce_only)
                     .unwrap_or(false);
 
-                let display_symbol = runtime
-                    .ctx
-                    .market_info(coin)
-                    .map(|m| m.symbol.as_str())
-                    .unwrap_or(coin);
-
+                self.metrics.record_order_filled("user_event");
                 self.log_order_fill(
-                    display_symbol,
+                    &display_symbol,
                     side,
                     px,
                     amount,
                     record_reduce_only,
                     cloid,
                     fee,
+                    Some(fill.fee_token.as_str()),
                     fill.oid,
                     false,
                     Some(fill.dir.as_str()),
@@ -1175,6 +2111,7 @@ impl Engine {
                                     );
 
                                     info!("[RECONCILE_FILLED] {} {} @ {}", side, amount, px);
+                                    self.metrics.record_order_filled("reconcile");
 
                                     // Update State
                                     runtime.pending_orders.remove(&cloid);
@@ -1265,6 +2202,9 @@ mod tests {
 
     fn pending_order(oid: Option<u64>) -> PendingOrder {
         PendingOrder {
+            coin: "ETH".to_string(),
+            side: OrderSide::Buy,
+            limit_px: 100.0,
             target_size: 1.0,
             filled_size: 0.0,
             weighted_avg_px: 0.0,
@@ -1274,9 +2214,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_close_position_result_reports_the_fill() {
+        let filled = OrderOutcome::Filled {
+            size: 2.0,
+            price: 99.5,
+        };
+        let message =
+            Engine::close_position_result(3, OrderSide::Sell, 2.0, "BTC", Some(&filled)).unwrap();
+        assert!(message.contains("closed 2 BTC with a Sell @ 99.5"));
+
+        let partial = OrderOutcome::Filled {
+            size: 0.5,
+            price: 99.5,
+        };
+        let err = Engine::close_position_result(3, OrderSide::Sell, 2.0, "BTC", Some(&partial))
+            .unwrap_err();
+        assert!(err.to_string().contains("1.5 is still open"));
+
+        let rejected = OrderOutcome::Rejected("Order could not immediately match".to_string());
+        let err = Engine::close_position_result(3, OrderSide::Sell, 2.0, "BTC", Some(&rejected))
+            .unwrap_err();
+        assert!(err.to_string().contains("could not immediately match"));
+        assert!(Engine::close_position_result(3, OrderSide::Sell, 2.0, "BTC", None).is_err());
+    }
+
     #[test]
     fn test_collect_shutdown_cancel_cloids_empty_when_no_pending_orders() {
-        let runtime = EngineRuntime::new(StrategyContext::new(HashMap::new()));
+        let runtime = EngineRuntime::new(StrategyContext::new(HashMap::new()), HashMap::new());
 
         let cloids = Engine::collect_shutdown_cancel_cloids(&runtime);
 
@@ -1285,7 +2250,7 @@ mod tests {
 
     #[test]
     fn test_collect_shutdown_cancel_cloids_returns_sorted_pending_orders() {
-        let mut runtime = EngineRuntime::new(StrategyContext::new(HashMap::new()));
+        let mut runtime = EngineRuntime::new(StrategyContext::new(HashMap::new()), HashMap::new());
         let cloid_b = Cloid::from_uuid(Uuid::from_u128(2));
         let cloid_a = Cloid::from_uuid(Uuid::from_u128(1));
 
@@ -1300,4 +2265,55 @@ mod tests {
 
         assert_eq!(cloids, vec![cloid_a, cloid_b]);
     }
+
+    #[test]
+    fn test_open_orders_report_remaining_size() {
+        let mut runtime = EngineRuntime::new(StrategyContext::new(HashMap::new()), HashMap::new());
+        let mut partially_filled = pending_order(Some(11));
+        partially_filled.filled_size = 0.25;
+        runtime
+            .pending_orders
+            .insert(Cloid::from_uuid(Uuid::from_u128(1)), partially_filled);
+
+        let orders = Engine::open_orders(&runtime);
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].oid, 11);
+        assert_eq!(orders[0].price, 100.0);
+        assert_eq!(orders[0].size, 0.75);
+        assert_eq!(orders[0].status, "OPEN");
+    }
+
+    #[test]
+    fn test_reload_plan_lists_queued_actions() {
+        let mut ctx = StrategyContext::new(HashMap::new());
+        let old = Cloid::from_uuid(Uuid::from_u128(1));
+        ctx.cancel_order(old);
+        ctx.place_order(OrderRequest::Limit {
+            symbol: "HYPE/USDC".to_string(),
+            side: OrderSide::Buy,
+            price: 80.0,
+            sz: 2.0,
+            reduce_only: false,
+            cloid: None,
+        });
+        let changes = vec![reload::ConfigChange {
+            field: "grid_range_low".to_string(),
+            old: serde_json::json!(90.0),
+            new: serde_json::json!(80.0),
+        }];
+
+        let plan = Engine::reload_plan(changes, &ctx, true);
+
+        assert_eq!(plan.cancels, vec![old.to_string()]);
+        assert_eq!(
+            plan.orders,
+            vec![PlannedOrder {
+                side: OrderSide::Buy.to_string(),
+                price: Some(80.0),
+                size: 2.0,
+            }]
+        );
+        assert!(plan.dry_run);
+    }
 }
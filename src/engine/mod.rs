@@ -5,10 +5,14 @@
 //! - `simulation`: Dry-run engine for previewing orders without execution
 //! - `common`: Shared utilities between engines
 //! - `context`: Strategy execution context
+//! - `exchange`: Order, cancel and leverage calls, signed locally or remotely
+//! - `preflight`: Account checks before live trading starts
 
 pub mod common;
 pub mod context;
+pub mod exchange;
 pub mod live;
+pub mod preflight;
 pub mod simulation;
 
 // Re-export main types for convenient imports
@@ -0,0 +1,661 @@
+//! Account preflight checks, run before live trading and by `--check`.
+//!
+//! Each check turns a misconfiguration that would otherwise surface as a
+//! rejected order once the engine is live into one line of a pass/fail
+//! report: the master account, the API wallet approval, the sub-account,
+//! the markets and leverage, the balances the strategy needs and the
+//! request budget. `accounts validate` runs the account checks alone.
+
+use crate::config::exchange::ExchangeConfig;
+use crate::config::simulation::SimulationConfig;
+use crate::config::strategy::StrategyConfig;
+use crate::constants::{AGENT_EXPIRY_WARNING, PREFLIGHT_REQUEST_RESERVE};
+use crate::engine::common;
+use crate::engine::simulation::SimulationEngine;
+use crate::signer::RemoteSigner;
+use crate::strategy::init_strategy;
+use anyhow::{anyhow, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::H160;
+use hyperliquid_rust_sdk::InfoClient;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// Trading can start, but something needs attention soon.
+    Warn,
+    Fail,
+    /// Does not apply to this strategy or account.
+    Skip,
+}
+
+impl CheckStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skip => "SKIP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<Check>,
+}
+
+impl PreflightReport {
+    /// True when no check failed. Warnings do not block trading.
+    pub fn passed(&self) -> bool {
+        !self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+/// The `userRole` info response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "role", content = "data", rename_all = "camelCase")]
+enum UserRole {
+    /// The address has never held funds on this network.
+    Missing,
+    User,
+    Agent {
+        user: H160,
+    },
+    Vault,
+    SubAccount {
+        master: H160,
+    },
+}
+
+/// Entry of the `extraAgents` info response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApprovedAgent {
+    address: H160,
+    #[serde(default)]
+    name: String,
+    /// Expiry in unix milliseconds.
+    valid_until: Option<u64>,
+}
+
+/// Entry of the `subAccounts` info response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubAccount {
+    name: String,
+    sub_account_user: H160,
+}
+
+/// Fields of the `meta` universe the SDK's `AssetMeta` leaves out.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PerpAsset {
+    name: String,
+    max_leverage: u32,
+    #[serde(default)]
+    only_isolated: bool,
+    #[serde(default)]
+    is_delisted: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PerpUniverse {
+    universe: Vec<PerpAsset>,
+}
+
+/// The `userRateLimit` info response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimit {
+    n_requests_used: u64,
+    n_requests_cap: u64,
+}
+
+/// Runs every check against the live exchange. Checks that cannot query
+/// the exchange fail rather than abort, so the report is always complete.
+pub async fn run(
+    config: &StrategyConfig,
+    exchange_config: &ExchangeConfig,
+) -> Result<PreflightReport> {
+    let mut info_client = common::setup_info_client(&exchange_config.network).await?;
+    let mut report = PreflightReport {
+        checks: account_checks(&info_client, exchange_config).await?,
+    };
+    let trading_account = H160::from_str(exchange_config.trading_account_address())
+        .map_err(|e| anyhow!("Invalid trading account address: {}", e))?;
+    let markets = common::load_metadata(&mut info_client, "[PREFLIGHT] ").await?;
+    let spot_markets: HashSet<String> = markets
+        .keys()
+        .filter(|symbol| symbol.contains('/'))
+        .cloned()
+        .collect();
+    match info_request::<PerpUniverse>(&info_client, serde_json::json!({"type": "meta"})).await {
+        Ok(meta) => {
+            report
+                .checks
+                .push(check_markets(config, &spot_markets, &meta.universe));
+            report.checks.push(check_leverage(config, &meta.universe));
+        }
+        Err(e) => {
+            report
+                .checks
+                .push(Check::new("markets", CheckStatus::Fail, e.to_string()));
+            report
+                .checks
+                .push(Check::new("leverage", CheckStatus::Fail, e.to_string()));
+        }
+    }
+
+    let (balances, planned_orders) = check_balances(config, exchange_config).await;
+    report.checks.push(balances);
+
+    let request = serde_json::json!({"type": "userRateLimit", "user": trading_account});
+    report.checks.push(
+        match info_request::<RateLimit>(&info_client, request).await {
+            Ok(limit) => {
+                check_rate_limit(&limit, planned_orders as u64 + PREFLIGHT_REQUEST_RESERVE)
+            }
+            Err(e) => Check::new("rate_limit", CheckStatus::Fail, e.to_string()),
+        },
+    );
+
+    info!(
+        "[PREFLIGHT] {} passed, {} warnings, {} failed",
+        report.count(CheckStatus::Pass),
+        report.count(CheckStatus::Warn),
+        report.count(CheckStatus::Fail)
+    );
+    Ok(report)
+}
+
+/// Checks of the account profile alone: the master account, the API wallet
+/// approval and the sub-account.
+pub async fn check_account(exchange_config: &ExchangeConfig) -> Result<PreflightReport> {
+    let info_client = common::setup_info_client(&exchange_config.network).await?;
+    Ok(PreflightReport {
+        checks: account_checks(&info_client, exchange_config).await?,
+    })
+}
+
+async fn account_checks(
+    info_client: &InfoClient,
+    exchange_config: &ExchangeConfig,
+) -> Result<Vec<Check>> {
+    let master = H160::from_str(&exchange_config.master_account_address)
+        .map_err(|e| anyhow!("Invalid master account address: {}", e))?;
+    let sub_account = exchange_config
+        .sub_account_address
+        .as_deref()
+        .map(H160::from_str)
+        .transpose()
+        .map_err(|e| anyhow!("Invalid sub-account address: {}", e))?;
+    let mut checks = Vec::new();
+
+    let request = serde_json::json!({"type": "userRole", "user": master});
+    checks.push(match info_request::<UserRole>(info_client, request).await {
+        Ok(role) => check_master_account(&role, master),
+        Err(e) => Check::new("master_account", CheckStatus::Fail, e.to_string()),
+    });
+
+    let api_wallet = match api_wallet_address(exchange_config).await {
+        Ok(api_wallet) => {
+            let request = serde_json::json!({"type": "extraAgents", "user": master});
+            match info_request::<Vec<ApprovedAgent>>(info_client, request).await {
+                Ok(agents) => check_api_wallet(&agents, api_wallet, master, now_ms()),
+                Err(e) => Check::new("api_wallet", CheckStatus::Fail, e.to_string()),
+            }
+        }
+        Err(e) => Check::new("api_wallet", CheckStatus::Fail, e.to_string()),
+    };
+    checks.push(api_wallet);
+
+    checks.push(match sub_account {
+        None => Check::new(
+            "sub_account",
+            CheckStatus::Skip,
+            "No sub_account_address; trading the master account",
+        ),
+        Some(sub_account) => {
+            let request = serde_json::json!({"type": "subAccounts", "user": master});
+            // The response is null for masters without sub-accounts
+            match info_request::<Option<Vec<SubAccount>>>(info_client, request).await {
+                Ok(subs) => check_sub_account(&subs.unwrap_or_default(), sub_account, master),
+                Err(e) => Check::new("sub_account", CheckStatus::Fail, e.to_string()),
+            }
+        }
+    });
+    Ok(checks)
+}
+
+async fn info_request<T: DeserializeOwned>(
+    info_client: &InfoClient,
+    request: serde_json::Value,
+) -> Result<T> {
+    let kind = request["type"].as_str().unwrap_or_default().to_string();
+    let response = info_client
+        .http_client
+        .post("/info", request.to_string())
+        .await
+        .map_err(|e| anyhow!("{} request failed: {}", kind, e))?;
+    serde_json::from_str(&response).map_err(|e| anyhow!("Invalid {} response: {}", kind, e))
+}
+
+/// Address the exchange sees signing: the local key's, or the remote signer's.
+async fn api_wallet_address(exchange_config: &ExchangeConfig) -> Result<H160> {
+    match (
+        &exchange_config.api_wallet_private_key,
+        &exchange_config.api_wallet_signer,
+    ) {
+        (Some(key), _) => {
+            let wallet: LocalWallet = key
+                .parse()
+                .map_err(|e| anyhow!("Invalid API wallet private key: {}", e))?;
+            Ok(wallet.address())
+        }
+        (None, Some(endpoint)) => Ok(RemoteSigner::new(endpoint)?.identity().await?.address),
+        (None, None) => Err(anyhow!("No API wallet key or signer configured")),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn check_master_account(role: &UserRole, master: H160) -> Check {
+    const NAME: &str = "master_account";
+    match role {
+        UserRole::User => Check::new(NAME, CheckStatus::Pass, format!("{:?} exists", master)),
+        UserRole::Missing => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "No account at {:?} on this network. Check master_account_address for typos.",
+                master
+            ),
+        ),
+        UserRole::Agent { user } => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{:?} is an API wallet of {:?}. Set master_account_address = \"{:?}\".",
+                master, user, user
+            ),
+        ),
+        UserRole::SubAccount { master: owner } => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{:?} is a sub-account of {:?}. Set it as sub_account_address and master_account_address = \"{:?}\".",
+                master, owner, owner
+            ),
+        ),
+        UserRole::Vault => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{:?} is a vault. Set it as sub_account_address and the vault leader as master_account_address.",
+                master
+            ),
+        ),
+    }
+}
+
+fn check_api_wallet(
+    agents: &[ApprovedAgent],
+    api_wallet: H160,
+    master: H160,
+    now_ms: u64,
+) -> Check {
+    const NAME: &str = "api_wallet";
+    if api_wallet == master {
+        return Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "Signing with the master account key {:?}. Use an approved API wallet instead.",
+                master
+            ),
+        );
+    }
+    let Some(agent) = agents.iter().find(|agent| agent.address == api_wallet) else {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{:?} is not an approved API wallet of {:?}. Approve it on the API page of the master account.",
+                api_wallet, master
+            ),
+        );
+    };
+    let label = if agent.name.is_empty() {
+        format!("{:?}", api_wallet)
+    } else {
+        format!("{:?} ('{}')", api_wallet, agent.name)
+    };
+    match agent.valid_until {
+        Some(until) if until <= now_ms => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{} expired {} ago. Approve a new API wallet.",
+                label,
+                days(now_ms - until)
+            ),
+        ),
+        Some(until) if until - now_ms < AGENT_EXPIRY_WARNING.as_millis() as u64 => Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "{} is approved but expires in {}",
+                label,
+                days(until - now_ms)
+            ),
+        ),
+        Some(until) => Check::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{} is approved, expires in {}", label, days(until - now_ms)),
+        ),
+        None => Check::new(NAME, CheckStatus::Pass, format!("{} is approved", label)),
+    }
+}
+
+fn days(ms: u64) -> String {
+    let days = ms / (24 * 60 * 60 * 1000);
+    if days == 0 {
+        format!("{}h", ms / (60 * 60 * 1000))
+    } else {
+        format!("{}d", days)
+    }
+}
+
+fn check_sub_account(subs: &[SubAccount], sub_account: H160, master: H160) -> Check {
+    match subs.iter().find(|sub| sub.sub_account_user == sub_account) {
+        Some(sub) => Check::new(
+            "sub_account",
+            CheckStatus::Pass,
+            format!("{:?} ('{}') belongs to {:?}", sub_account, sub.name, master),
+        ),
+        None => Check::new(
+            "sub_account",
+            CheckStatus::Fail,
+            format!("{:?} is not a sub-account of {:?}", sub_account, master),
+        ),
+    }
+}
+
+fn check_markets(
+    config: &StrategyConfig,
+    spot_markets: &HashSet<String>,
+    perps: &[PerpAsset],
+) -> Check {
+    let mut problems = Vec::new();
+    for symbol in config.markets() {
+        if symbol.contains('/') {
+            if !spot_markets.contains(&symbol) {
+                problems.push(format!("{} is not a spot market", symbol));
+            }
+            continue;
+        }
+        match perps.iter().find(|asset| asset.name == symbol) {
+            Some(asset) if asset.is_delisted => problems.push(format!("{} is delisted", symbol)),
+            Some(_) => {}
+            None => problems.push(format!("{} is not a perp market", symbol)),
+        }
+    }
+    if problems.is_empty() {
+        Check::new(
+            "markets",
+            CheckStatus::Pass,
+            format!("{} tradable", config.markets().join(", ")),
+        )
+    } else {
+        Check::new("markets", CheckStatus::Fail, problems.join("; "))
+    }
+}
+
+fn check_leverage(config: &StrategyConfig, perps: &[PerpAsset]) -> Check {
+    const NAME: &str = "leverage";
+    let StrategyConfig::PerpGrid(grid) = config else {
+        return Check::new(NAME, CheckStatus::Skip, "No leverage to set");
+    };
+    let Some(asset) = perps.iter().find(|asset| asset.name == grid.symbol) else {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} has no perp metadata", grid.symbol),
+        );
+    };
+    if grid.leverage > asset.max_leverage {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{}x exceeds the {}x maximum for {}",
+                grid.leverage, asset.max_leverage, grid.symbol
+            ),
+        );
+    }
+    if asset.only_isolated && !grid.is_isolated {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "{} only trades isolated margin. Set is_isolated = true.",
+                grid.symbol
+            ),
+        );
+    }
+    Check::new(
+        NAME,
+        CheckStatus::Pass,
+        format!(
+            "{}x of {}x max for {}",
+            grid.leverage, asset.max_leverage, grid.symbol
+        ),
+    )
+}
+
+/// Runs the strategy's first step against the real balances, without the
+/// `[simulation]` patches, and returns the check and the orders it planned.
+async fn check_balances(
+    config: &StrategyConfig,
+    exchange_config: &ExchangeConfig,
+) -> (Check, usize) {
+    const NAME: &str = "balances";
+    let mut engine = SimulationEngine::new(
+        config.clone(),
+        exchange_config.clone(),
+        SimulationConfig::default(),
+    );
+    let started = async {
+        let mut strategy = init_strategy(config.clone())?;
+        engine.initialize().await?;
+        engine.run_single_step(&mut strategy).await
+    }
+    .await;
+    match started {
+        Ok(_) => {
+            let orders = engine.get_orders().len();
+            (
+                Check::new(
+                    NAME,
+                    CheckStatus::Pass,
+                    format!("Balances cover the strategy; {} opening orders", orders),
+                ),
+                orders,
+            )
+        }
+        Err(e) => (Check::new(NAME, CheckStatus::Fail, e.to_string()), 0),
+    }
+}
+
+fn check_rate_limit(limit: &RateLimit, needed: u64) -> Check {
+    let left = limit.n_requests_cap.saturating_sub(limit.n_requests_used);
+    let detail = format!(
+        "{} of {} requests left, {} needed to start",
+        left, limit.n_requests_cap, needed
+    );
+    if left < needed {
+        Check::new(
+            "rate_limit",
+            CheckStatus::Fail,
+            format!(
+                "{}. Trade volume raises the cap; until then only one request per 10s is allowed.",
+                detail
+            ),
+        )
+    } else {
+        Check::new("rate_limit", CheckStatus::Pass, detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    fn agent(address: H160, valid_until: Option<u64>) -> ApprovedAgent {
+        ApprovedAgent {
+            address,
+            name: "grid-bot".to_string(),
+            valid_until,
+        }
+    }
+
+    #[test]
+    fn test_api_wallet_check() {
+        let master = H160::repeat_byte(0x11);
+        let api = H160::repeat_byte(0x33);
+        let now = 100 * DAY_MS;
+
+        let check = check_api_wallet(&[agent(api, Some(now + 30 * DAY_MS))], api, master, now);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.detail.contains("'grid-bot'") && check.detail.contains("30d"));
+
+        let soon = check_api_wallet(&[agent(api, Some(now + 2 * DAY_MS))], api, master, now);
+        assert_eq!(soon.status, CheckStatus::Warn);
+        let expired = check_api_wallet(&[agent(api, Some(now - DAY_MS))], api, master, now);
+        assert_eq!(expired.status, CheckStatus::Fail);
+        let unknown = check_api_wallet(&[agent(master, None)], api, master, now);
+        assert_eq!(unknown.status, CheckStatus::Fail);
+        assert_eq!(
+            check_api_wallet(&[], master, master, now).status,
+            CheckStatus::Warn
+        );
+
+        // Field names as the info endpoint sends them
+        let agents: Vec<ApprovedAgent> = serde_json::from_str(
+            r#"[{"name":"grid-bot","address":"0x3333333333333333333333333333333333333333","validUntil":1790000000000}]"#,
+        )
+        .unwrap();
+        assert_eq!(agents[0].address, api);
+    }
+
+    #[test]
+    fn test_master_account_check() {
+        let master = H160::repeat_byte(0x11);
+        let role = |json: &str| -> UserRole { serde_json::from_str(json).unwrap() };
+
+        assert_eq!(
+            check_master_account(&role(r#"{"role":"user"}"#), master).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_master_account(&role(r#"{"role":"missing"}"#), master).status,
+            CheckStatus::Fail
+        );
+        // A sub-account pasted as the master points at the real master
+        let check = check_master_account(
+            &role(
+                r#"{"role":"subAccount","data":{"master":"0x2222222222222222222222222222222222222222"}}"#,
+            ),
+            master,
+        );
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check
+            .detail
+            .contains("\"0x2222222222222222222222222222222222222222\""));
+        assert_eq!(
+            check_master_account(
+                &role(r#"{"role":"agent","data":{"user":"0x2222222222222222222222222222222222222222"}}"#),
+                master
+            )
+            .status,
+            CheckStatus::Fail
+        );
+    }
+
+    #[test]
+    fn test_market_leverage_and_budget_checks() {
+        let config: StrategyConfig = toml::from_str(
+            "type = \"perp_grid\"\nsymbol = \"BTC\"\nleverage = 10\ngrid_range_low = 90000.0\ngrid_range_high = 110000.0\ngrid_count = 5\ntotal_investment = 1000.0\ngrid_bias = \"long\"\n",
+        )
+        .unwrap();
+        let universe: PerpUniverse = serde_json::from_str(
+            r#"{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":40},{"name":"OLD","szDecimals":0,"maxLeverage":3,"onlyIsolated":true,"isDelisted":true}]}"#,
+        )
+        .unwrap();
+        let spot = HashSet::from(["HYPE/USDC".to_string()]);
+
+        assert_eq!(
+            check_markets(&config, &spot, &universe.universe).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_leverage(&config, &universe.universe).status,
+            CheckStatus::Pass
+        );
+
+        let mut capped = universe.universe.clone();
+        capped[0].max_leverage = 5;
+        let check = check_leverage(&config, &capped);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("10x exceeds the 5x maximum"));
+
+        capped[0].is_delisted = true;
+        assert_eq!(
+            check_markets(&config, &spot, &capped).status,
+            CheckStatus::Fail
+        );
+
+        let limit = RateLimit {
+            n_requests_used: 9_950,
+            n_requests_cap: 10_000,
+        };
+        assert_eq!(check_rate_limit(&limit, 40).status, CheckStatus::Pass);
+        assert_eq!(check_rate_limit(&limit, 60).status, CheckStatus::Fail);
+    }
+}
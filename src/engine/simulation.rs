@@ -58,14 +58,21 @@ impl SimulationEngine {
         // 2. Load Markets
         self.markets = self.load_metadata(&mut info_client).await?;
 
-        let target_symbol = self.config.symbol();
-        if !self.markets.contains_key(target_symbol) {
-            return Err(anyhow!(
-                "Symbol '{}' not found in available markets",
-                target_symbol
-            ));
+        for target_symbol in self.config.markets() {
+            if !self.markets.contains_key(&target_symbol) {
+                return Err(anyhow!(
+                    "Symbol '{}' not found in available markets",
+                    target_symbol
+                ));
+            }
         }
 
+        // Reject grids whose spacing cannot cover the account's maker fees
+        let user_address = H160::from_str(self.exchange_config.trading_account_address())
+            .map_err(|e| anyhow!("Invalid account address: {}", e))?;
+        common::validate_grid_fees(&info_client, user_address, &self.config, "[SIMULATION] ")
+            .await?;
+
         // 3. Create Context
         let mut ctx = StrategyContext::new(self.markets.clone());
 
@@ -73,6 +80,12 @@ impl SimulationEngine {
         info!("[SIMULATION] Fetching real balances from exchange");
         self.fetch_balances(&mut info_client, &mut ctx).await;
 
+        if let Some((symbol, atr)) = self.config.candle_feed() {
+            if let Some(market) = self.markets.get(symbol) {
+                common::fetch_candles(&info_client, market, atr, &mut ctx, "[SIMULATION] ").await;
+            }
+        }
+
         if self.sim_config.balances.is_empty() {
             info!("[SIMULATION] No simulation balance patches configured");
         } else {
@@ -97,9 +110,29 @@ impl SimulationEngine {
         }
         self.current_price = price;
 
-        // Update market info with current price
+        // Multi-market strategies value every market at its live mid
+        let mids = if self.config.markets().len() > 1 {
+            info_client
+                .all_mids()
+                .await
+                .map_err(|e| anyhow!("Failed to fetch mids: {}", e))?
+        } else {
+            HashMap::new()
+        };
 
         if let Some(ctx) = &mut self.ctx {
+            ctx.update_mid(&self.config.markets()[0], price);
+            for symbol in self.config.markets() {
+                let mid = self
+                    .markets
+                    .get(&symbol)
+                    .and_then(|info| mids.get(&info.coin))
+                    .and_then(|px| px.parse::<f64>().ok());
+                if let Some(mid) = mid {
+                    ctx.update_mid(&symbol, mid);
+                }
+            }
+
             // Run strategy tick
             strategy.on_tick(price, ctx)?;
         }
@@ -155,7 +188,7 @@ impl SimulationEngine {
     }
 
     async fn fetch_current_price(&self, info_client: &mut InfoClient) -> Result<f64> {
-        let target_symbol = self.config.symbol();
+        let target_symbol = &self.config.markets()[0];
         let market_info = self
             .markets
             .get(target_symbol)
@@ -0,0 +1,190 @@
+//! Equity and PnL time series for dashboards.
+//!
+//! The live engine samples the strategy summary into an [`EquityHistory`] at
+//! the `[history]` interval. The series keeps the latest sample of every
+//! minute, hour and UTC day, is saved as JSON after each sample so it survives
+//! restarts, and is handed to the broadcaster as a [`History`] snapshot.
+
+use crate::broadcast::types::{History, HistoryPoint, StrategySummary};
+use crate::engine::context::StrategyContext;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Bucket width in milliseconds and points kept, per tier.
+const MINUTE_TIER: (u64, usize) = (60_000, 24 * 60);
+const HOUR_TIER: (u64, usize) = (3_600_000, 30 * 24);
+const DAY_TIER: (u64, usize) = (86_400_000, 2 * 365);
+
+/// Time series backed by a JSON file.
+#[derive(Debug)]
+pub struct EquityHistory {
+    path: PathBuf,
+    history: History,
+}
+
+impl EquityHistory {
+    /// Loads the series saved at `path`, or starts an empty one if the file does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let history = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read history {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid history file {}", path.display()))?
+        } else {
+            History::default()
+        };
+        Ok(Self { path, history })
+    }
+
+    /// Adds a sample to every tier. Samples older than the newest point are ignored.
+    pub fn record(&mut self, point: HistoryPoint) {
+        let tiers = [
+            (&mut self.history.minute, MINUTE_TIER),
+            (&mut self.history.hour, HOUR_TIER),
+            (&mut self.history.day, DAY_TIER),
+        ];
+        for (points, (width_ms, capacity)) in tiers {
+            push(points, point.clone(), width_ms, capacity);
+        }
+    }
+
+    pub fn snapshot(&self) -> History {
+        self.history.clone()
+    }
+
+    /// Writes the series to a temporary file and renames it over `path`.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&self.history)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+fn push(points: &mut Vec<HistoryPoint>, point: HistoryPoint, width_ms: u64, capacity: usize) {
+    if let Some(last) = points.last_mut() {
+        if point.timestamp_ms < last.timestamp_ms {
+            return;
+        }
+        if point.timestamp_ms / width_ms == last.timestamp_ms / width_ms {
+            *last = point;
+            return;
+        }
+    }
+    points.push(point);
+    if points.len() > capacity {
+        points.drain(..points.len() - capacity);
+    }
+}
+
+/// Account equity in quote: spot balances at mid for spot grids, the perp
+/// account value for perp grids and the portfolio value for rebalancing.
+/// TWAP has none.
+pub fn summary_equity(summary: &StrategySummary, ctx: &StrategyContext) -> Option<f64> {
+    match summary {
+        StrategySummary::SpotGrid(g) => ctx
+            .get_mid(&g.symbol)
+            .map(|mid| g.base_balance * mid + g.quote_balance),
+        StrategySummary::PerpGrid(_) => Some(ctx.get_perp_total("USDC")),
+        StrategySummary::Twap(_) => None,
+        StrategySummary::Rebalance(r) => Some(r.portfolio_value),
+    }
+}
+
+/// One history point from the current summary and the mid of `symbol`.
+pub fn sample(
+    summary: &StrategySummary,
+    ctx: &StrategyContext,
+    symbol: &str,
+    timestamp_ms: u64,
+) -> HistoryPoint {
+    let mut point = HistoryPoint {
+        timestamp_ms,
+        equity: summary_equity(summary, ctx),
+        matched_profit: None,
+        unrealized_pnl: None,
+        position_size: None,
+        price: ctx.get_mid(symbol),
+    };
+    match summary {
+        StrategySummary::SpotGrid(g) => {
+            point.matched_profit = Some(g.matched_profit);
+            point.position_size = Some(g.position_size);
+        }
+        StrategySummary::PerpGrid(g) => {
+            point.matched_profit = Some(g.matched_profit);
+            point.unrealized_pnl = Some(g.unrealized_pnl);
+            point.position_size = Some(g.position_size);
+        }
+        StrategySummary::Twap(t) => point.position_size = Some(t.filled_size),
+        StrategySummary::Rebalance(_) => {}
+    }
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp_ms: u64, equity: f64) -> HistoryPoint {
+        HistoryPoint {
+            timestamp_ms,
+            equity: Some(equity),
+            matched_profit: None,
+            unrealized_pnl: None,
+            position_size: None,
+            price: None,
+        }
+    }
+
+    #[test]
+    fn test_tiers_keep_latest_sample_per_bucket() {
+        let path = std::env::temp_dir().join(format!("hlbot-history-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut history = EquityHistory::open(&path).unwrap();
+
+        // Two samples in the first minute, one in the next, one an hour later
+        history.record(point(0, 100.0));
+        history.record(point(30_000, 101.0));
+        history.record(point(60_000, 102.0));
+        history.record(point(3_600_000, 110.0));
+        // Out of order samples are dropped
+        history.record(point(59_000, 0.0));
+
+        let equity = |points: &[HistoryPoint]| -> Vec<f64> {
+            points.iter().filter_map(|p| p.equity).collect()
+        };
+        let snapshot = history.snapshot();
+        assert_eq!(equity(&snapshot.minute), [101.0, 102.0, 110.0]);
+        assert_eq!(equity(&snapshot.hour), [102.0, 110.0]);
+        assert_eq!(equity(&snapshot.day), [110.0]);
+
+        // Survives a restart
+        history.save().unwrap();
+        let reloaded = EquityHistory::open(&path).unwrap();
+        assert_eq!(reloaded.snapshot(), snapshot);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_minute_tier_is_bounded() {
+        let mut points = Vec::new();
+        for minute in 0..(MINUTE_TIER.1 as u64 + 10) {
+            push(
+                &mut points,
+                point(minute * 60_000, 1.0),
+                MINUTE_TIER.0,
+                MINUTE_TIER.1,
+            );
+        }
+        assert_eq!(points.len(), MINUTE_TIER.1);
+        assert_eq!(points[0].timestamp_ms, 10 * 60_000);
+    }
+}
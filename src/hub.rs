@@ -0,0 +1,480 @@
+//! Dashboard hub for several bots.
+//!
+//! The hub connects to the WebSocket server of every bot in its config as a
+//! client, re-serves their events on its own [`StatusBroadcaster`] tagged with
+//! the bot name, and broadcasts a [`PortfolioSummary`] with the totals across
+//! bots. Upstream connections resume from the last `seq` received, so a short
+//! outage of a bot or of the network does not resend its whole state.
+
+use crate::broadcast::types::{
+    BotStatus, CoinExposure, CommandResult, PortfolioSummary, SequencedEvent, StrategySummary,
+    WSEvent,
+};
+use crate::broadcast::StatusBroadcaster;
+use crate::config::hub::{HubBotConfig, HubConfig};
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use log::{info, warn};
+use std::collections::BTreeMap;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+
+/// How often the portfolio summary is rebuilt, when something changed.
+const PORTFOLIO_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const SIZE_EPSILON: f64 = 1e-12;
+
+/// What a bot connection reports to the hub.
+enum Upstream {
+    Connected,
+    Disconnected,
+    Event(Box<WSEvent>),
+}
+
+/// Follows every bot in `config` and re-serves them on `broadcaster`. Runs
+/// until the process stops.
+pub async fn run(config: HubConfig, broadcaster: StatusBroadcaster) -> Result<()> {
+    let mut control = broadcaster
+        .take_control_receiver()
+        .ok_or_else(|| anyhow!("Control receiver already taken"))?;
+    let (updates, mut upstream) = mpsc::unbounded_channel();
+    for bot in &config.bots {
+        let token = bot.resolve_auth_token()?;
+        tokio::spawn(follow(bot.clone(), token, updates.clone()));
+    }
+
+    let mut portfolio = Portfolio::new(config.bots.iter().map(|bot| bot.name.clone()));
+    let mut ticker = tokio::time::interval(PORTFOLIO_INTERVAL);
+    let mut changed = true;
+    loop {
+        tokio::select! {
+            Some((bot, update)) = upstream.recv() => match update {
+                Upstream::Connected => {
+                    info!("[HUB] Connected to '{}'", bot);
+                    changed |= portfolio.set_connected(&bot, true);
+                }
+                Upstream::Disconnected => {
+                    warn!("[HUB] Lost connection to '{}'", bot);
+                    changed |= portfolio.set_connected(&bot, false);
+                }
+                Upstream::Event(event) => {
+                    changed |= portfolio.observe(&bot, &event);
+                    broadcaster.send_from(&bot, *event);
+                }
+            },
+            Some(request) = control.recv() => {
+                broadcaster.send(WSEvent::CommandResult(CommandResult {
+                    id: request.id,
+                    command: request.command.name().to_string(),
+                    success: false,
+                    message: "The hub is read-only; send commands to the bot directly".to_string(),
+                }));
+            }
+            _ = ticker.tick() => {
+                if changed {
+                    broadcaster.send(WSEvent::PortfolioSummary(portfolio.summary()));
+                    changed = false;
+                }
+            }
+        }
+    }
+}
+
+/// Keeps a connection to one bot open, reconnecting with backoff.
+async fn follow(
+    bot: HubBotConfig,
+    token: Option<String>,
+    updates: mpsc::UnboundedSender<(String, Upstream)>,
+) {
+    let mut last_seq: Option<u64> = None;
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        match connect(&bot.url, token.as_deref(), last_seq).await {
+            Ok(mut stream) => {
+                backoff = MIN_BACKOFF;
+                if updates
+                    .send((bot.name.clone(), Upstream::Connected))
+                    .is_err()
+                {
+                    return;
+                }
+                while let Some(message) = stream.next().await {
+                    let text = match message {
+                        Ok(Message::Text(text)) => text,
+                        Ok(Message::Close(_)) => break,
+                        Ok(_) => continue,
+                        Err(e) => {
+                            warn!("[HUB] Error from '{}': {}", bot.name, e);
+                            break;
+                        }
+                    };
+                    match serde_json::from_str::<SequencedEvent>(&text) {
+                        Ok(event) => {
+                            last_seq = Some(event.seq);
+                            let update = (bot.name.clone(), Upstream::Event(Box::new(event.event)));
+                            if updates.send(update).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => warn!("[HUB] Ignoring message from '{}': {}", bot.name, e),
+                    }
+                }
+                if updates
+                    .send((bot.name.clone(), Upstream::Disconnected))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            Err(e) => warn!("[HUB] Cannot reach '{}' at {}: {}", bot.name, bot.url, e),
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+async fn connect(
+    url: &str,
+    token: Option<&str>,
+    resume_from: Option<u64>,
+) -> Result<
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+> {
+    let url = match resume_from {
+        Some(seq) if url.contains('?') => format!("{}&resume_from={}", url, seq),
+        Some(seq) => format!("{}/?resume_from={}", url.trim_end_matches('/'), seq),
+        None => url.to_string(),
+    };
+    let mut request = url.into_client_request()?;
+    if let Some(token) = token {
+        request
+            .headers_mut()
+            .insert("Authorization", format!("Bearer {}", token).parse()?);
+    }
+    let (stream, _) = connect_async(request).await?;
+    Ok(stream)
+}
+
+/// Latest summary and price of every bot, in config order.
+pub struct Portfolio {
+    bots: Vec<BotView>,
+}
+
+struct BotView {
+    name: String,
+    connected: bool,
+    summary: Option<StrategySummary>,
+    price: Option<f64>,
+}
+
+impl Portfolio {
+    pub fn new(names: impl IntoIterator<Item = String>) -> Self {
+        let bots = names
+            .into_iter()
+            .map(|name| BotView {
+                name,
+                connected: false,
+                summary: None,
+                price: None,
+            })
+            .collect();
+        Self { bots }
+    }
+
+    fn view(&mut self, bot: &str) -> Option<&mut BotView> {
+        self.bots.iter_mut().find(|view| view.name == bot)
+    }
+
+    /// Returns whether the summary changed.
+    pub fn set_connected(&mut self, bot: &str, connected: bool) -> bool {
+        self.view(bot)
+            .is_some_and(|view| std::mem::replace(&mut view.connected, connected) != connected)
+    }
+
+    /// Takes summaries and prices from a bot's event. Returns whether the
+    /// summary changed.
+    pub fn observe(&mut self, bot: &str, event: &WSEvent) -> bool {
+        let Some(view) = self.view(bot) else {
+            return false;
+        };
+        match event {
+            WSEvent::SpotGridSummary(s) => {
+                view.summary = Some(StrategySummary::SpotGrid(s.clone()))
+            }
+            WSEvent::PerpGridSummary(s) => {
+                view.summary = Some(StrategySummary::PerpGrid(s.clone()))
+            }
+            WSEvent::TwapSummary(s) => view.summary = Some(StrategySummary::Twap(s.clone())),
+            WSEvent::RebalanceSummary(s) => {
+                view.summary = Some(StrategySummary::Rebalance(s.clone()))
+            }
+            WSEvent::MarketUpdate(m) => view.price = Some(m.price),
+            WSEvent::Resync(_) => {
+                view.summary = None;
+                view.price = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Totals across bots. Bots keep their last reported figures while disconnected.
+    pub fn summary(&self) -> PortfolioSummary {
+        // Coin -> (net size, latest price)
+        let mut coins: BTreeMap<String, (f64, Option<f64>)> = BTreeMap::new();
+        let mut add = |coin: &str, size: f64, price: Option<f64>| {
+            let entry = coins.entry(coin.to_string()).or_insert((0.0, None));
+            entry.0 += size;
+            entry.1 = price.or(entry.1);
+        };
+
+        let mut bots = Vec::with_capacity(self.bots.len());
+        for view in &self.bots {
+            let mut status = BotStatus {
+                bot: view.name.clone(),
+                connected: view.connected,
+                strategy: None,
+                equity: None,
+                pnl: None,
+            };
+            match &view.summary {
+                Some(StrategySummary::SpotGrid(g)) => {
+                    status.strategy = Some("spot_grid".to_string());
+                    status.equity = view.price.map(|p| g.base_balance * p + g.quote_balance);
+                    status.pnl = Some(g.total_profit);
+                    add(base_coin(&g.symbol), g.base_balance, view.price);
+                }
+                Some(StrategySummary::PerpGrid(g)) => {
+                    status.strategy = Some("perp_grid".to_string());
+                    status.equity = Some(g.margin_balance);
+                    status.pnl = Some(g.total_profit);
+                    add(base_coin(&g.symbol), g.position_size, view.price);
+                }
+                Some(StrategySummary::Twap(t)) => {
+                    status.strategy = Some("twap".to_string());
+                    let sign = if t.side.eq_ignore_ascii_case("buy") {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    add(base_coin(&t.symbol), sign * t.filled_size, view.price);
+                }
+                Some(StrategySummary::Rebalance(r)) => {
+                    status.strategy = Some("rebalance".to_string());
+                    status.equity = Some(r.portfolio_value);
+                    for a in r.allocations.iter().filter(|a| a.asset != r.quote_asset) {
+                        add(&a.asset, a.balance, Some(a.price));
+                    }
+                }
+                None => {}
+            }
+            bots.push(status);
+        }
+
+        let mut exposure: Vec<CoinExposure> = coins
+            .into_iter()
+            .filter(|(_, (size, _))| size.abs() > SIZE_EPSILON)
+            .map(|(coin, (size, price))| CoinExposure {
+                coin,
+                size,
+                notional: price.map(|p| size * p),
+            })
+            .collect();
+        exposure.sort_by(|a, b| {
+            let abs = |e: &CoinExposure| e.notional.unwrap_or(0.0).abs();
+            abs(b).total_cmp(&abs(a))
+        });
+
+        PortfolioSummary {
+            total_equity: bots.iter().filter_map(|b| b.equity).sum(),
+            total_pnl: bots.iter().filter_map(|b| b.pnl).sum(),
+            bots,
+            exposure,
+        }
+    }
+}
+
+/// `HYPE` for the spot pair `HYPE/USDC`; perp symbols are already coins.
+fn base_coin(symbol: &str) -> &str {
+    symbol.split('/').next().unwrap_or(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::types::{MarketEvent, PerpGridSummary, SpotGridSummary};
+    use crate::config::broadcast::WebsocketConfig;
+
+    fn spot(base_balance: f64, quote_balance: f64, total_profit: f64) -> WSEvent {
+        WSEvent::SpotGridSummary(SpotGridSummary {
+            symbol: "HYPE/USDC".to_string(),
+            state: "Running".to_string(),
+            uptime: "1h".to_string(),
+            position_size: base_balance,
+            matched_profit: 0.0,
+            total_profit,
+            total_fees: 0.0,
+            initial_entry_price: None,
+            grid_count: 10,
+            grid_range_low: 20.0,
+            grid_range_high: 30.0,
+            grid_spacing_pct: (1.0, 1.0),
+            roundtrips: 0,
+            base_balance,
+            quote_balance,
+        })
+    }
+
+    fn perp(symbol: &str, position_size: f64, margin_balance: f64) -> WSEvent {
+        WSEvent::PerpGridSummary(PerpGridSummary {
+            symbol: symbol.to_string(),
+            state: "Running".to_string(),
+            uptime: "1h".to_string(),
+            position_size,
+            position_side: "Short".to_string(),
+            avg_entry_price: 25.0,
+            matched_profit: 0.0,
+            total_profit: -5.0,
+            unrealized_pnl: 0.0,
+            total_fees: 0.0,
+            leverage: 3,
+            grid_bias: "short".to_string(),
+            grid_count: 10,
+            grid_range_low: 20.0,
+            grid_range_high: 30.0,
+            grid_spacing_pct: (1.0, 1.0),
+            roundtrips: 0,
+            margin_balance,
+            initial_entry_price: None,
+        })
+    }
+
+    fn market(price: f64) -> WSEvent {
+        WSEvent::MarketUpdate(MarketEvent { price })
+    }
+
+    #[test]
+    fn test_portfolio_totals_and_exposure() {
+        let mut portfolio = Portfolio::new(["spot".to_string(), "perp".to_string()]);
+        assert!(portfolio.set_connected("spot", true));
+        assert!(!portfolio.set_connected("spot", true));
+        assert!(!portfolio.observe("unknown", &market(1.0)));
+
+        assert!(portfolio.observe("spot", &spot(10.0, 500.0, 12.0)));
+        // Spot equity needs a price
+        assert_eq!(portfolio.summary().bots[0].equity, None);
+        portfolio.observe("spot", &market(25.0));
+        portfolio.observe("perp", &perp("HYPE", -4.0, 1000.0));
+        portfolio.observe("perp", &market(26.0));
+
+        let summary = portfolio.summary();
+        assert_eq!(summary.bots[0].equity, Some(750.0));
+        assert_eq!(summary.bots[0].strategy.as_deref(), Some("spot_grid"));
+        assert!(!summary.bots[1].connected);
+        assert_eq!(summary.total_equity, 1750.0);
+        assert_eq!(summary.total_pnl, 7.0);
+        // Spot holdings and the perp short net out per coin
+        assert_eq!(
+            summary.exposure,
+            [CoinExposure {
+                coin: "HYPE".to_string(),
+                size: 6.0,
+                notional: Some(156.0),
+            }]
+        );
+
+        // A resyncing bot drops out until its snapshot arrives
+        portfolio.observe(
+            "perp",
+            &WSEvent::Resync(crate::broadcast::types::Resync {
+                reason: crate::broadcast::types::ResyncReason::ResumeUnavailable,
+            }),
+        );
+        assert_eq!(portfolio.summary().total_equity, 750.0);
+    }
+
+    async fn server(port: u16) -> StatusBroadcaster {
+        let broadcaster =
+            StatusBroadcaster::new(Some(WebsocketConfig::new("127.0.0.1", port))).unwrap();
+        while tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_err()
+        {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        broadcaster
+    }
+
+    async fn next_event<S>(client: &mut S) -> SequencedEvent
+    where
+        S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>>
+            + Unpin,
+    {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(&message.into_text().unwrap()).unwrap()
+    }
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[tokio::test]
+    async fn test_hub_tags_events_with_bot_name() {
+        let bot_port = free_port();
+        let bot = server(bot_port).await;
+        bot.send(perp("BTC", 0.5, 2000.0));
+
+        let hub_port = free_port();
+        let hub = server(hub_port).await;
+        let config: HubConfig = toml::from_str(&format!(
+            "[[bots]]\nname = \"btc\"\nurl = \"ws://127.0.0.1:{}\"\n",
+            bot_port
+        ))
+        .unwrap();
+        tokio::spawn(run(config, hub));
+
+        let (mut client, _) = connect_async(format!("ws://127.0.0.1:{}", hub_port))
+            .await
+            .unwrap();
+        // The bot's cached summary arrives tagged, then the portfolio totals
+        let mut tagged = false;
+        loop {
+            let event = next_event(&mut client).await;
+            match event.event {
+                WSEvent::PerpGridSummary(_) => {
+                    assert_eq!(event.bot.as_deref(), Some("btc"));
+                    tagged = true;
+                }
+                WSEvent::PortfolioSummary(summary) if tagged => {
+                    assert_eq!(event.bot, None);
+                    assert_eq!(summary.total_equity, 2000.0);
+                    assert!(summary.bots[0].connected);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        bot.send(market(60000.0));
+        loop {
+            let event = next_event(&mut client).await;
+            if let WSEvent::MarketUpdate(m) = event.event {
+                assert_eq!(m.price, 60000.0);
+                assert_eq!(event.bot.as_deref(), Some("btc"));
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Append-only trade journal.
+//!
+//! The live engine records every order request, ack, fill, reject, cancel and
+//! reconciliation event to an embedded SQLite database, keyed by bot name and
+//! cloid. The `journal` subcommand reads it back for accounting and post-mortems.
+
+use crate::model::{Cloid, OrderSide};
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, params_from_iter, types::Value, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Journal location used when `--journal-file` is not given.
+pub const DEFAULT_JOURNAL_FILE: &str = "data/journal.sqlite";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp_ms INTEGER NOT NULL,
+    bot TEXT NOT NULL,
+    event TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    side TEXT,
+    price REAL,
+    size REAL,
+    reduce_only INTEGER,
+    cloid TEXT,
+    oid INTEGER,
+    fee REAL,
+    is_taker INTEGER,
+    raw_dir TEXT,
+    details TEXT,
+    fee_token TEXT
+);
+CREATE INDEX IF NOT EXISTS journal_bot_cloid ON journal (bot, cloid);
+CREATE INDEX IF NOT EXISTS journal_bot_time ON journal (bot, timestamp_ms);
+CREATE TRIGGER IF NOT EXISTS journal_no_update BEFORE UPDATE ON journal
+BEGIN SELECT RAISE(ABORT, 'journal is append-only'); END;
+CREATE TRIGGER IF NOT EXISTS journal_no_delete BEFORE DELETE ON journal
+BEGIN SELECT RAISE(ABORT, 'journal is append-only'); END;
+";
+
+const COLUMNS: &str = "id, timestamp_ms, bot, event, symbol, side, price, size, reduce_only, \
+                       cloid, oid, fee, is_taker, raw_dir, details, fee_token";
+
+/// One journal row. `event` uses the `[ORDER_AUDIT]` event names
+/// (`req`, `open`, `fill`, `reject`, `cancel_request`, `cancel_result`, `reconcile_*`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Row id, assigned on insert.
+    pub id: i64,
+    pub timestamp_ms: u64,
+    pub bot: String,
+    pub event: String,
+    pub symbol: String,
+    pub side: Option<OrderSide>,
+    pub price: Option<f64>,
+    pub size: Option<f64>,
+    pub reduce_only: Option<bool>,
+    pub cloid: Option<String>,
+    pub oid: Option<u64>,
+    pub fee: Option<f64>,
+    pub is_taker: Option<bool>,
+    pub raw_dir: Option<String>,
+    pub details: Option<String>,
+    /// Token `fee` is paid in. Spot buys pay it in the base token.
+    #[serde(default)]
+    pub fee_token: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(event: &str, symbol: &str) -> Self {
+        Self {
+            event: event.to_string(),
+            symbol: symbol.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let side: Option<String> = row.get(5)?;
+        Ok(Self {
+            id: row.get(0)?,
+            timestamp_ms: row.get(1)?,
+            bot: row.get(2)?,
+            event: row.get(3)?,
+            symbol: row.get(4)?,
+            side: side.as_deref().and_then(parse_side),
+            price: row.get(6)?,
+            size: row.get(7)?,
+            reduce_only: row.get(8)?,
+            cloid: row.get(9)?,
+            oid: row.get(10)?,
+            fee: row.get(11)?,
+            is_taker: row.get(12)?,
+            raw_dir: row.get(13)?,
+            details: row.get(14)?,
+            fee_token: row.get(15)?,
+        })
+    }
+}
+
+/// Filters for [`Journal::query`]. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct JournalQuery {
+    pub bot: Option<String>,
+    pub cloid: Option<String>,
+    pub event: Option<String>,
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+    /// Keep only the most recent N matching rows.
+    pub limit: Option<usize>,
+}
+
+/// Handle to the journal database. Safe to share between tasks.
+pub struct Journal {
+    conn: Mutex<Connection>,
+}
+
+impl Journal {
+    /// Opens (or creates) the journal at `path`, creating parent directories as needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open journal {}", path.display()))?;
+        // Several bots may share one journal file.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        Self::init(conn)
+    }
+
+    /// Opens a journal that lives only as long as the handle.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize journal schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Appends an entry. A zero `timestamp_ms` is replaced with the current time.
+    pub fn record(&self, entry: &JournalEntry) -> Result<()> {
+        let timestamp_ms = if entry.timestamp_ms == 0 {
+            now_ms()
+        } else {
+            entry.timestamp_ms
+        };
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow!("Journal lock poisoned"))?;
+        conn.execute(
+            "INSERT INTO journal (timestamp_ms, bot, event, symbol, side, price, size, \
+             reduce_only, cloid, oid, fee, is_taker, raw_dir, details, fee_token) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                timestamp_ms,
+                entry.bot,
+                entry.event,
+                entry.symbol,
+                entry.side.map(side_str),
+                entry.price,
+                entry.size,
+                entry.reduce_only,
+                entry.cloid,
+                entry.oid,
+                entry.fee,
+                entry.is_taker,
+                entry.raw_dir,
+                entry.details,
+                entry.fee_token,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns matching entries, oldest first.
+    pub fn query(&self, query: &JournalQuery) -> Result<Vec<JournalEntry>> {
+        let mut clauses = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(bot) = &query.bot {
+            clauses.push("bot = ?");
+            values.push(Value::Text(bot.clone()));
+        }
+        if let Some(cloid) = &query.cloid {
+            // Match the stored wire format regardless of how the cloid was typed.
+            let cloid =
+                Cloid::from_hex_str(cloid).ok_or_else(|| anyhow!("Invalid cloid: {}", cloid))?;
+            clauses.push("cloid = ?");
+            values.push(Value::Text(cloid.to_string()));
+        }
+        if let Some(event) = &query.event {
+            clauses.push("event = ?");
+            values.push(Value::Text(event.clone()));
+        }
+        if let Some(since) = query.since_ms {
+            clauses.push("timestamp_ms >= ?");
+            values.push(Value::Integer(since as i64));
+        }
+        if let Some(until) = query.until_ms {
+            clauses.push("timestamp_ms < ?");
+            values.push(Value::Integer(until as i64));
+        }
+
+        let filter = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", clauses.join(" AND "))
+        };
+        let sql = match query.limit {
+            Some(limit) => format!(
+                "SELECT {COLUMNS} FROM (SELECT {COLUMNS} FROM journal{filter} \
+                 ORDER BY id DESC LIMIT {limit}) ORDER BY id"
+            ),
+            None => format!("SELECT {COLUMNS} FROM journal{filter} ORDER BY id"),
+        };
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow!("Journal lock poisoned"))?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), JournalEntry::from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read journal")
+    }
+}
+
+/// Writes entries as CSV with a header row.
+pub fn write_csv<W: Write>(entries: &[JournalEntry], writer: W) -> Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    for entry in entries {
+        csv.serialize(entry)?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Reads entries back from a CSV file written by [`write_csv`].
+pub fn read_csv(path: impl AsRef<Path>) -> Result<Vec<JournalEntry>> {
+    let path = path.as_ref();
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    reader
+        .deserialize()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid journal CSV {}", path.display()))
+}
+
+fn side_str(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "buy",
+        OrderSide::Sell => "sell",
+    }
+}
+
+fn parse_side(s: &str) -> Option<OrderSide> {
+    match s {
+        "buy" => Some(OrderSide::Buy),
+        "sell" => Some(OrderSide::Sell),
+        _ => None,
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(bot: &str, cloid: &Cloid, timestamp_ms: u64) -> JournalEntry {
+        JournalEntry {
+            timestamp_ms,
+            bot: bot.to_string(),
+            side: Some(OrderSide::Sell),
+            price: Some(25.5),
+            size: Some(2.0),
+            reduce_only: Some(false),
+            cloid: Some(cloid.to_string()),
+            oid: Some(42),
+            fee: Some(0.02),
+            is_taker: Some(false),
+            raw_dir: Some("Sell".to_string()),
+            fee_token: Some("USDC".to_string()),
+            ..JournalEntry::new("fill", "HYPE/USDC")
+        }
+    }
+
+    #[test]
+    fn test_journal_records_and_filters() {
+        let journal = Journal::open_in_memory().unwrap();
+        let a = Cloid::new();
+        let b = Cloid::new();
+        journal.record(&fill("bot-a", &a, 1_000)).unwrap();
+        journal
+            .record(&JournalEntry {
+                bot: "bot-a".to_string(),
+                cloid: Some(b.to_string()),
+                details: Some("status=missing_from_open_orders".to_string()),
+                timestamp_ms: 2_000,
+                ..JournalEntry::new("reconcile_missing", "HYPE/USDC")
+            })
+            .unwrap();
+        journal.record(&fill("bot-b", &a, 3_000)).unwrap();
+
+        let all = journal.query(&JournalQuery::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].id, 1);
+        assert_eq!(
+            all[0],
+            JournalEntry {
+                id: 1,
+                ..fill("bot-a", &a, 1_000)
+            }
+        );
+
+        let by_bot = JournalQuery {
+            bot: Some("bot-a".to_string()),
+            ..JournalQuery::default()
+        };
+        assert_eq!(journal.query(&by_bot).unwrap().len(), 2);
+
+        // Cloids match without the 0x prefix.
+        let by_cloid = JournalQuery {
+            cloid: Some(format!("{:032x}", a.as_uuid().as_u128())),
+            ..JournalQuery::default()
+        };
+        let rows = journal.query(&by_cloid).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| r.event == "fill"));
+
+        let window = JournalQuery {
+            since_ms: Some(2_000),
+            until_ms: Some(3_000),
+            ..JournalQuery::default()
+        };
+        let rows = journal.query(&window).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].event, "reconcile_missing");
+
+        let latest = JournalQuery {
+            limit: Some(2),
+            ..JournalQuery::default()
+        };
+        let ids: Vec<i64> = journal
+            .query(&latest)
+            .unwrap()
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_journal_is_append_only() {
+        let journal = Journal::open_in_memory().unwrap();
+        journal.record(&fill("bot", &Cloid::new(), 1)).unwrap();
+        let conn = journal.conn.lock().unwrap();
+        assert!(conn.execute("DELETE FROM journal", []).is_err());
+        assert!(conn.execute("UPDATE journal SET fee = 0", []).is_err());
+    }
+
+    #[test]
+    fn test_journal_persists_and_exports_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("journal.sqlite");
+        let cloid = Cloid::new();
+        Journal::open(&path)
+            .unwrap()
+            .record(&fill("bot", &cloid, 5))
+            .unwrap();
+
+        let entries = Journal::open(&path)
+            .unwrap()
+            .query(&JournalQuery::default())
+            .unwrap();
+        let mut out = Vec::new();
+        write_csv(&entries, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "id,timestamp_ms,bot,event,symbol,side,price,size,reduce_only,cloid,oid,fee,is_taker,raw_dir,details,fee_token"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "1,5,bot,fill,HYPE/USDC,sell,25.5,2.0,false,{},42,0.02,false,Sell,,USDC",
+                cloid
+            )
+        );
+    }
+}
@@ -3,6 +3,13 @@ pub mod config;
 pub mod constants;
 pub mod engine;
 pub mod error;
+pub mod history;
+pub mod hub;
+pub mod journal;
+pub mod metrics;
 pub mod model;
+pub mod notify;
+pub mod report;
+pub mod signer;
 pub mod strategy;
 pub mod ui;
@@ -1,20 +1,46 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use ethers::signers::LocalWallet;
+use ethers::types::H160;
+use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient};
+use hyperliquid_trading_bot::broadcast::sink::open_sinks;
 use hyperliquid_trading_bot::broadcast::StatusBroadcaster;
+use hyperliquid_trading_bot::config::accounts;
 use hyperliquid_trading_bot::config::bot::BotConfig;
 use hyperliquid_trading_bot::config::broadcast::load_broadcast_config;
-use hyperliquid_trading_bot::config::exchange::ExchangeConfig;
+use hyperliquid_trading_bot::config::exchange::{resolve_accounts_file_path, ExchangeConfig};
+use hyperliquid_trading_bot::config::hub::load_hub_config;
+use hyperliquid_trading_bot::config::notification::AlertKind;
+use hyperliquid_trading_bot::config::signer::load_signer_config;
+use hyperliquid_trading_bot::config::sink::SinkConfig;
+use hyperliquid_trading_bot::config::strategy::StrategyConfig;
 use hyperliquid_trading_bot::config::{exchange::load_exchange_config, load_bot_config};
+use hyperliquid_trading_bot::constants::{INVESTMENT_BUFFER_PERP, INVESTMENT_BUFFER_SPOT};
 use hyperliquid_trading_bot::engine::simulation::SimulationEngine;
 use hyperliquid_trading_bot::engine::Engine;
+use hyperliquid_trading_bot::engine::{common, preflight};
+use hyperliquid_trading_bot::history::EquityHistory;
+use hyperliquid_trading_bot::journal::{
+    self, Journal, JournalEntry, JournalQuery, DEFAULT_JOURNAL_FILE,
+};
+use hyperliquid_trading_bot::metrics::{self, EngineMetrics};
+use hyperliquid_trading_bot::notify::{self, Alert, Notifier};
+use hyperliquid_trading_bot::report::{self, CostBasis, PnlReport};
+use hyperliquid_trading_bot::signer::{self, server::SignerService};
+use hyperliquid_trading_bot::strategy::common::recommend_grid;
 use hyperliquid_trading_bot::strategy::init_strategy;
 use hyperliquid_trading_bot::ui::console::ConsoleRenderer;
-use log::{error, info}; // Keep this import
+use log::{error, info, warn}; // Keep this import
 use std::backtrace::Backtrace;
+use std::str::FromStr;
+use std::sync::Arc;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Hyperliquid Trading Bot", long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long)]
     config: Option<String>,
 
@@ -30,6 +56,134 @@ struct Args {
     /// Run in simulation mode (dry run preview)
     #[arg(long)]
     dry_run: bool,
+
+    /// Suggest grid_count/spread_bips for the config's range, investment and fee tier
+    #[arg(long)]
+    recommend: bool,
+
+    /// Check the API wallet, sub-account, markets, leverage, balances and rate limit, then exit
+    #[arg(long)]
+    check: bool,
+
+    /// Start live trading without the preflight checks
+    #[arg(long)]
+    skip_preflight: bool,
+
+    /// Trade journal database (default: data/journal.sqlite)
+    #[arg(long, global = true)]
+    journal_file: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Query the trade journal or export it to CSV
+    Journal(JournalArgs),
+    /// Realized PnL report from the trade journal or a saved fill file
+    Report(ReportArgs),
+    /// Serve the WebSocket feeds of several bots as one stream with portfolio totals
+    Hub(HubArgs),
+    /// Hold an account's API wallet key and sign the actions its policy allows
+    Signer(SignerArgs),
+    /// Add, list, validate or remove profiles in the accounts file
+    Accounts(AccountsArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct JournalArgs {
+    /// Only entries for this bot name
+    #[arg(long)]
+    bot: Option<String>,
+
+    /// Only entries for this client order id
+    #[arg(long)]
+    cloid: Option<String>,
+
+    /// Only entries of this event type (req, open, fill, reject, cancel_request, ...)
+    #[arg(long)]
+    event: Option<String>,
+
+    /// Only entries at or after this unix time in milliseconds
+    #[arg(long)]
+    since: Option<u64>,
+
+    /// Only entries before this unix time in milliseconds
+    #[arg(long)]
+    until: Option<u64>,
+
+    /// Show only the most recent N matching entries
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Write matching entries to this CSV file instead of printing them
+    #[arg(long)]
+    csv: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ReportArgs {
+    /// Journal CSV export to read instead of the journal database
+    #[arg(long)]
+    fills: Option<String>,
+
+    /// Only fills for this bot name
+    #[arg(long)]
+    bot: Option<String>,
+
+    /// Only fills for this symbol (e.g. "HYPE/USDC" or "BTC")
+    #[arg(long)]
+    symbol: Option<String>,
+
+    /// Report lots closed in this calendar year (UTC)
+    #[arg(long, value_parser = report::parse_year)]
+    year: Option<i32>,
+
+    /// Lot matching: fifo, lifo or average
+    #[arg(long, default_value = "fifo")]
+    method: CostBasis,
+
+    /// Write CSV and Markdown reports into this directory
+    #[arg(long)]
+    out_dir: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct HubArgs {
+    /// Hub TOML file listing the bots to follow
+    config: String,
+}
+
+#[derive(clap::Args, Debug)]
+struct SignerArgs {
+    /// Signer TOML file with the account, listen address and policy
+    config: String,
+}
+
+#[derive(clap::Args, Debug)]
+struct AccountsArgs {
+    #[command(subcommand)]
+    command: AccountsCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum AccountsCommand {
+    /// List profiles with their API wallet addresses; keys are redacted
+    List,
+    /// Add a profile interactively
+    Add,
+    /// Check a profile's master account, sub-account and API wallet on the exchange
+    Validate {
+        /// Account profile name
+        name: String,
+    },
+    /// Remove a profile
+    Remove {
+        /// Account profile name
+        name: String,
+
+        /// Do not ask for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 use tracing_subscriber::layer::SubscriberExt;
@@ -51,11 +205,13 @@ async fn main() -> Result<()> {
     // ---------------------------------------------------------
     // 1. Setup Logging (Tracing)
     // ---------------------------------------------------------
-    let file_appender = tracing_appender::rolling::daily("logs", log_file_name(args.dry_run));
+    let file_appender =
+        tracing_appender::rolling::daily("logs", log_file_name(args.dry_run || args.check));
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
 
-    // Console Layer (Env Filter)
+    // Console Layer (Env Filter), on stderr so stdout stays free for the stdout event sink
     let console_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
         .with_target(false)
         .with_level(true)
         .with_filter(
@@ -99,8 +255,14 @@ async fn main() -> Result<()> {
 
     info!(
         "Initialized logging for mode={} file=logs/{}.*",
-        if args.dry_run { "simulation" } else { "live" },
-        log_file_name(args.dry_run)
+        if args.dry_run {
+            "simulation"
+        } else if args.check {
+            "check"
+        } else {
+            "live"
+        },
+        log_file_name(args.dry_run || args.check)
     );
 
     if args.list_strategies {
@@ -108,6 +270,24 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    let journal_file = args
+        .journal_file
+        .clone()
+        .unwrap_or_else(|| DEFAULT_JOURNAL_FILE.to_string());
+
+    match args.command {
+        Some(Command::Journal(journal_args)) => return run_journal(&journal_file, journal_args),
+        Some(Command::Report(report_args)) => return run_report(&journal_file, report_args),
+        Some(Command::Hub(hub_args)) => return run_hub(hub_args).await,
+        Some(Command::Signer(signer_args)) => {
+            return run_signer(signer_args, args.accounts_file.as_deref()).await
+        }
+        Some(Command::Accounts(accounts_args)) => {
+            return run_accounts(accounts_args, args.accounts_file.as_deref()).await
+        }
+        None => {}
+    }
+
     if args.create {
         if let Err(e) = hyperliquid_trading_bot::config::creator::create_config() {
             error!("Error creating config: {}", e);
@@ -123,6 +303,9 @@ async fn main() -> Result<()> {
     // Load configuration
     info!("Loading config from: {}", config_path);
     let bot_config = load_bot_config(&config_path)?;
+    if let Some(notifications) = &bot_config.notifications {
+        notify::validate_templates(notifications)?;
+    }
 
     // Load exchange configuration
     let exchange_config =
@@ -138,6 +321,14 @@ async fn main() -> Result<()> {
         exchange_config.network
     );
 
+    if args.recommend {
+        return run_recommendation(bot_config, exchange_config).await;
+    }
+
+    if args.check {
+        return run_check(bot_config, exchange_config).await;
+    }
+
     // --- DRY RUN MODE ---
     if args.dry_run {
         info!("[SIMULATION] Running in dry-run mode...");
@@ -145,8 +336,39 @@ async fn main() -> Result<()> {
     }
 
     // --- LIVE TRADING MODE ---
+    if args.skip_preflight {
+        warn!("Skipping preflight checks (--skip-preflight)");
+    } else {
+        let report = match preflight::run(&bot_config.strategy, &exchange_config).await {
+            Ok(report) => report,
+            Err(e) => {
+                error!("Preflight checks could not run: {}", e);
+                std::process::exit(1);
+            }
+        };
+        ConsoleRenderer::render_preflight(
+            &bot_config.strategy,
+            &exchange_config.account_name,
+            &report,
+        );
+        if !report.passed() {
+            error!("Preflight checks failed. Fix them, or start with --skip-preflight.");
+            std::process::exit(1);
+        }
+    }
+
     // Load broadcast configuration (WebSocket)
-    let broadcast_config = load_broadcast_config(bot_config.websocket_port());
+    let broadcast_config = match load_broadcast_config(
+        bot_config.websocket_port(),
+        bot_config.metrics_port,
+        &bot_config.websocket_settings(),
+    ) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Invalid WebSocket settings: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     info!(
         "Starting bot '{}' with {} Strategy for {} on account '{}' (ws port {})",
@@ -157,8 +379,26 @@ async fn main() -> Result<()> {
         bot_config.websocket_port()
     );
 
-    let ws_config = Some(broadcast_config.websocket.clone());
-    let broadcaster = StatusBroadcaster::new(ws_config.clone());
+    let sink_configs = bot_config.sink_configs();
+    let sinks = match open_sinks(&sink_configs, &bot_config.name) {
+        Ok(sinks) => sinks,
+        Err(e) => {
+            error!("Failed to open event sinks: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let sink_names: Vec<&str> = sink_configs.iter().map(SinkConfig::name).collect();
+    info!("Broadcasting events to: {}", sink_names.join(", "));
+    let ws_config = sink_configs
+        .contains(&SinkConfig::Websocket)
+        .then(|| broadcast_config.websocket.clone());
+    let broadcaster = match StatusBroadcaster::with_sinks(ws_config.clone(), sinks) {
+        Ok(broadcaster) => broadcaster,
+        Err(e) => {
+            error!("Failed to start WebSocket server: {}", e);
+            std::process::exit(1);
+        }
+    };
     if let Some(conf) = ws_config {
         info!(
             "WebSocket Status Server enabled on {}:{}",
@@ -166,6 +406,12 @@ async fn main() -> Result<()> {
         );
     }
 
+    let notifier = Notifier::new(
+        &bot_config.notification_config(),
+        &bot_config.name,
+        bot_config.strategy.symbol(),
+    );
+
     // Initialize Strategy
     let strategy = match init_strategy(bot_config.strategy.clone()) {
         Ok(s) => s,
@@ -175,13 +421,59 @@ async fn main() -> Result<()> {
             broadcaster.send(hyperliquid_trading_bot::broadcast::types::WSEvent::Error(
                 e.to_string(),
             ));
+            notifier.notify(Alert::new(
+                AlertKind::Error,
+                "Strategy initialization failed",
+                e.to_string(),
+            ));
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
             std::process::exit(1);
         }
     };
 
+    let journal = match Journal::open(&journal_file) {
+        Ok(j) => j,
+        Err(e) => {
+            error!("Failed to open trade journal {}: {}", journal_file, e);
+            std::process::exit(1);
+        }
+    };
+    info!("Recording trade journal to {}", journal_file);
+
+    let engine_metrics = EngineMetrics::new(&bot_config.name, bot_config.strategy.symbol());
+    if let Some(conf) = broadcast_config.metrics {
+        let metrics_clone = engine_metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                metrics::serve(conf.host, conf.port, conf.auth_token, metrics_clone).await
+            {
+                error!("Metrics endpoint failed: {}", e);
+            }
+        });
+    }
+
     // Initialize Engine
-    let engine = Engine::new(bot_config.strategy, exchange_config, broadcaster.clone());
+    let history_config = bot_config.history_config();
+    let mut engine = Engine::new(bot_config.strategy, exchange_config, broadcaster.clone())
+        .with_journal(&bot_config.name, journal)
+        .with_metrics(engine_metrics)
+        .with_notifier(notifier.clone())
+        .with_config_file(&config_path);
+    if history_config.enabled {
+        let history_file = history_config.file_for(&bot_config.name);
+        let history = match EquityHistory::open(&history_file) {
+            Ok(history) => history,
+            Err(e) => {
+                error!("Failed to load equity history: {:#}", e);
+                std::process::exit(1);
+            }
+        };
+        info!("Recording equity history to {}", history_file);
+        engine = engine.with_history(
+            history,
+            std::time::Duration::from_secs(history_config.interval_secs),
+        );
+    }
 
     // Run Engine
     if let Err(e) = engine.run(strategy).await {
@@ -190,6 +482,7 @@ async fn main() -> Result<()> {
         broadcaster.send(hyperliquid_trading_bot::broadcast::types::WSEvent::Error(
             e.to_string(),
         ));
+        notifier.notify(Alert::new(AlertKind::Error, "Engine error", e.to_string()));
         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
         std::process::exit(1);
     }
@@ -197,19 +490,209 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::log_file_name;
+/// Query the trade journal and print or export the matching entries.
+fn run_journal(journal_file: &str, args: JournalArgs) -> Result<()> {
+    if !std::path::Path::new(journal_file).exists() {
+        return Err(anyhow::anyhow!("Trade journal not found: {}", journal_file));
+    }
+    let entries = Journal::open(journal_file)?.query(&JournalQuery {
+        bot: args.bot,
+        cloid: args.cloid,
+        event: args.event,
+        since_ms: args.since,
+        until_ms: args.until,
+        limit: args.limit,
+    })?;
 
-    #[test]
-    fn test_log_file_name_for_live_mode() {
-        assert_eq!(log_file_name(false), "application.log");
+    match args.csv {
+        Some(path) => {
+            let file = std::fs::File::create(&path)?;
+            journal::write_csv(&entries, file)?;
+            info!("Exported {} journal entries to {}", entries.len(), path);
+        }
+        None => ConsoleRenderer::render_journal(&entries),
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_log_file_name_for_dry_run_mode() {
-        assert_eq!(log_file_name(true), "simulation.log");
+/// Build a realized PnL report and print it, optionally writing the report files.
+fn run_report(journal_file: &str, args: ReportArgs) -> Result<()> {
+    let entries = match &args.fills {
+        Some(path) => journal::read_csv(path)?,
+        None => {
+            if !std::path::Path::new(journal_file).exists() {
+                return Err(anyhow::anyhow!("Trade journal not found: {}", journal_file));
+            }
+            Journal::open(journal_file)?.query(&JournalQuery {
+                event: Some("fill".to_string()),
+                ..JournalQuery::default()
+            })?
+        }
+    };
+    let entries: Vec<JournalEntry> = entries
+        .into_iter()
+        .filter(|e| args.bot.as_ref().is_none_or(|bot| &e.bot == bot))
+        .filter(|e| args.symbol.as_ref().is_none_or(|s| &e.symbol == s))
+        .collect();
+    let fills = report::fills_from_journal(&entries);
+    info!("Building {} report from {} fills", args.method, fills.len());
+
+    let pnl = PnlReport::build(&fills, args.method, args.year);
+    println!("{}", pnl.to_markdown());
+    if let Some(dir) = &args.out_dir {
+        pnl.write_files(dir)?;
+        info!("Wrote report files to {}", dir);
+    }
+    Ok(())
+}
+
+/// Follow the bots listed in the hub config and re-serve them on one WebSocket port.
+async fn run_hub(args: HubArgs) -> Result<()> {
+    let config = load_hub_config(&args.config)?;
+    let broadcast_config = load_broadcast_config(config.port, None, &config.websocket_settings())?;
+    let conf = broadcast_config.websocket;
+    info!(
+        "Starting hub for {} bots on {}:{}",
+        config.bots.len(),
+        conf.host,
+        conf.port
+    );
+    let broadcaster = StatusBroadcaster::new(Some(conf))?;
+    hyperliquid_trading_bot::hub::run(config, broadcaster).await
+}
+
+/// Serve signatures for the signer config's account, checked against its policy.
+async fn run_signer(args: SignerArgs, accounts_file: Option<&str>) -> Result<()> {
+    let config = load_signer_config(&args.config)?;
+    let exchange_config = load_exchange_config(&config.account, accounts_file)?;
+    let key = exchange_config
+        .api_wallet_private_key
+        .as_deref()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Account '{}' points at a signer itself; give the signer a key source",
+                config.account
+            )
+        })?;
+    let wallet: LocalWallet = key
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid API wallet private key: {}", e))?;
+    let vault_address = exchange_config
+        .vault_address()
+        .map(H160::from_str)
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid vault address: {}", e))?;
+    let base_url = if exchange_config.network == "mainnet" {
+        BaseUrl::Mainnet
+    } else {
+        BaseUrl::Testnet
+    };
+
+    // Only used for its asset ids; the signer never posts to the exchange
+    let client = ExchangeClient::new(None, wallet.clone(), Some(base_url), None, vault_address)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load exchange metadata: {}", e))?;
+    let service = Arc::new(SignerService::new(
+        wallet,
+        &exchange_config.network,
+        vault_address,
+        &config.policy,
+        &client.coin_to_asset,
+    )?);
+    // Orders are valued at the signer's own mids, not at the prices the bot sends
+    let info_client = common::setup_info_client(&exchange_config.network).await?;
+    tokio::spawn(signer::server::refresh_mids(service.clone(), info_client));
+    info!(
+        "Signing for account '{}' on {} | markets {} | max order notional {} | {} per {}s | {} bps through mid | max leverage {}",
+        config.account,
+        exchange_config.network,
+        config.policy.allowed_assets.join(", "),
+        config.policy.max_order_notional,
+        config.policy.max_window_notional,
+        config.policy.notional_window_secs,
+        config.policy.max_price_deviation_bps,
+        config
+            .policy
+            .max_leverage
+            .map_or("none".to_string(), |l| format!("{}x", l))
+    );
+    signer::server::serve(service, &config.listen).await
+}
+
+async fn run_accounts(args: AccountsArgs, accounts_file: Option<&str>) -> Result<()> {
+    let path = resolve_accounts_file_path(accounts_file)?;
+    match args.command {
+        AccountsCommand::List => {
+            let accounts = accounts::list_accounts(&path)?;
+            ConsoleRenderer::render_accounts(&path.display().to_string(), &accounts);
+        }
+        AccountsCommand::Add => {
+            let name = accounts::add_account_interactive(&path)?;
+            println!("Added account '{}' to {}", name, path.display());
+            println!(
+                "Check it against the exchange with: accounts validate {}",
+                name
+            );
+        }
+        AccountsCommand::Validate { name } => {
+            let exchange_config = load_exchange_config(&name, accounts_file)?;
+            let report = preflight::check_account(&exchange_config).await?;
+            ConsoleRenderer::render_account_check(&name, &report);
+            if !report.passed() {
+                std::process::exit(1);
+            }
+        }
+        AccountsCommand::Remove { name, yes } => {
+            if accounts::remove_account_from_file(&path, &name, yes)? {
+                println!("Removed account '{}' from {}", name, path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the preflight report and exit non-zero when a check fails.
+async fn run_check(bot_config: BotConfig, exchange_config: ExchangeConfig) -> Result<()> {
+    let report = preflight::run(&bot_config.strategy, &exchange_config).await?;
+    ConsoleRenderer::render_preflight(&bot_config.strategy, &exchange_config.account_name, &report);
+    if !report.passed() {
+        std::process::exit(1);
     }
+    Ok(())
+}
+
+/// Print a suggested grid spacing for the configured range and investment.
+async fn run_recommendation(bot_config: BotConfig, exchange_config: ExchangeConfig) -> Result<()> {
+    let (grid_type, low, high, investment) = match &bot_config.strategy {
+        StrategyConfig::SpotGrid(c) => (
+            c.grid_type,
+            c.grid_range_low,
+            c.grid_range_high,
+            INVESTMENT_BUFFER_SPOT.markdown(c.total_investment),
+        ),
+        StrategyConfig::PerpGrid(c) => (
+            c.grid_type,
+            c.grid_range_low,
+            c.grid_range_high,
+            INVESTMENT_BUFFER_PERP.markdown(c.total_investment),
+        ),
+        other => {
+            return Err(anyhow::anyhow!(
+                "--recommend only applies to grid strategies, not {}",
+                other.type_name()
+            ))
+        }
+    };
+
+    let info_client = common::setup_info_client(&exchange_config.network).await?;
+    let user_address = H160::from_str(exchange_config.trading_account_address())
+        .map_err(|e| anyhow::anyhow!("Invalid trading account address: {}", e))?;
+    let fees =
+        common::fee_rates_or_default(&info_client, user_address, &bot_config.strategy, "").await;
+
+    let rec = recommend_grid(grid_type, low, high, investment, &fees)?;
+    ConsoleRenderer::render_recommendation(&bot_config.strategy, &fees, &rec);
+    Ok(())
 }
 
 /// Run simulation (dry run) mode.
@@ -258,3 +741,18 @@ async fn run_simulation(bot_config: BotConfig, exchange_config: ExchangeConfig)
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::log_file_name;
+
+    #[test]
+    fn test_log_file_name_for_live_mode() {
+        assert_eq!(log_file_name(false), "application.log");
+    }
+
+    #[test]
+    fn test_log_file_name_for_dry_run_mode() {
+        assert_eq!(log_file_name(true), "simulation.log");
+    }
+}
@@ -0,0 +1,472 @@
+//! Prometheus metrics for engine and strategy health.
+//!
+//! The live engine updates an [`EngineMetrics`] handle as it runs and
+//! [`serve`] exposes it at `GET /metrics` in the Prometheus text format.
+
+use crate::broadcast::types::StrategySummary;
+use crate::engine::context::StrategyContext;
+use crate::history::summary_equity;
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    ticks: u64,
+    orders_sent: u64,
+    orders_rejected: BTreeMap<String, u64>,
+    orders_filled: BTreeMap<String, u64>,
+    reconciliations: BTreeMap<String, u64>,
+    pending_orders: usize,
+    last_market_data: Option<Instant>,
+    order_latency_sum: f64,
+    order_latency_count: u64,
+    position_size: Option<f64>,
+    equity: Option<f64>,
+    matched_profit: Option<f64>,
+    total_profit: Option<f64>,
+    total_fees: Option<f64>,
+}
+
+/// Shared, cheaply clonable metrics handle.
+#[derive(Debug, Clone)]
+pub struct EngineMetrics {
+    labels: String,
+    state: Arc<Mutex<MetricsState>>,
+}
+
+impl EngineMetrics {
+    /// Every sample carries `bot` and `symbol` labels.
+    pub fn new(bot: &str, symbol: &str) -> Self {
+        Self {
+            labels: format!(
+                "bot=\"{}\",symbol=\"{}\"",
+                escape_label(bot),
+                escape_label(symbol)
+            ),
+            state: Arc::new(Mutex::new(MetricsState::default())),
+        }
+    }
+
+    fn with_state(&self, f: impl FnOnce(&mut MetricsState)) {
+        if let Ok(mut state) = self.state.lock() {
+            f(&mut state);
+        }
+    }
+
+    pub fn record_tick(&self) {
+        self.with_state(|s| s.ticks += 1);
+    }
+
+    pub fn record_market_data(&self) {
+        self.with_state(|s| s.last_market_data = Some(Instant::now()));
+    }
+
+    pub fn record_orders_sent(&self, count: usize) {
+        self.with_state(|s| s.orders_sent += count as u64);
+    }
+
+    /// `reason` is `exchange` (order rejected), `bulk_error` (batch rejected) or `request_failed`.
+    pub fn record_order_rejected(&self, reason: &str) {
+        self.with_state(|s| *s.orders_rejected.entry(reason.to_string()).or_default() += 1);
+    }
+
+    /// `source` is `user_event`, `immediate` (filled in the placement response) or `reconcile`.
+    pub fn record_order_filled(&self, source: &str) {
+        self.with_state(|s| *s.orders_filled.entry(source.to_string()).or_default() += 1);
+    }
+
+    pub fn record_reconciliation(&self, outcome: &str) {
+        self.with_state(|s| *s.reconciliations.entry(outcome.to_string()).or_default() += 1);
+    }
+
+    pub fn record_order_latency(&self, elapsed: Duration) {
+        self.with_state(|s| {
+            s.order_latency_sum += elapsed.as_secs_f64();
+            s.order_latency_count += 1;
+        });
+    }
+
+    pub fn set_pending_orders(&self, count: usize) {
+        self.with_state(|s| s.pending_orders = count);
+    }
+
+    /// Updates the strategy gauges from a summary.
+    ///
+    /// Equity is the spot balances at mid for spot grids, the perp account value
+    /// for perp grids and the portfolio value for rebalancing. TWAP reports fees only.
+    pub fn observe_summary(&self, summary: &StrategySummary, ctx: &StrategyContext) {
+        let equity = summary_equity(summary, ctx);
+        self.with_state(|s| {
+            s.equity = equity;
+            match summary {
+                StrategySummary::SpotGrid(g) => {
+                    s.position_size = Some(g.position_size);
+                    s.matched_profit = Some(g.matched_profit);
+                    s.total_profit = Some(g.total_profit);
+                    s.total_fees = Some(g.total_fees);
+                }
+                StrategySummary::PerpGrid(g) => {
+                    s.position_size = Some(g.position_size);
+                    s.matched_profit = Some(g.matched_profit);
+                    s.total_profit = Some(g.total_profit);
+                    s.total_fees = Some(g.total_fees);
+                }
+                StrategySummary::Twap(t) => {
+                    s.position_size = Some(t.filled_size);
+                    s.total_fees = Some(t.total_fees);
+                }
+                StrategySummary::Rebalance(r) => {
+                    s.total_fees = Some(r.total_fees);
+                }
+            }
+        });
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let mut m = Exposition {
+            out: String::new(),
+            labels: &self.labels,
+        };
+
+        m.scalar(
+            "hlbot_ticks_total",
+            "counter",
+            "Price ticks processed by the strategy.",
+            state.ticks as f64,
+        );
+        m.scalar(
+            "hlbot_orders_sent_total",
+            "counter",
+            "Orders sent to the exchange.",
+            state.orders_sent as f64,
+        );
+        m.labelled(
+            "hlbot_orders_rejected_total",
+            "Orders rejected, by reason.",
+            "reason",
+            &state.orders_rejected,
+        );
+        m.labelled(
+            "hlbot_orders_filled_total",
+            "Fills received, by source.",
+            "source",
+            &state.orders_filled,
+        );
+        m.labelled(
+            "hlbot_reconciliations_total",
+            "Reconciliation outcomes for orders missing from the book.",
+            "outcome",
+            &state.reconciliations,
+        );
+        m.scalar(
+            "hlbot_pending_orders",
+            "gauge",
+            "Orders tracked as open by the engine.",
+            state.pending_orders as f64,
+        );
+        if let Some(last) = state.last_market_data {
+            m.scalar(
+                "hlbot_feed_lag_seconds",
+                "gauge",
+                "Seconds since the last market data message.",
+                last.elapsed().as_secs_f64(),
+            );
+        }
+
+        m.header(
+            "hlbot_order_latency_seconds",
+            "summary",
+            "Round-trip time of order placement requests.",
+        );
+        m.sample(
+            "hlbot_order_latency_seconds_sum",
+            "",
+            state.order_latency_sum,
+        );
+        m.sample(
+            "hlbot_order_latency_seconds_count",
+            "",
+            state.order_latency_count as f64,
+        );
+
+        let gauges = [
+            (
+                "hlbot_position_size",
+                "Strategy position size in base units.",
+                state.position_size,
+            ),
+            ("hlbot_equity", "Account equity in quote.", state.equity),
+            (
+                "hlbot_matched_profit",
+                "Profit from completed grid roundtrips.",
+                state.matched_profit,
+            ),
+            (
+                "hlbot_total_profit",
+                "Total strategy profit after fees.",
+                state.total_profit,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            if let Some(value) = value {
+                m.scalar(name, "gauge", help, value);
+            }
+        }
+        // Only grows while the strategy runs and starts over with the process
+        if let Some(fees) = state.total_fees {
+            m.scalar(
+                "hlbot_fees_total",
+                "counter",
+                "Fees paid by the strategy.",
+                fees,
+            );
+        }
+        m.out
+    }
+}
+
+/// Text exposition writer that adds the bot labels to every sample.
+struct Exposition<'a> {
+    out: String,
+    labels: &'a str,
+}
+
+impl Exposition<'_> {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, extra_labels: &str, value: f64) {
+        let _ = writeln!(
+            self.out,
+            "{}{{{}{}}} {}",
+            name, self.labels, extra_labels, value
+        );
+    }
+
+    fn scalar(&mut self, name: &str, kind: &str, help: &str, value: f64) {
+        self.header(name, kind, help);
+        self.sample(name, "", value);
+    }
+
+    fn labelled(&mut self, name: &str, help: &str, label: &str, counts: &BTreeMap<String, u64>) {
+        self.header(name, "counter", help);
+        for (value, count) in counts {
+            let extra = format!(",{}=\"{}\"", label, escape_label(value));
+            self.sample(name, &extra, *count as f64);
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves `GET /metrics` on `host:port` until the process exits. With
+/// `auth_token`, scrapers must send `Authorization: Bearer <token>`.
+pub async fn serve(
+    host: String,
+    port: u16,
+    auth_token: Option<String>,
+    metrics: EngineMetrics,
+) -> anyhow::Result<()> {
+    let auth_token = auth_token.map(Arc::new);
+    let addr = format!("{}:{}", host, port);
+    let listener = TcpListener::bind(&addr).await?;
+    info!("Metrics endpoint listening on: http://{}/metrics", addr);
+
+    while let Ok((stream, peer_addr)) = listener.accept().await {
+        let metrics = metrics.clone();
+        let auth_token = auth_token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_request(stream, auth_token.as_deref(), &metrics).await {
+                warn!("Error serving metrics to {}: {}", peer_addr, e);
+            }
+        });
+    }
+    Ok(())
+}
+
+async fn handle_request(
+    mut stream: TcpStream,
+    auth_token: Option<&String>,
+    metrics: &EngineMetrics,
+) -> anyhow::Result<()> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let mut parts = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let (method, path) = (parts.next(), parts.next());
+    let authorized = auth_token.is_none_or(|token| {
+        request.lines().skip(1).any(|line| {
+            line.split_once(':').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("authorization")
+                    && value.trim().strip_prefix("Bearer ") == Some(token.as_str())
+            })
+        })
+    });
+    let (status, content_type, body) = match (method, path) {
+        (Some("GET"), Some("/metrics")) if !authorized => (
+            "401 Unauthorized",
+            "text/plain; charset=utf-8",
+            "Unauthorized\n".to_string(),
+        ),
+        (Some("GET"), Some("/metrics")) => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            metrics.render(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Not Found\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::types::PerpGridSummary;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_counters_and_summary_gauges() {
+        let metrics = EngineMetrics::new("btc-grid", "BTC");
+        metrics.record_tick();
+        metrics.record_tick();
+        metrics.record_orders_sent(3);
+        metrics.record_order_rejected("exchange");
+        metrics.record_order_filled("user_event");
+        metrics.record_reconciliation("filled");
+        metrics.record_order_latency(Duration::from_millis(250));
+        metrics.set_pending_orders(2);
+
+        let mut ctx = StrategyContext::new(HashMap::new());
+        ctx.update_perp_balance("USDC".to_string(), 1500.0, 900.0);
+        let summary = StrategySummary::PerpGrid(PerpGridSummary {
+            symbol: "BTC".to_string(),
+            state: "Running".to_string(),
+            uptime: "1m".to_string(),
+            position_size: -0.5,
+            position_side: "Short".to_string(),
+            avg_entry_price: 90000.0,
+            matched_profit: 12.5,
+            total_profit: 10.0,
+            unrealized_pnl: -1.0,
+            total_fees: 1.5,
+            leverage: 5,
+            grid_bias: "short".to_string(),
+            grid_count: 10,
+            grid_range_low: 85000.0,
+            grid_range_high: 95000.0,
+            grid_spacing_pct: (1.0, 1.0),
+            roundtrips: 3,
+            margin_balance: 900.0,
+            initial_entry_price: None,
+        });
+        metrics.observe_summary(&summary, &ctx);
+
+        let text = metrics.render();
+        let l = "bot=\"btc-grid\",symbol=\"BTC\"";
+        for line in [
+            format!("hlbot_ticks_total{{{}}} 2", l),
+            format!("hlbot_orders_sent_total{{{}}} 3", l),
+            format!("hlbot_orders_rejected_total{{{},reason=\"exchange\"}} 1", l),
+            format!("hlbot_orders_filled_total{{{},source=\"user_event\"}} 1", l),
+            format!("hlbot_reconciliations_total{{{},outcome=\"filled\"}} 1", l),
+            format!("hlbot_pending_orders{{{}}} 2", l),
+            format!("hlbot_order_latency_seconds_sum{{{}}} 0.25", l),
+            format!("hlbot_order_latency_seconds_count{{{}}} 1", l),
+            format!("hlbot_position_size{{{}}} -0.5", l),
+            format!("hlbot_equity{{{}}} 1500", l),
+            format!("hlbot_matched_profit{{{}}} 12.5", l),
+            format!("hlbot_fees_total{{{}}} 1.5", l),
+        ] {
+            assert!(text.lines().any(|t| t == line), "missing {}", line);
+        }
+        assert!(text.contains("# TYPE hlbot_ticks_total counter"));
+        assert!(text.contains("# TYPE hlbot_fees_total counter"));
+        // No market data yet, so no feed lag sample.
+        assert!(!text.contains("hlbot_feed_lag_seconds"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_metrics_over_http() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let metrics = EngineMetrics::new("bot", "HYPE/USDC");
+        metrics.record_tick();
+        tokio::spawn(serve(
+            "127.0.0.1".to_string(),
+            port,
+            Some("s3cret".to_string()),
+            metrics,
+        ));
+
+        let get = |headers: &'static str| async move {
+            let mut response = String::new();
+            for _ in 0..50 {
+                if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)).await {
+                    let request = format!(
+                        "GET /metrics HTTP/1.1\r\nHost: localhost\r\n{}\r\n",
+                        headers
+                    );
+                    stream.write_all(request.as_bytes()).await.unwrap();
+                    stream.read_to_string(&mut response).await.unwrap();
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            response
+        };
+        assert!(get("").await.starts_with("HTTP/1.1 401 Unauthorized"));
+        assert!(get("Authorization: Bearer wrong\r\n")
+            .await
+            .starts_with("HTTP/1.1 401"));
+        let response = get("authorization: Bearer s3cret\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("hlbot_ticks_total{bot=\"bot\",symbol=\"HYPE/USDC\"} 1"));
+    }
+}
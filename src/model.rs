@@ -111,6 +111,17 @@ pub struct OrderFill {
     pub raw_dir: Option<String>,
 }
 
+/// OHLC candle from the exchange candle snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    /// Open time in milliseconds since the epoch.
+    pub open_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum OrderRequest {
     Limit {
@@ -121,10 +132,14 @@ pub enum OrderRequest {
         reduce_only: bool,
         cloid: Option<Cloid>,
     },
+    /// Immediate-or-cancel order. `limit_px` caps the price the order may cross
+    /// to; when `None` the engine uses the current mid price.
     Market {
         symbol: String,
         side: OrderSide,
         sz: f64,
+        reduce_only: bool,
+        limit_px: Option<f64>,
         cloid: Option<Cloid>,
     },
     Cancel {
@@ -132,6 +147,18 @@ pub enum OrderRequest {
     },
 }
 
+impl OrderRequest {
+    /// Market symbol the order trades. Cancels carry no symbol.
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            OrderRequest::Limit { symbol, .. } | OrderRequest::Market { symbol, .. } => {
+                Some(symbol)
+            }
+            OrderRequest::Cancel { .. } => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct OrderId(pub u64);
 
@@ -159,6 +186,22 @@ impl Spread {
     }
 }
 
+/// Maker and taker fee rates as fractions of notional.
+///
+/// 0.00015 means 0.015%.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeeRates {
+    pub maker: f64,
+    pub taker: f64,
+}
+
+impl FeeRates {
+    /// Maker fees paid by one buy and one sell, as a percentage of notional.
+    pub fn roundtrip_maker_pct(&self) -> f64 {
+        self.maker * 2.0 * 100.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
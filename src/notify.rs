@@ -0,0 +1,333 @@
+//! Outbound alert notifications via webhooks.
+//!
+//! The engine and `main` hand [`Alert`]s to a [`Notifier`], which routes them
+//! to the configured webhooks from a background task so trading never waits on
+//! a slow endpoint. Each webhook has its own per-alert-type rate limit.
+
+use crate::config::notification::{AlertKind, NotificationConfig, WebhookConfig, WebhookFormat};
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A single notification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub title: String,
+    pub message: String,
+    pub timestamp_ms: u64,
+}
+
+impl Alert {
+    pub fn new(kind: AlertKind, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            message: message.into(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Renders every webhook template with a sample alert, so a template that is
+/// not valid JSON fails at startup instead of on the first alert.
+pub fn validate_templates(config: &NotificationConfig) -> Result<()> {
+    let sample = Alert::new(AlertKind::Error, "title", "message");
+    for (i, webhook) in config.webhooks.iter().enumerate() {
+        if webhook.template.is_some() {
+            // Not the url: Telegram urls carry the bot token
+            render_payload(webhook, "bot", "symbol", &sample)
+                .map_err(|e| anyhow!("Webhook {}: {}", i + 1, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds the JSON body for `webhook`.
+pub fn render_payload(
+    webhook: &WebhookConfig,
+    bot: &str,
+    symbol: &str,
+    alert: &Alert,
+) -> Result<Value> {
+    let text = format!("[{}] {} ({}): {}", bot, alert.title, symbol, alert.message);
+    if let Some(template) = &webhook.template {
+        let vars = [
+            ("bot", bot.to_string()),
+            ("event", alert.kind.to_string()),
+            ("symbol", symbol.to_string()),
+            ("title", alert.title.clone()),
+            ("message", alert.message.clone()),
+            ("text", text),
+            ("timestamp_ms", alert.timestamp_ms.to_string()),
+        ];
+        let mut body = template.clone();
+        for (name, value) in vars {
+            // Escape as JSON string content so values can sit inside quotes.
+            let escaped = serde_json::to_string(&value)?;
+            body = body.replace(&format!("{{{{{}}}}}", name), &escaped[1..escaped.len() - 1]);
+        }
+        return serde_json::from_str(&body)
+            .map_err(|e| anyhow!("Webhook template is not valid JSON: {}", e));
+    }
+
+    Ok(match webhook.format {
+        WebhookFormat::Generic => json!({
+            "bot": bot,
+            "event": alert.kind,
+            "symbol": symbol,
+            "title": alert.title,
+            "message": alert.message,
+            "timestamp_ms": alert.timestamp_ms,
+        }),
+        WebhookFormat::Slack => json!({ "text": text }),
+        WebhookFormat::Discord => json!({ "content": text }),
+        WebhookFormat::Telegram => json!({
+            "chat_id": webhook.chat_id.clone().unwrap_or_default(),
+            "text": text,
+        }),
+    })
+}
+
+/// Sliding one-minute window of send times.
+#[derive(Debug, Default)]
+struct RateLimiter {
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    fn allow(&mut self, now: Instant, max_per_minute: u32) -> bool {
+        while self
+            .sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= max_per_minute as usize {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// Handle for sending alerts. Cheap to clone; a no-op when no webhooks are configured.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    sender: Option<mpsc::UnboundedSender<Alert>>,
+}
+
+impl Notifier {
+    /// Starts the delivery task. Must be called inside a Tokio runtime.
+    pub fn new(config: &NotificationConfig, bot: &str, symbol: &str) -> Self {
+        if config.webhooks.is_empty() {
+            return Self::default();
+        }
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(deliver(
+            receiver,
+            config.webhooks.clone(),
+            bot.to_string(),
+            symbol.to_string(),
+        ));
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    pub fn notify(&self, alert: Alert) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(alert);
+        }
+    }
+}
+
+async fn deliver(
+    mut receiver: mpsc::UnboundedReceiver<Alert>,
+    webhooks: Vec<WebhookConfig>,
+    bot: String,
+    symbol: String,
+) {
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Notifications disabled, failed to build HTTP client: {}", e);
+            return;
+        }
+    };
+    let mut limiters: HashMap<(usize, AlertKind), RateLimiter> = HashMap::new();
+
+    while let Some(alert) = receiver.recv().await {
+        for (i, webhook) in webhooks.iter().enumerate() {
+            if !webhook.accepts(alert.kind) {
+                continue;
+            }
+            let limiter = limiters.entry((i, alert.kind)).or_default();
+            if !limiter.allow(Instant::now(), webhook.max_per_minute) {
+                debug!(
+                    "Rate limit reached for {} alerts on webhook #{}, dropping",
+                    alert.kind, i
+                );
+                continue;
+            }
+            let payload = match render_payload(webhook, &bot, &symbol, &alert) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Webhook #{} payload error: {}", i, e);
+                    continue;
+                }
+            };
+            match client.post(&webhook.url).json(&payload).send().await {
+                Ok(res) if res.status().is_success() => {}
+                Ok(res) => warn!("Webhook #{} returned {}", i, res.status()),
+                Err(e) => warn!("Webhook #{} failed: {}", i, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn webhook(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            format: WebhookFormat::Generic,
+            chat_id: None,
+            template: None,
+            events: Vec::new(),
+            max_per_minute: 10,
+        }
+    }
+
+    #[test]
+    fn test_render_payload_formats_and_template() {
+        let alert = Alert {
+            kind: AlertKind::RangeExit,
+            title: "Price left grid range".to_string(),
+            message: "Price 25 \"above\" 24".to_string(),
+            timestamp_ms: 7,
+        };
+        let generic = render_payload(&webhook("http://x"), "bot", "HYPE/USDC", &alert).unwrap();
+        assert_eq!(generic["event"], "range_exit");
+        assert_eq!(generic["timestamp_ms"], 7);
+
+        let slack = WebhookConfig {
+            format: WebhookFormat::Slack,
+            ..webhook("http://x")
+        };
+        let payload = render_payload(&slack, "bot", "HYPE/USDC", &alert).unwrap();
+        assert_eq!(
+            payload["text"],
+            "[bot] Price left grid range (HYPE/USDC): Price 25 \"above\" 24"
+        );
+
+        let custom = WebhookConfig {
+            template: Some(r#"{"msg": "{{message}}", "at": {{timestamp_ms}}}"#.to_string()),
+            ..webhook("http://x")
+        };
+        let payload = render_payload(&custom, "bot", "HYPE/USDC", &alert).unwrap();
+        assert_eq!(payload, json!({"msg": "Price 25 \"above\" 24", "at": 7}));
+
+        let broken = WebhookConfig {
+            template: Some("{\"msg\": {{message}}}".to_string()),
+            ..webhook("http://x")
+        };
+        assert!(render_payload(&broken, "bot", "HYPE/USDC", &alert).is_err());
+
+        let config = NotificationConfig {
+            webhooks: vec![custom, broken],
+        };
+        let err = validate_templates(&config).unwrap_err().to_string();
+        assert!(err.starts_with("Webhook 2: Webhook template is not valid JSON"));
+    }
+
+    #[test]
+    fn test_rate_limiter_window() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert!(limiter.allow(start, 2));
+        assert!(limiter.allow(start + Duration::from_secs(1), 2));
+        assert!(!limiter.allow(start + Duration::from_secs(2), 2));
+        assert!(limiter.allow(start + Duration::from_secs(60), 2));
+    }
+
+    #[tokio::test]
+    async fn test_notifier_routes_and_limits() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (body_tx, mut body_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 8192];
+                let mut len = 0;
+                // Read headers and body, then answer 200.
+                loop {
+                    let n = stream.read(&mut buf[len..]).await.unwrap();
+                    len += n;
+                    let text = String::from_utf8_lossy(&buf[..len]);
+                    if let Some(split) = text.find("\r\n\r\n") {
+                        let content_length = text
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if len >= split + 4 + content_length {
+                            body_tx
+                                .send(text[split + 4..split + 4 + content_length].to_string())
+                                .unwrap();
+                            break;
+                        }
+                    }
+                    if n == 0 {
+                        break;
+                    }
+                }
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+
+        let config = NotificationConfig {
+            webhooks: vec![WebhookConfig {
+                events: vec![AlertKind::Error],
+                max_per_minute: 1,
+                ..webhook(&url)
+            }],
+        };
+        let notifier = Notifier::new(&config, "bot", "BTC");
+        notifier.notify(Alert::new(AlertKind::Fill, "Fill", "ignored"));
+        notifier.notify(Alert::new(AlertKind::Error, "Engine error", "boom"));
+        notifier.notify(Alert::new(AlertKind::Error, "Engine error", "rate limited"));
+
+        let body = tokio::time::timeout(Duration::from_secs(5), body_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let payload: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["event"], "error");
+        assert_eq!(payload["message"], "boom");
+        assert!(
+            tokio::time::timeout(Duration::from_millis(300), body_rx.recv())
+                .await
+                .is_err()
+        );
+    }
+}
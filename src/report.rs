@@ -0,0 +1,654 @@
+//! Realized PnL reports from the fill history.
+//!
+//! Fills are read from the trade journal or from a journal CSV export, matched
+//! into lots by FIFO, LIFO or average cost, and summarized per asset and per
+//! UTC day. Fees follow the lot: opening fees are part of the cost basis and
+//! are realized together with the closing fee. Spot buys pay their fee in the
+//! base token; it is valued at the fill price so every fee is in quote.
+
+use crate::journal::JournalEntry;
+use crate::model::{Cloid, OrderFill, OrderSide};
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+const SIZE_EPSILON: f64 = 1e-9;
+
+/// How closing fills are matched against open lots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CostBasis {
+    #[default]
+    Fifo,
+    Lifo,
+    Average,
+}
+
+impl FromStr for CostBasis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fifo" => Ok(Self::Fifo),
+            "lifo" => Ok(Self::Lifo),
+            "average" | "avg" => Ok(Self::Average),
+            other => Err(format!(
+                "unknown cost basis '{}', expected fifo, lifo or average",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CostBasis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fifo => write!(f, "FIFO"),
+            Self::Lifo => write!(f, "LIFO"),
+            Self::Average => write!(f, "Average Cost"),
+        }
+    }
+}
+
+/// A fill with the time and market it happened on.
+#[derive(Debug, Clone)]
+pub struct FillRecord {
+    pub timestamp_ms: u64,
+    pub symbol: String,
+    pub oid: Option<u64>,
+    pub fill: OrderFill,
+}
+
+impl FillRecord {
+    /// Converts a journal `fill` row. Other events yield `None`.
+    pub fn from_journal(entry: &JournalEntry) -> Option<Self> {
+        if entry.event != "fill" {
+            return None;
+        }
+        let side = entry.side?;
+        let price = entry.price?;
+        Some(Self {
+            timestamp_ms: entry.timestamp_ms,
+            symbol: entry.symbol.clone(),
+            oid: entry.oid,
+            fill: OrderFill {
+                side,
+                size: entry.size?,
+                price,
+                fee: quote_fee(entry, side, price),
+                cloid: entry.cloid.as_deref().and_then(Cloid::from_hex_str),
+                reduce_only: entry.reduce_only,
+                raw_dir: entry.raw_dir.clone(),
+            },
+        })
+    }
+
+    fn is_spot(&self) -> bool {
+        self.symbol.contains('/')
+    }
+}
+
+/// Fee of a journal fill in the quote asset. Rows journaled without a fee
+/// token follow the exchange's rule that spot buys pay in the base token.
+fn quote_fee(entry: &JournalEntry, side: OrderSide, price: f64) -> f64 {
+    let fee = entry.fee.unwrap_or(0.0);
+    let Some((base, _)) = entry.symbol.split_once('/') else {
+        return fee;
+    };
+    let in_base = match entry.fee_token.as_deref() {
+        Some(token) => token == base,
+        None => side.is_buy(),
+    };
+    if in_base {
+        fee * price
+    } else {
+        fee
+    }
+}
+
+/// Extracts fills from journal rows.
+///
+/// An IOC order filled in the placement response is journaled again when the
+/// exchange reports the fill with its fee. The placement copy has no `raw_dir`
+/// and is dropped when the exchange copy for the same oid is present.
+pub fn fills_from_journal(entries: &[JournalEntry]) -> Vec<FillRecord> {
+    let reported: HashSet<u64> = entries
+        .iter()
+        .filter(|e| e.event == "fill" && e.raw_dir.is_some())
+        .filter_map(|e| e.oid)
+        .collect();
+    entries
+        .iter()
+        .filter(|e| e.raw_dir.is_some() || e.oid.is_none_or(|oid| !reported.contains(&oid)))
+        .filter_map(FillRecord::from_journal)
+        .collect()
+}
+
+/// One closed (part of a) lot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealizedLot {
+    pub timestamp_ms: u64,
+    pub symbol: String,
+    /// Positive for a closed long, negative for a closed short.
+    pub size: f64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub gross_pnl: f64,
+    /// Opening fee share plus closing fee share.
+    pub fees: f64,
+}
+
+impl RealizedLot {
+    pub fn net_pnl(&self) -> f64 {
+        self.gross_pnl - self.fees
+    }
+}
+
+/// Lots still open after the last fill.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OpenPosition {
+    /// Positive for long, negative for short.
+    pub size: f64,
+    pub avg_price: f64,
+    /// Opening fees not yet realized.
+    pub fees: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Lot {
+    size: f64,
+    price: f64,
+    fee: f64,
+}
+
+#[derive(Debug, Default)]
+struct LotBook {
+    lots: VecDeque<Lot>,
+    /// Direction of the open lots; meaningless while `lots` is empty.
+    long: bool,
+    /// Spot size sold without an open lot (inventory from before the history).
+    unmatched: f64,
+}
+
+impl LotBook {
+    fn open(&mut self, method: CostBasis, lot: Lot) {
+        match (method, self.lots.front_mut()) {
+            (CostBasis::Average, Some(avg)) => {
+                let size = avg.size + lot.size;
+                avg.price = (avg.price * avg.size + lot.price * lot.size) / size;
+                avg.size = size;
+                avg.fee += lot.fee;
+            }
+            _ => self.lots.push_back(lot),
+        }
+    }
+
+    fn apply(&mut self, method: CostBasis, record: &FillRecord, realized: &mut Vec<RealizedLot>) {
+        let fill = &record.fill;
+        if fill.size <= 0.0 {
+            return;
+        }
+        let buy = fill.side.is_buy();
+        let mut remaining = fill.size;
+        let fee_per_unit = fill.fee / fill.size;
+
+        if !self.lots.is_empty() && self.long != buy {
+            while remaining > SIZE_EPSILON {
+                let lot = match method {
+                    CostBasis::Lifo => self.lots.back_mut(),
+                    _ => self.lots.front_mut(),
+                };
+                let Some(lot) = lot else { break };
+                let qty = remaining.min(lot.size);
+                let open_fee = lot.fee * qty / lot.size;
+                let direction = if self.long { 1.0 } else { -1.0 };
+                realized.push(RealizedLot {
+                    timestamp_ms: record.timestamp_ms,
+                    symbol: record.symbol.clone(),
+                    size: qty * direction,
+                    entry_price: lot.price,
+                    exit_price: fill.price,
+                    gross_pnl: (fill.price - lot.price) * qty * direction,
+                    fees: open_fee + fee_per_unit * qty,
+                });
+                lot.size -= qty;
+                lot.fee -= open_fee;
+                remaining -= qty;
+                if lot.size <= SIZE_EPSILON {
+                    match method {
+                        CostBasis::Lifo => self.lots.pop_back(),
+                        _ => self.lots.pop_front(),
+                    };
+                }
+            }
+        }
+
+        if remaining <= SIZE_EPSILON {
+            return;
+        }
+        if record.is_spot() && !buy {
+            self.unmatched += remaining;
+            return;
+        }
+        self.long = buy;
+        self.open(
+            method,
+            Lot {
+                size: remaining,
+                price: fill.price,
+                fee: fee_per_unit * remaining,
+            },
+        );
+    }
+
+    fn position(&self) -> OpenPosition {
+        let size: f64 = self.lots.iter().map(|l| l.size).sum();
+        if size <= SIZE_EPSILON {
+            return OpenPosition::default();
+        }
+        OpenPosition {
+            size: if self.long { size } else { -size },
+            avg_price: self.lots.iter().map(|l| l.price * l.size).sum::<f64>() / size,
+            fees: self.lots.iter().map(|l| l.fee).sum(),
+        }
+    }
+}
+
+/// Per-asset line of the report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssetPnl {
+    pub symbol: String,
+    pub closed_lots: usize,
+    pub closed_size: f64,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    pub realized_pnl: f64,
+    pub open_size: f64,
+    pub open_avg_price: f64,
+    /// Spot size sold without a matching buy in the history.
+    pub unmatched_size: f64,
+}
+
+/// Per-day line of the report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyPnl {
+    pub date: String,
+    pub fills: usize,
+    pub volume: f64,
+    pub closed_lots: usize,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    pub realized_pnl: f64,
+}
+
+/// Realized PnL for a set of fills.
+#[derive(Debug, Clone)]
+pub struct PnlReport {
+    pub method: CostBasis,
+    pub year: Option<i32>,
+    pub realized: Vec<RealizedLot>,
+    pub by_asset: Vec<AssetPnl>,
+    pub by_day: Vec<DailyPnl>,
+}
+
+impl PnlReport {
+    /// Matches `fills` into lots and summarizes the lots closed in `year` (all years if `None`).
+    ///
+    /// Lots are always matched over the full history so positions opened in
+    /// earlier years keep their cost basis.
+    pub fn build(fills: &[FillRecord], method: CostBasis, year: Option<i32>) -> Self {
+        let mut fills: Vec<&FillRecord> = fills.iter().collect();
+        fills.sort_by_key(|f| f.timestamp_ms);
+
+        let in_period = |ts: u64| year.is_none_or(|y| utc_date(ts).0 == y);
+        let mut books: BTreeMap<String, LotBook> = BTreeMap::new();
+        let mut realized = Vec::new();
+        let mut days: BTreeMap<String, DailyPnl> = BTreeMap::new();
+
+        for record in fills {
+            let book = books.entry(record.symbol.clone()).or_default();
+            let unmatched_before = book.unmatched;
+            let start = realized.len();
+            book.apply(method, record, &mut realized);
+            if !in_period(record.timestamp_ms) {
+                realized.truncate(start);
+                book.unmatched = unmatched_before;
+                continue;
+            }
+
+            let date = format_date(record.timestamp_ms);
+            let day = days.entry(date.clone()).or_insert_with(|| DailyPnl {
+                date,
+                fills: 0,
+                volume: 0.0,
+                closed_lots: 0,
+                gross_pnl: 0.0,
+                fees: 0.0,
+                realized_pnl: 0.0,
+            });
+            day.fills += 1;
+            day.volume += record.fill.price * record.fill.size;
+            for lot in &realized[start..] {
+                day.closed_lots += 1;
+                day.gross_pnl += lot.gross_pnl;
+                day.fees += lot.fees;
+                day.realized_pnl += lot.net_pnl();
+            }
+        }
+
+        let by_asset = books
+            .iter()
+            .map(|(symbol, book)| {
+                let lots: Vec<&RealizedLot> =
+                    realized.iter().filter(|l| &l.symbol == symbol).collect();
+                let position = book.position();
+                AssetPnl {
+                    symbol: symbol.clone(),
+                    closed_lots: lots.len(),
+                    closed_size: lots.iter().map(|l| l.size.abs()).sum(),
+                    gross_pnl: lots.iter().map(|l| l.gross_pnl).sum(),
+                    fees: lots.iter().map(|l| l.fees).sum(),
+                    realized_pnl: lots.iter().map(|l| l.net_pnl()).sum(),
+                    open_size: position.size,
+                    open_avg_price: position.avg_price,
+                    unmatched_size: book.unmatched,
+                }
+            })
+            .collect();
+
+        Self {
+            method,
+            year,
+            realized,
+            by_asset,
+            by_day: days.into_values().collect(),
+        }
+    }
+
+    pub fn total_realized(&self) -> f64 {
+        self.by_asset.iter().map(|a| a.realized_pnl).sum()
+    }
+
+    pub fn write_asset_csv<W: Write>(&self, writer: W) -> Result<()> {
+        write_csv(&self.by_asset, writer)
+    }
+
+    pub fn write_daily_csv<W: Write>(&self, writer: W) -> Result<()> {
+        write_csv(&self.by_day, writer)
+    }
+
+    /// Renders both tables as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let period = self
+            .year
+            .map(|y| y.to_string())
+            .unwrap_or_else(|| "All Time".to_string());
+        let mut md = format!(
+            "# Realized PnL Report ({})\n\nCost basis: {}\n\nTotal realized PnL: {:.2}\n\n",
+            period,
+            self.method,
+            self.total_realized()
+        );
+
+        md.push_str("## By Asset\n\n");
+        md.push_str(
+            "| Asset | Closed Lots | Closed Size | Gross PnL | Fees | Realized PnL | Open Size | Open Avg Price | Unmatched Size |\n",
+        );
+        md.push_str("| :--- | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: |\n");
+        for a in &self.by_asset {
+            md.push_str(&format!(
+                "| {} | {} | {:.6} | {:.2} | {:.2} | {:.2} | {:.6} | {:.6} | {:.6} |\n",
+                a.symbol,
+                a.closed_lots,
+                a.closed_size,
+                a.gross_pnl,
+                a.fees,
+                a.realized_pnl,
+                a.open_size,
+                a.open_avg_price,
+                a.unmatched_size
+            ));
+        }
+
+        md.push_str("\n## By Day (UTC)\n\n");
+        md.push_str("| Date | Fills | Volume | Closed Lots | Gross PnL | Fees | Realized PnL |\n");
+        md.push_str("| :--- | ---: | ---: | ---: | ---: | ---: | ---: |\n");
+        for d in &self.by_day {
+            md.push_str(&format!(
+                "| {} | {} | {:.2} | {} | {:.2} | {:.2} | {:.2} |\n",
+                d.date, d.fills, d.volume, d.closed_lots, d.gross_pnl, d.fees, d.realized_pnl
+            ));
+        }
+        md
+    }
+
+    /// Writes `realized_by_asset` and `realized_by_day` as CSV and the Markdown report into `dir`.
+    pub fn write_files(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        self.write_asset_csv(std::fs::File::create(dir.join("realized_by_asset.csv"))?)?;
+        self.write_daily_csv(std::fs::File::create(dir.join("realized_by_day.csv"))?)?;
+        std::fs::write(dir.join("realized_pnl.md"), self.to_markdown())?;
+        Ok(())
+    }
+}
+
+fn write_csv<T: Serialize, W: Write>(rows: &[T], writer: W) -> Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    for row in rows {
+        csv.serialize(row)?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Parses a report year such as `2025`.
+pub fn parse_year(s: &str) -> Result<i32> {
+    s.parse::<i32>()
+        .ok()
+        .filter(|y| (1970..=9999).contains(y))
+        .ok_or_else(|| anyhow!("Invalid year: {}", s))
+}
+
+/// UTC calendar date (year, month, day) of a unix timestamp in milliseconds.
+fn utc_date(timestamp_ms: u64) -> (i32, u32, u32) {
+    // Days-to-civil conversion from Howard Hinnant's date algorithms.
+    let z = (timestamp_ms / 86_400_000) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+    (year, month, day)
+}
+
+pub(crate) fn format_date(timestamp_ms: u64) -> String {
+    let (y, m, d) = utc_date(timestamp_ms);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: u64 = 86_400_000;
+
+    fn record(
+        symbol: &str,
+        ts: u64,
+        side: OrderSide,
+        size: f64,
+        price: f64,
+        fee: f64,
+    ) -> FillRecord {
+        FillRecord {
+            timestamp_ms: ts,
+            symbol: symbol.to_string(),
+            oid: None,
+            fill: OrderFill {
+                side,
+                size,
+                price,
+                fee,
+                cloid: None,
+                reduce_only: None,
+                raw_dir: None,
+            },
+        }
+    }
+
+    fn ladder() -> Vec<FillRecord> {
+        vec![
+            record("HYPE/USDC", 1, OrderSide::Buy, 1.0, 10.0, 0.1),
+            record("HYPE/USDC", 2, OrderSide::Buy, 1.0, 12.0, 0.1),
+            record("HYPE/USDC", 3, OrderSide::Sell, 1.0, 15.0, 0.2),
+        ]
+    }
+
+    #[test]
+    fn test_cost_basis_methods() {
+        let fifo = PnlReport::build(&ladder(), CostBasis::Fifo, None);
+        assert!((fifo.realized[0].gross_pnl - 5.0).abs() < 1e-9);
+        assert!((fifo.realized[0].fees - 0.3).abs() < 1e-9);
+        assert!((fifo.by_asset[0].open_avg_price - 12.0).abs() < 1e-9);
+
+        let lifo = PnlReport::build(&ladder(), CostBasis::Lifo, None);
+        assert!((lifo.realized[0].gross_pnl - 3.0).abs() < 1e-9);
+        assert!((lifo.by_asset[0].open_avg_price - 10.0).abs() < 1e-9);
+
+        let avg = PnlReport::build(&ladder(), CostBasis::Average, None);
+        assert!((avg.realized[0].gross_pnl - 4.0).abs() < 1e-9);
+        assert!((avg.total_realized() - 3.7).abs() < 1e-9);
+        let asset = &avg.by_asset[0];
+        assert!((asset.open_size - 1.0).abs() < 1e-9);
+        assert!((asset.open_avg_price - 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_perp_short_flip_and_spot_unmatched_sell() {
+        let fills = vec![
+            record("BTC", 1, OrderSide::Sell, 2.0, 100.0, 0.0),
+            // Covers the short and opens a 1.0 long.
+            record("BTC", 2, OrderSide::Buy, 3.0, 90.0, 0.3),
+            record("BTC", 3, OrderSide::Sell, 1.0, 95.0, 0.0),
+            record("HYPE/USDC", 4, OrderSide::Sell, 2.0, 20.0, 0.0),
+        ];
+        let report = PnlReport::build(&fills, CostBasis::Fifo, None);
+
+        let btc = &report.by_asset[0];
+        assert_eq!(btc.symbol, "BTC");
+        assert_eq!(btc.closed_lots, 2);
+        assert!((btc.gross_pnl - 25.0).abs() < 1e-9);
+        assert!((btc.fees - 0.3).abs() < 1e-9);
+        assert!(btc.open_size.abs() < 1e-9);
+        assert_eq!(report.realized[0].size, -2.0);
+
+        let hype = &report.by_asset[1];
+        assert_eq!(hype.closed_lots, 0);
+        assert!((hype.unmatched_size - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_year_filter_keeps_earlier_cost_basis() {
+        // 2024-12-31 and 2025-01-01 UTC.
+        let dec_31 = 20_088 * DAY_MS;
+        let jan_1 = 20_089 * DAY_MS;
+        assert_eq!(format_date(dec_31), "2024-12-31");
+        assert_eq!(format_date(jan_1 + 5), "2025-01-01");
+        assert_eq!(format_date(0), "1970-01-01");
+
+        let fills = vec![
+            record("BTC", dec_31, OrderSide::Buy, 1.0, 100.0, 0.0),
+            record("BTC", dec_31 + 1, OrderSide::Sell, 0.5, 110.0, 0.0),
+            record("BTC", jan_1, OrderSide::Sell, 0.5, 120.0, 0.0),
+        ];
+        let report = PnlReport::build(&fills, CostBasis::Fifo, Some(2025));
+        assert_eq!(report.realized.len(), 1);
+        assert!((report.total_realized() - 10.0).abs() < 1e-9);
+        assert_eq!(report.by_day.len(), 1);
+        assert_eq!(report.by_day[0].date, "2025-01-01");
+        assert_eq!(report.by_day[0].fills, 1);
+    }
+
+    #[test]
+    fn test_fills_from_journal_drops_placement_copy() {
+        let fill = |oid: u64, fee: f64, raw_dir: Option<&str>| JournalEntry {
+            side: Some(OrderSide::Buy),
+            price: Some(10.0),
+            size: Some(1.0),
+            oid: Some(oid),
+            fee: Some(fee),
+            raw_dir: raw_dir.map(str::to_string),
+            ..JournalEntry::new("fill", "BTC")
+        };
+        let entries = vec![
+            JournalEntry::new("req", "BTC"),
+            fill(1, 0.0, None),
+            fill(1, 0.05, Some("Open Long")),
+            fill(2, 0.0, None),
+        ];
+        let fills = fills_from_journal(&entries);
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].fill.fee, 0.05);
+        assert_eq!(fills[1].oid, Some(2));
+    }
+
+    #[test]
+    fn test_spot_buy_fee_in_base_token() {
+        let fill = |side, price: f64, fee: f64, fee_token: &str| JournalEntry {
+            timestamp_ms: price as u64,
+            side: Some(side),
+            price: Some(price),
+            size: Some(10.0),
+            fee: Some(fee),
+            fee_token: Some(fee_token.to_string()),
+            raw_dir: Some(side.to_string()),
+            ..JournalEntry::new("fill", "HYPE/USDC")
+        };
+        // Buy 10 HYPE at 20 paying 0.01 HYPE, sell 10 at 25 paying 0.1 USDC
+        let entries = vec![
+            fill(OrderSide::Buy, 20.0, 0.01, "HYPE"),
+            fill(OrderSide::Sell, 25.0, 0.1, "USDC"),
+        ];
+        let fills = fills_from_journal(&entries);
+        assert!((fills[0].fill.fee - 0.2).abs() < 1e-9);
+        let report = PnlReport::build(&fills, CostBasis::Fifo, None);
+        let lot = &report.realized[0];
+        assert!((lot.gross_pnl - 50.0).abs() < 1e-9);
+        assert!((lot.fees - 0.3).abs() < 1e-9);
+        assert!((report.total_realized() - 49.7).abs() < 1e-9);
+
+        // Rows from before fee tokens were journaled
+        let legacy = JournalEntry {
+            fee_token: None,
+            ..fill(OrderSide::Buy, 20.0, 0.01, "HYPE")
+        };
+        assert!((quote_fee(&legacy, OrderSide::Buy, 20.0) - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_report_outputs() {
+        let report = PnlReport::build(&ladder(), CostBasis::Fifo, None);
+        let mut out = Vec::new();
+        report.write_asset_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with(
+            "symbol,closed_lots,closed_size,gross_pnl,fees,realized_pnl,open_size,open_avg_price,unmatched_size\n"
+        ));
+
+        let md = report.to_markdown();
+        assert!(md.contains("Cost basis: FIFO"));
+        assert!(md.contains("| HYPE/USDC | 1 | 1.000000 | 5.00 | 0.30 | 4.70 |"));
+        assert!(md.contains("| 1970-01-01 | 3 | 37.00 | 1 | 5.00 | 0.30 | 4.70 |"));
+
+        assert_eq!("lifo".parse::<CostBasis>(), Ok(CostBasis::Lifo));
+        assert!("hifo".parse::<CostBasis>().is_err());
+    }
+}
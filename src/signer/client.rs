@@ -0,0 +1,88 @@
+use super::{SignerIdentity, SignerRequest, SignerResponse};
+use crate::config::signer::SignerEndpoint;
+use crate::constants::SIGNER_TIMEOUT;
+use anyhow::{anyhow, Context, Result};
+use ethers::types::Signature;
+use hyperliquid_rust_sdk::Actions;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+/// Connection settings for a `signer` process. Every request opens its own
+/// connection, so a restarted signer is picked up without reconnecting.
+pub struct RemoteSigner {
+    endpoint: SignerEndpoint,
+    http: reqwest::Client,
+}
+
+impl RemoteSigner {
+    pub fn new(endpoint: &str) -> Result<Self> {
+        Ok(Self {
+            endpoint: SignerEndpoint::parse(endpoint)?,
+            http: reqwest::Client::builder()
+                .timeout(SIGNER_TIMEOUT)
+                .no_proxy()
+                .build()?,
+        })
+    }
+
+    pub async fn identity(&self) -> Result<SignerIdentity> {
+        match self.request(&SignerRequest::Identity).await? {
+            SignerResponse::Identity(identity) => Ok(identity),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Signature of `action`, or the reason the signer refused it.
+    pub async fn sign(&self, action: &Actions, nonce: u64) -> Result<Signature> {
+        let request = SignerRequest::Sign {
+            action: action.clone(),
+            nonce,
+        };
+        match self.request(&request).await? {
+            SignerResponse::Signature { signature } => Ok(signature),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    async fn request(&self, request: &SignerRequest) -> Result<SignerResponse> {
+        let body = serde_json::to_string(request)?;
+        let response = match &self.endpoint {
+            SignerEndpoint::Unix(path) => {
+                tokio::time::timeout(SIGNER_TIMEOUT, unix_request(path, &body))
+                    .await
+                    .map_err(|_| anyhow!("Signer at {} timed out", path.display()))??
+            }
+            SignerEndpoint::Http(addr) => {
+                self.http
+                    .post(format!("http://{}/", addr))
+                    .header("Content-Type", "application/json")
+                    .body(body)
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to reach signer at {}", addr))?
+                    .text()
+                    .await?
+            }
+        };
+        serde_json::from_str(&response)
+            .with_context(|| format!("Invalid signer response: {}", response.trim()))
+    }
+}
+
+async fn unix_request(path: &std::path::Path, body: &str) -> Result<String> {
+    let mut stream = UnixStream::connect(path)
+        .await
+        .with_context(|| format!("Failed to reach signer at {}", path.display()))?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.write_all(b"\n").await?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await?;
+    Ok(line)
+}
+
+fn unexpected(response: SignerResponse) -> anyhow::Error {
+    match response {
+        SignerResponse::Rejected { reason } => anyhow!("Signer refused: {}", reason),
+        other => anyhow!("Unexpected signer response: {:?}", other),
+    }
+}
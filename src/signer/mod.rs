@@ -0,0 +1,193 @@
+//! Remote signing of exchange actions.
+//!
+//! A bot whose account sets `api_wallet_signer` never loads the API wallet
+//! key. It builds each order, cancel and leverage action itself and asks a
+//! separate `signer` process, reachable over a Unix socket or loopback HTTP,
+//! for the signature. The signer holds the key, checks every action against
+//! its policy and signs with its own network and vault settings, so a
+//! compromised bot can only place orders the policy allows.
+//!
+//! - `client`: the bot side, [`RemoteSigner`]
+//! - `server`: the `signer` process and its policy
+//!
+//! Requests and responses are one JSON object each: a line on the Unix
+//! socket, a POST body over HTTP.
+
+pub mod client;
+pub mod server;
+
+pub use client::RemoteSigner;
+
+use anyhow::{anyhow, Result};
+use ethers::contract::{Eip712, EthAbiType};
+use ethers::signers::LocalWallet;
+use ethers::types::transaction::eip712::Eip712 as _;
+use ethers::types::{Signature, H160, H256};
+use ethers::utils::keccak256;
+use hyperliquid_rust_sdk::Actions;
+use serde::{Deserialize, Serialize};
+
+/// Sent by the bot to the signer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// Which wallet, network and vault the signer signs for.
+    Identity,
+    Sign {
+        action: Actions,
+        nonce: u64,
+    },
+}
+
+/// The signer's answer to a [`SignerRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum SignerResponse {
+    Identity(SignerIdentity),
+    Signature {
+        signature: Signature,
+    },
+    /// Refused by the policy, or the request could not be read.
+    Rejected {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignerIdentity {
+    /// API wallet address the signatures recover to.
+    pub address: H160,
+    pub network: String,
+    /// Sub-account every action is signed for, if any.
+    pub vault_address: Option<H160>,
+}
+
+/// The typed data Hyperliquid L1 actions are signed as.
+#[derive(Debug, Clone, Eip712, EthAbiType)]
+#[eip712(
+    name = "Exchange",
+    version = "1",
+    chain_id = 1337,
+    verifying_contract = "0x0000000000000000000000000000000000000000"
+)]
+struct Agent {
+    source: String,
+    connection_id: H256,
+}
+
+/// Hash the exchange recomputes from a posted action to check its signature.
+pub fn action_hash(action: &Actions, nonce: u64, vault_address: Option<H160>) -> Result<H256> {
+    let mut bytes =
+        rmp_serde::to_vec_named(action).map_err(|e| anyhow!("Failed to encode action: {}", e))?;
+    bytes.extend(nonce.to_be_bytes());
+    match vault_address {
+        Some(vault_address) => {
+            bytes.push(1);
+            bytes.extend(vault_address.to_fixed_bytes());
+        }
+        None => bytes.push(0),
+    }
+    Ok(H256(keccak256(bytes)))
+}
+
+/// Signs an L1 action the way the SDK's `ExchangeClient` does.
+pub fn sign_l1_action(
+    wallet: &LocalWallet,
+    action: &Actions,
+    nonce: u64,
+    vault_address: Option<H160>,
+    is_mainnet: bool,
+) -> Result<Signature> {
+    sign_connection_id(
+        wallet,
+        action_hash(action, nonce, vault_address)?,
+        is_mainnet,
+    )
+}
+
+fn sign_connection_id(
+    wallet: &LocalWallet,
+    connection_id: H256,
+    is_mainnet: bool,
+) -> Result<Signature> {
+    let agent = Agent {
+        source: if is_mainnet { "a" } else { "b" }.to_string(),
+        connection_id,
+    };
+    let digest = agent
+        .encode_eip712()
+        .map_err(|e| anyhow!("Failed to encode typed data: {}", e))?;
+    wallet
+        .sign_hash(H256(digest))
+        .map_err(|e| anyhow!("Failed to sign action: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    pub(crate) fn test_wallet() -> LocalWallet {
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap()
+    }
+
+    pub(crate) fn order(asset: u32, px: &str, sz: &str) -> Actions {
+        serde_json::from_value(serde_json::json!({
+            "type": "order",
+            "orders": [{
+                "a": asset,
+                "b": true,
+                "p": px,
+                "s": sz,
+                "r": false,
+                "t": {"limit": {"tif": "Gtc"}},
+                "c": "0x1e60610f0b3d420597c8c7c4a6f3c1d1",
+            }],
+            "grouping": "na",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sign_l1_action_matches_sdk() {
+        // Vectors from the SDK's own signature tests
+        let connection_id =
+            H256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
+                .unwrap();
+        let wallet = test_wallet();
+        assert_eq!(
+            sign_connection_id(&wallet, connection_id, true).unwrap().to_string(),
+            "fa8a41f6a3fa728206df80801a83bcbfbab08649cd34d9c0bfba7c7b2f99340f53a00226604567b98a1492803190d65a201d6805e5831b7044f17fd530aec7841c"
+        );
+        assert_eq!(
+            sign_connection_id(&wallet, connection_id, false).unwrap().to_string(),
+            "1713c0fc661b792a50e8ffdd59b637b1ed172d9a3aa4d801d9d88646710fb74b33959f4d075a7ccbec9f2374a6da21ffa4448d58d0413a0d335775f680a881431c"
+        );
+    }
+
+    #[test]
+    fn test_action_hash_survives_the_wire() {
+        // The signer hashes the action it parsed, the bot posts the one it built
+        let action = order(0, "65000", "0.01");
+        let request = serde_json::to_string(&SignerRequest::Sign {
+            action: action.clone(),
+            nonce: 1,
+        })
+        .unwrap();
+        let SignerRequest::Sign { action: parsed, .. } = serde_json::from_str(&request).unwrap()
+        else {
+            panic!("expected a sign request");
+        };
+        let vault = Some(H160::repeat_byte(0x22));
+        assert_eq!(
+            action_hash(&parsed, 1, vault).unwrap(),
+            action_hash(&action, 1, vault).unwrap()
+        );
+        assert_ne!(
+            action_hash(&action, 1, None).unwrap(),
+            action_hash(&action, 1, vault).unwrap()
+        );
+    }
+}
@@ -0,0 +1,677 @@
+use super::{sign_l1_action, SignerIdentity, SignerRequest, SignerResponse};
+use crate::config::signer::{SignerEndpoint, SignerPolicy};
+use crate::constants::{MAX_SIGNER_MID_AGE, MAX_SIGNER_NONCE_SKEW, SIGNER_MID_REFRESH_INTERVAL};
+use anyhow::{anyhow, Context, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::H160;
+use hyperliquid_rust_sdk::{Actions, InfoClient};
+use log::{info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+
+/// Largest HTTP request the signer reads.
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Holds the API wallet key and signs the actions its policy allows.
+pub struct SignerService {
+    wallet: LocalWallet,
+    network: String,
+    vault_address: Option<H160>,
+    /// Exchange asset id -> symbol of every allowed market.
+    assets: HashMap<u32, String>,
+    /// Coin names used by `all_mids` -> asset id, for the allowed markets.
+    coins: HashMap<String, u32>,
+    /// Latest mid of each allowed market and when it was fetched.
+    mids: RwLock<HashMap<u32, (f64, Instant)>>,
+    max_order_notional: f64,
+    /// Asset id -> largest order size in base units.
+    max_order_size: HashMap<u32, f64>,
+    max_price_deviation_bps: f64,
+    max_window_notional: f64,
+    notional_window: Duration,
+    /// Notional of the orders signed within `notional_window`, oldest first.
+    signed: Mutex<VecDeque<(Instant, f64)>>,
+    max_leverage: Option<u32>,
+}
+
+impl SignerService {
+    /// `coin_to_asset` maps symbols to exchange asset ids, as loaded by the
+    /// SDK's `ExchangeClient`.
+    pub fn new(
+        wallet: LocalWallet,
+        network: &str,
+        vault_address: Option<H160>,
+        policy: &SignerPolicy,
+        coin_to_asset: &HashMap<String, u32>,
+    ) -> Result<Self> {
+        let assets: HashMap<u32, String> = policy
+            .allowed_assets
+            .iter()
+            .map(|symbol| {
+                coin_to_asset
+                    .get(symbol)
+                    .map(|&asset| (asset, symbol.clone()))
+                    .ok_or_else(|| anyhow!("policy.allowed_assets: unknown market '{}'", symbol))
+            })
+            .collect::<Result<_>>()?;
+        // Spot markets are listed under both their pair name and their `@index`
+        let coins = coin_to_asset
+            .iter()
+            .filter(|(_, asset)| assets.contains_key(asset))
+            .map(|(coin, &asset)| (coin.clone(), asset))
+            .collect();
+        let max_order_size = policy
+            .max_order_size
+            .iter()
+            .filter_map(|(symbol, &size)| coin_to_asset.get(symbol).map(|&asset| (asset, size)))
+            .collect();
+        Ok(Self {
+            wallet,
+            network: network.to_string(),
+            vault_address,
+            assets,
+            coins,
+            mids: RwLock::new(HashMap::new()),
+            max_order_notional: policy.max_order_notional,
+            max_order_size,
+            max_price_deviation_bps: policy.max_price_deviation_bps,
+            max_window_notional: policy.max_window_notional,
+            notional_window: Duration::from_secs(policy.notional_window_secs),
+            signed: Mutex::new(VecDeque::new()),
+            max_leverage: policy.max_leverage,
+        })
+    }
+
+    /// Stores the allowed markets' mids from an `all_mids` response.
+    pub fn update_mids(&self, all_mids: &HashMap<String, String>) {
+        let now = Instant::now();
+        let mut mids = self.mids.write().unwrap_or_else(|e| e.into_inner());
+        for (coin, px) in all_mids {
+            let Some(&asset) = self.coins.get(coin) else {
+                continue;
+            };
+            if let Ok(mid) = parse_wire(px) {
+                if mid > 0.0 {
+                    mids.insert(asset, (mid, now));
+                }
+            }
+        }
+    }
+
+    pub fn handle(&self, request: SignerRequest) -> SignerResponse {
+        match request {
+            SignerRequest::Identity => SignerResponse::Identity(SignerIdentity {
+                address: self.wallet.address(),
+                network: self.network.clone(),
+                vault_address: self.vault_address,
+            }),
+            SignerRequest::Sign { action, nonce } => {
+                let kind = action_type(&action);
+                let signed = self.check(&action, nonce).and_then(|()| {
+                    sign_l1_action(
+                        &self.wallet,
+                        &action,
+                        nonce,
+                        self.vault_address,
+                        self.network == "mainnet",
+                    )
+                });
+                match signed {
+                    Ok(signature) => {
+                        info!("[SIGNER] Signed {} (nonce {})", kind, nonce);
+                        SignerResponse::Signature { signature }
+                    }
+                    Err(e) => {
+                        warn!("[SIGNER] Refused {} (nonce {}): {}", kind, nonce, e);
+                        SignerResponse::Rejected {
+                            reason: e.to_string(),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Answers one JSON request with one JSON response.
+    fn respond(&self, request: &str) -> String {
+        let response = match serde_json::from_str(request) {
+            Ok(request) => self.handle(request),
+            Err(e) => SignerResponse::Rejected {
+                reason: format!("Invalid request: {}", e),
+            },
+        };
+        serde_json::to_string(&response).unwrap_or_default()
+    }
+
+    fn check(&self, action: &Actions, nonce: u64) -> Result<()> {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        if now_ms.abs_diff(nonce) > MAX_SIGNER_NONCE_SKEW.as_millis() as u64 {
+            return Err(anyhow!(
+                "nonce {} is more than {}s from the signer's clock",
+                nonce,
+                MAX_SIGNER_NONCE_SKEW.as_secs()
+            ));
+        }
+
+        match action {
+            Actions::Order(bulk) => {
+                if bulk.builder.is_some() {
+                    return Err(anyhow!("builder fees are not allowed"));
+                }
+                let mut total = 0.0;
+                for order in &bulk.orders {
+                    let symbol = self.allowed(order.asset)?;
+                    let size = parse_wire(&order.sz)?;
+                    if let Some(&max) = self.max_order_size.get(&order.asset) {
+                        if size > max {
+                            return Err(anyhow!(
+                                "{} order of {} exceeds max_order_size {}",
+                                symbol,
+                                size,
+                                max
+                            ));
+                        }
+                    }
+                    let limit_px = parse_wire(&order.limit_px)?;
+                    let mid = self.mid(order.asset, symbol)?;
+                    // How far the order is priced through the mid: below it for a
+                    // sell, above it for a buy. Resting on the far side is harmless.
+                    let through = if order.is_buy {
+                        limit_px - mid
+                    } else {
+                        mid - limit_px
+                    };
+                    let through_bps = through / mid * 10_000.0;
+                    if through_bps > self.max_price_deviation_bps {
+                        return Err(anyhow!(
+                            "{} order at {} is {:.0} bps through the mid {}, more than max_price_deviation_bps {}",
+                            symbol,
+                            limit_px,
+                            through_bps,
+                            mid,
+                            self.max_price_deviation_bps
+                        ));
+                    }
+                    // A limit through the book fills at the market, not at the limit
+                    let notional = limit_px.max(mid) * size;
+                    if notional > self.max_order_notional {
+                        return Err(anyhow!(
+                            "{} order of {:.2} exceeds max_order_notional {}",
+                            symbol,
+                            notional,
+                            self.max_order_notional
+                        ));
+                    }
+                    total += notional;
+                }
+                self.spend(total)?;
+            }
+            Actions::Cancel(bulk) => {
+                for cancel in &bulk.cancels {
+                    self.allowed(cancel.asset)?;
+                }
+            }
+            Actions::CancelByCloid(bulk) => {
+                for cancel in &bulk.cancels {
+                    self.allowed(cancel.asset)?;
+                }
+            }
+            Actions::UpdateLeverage(update) => {
+                let symbol = self.allowed(update.asset)?;
+                match self.max_leverage {
+                    Some(max) if update.leverage <= max => {}
+                    Some(max) => {
+                        return Err(anyhow!(
+                            "{}x leverage on {} exceeds max_leverage {}",
+                            update.leverage,
+                            symbol,
+                            max
+                        ))
+                    }
+                    None => return Err(anyhow!("leverage changes need policy.max_leverage")),
+                }
+            }
+            other => return Err(anyhow!("{} actions are not allowed", action_type(other))),
+        }
+        Ok(())
+    }
+
+    /// Counts `notional` against the rolling window budget, or refuses it.
+    fn spend(&self, notional: f64) -> Result<()> {
+        let mut signed = self.signed.lock().unwrap_or_else(|e| e.into_inner());
+        while signed
+            .front()
+            .is_some_and(|(at, _)| at.elapsed() > self.notional_window)
+        {
+            signed.pop_front();
+        }
+        let used: f64 = signed.iter().map(|(_, n)| n).sum();
+        if used + notional > self.max_window_notional {
+            return Err(anyhow!(
+                "orders of {:.2} would bring the last {}s to {:.2}, over max_window_notional {}",
+                notional,
+                self.notional_window.as_secs(),
+                used + notional,
+                self.max_window_notional
+            ));
+        }
+        signed.push_back((Instant::now(), notional));
+        Ok(())
+    }
+
+    /// Mid of an allowed market, if it was fetched recently enough to value an order.
+    fn mid(&self, asset: u32, symbol: &str) -> Result<f64> {
+        let mids = self.mids.read().unwrap_or_else(|e| e.into_inner());
+        match mids.get(&asset) {
+            Some(&(mid, fetched)) if fetched.elapsed() <= MAX_SIGNER_MID_AGE => Ok(mid),
+            _ => Err(anyhow!(
+                "no {} mid from the last {}s to value the order at",
+                symbol,
+                MAX_SIGNER_MID_AGE.as_secs()
+            )),
+        }
+    }
+
+    fn allowed(&self, asset: u32) -> Result<&str> {
+        self.assets
+            .get(&asset)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("asset {} is not in policy.allowed_assets", asset))
+    }
+}
+
+fn action_type(action: &Actions) -> String {
+    serde_json::to_value(action)
+        .ok()
+        .and_then(|value| value["type"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn parse_wire(value: &str) -> Result<f64> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .ok_or_else(|| anyhow!("invalid number '{}'", value))
+}
+
+/// Refreshes `service`'s mids from the exchange until the process is stopped.
+/// Orders are refused while the mids are stale.
+pub async fn refresh_mids(service: Arc<SignerService>, info_client: InfoClient) {
+    let mut interval = tokio::time::interval(SIGNER_MID_REFRESH_INTERVAL);
+    loop {
+        interval.tick().await;
+        match info_client.all_mids().await {
+            Ok(mids) => service.update_mids(&mids),
+            Err(e) => warn!("[SIGNER] Failed to fetch mids: {}", e),
+        }
+    }
+}
+
+/// Serves `service` on `listen` until the process is stopped.
+pub async fn serve(service: Arc<SignerService>, listen: &str) -> Result<()> {
+    match SignerEndpoint::parse(listen)? {
+        SignerEndpoint::Unix(path) => serve_unix(service, &path).await,
+        SignerEndpoint::Http(addr) => serve_http(service, addr).await,
+    }
+}
+
+async fn serve_unix(service: Arc<SignerService>, path: &Path) -> Result<()> {
+    // A socket left behind by a previous run blocks the bind
+    if path.exists() {
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+    let listener = bind_unix(path)?;
+    info!("[SIGNER] Listening on unix:{}", path.display());
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_unix(&service, stream).await {
+                warn!("[SIGNER] Connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Binds `path` with mode 660 (owner and group: put the bot's user in the
+/// signer's group). The socket is bound and restricted inside a private
+/// directory, then moved into place, so it is never reachable with wider
+/// permissions.
+fn bind_unix(path: &Path) -> Result<UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let staging = parent.join(format!(".hlbot-signer-{}", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+    let staged = staging.join("signer.sock");
+    let bound = UnixListener::bind(&staged)
+        .map_err(anyhow::Error::from)
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o660))?;
+            std::fs::rename(&staged, path)?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    bound.with_context(|| format!("Failed to listen on {}", path.display()))
+}
+
+async fn handle_unix(service: &SignerService, stream: UnixStream) -> Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    (&mut stream)
+        .take(MAX_REQUEST_BYTES as u64)
+        .read_line(&mut line)
+        .await?;
+    if !line.ends_with('\n') && line.len() >= MAX_REQUEST_BYTES {
+        return Err(anyhow!("request too large"));
+    }
+    let mut response = service.respond(&line);
+    response.push('\n');
+    stream.get_mut().write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+async fn serve_http(service: Arc<SignerService>, addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to listen on {}", addr))?;
+    info!("[SIGNER] Listening on http://{}", addr);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_http(&service, stream).await {
+                warn!("[SIGNER] Connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Reads one `POST` request and answers it. Anything else gets a 405.
+async fn handle_http(service: &SignerService, mut stream: TcpStream) -> Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_len = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Err(anyhow!("request head too large"));
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow!("connection closed before the request head"));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_len]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_REQUEST_BYTES {
+        return Err(anyhow!("request body too large"));
+    }
+    while buf.len() < head_len + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow!("connection closed before the request body"));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let (status, body) = if head.starts_with("POST ") {
+        let body = String::from_utf8_lossy(&buf[head_len..head_len + content_length]);
+        ("200 OK", service.respond(&body))
+    } else {
+        let rejected = SignerResponse::Rejected {
+            reason: "Only POST is supported".to_string(),
+        };
+        (
+            "405 Method Not Allowed",
+            serde_json::to_string(&rejected).unwrap_or_default(),
+        )
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::tests::{order, test_wallet};
+    use crate::signer::RemoteSigner;
+    use hyperliquid_rust_sdk::{SetReferrer, UpdateLeverage};
+
+    fn policy() -> SignerPolicy {
+        SignerPolicy {
+            allowed_assets: vec!["BTC".to_string()],
+            max_order_notional: 1000.0,
+            max_order_size: HashMap::from([("BTC".to_string(), 0.1)]),
+            max_price_deviation_bps: 100.0,
+            max_window_notional: 100_000.0,
+            notional_window_secs: 3600,
+            max_leverage: Some(5),
+        }
+    }
+
+    fn service() -> SignerService {
+        service_with(&policy())
+    }
+
+    fn service_with(policy: &SignerPolicy) -> SignerService {
+        let coin_to_asset = HashMap::from([("BTC".to_string(), 0), ("ETH".to_string(), 1)]);
+        let service =
+            SignerService::new(test_wallet(), "testnet", None, policy, &coin_to_asset).unwrap();
+        service.update_mids(&HashMap::from([
+            ("BTC".to_string(), "65000".to_string()),
+            ("ETH".to_string(), "3000".to_string()),
+        ]));
+        service
+    }
+
+    fn sell(asset: u32, px: &str, sz: &str) -> Actions {
+        let mut action = order(asset, px, sz);
+        if let Actions::Order(bulk) = &mut action {
+            bulk.orders[0].is_buy = false;
+        }
+        action
+    }
+
+    fn now_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    fn refused(service: &SignerService, action: Actions, nonce: u64) -> String {
+        match service.handle(SignerRequest::Sign { action, nonce }) {
+            SignerResponse::Rejected { reason } => reason,
+            other => panic!("expected a refusal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_policy_limits_what_is_signed() {
+        let service = service();
+        let nonce = now_ms();
+
+        let action = order(0, "65000", "0.01");
+        match service.handle(SignerRequest::Sign {
+            action: action.clone(),
+            nonce,
+        }) {
+            SignerResponse::Signature { signature } => assert_eq!(
+                signature,
+                sign_l1_action(&test_wallet(), &action, nonce, None, false).unwrap()
+            ),
+            other => panic!("expected a signature, got {:?}", other),
+        }
+
+        assert!(refused(&service, order(0, "65000", "0.02"), nonce).contains("max_order_notional"));
+        assert!(refused(&service, order(1, "3000", "0.01"), nonce).contains("allowed_assets"));
+        assert!(refused(&service, action, nonce - 120_000).contains("nonce"));
+
+        // Sells far below the mid and buys far above it are refused
+        assert!(refused(&service, sell(0, "0.000001", "1000"), nonce).contains("max_order_size"));
+        assert!(refused(&service, sell(0, "0.000001", "0.01"), nonce)
+            .contains("max_price_deviation_bps"));
+        assert!(
+            refused(&service, order(0, "66000", "0.01"), nonce).contains("max_price_deviation_bps")
+        );
+        // A sell within the deviation is valued at the mid
+        assert!(refused(&service, sell(0, "64500", "0.05"), nonce).contains("max_order_notional"));
+        // Resting far from the mid on the passive side is fine
+        assert!(matches!(
+            service.handle(SignerRequest::Sign {
+                action: sell(0, "80000", "0.01"),
+                nonce
+            }),
+            SignerResponse::Signature { .. }
+        ));
+
+        // Without a mid the signer cannot value orders
+        let blind = SignerService::new(
+            test_wallet(),
+            "testnet",
+            None,
+            &SignerPolicy {
+                max_order_size: HashMap::new(),
+                ..policy()
+            },
+            &HashMap::from([("BTC".to_string(), 0)]),
+        )
+        .unwrap();
+        assert!(refused(&blind, order(0, "65000", "0.01"), nonce).contains("no BTC mid"));
+
+        let leverage = |leverage| {
+            Actions::UpdateLeverage(UpdateLeverage {
+                asset: 0,
+                is_cross: true,
+                leverage,
+            })
+        };
+        assert!(refused(&service, leverage(10), nonce).contains("max_leverage"));
+        assert!(matches!(
+            service.handle(SignerRequest::Sign {
+                action: leverage(3),
+                nonce
+            }),
+            SignerResponse::Signature { .. }
+        ));
+
+        let referrer = Actions::SetReferrer(SetReferrer {
+            code: "X".to_string(),
+        });
+        assert!(refused(&service, referrer, nonce).contains("setReferrer actions are not allowed"));
+    }
+
+    #[test]
+    fn test_window_notional_budget() {
+        let service = service_with(&SignerPolicy {
+            max_window_notional: 2000.0,
+            ..policy()
+        });
+        let nonce = now_ms();
+        // 650 each: three fit in the window, the fourth does not
+        for _ in 0..3 {
+            assert!(matches!(
+                service.handle(SignerRequest::Sign {
+                    action: sell(0, "65000", "0.01"),
+                    nonce
+                }),
+                SignerResponse::Signature { .. }
+            ));
+        }
+        assert!(refused(&service, sell(0, "65000", "0.01"), nonce).contains("max_window_notional"));
+        // Refused orders do not use up the budget
+        assert!(matches!(
+            service.handle(SignerRequest::Sign {
+                action: sell(0, "65000", "0.0007"),
+                nonce
+            }),
+            SignerResponse::Signature { .. }
+        ));
+    }
+
+    /// Serves `service()` on `listen` and signs through it as the bot would.
+    async fn round_trip(listen: String) {
+        let server = tokio::spawn({
+            let listen = listen.clone();
+            async move { serve(Arc::new(service()), &listen).await }
+        });
+        let signer = RemoteSigner::new(&listen).unwrap();
+
+        let mut identity = signer.identity().await;
+        for _ in 0..50 {
+            if identity.is_ok() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            identity = signer.identity().await;
+        }
+        let identity = identity.unwrap();
+        assert_eq!(identity.address, test_wallet().address());
+        assert_eq!(identity.network, "testnet");
+
+        let nonce = now_ms();
+        let action = order(0, "65000", "0.01");
+        assert_eq!(
+            signer.sign(&action, nonce).await.unwrap(),
+            sign_l1_action(&test_wallet(), &action, nonce, None, false).unwrap()
+        );
+        let err = signer
+            .sign(&order(0, "65000", "1"), nonce)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Signer refused"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_transports() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("signer.sock");
+        round_trip(format!("unix:{}", socket.display())).await;
+        let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o660);
+        // Only the socket is left, not the staging directory
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        round_trip(format!("http://127.0.0.1:{}", port)).await;
+    }
+}
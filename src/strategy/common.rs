@@ -1,4 +1,9 @@
-use super::types::GridType;
+use super::types::{CompoundAllocation, DistributionShape, GridType, SizeDistribution};
+use crate::config::strategy::{AtrSpacingConfig, CompoundConfig, GridRangeConfig};
+use crate::constants::{GEOMETRIC_SIZE_RATIO, MAX_FEE_SHARE_OF_SPACING};
+use crate::engine::context::MIN_NOTIONAL_VALUE;
+use crate::model::{Candle, FeeRates};
+use anyhow::{anyhow, Result};
 use std::time::Duration;
 
 /// Format a Duration as a human-readable uptime string.
@@ -149,6 +154,347 @@ pub fn calculate_grid_spacing_pct(
     }
 }
 
+/// Smallest and largest zone spacing of an explicit ladder, as `(min%, max%)`
+/// of each zone's lower price.
+pub fn calculate_ladder_spacing_pct(prices: &[f64]) -> (f64, f64) {
+    prices
+        .windows(2)
+        .map(|level| (level[1] - level[0]) / level[0] * 100.0)
+        .fold(None, |acc: Option<(f64, f64)>, pct| match acc {
+            Some((min, max)) => Some((min.min(pct), max.max(pct))),
+            None => Some((pct, pct)),
+        })
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Geometric levels of an infinity grid from `grid_range_low`: every level at or
+/// below `entry_price`, then enough levels for `levels_above` zones starting above it.
+/// Always at least three levels.
+pub fn calculate_infinity_grid_prices(
+    grid_range_low: f64,
+    spread_bips: f64,
+    entry_price: f64,
+    levels_above: u32,
+) -> Vec<f64> {
+    let ratio = 1.0 + (spread_bips / 10000.0);
+    let level = |k: usize| grid_range_low * ratio.powi(k as i32);
+
+    let below = (0..).take_while(|&k| level(k) <= entry_price).count();
+    let count = (below + levels_above as usize + 1).max(3);
+    (0..count).map(level).collect()
+}
+
+/// Levels of one range of a layered grid, from `low` to `high` inclusive.
+fn calculate_range_prices(range: &GridRangeConfig) -> Vec<f64> {
+    let mut prices = match (range.grid_count, range.spread_bips) {
+        (Some(count), _) => calculate_grid_prices(range.grid_type, range.low, range.high, count),
+        (None, Some(bips)) => calculate_fitted_grid_prices(range.low, range.high, bips, usize::MAX),
+        (None, None) => Vec::new(),
+    };
+    // Pin the top level so the next range starts exactly where this one ends
+    if let Some(last) = prices.last_mut() {
+        *last = range.high;
+    }
+    prices
+}
+
+/// Levels of a layered grid. Adjacent ranges share their boundary level.
+pub fn calculate_layered_grid_prices(ranges: &[GridRangeConfig]) -> Vec<f64> {
+    let mut prices: Vec<f64> = Vec::new();
+    for range in ranges {
+        let skip = usize::from(!prices.is_empty());
+        prices.extend(calculate_range_prices(range).into_iter().skip(skip));
+    }
+    prices
+}
+
+/// Zone shares for a layered grid, summing to 1. Each range receives its
+/// `investment` share, split across its own zones by `distribution`.
+pub fn layered_zone_weights(
+    distribution: &SizeDistribution,
+    ranges: &[GridRangeConfig],
+    entry_price: f64,
+) -> Result<Vec<f64>> {
+    let total: f64 = ranges.iter().map(|r| r.investment).sum();
+    if total <= 0.0 {
+        return Err(anyhow!("Range investments must sum to a positive value"));
+    }
+
+    let mut weights = Vec::new();
+    for range in ranges {
+        let share = range.investment / total;
+        let inner = zone_weights(distribution, &calculate_range_prices(range), entry_price)?;
+        weights.extend(inner.into_iter().map(|w| w * share));
+    }
+    Ok(weights)
+}
+
+/// Index of the range containing a zone's midpoint.
+pub fn range_index(ranges: &[GridRangeConfig], lower: f64, upper: f64) -> Option<usize> {
+    let mid = (lower + upper) / 2.0;
+    ranges.iter().position(|r| mid >= r.low && mid <= r.high)
+}
+
+/// Average true range over the last `lookback` candles.
+///
+/// True range is the largest of high - low and the gaps to the previous close.
+/// Returns `None` when fewer than `lookback + 1` candles are available.
+pub fn calculate_atr(candles: &[Candle], lookback: usize) -> Option<f64> {
+    if lookback == 0 || candles.len() < lookback + 1 {
+        return None;
+    }
+
+    let window = &candles[candles.len() - lookback - 1..];
+    let total: f64 = window
+        .windows(2)
+        .map(|pair| {
+            let prev_close = pair[0].close;
+            let c = pair[1];
+            (c.high - c.low)
+                .max((c.high - prev_close).abs())
+                .max((c.low - prev_close).abs())
+        })
+        .sum();
+
+    Some(total / lookback as f64)
+}
+
+/// Level spacing in basis points derived from ATR.
+///
+/// ATR is taken relative to the last close, scaled by the multiplier and
+/// clamped to the configured bounds.
+pub fn atr_spread_bips(candles: &[Candle], atr: &AtrSpacingConfig) -> Option<f64> {
+    let value = calculate_atr(candles, atr.lookback as usize)?;
+    let last_close = candles.last()?.close;
+    if last_close <= 0.0 || value <= 0.0 {
+        return None;
+    }
+
+    let mut bips = value / last_close * atr.multiplier * 10000.0;
+    if let Some(min) = atr.min_spread_bips {
+        bips = bips.max(min);
+    }
+    if let Some(max) = atr.max_spread_bips {
+        bips = bips.min(max);
+    }
+    Some(bips)
+}
+
+/// Geometric levels from `grid_range_low` to `grid_range_high`, both included.
+///
+/// The zone count is the whole number that brings spacing closest to `spread_bips`,
+/// capped at `max_zones`. Returns an empty list when no zone fits.
+pub fn calculate_fitted_grid_prices(
+    grid_range_low: f64,
+    grid_range_high: f64,
+    spread_bips: f64,
+    max_zones: usize,
+) -> Vec<f64> {
+    if max_zones == 0 || grid_range_low >= grid_range_high || spread_bips <= 0.0 {
+        return Vec::new();
+    }
+
+    let ratio = 1.0 + (spread_bips / 10000.0);
+    let ideal = ((grid_range_high / grid_range_low).ln() / ratio.ln()).round() as usize;
+    let zones = ideal.clamp(1, max_zones);
+
+    let mut prices = calculate_grid_prices(
+        GridType::Geometric,
+        grid_range_low,
+        grid_range_high,
+        zones as u32 + 1,
+    );
+    // Pin the top level so the ladder joins neighbouring zones exactly
+    if let Some(last) = prices.last_mut() {
+        *last = grid_range_high;
+    }
+    prices
+}
+
+/// Share of the investment given to each zone, summing to 1.
+///
+/// Zone `i` spans `prices[i]..prices[i + 1]`. Preset shapes weight a zone by its
+/// distance in levels from `entry_price`, counted separately above and below it,
+/// so the zones nearest the entry are the smallest.
+pub fn zone_weights(
+    distribution: &SizeDistribution,
+    prices: &[f64],
+    entry_price: f64,
+) -> Result<Vec<f64>> {
+    let num_zones = prices.len().saturating_sub(1);
+    let raw: Vec<f64> = match distribution {
+        SizeDistribution::Weights(weights) => {
+            if weights.len() != num_zones {
+                return Err(anyhow!(
+                    "size_distribution has {} weights but the grid has {} zones",
+                    weights.len(),
+                    num_zones
+                ));
+            }
+            weights.clone()
+        }
+        SizeDistribution::Shape(shape) => {
+            let first_above = prices
+                .windows(2)
+                .take_while(|level| (level[0] + level[1]) / 2.0 <= entry_price)
+                .count();
+            (0..num_zones)
+                .map(|i| {
+                    let steps = if i < first_above {
+                        first_above - 1 - i
+                    } else {
+                        i - first_above
+                    } as f64;
+                    match shape {
+                        DistributionShape::Flat => 1.0,
+                        DistributionShape::Linear => 1.0 + steps,
+                        DistributionShape::Geometric => GEOMETRIC_SIZE_RATIO.powf(steps),
+                        DistributionShape::Pyramid => (1.0 + steps).powi(2),
+                    }
+                })
+                .collect()
+        }
+    };
+
+    let total: f64 = raw.iter().sum();
+    if total <= 0.0 {
+        return Err(anyhow!(
+            "size_distribution weights must sum to a positive value"
+        ));
+    }
+    Ok(raw.into_iter().map(|w| w / total).collect())
+}
+
+/// Split reinvested profit across zones waiting to open.
+///
+/// `candidates` are (zone index, order price, current notional). Returns the quote
+/// amount assigned to each chosen zone.
+pub fn allocate_compound(
+    amount: f64,
+    candidates: &[(usize, f64, f64)],
+    compound: &CompoundConfig,
+    current_price: f64,
+) -> Vec<(usize, f64)> {
+    if amount <= 0.0 || candidates.is_empty() {
+        return Vec::new();
+    }
+
+    match compound.allocation {
+        CompoundAllocation::Proportional => {
+            let total: f64 = candidates.iter().map(|c| c.2).sum();
+            if total <= 0.0 {
+                return Vec::new();
+            }
+            candidates
+                .iter()
+                .map(|&(idx, _, notional)| (idx, amount * notional / total))
+                .collect()
+        }
+        CompoundAllocation::Nearest => {
+            let mut nearest = candidates.to_vec();
+            nearest.sort_by(|a, b| {
+                (a.1 - current_price)
+                    .abs()
+                    .total_cmp(&(b.1 - current_price).abs())
+            });
+            nearest.truncate(compound.nearest_zones);
+            let share = amount / nearest.len() as f64;
+            nearest
+                .into_iter()
+                .map(|(idx, _, _)| (idx, share))
+                .collect()
+        }
+    }
+}
+
+/// Suggested grid spacing for a range and investment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridRecommendation {
+    pub grid_count: u32,
+    /// Equivalent geometric spread, rounded up to 0.1 bips. `None` for arithmetic grids.
+    pub spread_bips: Option<f64>,
+    /// (min, max) zone spacing in percent of price.
+    pub spacing_pct: (f64, f64),
+    pub investment_per_zone: f64,
+    /// Profit per roundtrip in the tightest zone after maker fees, in percent.
+    pub net_profit_pct: f64,
+    /// Most zones the investment can fund at the minimum order value.
+    pub max_zones_by_notional: usize,
+    /// Most zones whose spacing keeps fees within `MAX_FEE_SHARE_OF_SPACING`.
+    pub max_zones_by_fees: usize,
+}
+
+/// Propose the densest grid that keeps every zone above the minimum order value
+/// and keeps round-trip maker fees within `MAX_FEE_SHARE_OF_SPACING` of its spacing.
+///
+/// `investment` is the amount actually spread over the zones (after buffers).
+pub fn recommend_grid(
+    grid_type: GridType,
+    grid_range_low: f64,
+    grid_range_high: f64,
+    investment: f64,
+    fees: &FeeRates,
+) -> Result<GridRecommendation> {
+    if grid_range_low <= 0.0 || grid_range_low >= grid_range_high {
+        return Err(anyhow!(
+            "Invalid grid range {} - {}",
+            grid_range_low,
+            grid_range_high
+        ));
+    }
+
+    let max_zones_by_notional = (investment / MIN_NOTIONAL_VALUE).floor() as usize;
+
+    // Smallest spacing (as a fraction) where fees stay within the allowed share
+    let min_spacing = fees.roundtrip_maker_pct() / 100.0 / MAX_FEE_SHARE_OF_SPACING;
+    let max_zones_by_fees = if min_spacing <= 0.0 {
+        usize::MAX
+    } else {
+        match grid_type {
+            GridType::Geometric => ((grid_range_high / grid_range_low).ln()
+                / (1.0 + min_spacing).ln())
+            .floor() as usize,
+            // Arithmetic spacing is tightest, in percent, at the top of the range
+            GridType::Arithmetic => ((grid_range_high - grid_range_low)
+                / (grid_range_high * min_spacing))
+                .floor() as usize,
+        }
+    };
+
+    let zones = max_zones_by_notional.min(max_zones_by_fees);
+    if zones < 2 {
+        let reason = if max_zones_by_notional < 2 {
+            format!(
+                "investment {:.2} funds only {} zone(s) at the {} minimum order value",
+                investment, max_zones_by_notional, MIN_NOTIONAL_VALUE
+            )
+        } else {
+            format!(
+                "the range is too narrow for a profitable roundtrip at {:.4}% maker fees",
+                fees.maker * 100.0
+            )
+        };
+        return Err(anyhow!("Cannot fit a grid of at least 2 zones: {}", reason));
+    }
+
+    let spacing_pct =
+        calculate_grid_spacing_pct(&grid_type, grid_range_low, grid_range_high, zones as u32);
+    let spread_bips = match grid_type {
+        GridType::Geometric => Some((spacing_pct.0 * 100.0 * 10.0).ceil() / 10.0),
+        GridType::Arithmetic => None,
+    };
+
+    Ok(GridRecommendation {
+        grid_count: zones as u32 + 1,
+        spread_bips,
+        spacing_pct,
+        investment_per_zone: investment / zones as f64,
+        net_profit_pct: spacing_pct.0 - fees.roundtrip_maker_pct(),
+        max_zones_by_notional,
+        max_zones_by_fees,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,11 +506,11 @@ mod tests {
         let trigger = 110.0;
 
         // Not triggered yet
-        assert_eq!(check_trigger(105.0, trigger, start), false);
+        assert!(!check_trigger(105.0, trigger, start));
 
         // Triggered
-        assert_eq!(check_trigger(110.0, trigger, start), true);
-        assert_eq!(check_trigger(111.0, trigger, start), true);
+        assert!(check_trigger(110.0, trigger, start));
+        assert!(check_trigger(111.0, trigger, start));
     }
 
     #[test]
@@ -174,11 +520,11 @@ mod tests {
         let trigger = 90.0;
 
         // Not triggered yet
-        assert_eq!(check_trigger(95.0, trigger, start), false);
+        assert!(!check_trigger(95.0, trigger, start));
 
         // Triggered
-        assert_eq!(check_trigger(90.0, trigger, start), true);
-        assert_eq!(check_trigger(89.0, trigger, start), true);
+        assert!(check_trigger(90.0, trigger, start));
+        assert!(check_trigger(89.0, trigger, start));
     }
 
     #[test]
@@ -246,4 +592,213 @@ mod tests {
         assert_eq!(format_uptime(Duration::from_secs(90061)), "1d 1h 1m");
         assert_eq!(format_uptime(Duration::from_secs(259200)), "3d 0h 0m"); // 3 days
     }
+
+    fn candle(open_time: u64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            open_time,
+            open: close,
+            high,
+            low,
+            close,
+        }
+    }
+
+    #[test]
+    fn test_calculate_atr() {
+        let candles = vec![
+            candle(0, 101.0, 99.0, 100.0),
+            // Range 2
+            candle(1, 102.0, 100.0, 101.0),
+            // Gap up: |106 - 101| = 5 beats range 2
+            candle(2, 106.0, 104.0, 105.0),
+            // Range 3
+            candle(3, 106.0, 103.0, 104.0),
+        ];
+
+        assert!((calculate_atr(&candles, 3).unwrap() - 10.0 / 3.0).abs() < 1e-9);
+        // Only the most recent candles are used
+        assert!((calculate_atr(&candles, 1).unwrap() - 3.0).abs() < 1e-9);
+        assert!(calculate_atr(&candles, 4).is_none());
+    }
+
+    #[test]
+    fn test_atr_spread_bips_multiplier_and_bounds() {
+        // ATR 2 on a close of 100 = 2% = 200 bips
+        let candles: Vec<Candle> = (0..15).map(|i| candle(i, 101.0, 99.0, 100.0)).collect();
+        let mut atr = AtrSpacingConfig {
+            lookback: 14,
+            multiplier: 0.5,
+            ..AtrSpacingConfig::default()
+        };
+        assert!((atr_spread_bips(&candles, &atr).unwrap() - 100.0).abs() < 1e-9);
+
+        atr.max_spread_bips = Some(80.0);
+        assert!((atr_spread_bips(&candles, &atr).unwrap() - 80.0).abs() < 1e-9);
+
+        atr.max_spread_bips = None;
+        atr.min_spread_bips = Some(150.0);
+        assert!((atr_spread_bips(&candles, &atr).unwrap() - 150.0).abs() < 1e-9);
+
+        assert!(atr_spread_bips(&candles[..10], &atr).is_none());
+    }
+
+    #[test]
+    fn test_calculate_fitted_grid_prices() {
+        // 100 -> 121 at ~10% spacing fits exactly 2 zones
+        let prices = calculate_fitted_grid_prices(100.0, 121.0, 1000.0, 10);
+        assert_eq!(prices.len(), 3);
+        assert!((prices[0] - 100.0).abs() < 1e-9);
+        assert!((prices[1] - 110.0).abs() < 1e-9);
+        assert!((prices[2] - 121.0).abs() < 1e-9);
+
+        // Capped zone count keeps both endpoints
+        let capped = calculate_fitted_grid_prices(100.0, 121.0, 10.0, 4);
+        assert_eq!(capped.len(), 5);
+        assert!((capped[4] - 121.0).abs() < 1e-9);
+
+        assert!(calculate_fitted_grid_prices(100.0, 121.0, 1000.0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_recommend_grid_limits() {
+        let fees = FeeRates {
+            maker: 0.0004,
+            taker: 0.0007,
+        };
+
+        // Fees bind: 0.08% round trip needs >= 0.16% spacing
+        let rec = recommend_grid(GridType::Geometric, 100.0, 110.0, 100_000.0, &fees).unwrap();
+        assert_eq!(rec.max_zones_by_fees, 59);
+        assert_eq!(rec.grid_count, 60);
+        assert!(rec.spacing_pct.0 >= 0.16);
+        assert!(rec.net_profit_pct > 0.0);
+        let bips = rec.spread_bips.unwrap();
+        assert!(bips >= rec.spacing_pct.0 * 100.0);
+        // Rounding the spread up may drop the top level, never add one
+        let levels = calculate_grid_prices_by_spread(100.0, 110.0, bips).len() as u32;
+        assert!(levels == rec.grid_count || levels + 1 == rec.grid_count);
+
+        // Notional binds: 110 / 11 = 10 zones
+        let rec = recommend_grid(GridType::Arithmetic, 100.0, 200.0, 110.0, &fees).unwrap();
+        assert_eq!(rec.max_zones_by_notional, 10);
+        assert_eq!(rec.grid_count, 11);
+        assert_eq!(rec.spread_bips, None);
+        assert!((rec.investment_per_zone - 11.0).abs() < 1e-9);
+
+        assert!(recommend_grid(GridType::Geometric, 100.0, 110.0, 15.0, &fees).is_err());
+        assert!(recommend_grid(GridType::Geometric, 100.0, 100.2, 1000.0, &fees).is_err());
+    }
+
+    #[test]
+    fn test_allocate_compound() {
+        let candidates = [(0, 90.0, 100.0), (1, 95.0, 300.0), (2, 99.0, 100.0)];
+
+        let proportional = CompoundConfig::default();
+        let split = allocate_compound(10.0, &candidates, &proportional, 100.0);
+        assert_eq!(split, vec![(0, 2.0), (1, 6.0), (2, 2.0)]);
+
+        let nearest = CompoundConfig {
+            allocation: CompoundAllocation::Nearest,
+            nearest_zones: 2,
+            interval_secs: None,
+        };
+        let split = allocate_compound(10.0, &candidates, &nearest, 100.0);
+        assert_eq!(split, vec![(2, 5.0), (1, 5.0)]);
+
+        assert!(allocate_compound(0.0, &candidates, &nearest, 100.0).is_empty());
+        assert!(allocate_compound(10.0, &[], &proportional, 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_zone_weights() {
+        let prices = [90.0, 95.0, 100.0, 105.0, 110.0, 115.0];
+        let shares = |dist: SizeDistribution| zone_weights(&dist, &prices, 102.0).unwrap();
+
+        let flat = shares(SizeDistribution::default());
+        assert!(flat.iter().all(|w| (w - 0.2).abs() < 1e-12));
+
+        // Zones 0..2 sit below the entry, 2..5 above; distance restarts at each side.
+        let linear = shares(SizeDistribution::Shape(DistributionShape::Linear));
+        let expected = [2.0, 1.0, 1.0, 2.0, 3.0].map(|w| w / 9.0);
+        for (w, e) in linear.iter().zip(expected) {
+            assert!((w - e).abs() < 1e-12);
+        }
+
+        let pyramid = shares(SizeDistribution::Shape(DistributionShape::Pyramid));
+        assert!((pyramid[4] / pyramid[2] - 9.0).abs() < 1e-9);
+
+        let geometric = shares(SizeDistribution::Shape(DistributionShape::Geometric));
+        assert!((geometric[0] / geometric[1] - GEOMETRIC_SIZE_RATIO).abs() < 1e-9);
+        assert!((geometric.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let explicit = shares(SizeDistribution::Weights(vec![1.0, 1.0, 2.0, 2.0, 4.0]));
+        assert!((explicit[4] - 0.4).abs() < 1e-12);
+
+        let short = SizeDistribution::Weights(vec![1.0, 2.0]);
+        assert!(zone_weights(&short, &prices, 102.0).is_err());
+    }
+
+    #[test]
+    fn test_layered_grid_prices_and_weights() {
+        let ranges = vec![
+            GridRangeConfig {
+                low: 90.0,
+                high: 100.0,
+                grid_type: GridType::Arithmetic,
+                grid_count: Some(5),
+                spread_bips: None,
+                investment: 600.0,
+            },
+            GridRangeConfig {
+                low: 100.0,
+                high: 120.0,
+                grid_type: GridType::Arithmetic,
+                grid_count: Some(3),
+                spread_bips: None,
+                investment: 400.0,
+            },
+        ];
+
+        let prices = calculate_layered_grid_prices(&ranges);
+        assert_eq!(prices, vec![90.0, 92.5, 95.0, 97.5, 100.0, 110.0, 120.0]);
+
+        let weights = layered_zone_weights(&SizeDistribution::default(), &ranges, 105.0).unwrap();
+        assert_eq!(weights.len(), prices.len() - 1);
+        assert!(weights[..4].iter().all(|w| (w - 0.15).abs() < 1e-12));
+        assert!(weights[4..].iter().all(|w| (w - 0.2).abs() < 1e-12));
+
+        assert_eq!(range_index(&ranges, 97.5, 100.0), Some(0));
+        assert_eq!(range_index(&ranges, 100.0, 110.0), Some(1));
+        assert_eq!(range_index(&ranges, 120.0, 125.0), None);
+
+        let (min_pct, max_pct) = calculate_ladder_spacing_pct(&prices);
+        assert!((min_pct - 2.5 / 97.5 * 100.0).abs() < 1e-9);
+        assert!((max_pct - 10.0).abs() < 1e-9);
+
+        // Spread ranges are fitted so the next range starts on their last level
+        let fitted = GridRangeConfig {
+            grid_count: None,
+            spread_bips: Some(100.0),
+            grid_type: GridType::Geometric,
+            ..ranges[0].clone()
+        };
+        let prices = calculate_layered_grid_prices(&[fitted, ranges[1].clone()]);
+        assert!(prices.contains(&100.0));
+        assert_eq!(prices.last(), Some(&120.0));
+    }
+
+    #[test]
+    fn test_infinity_grid_prices() {
+        // 100 bips from 100: 100, 101, 102.01, 103.03, ...
+        let prices = calculate_infinity_grid_prices(100.0, 100.0, 102.5, 2);
+        assert_eq!(prices.len(), 6);
+        assert!(prices[2] <= 102.5 && prices[3] > 102.5);
+        // Two zones start above the entry: [103.03-104.06] and [104.06-105.10]
+        assert_eq!(prices.windows(2).filter(|z| z[0] > 102.5).count(), 2);
+
+        // Entry below the lower bound still yields a usable ladder
+        let prices = calculate_infinity_grid_prices(100.0, 100.0, 90.0, 1);
+        assert_eq!(prices.len(), 3);
+        assert_eq!(prices[0], 100.0);
+    }
 }
@@ -2,16 +2,18 @@ use crate::config::strategy::StrategyConfig;
 
 pub mod common;
 pub mod perp_grid;
+pub mod rebalance;
 pub mod spot_grid;
+pub mod twap;
 pub mod types;
 
 use crate::broadcast::types::{GridState, StrategySummary};
 use crate::engine::context::StrategyContext;
 use crate::model::{Cloid, OrderFill};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 /// Core strategy trait that all trading strategies must implement
-pub trait Strategy {
+pub trait Strategy: StrategyClone {
     /// Called on each price tick
     fn on_tick(&mut self, price: f64, ctx: &mut StrategyContext) -> Result<()>;
 
@@ -21,6 +23,12 @@ pub trait Strategy {
     /// Called when an order fails
     fn on_order_failed(&mut self, cloid: Cloid, ctx: &mut StrategyContext) -> Result<()>;
 
+    /// Called when the engine cancelled an order on its own (e.g. `cancel_all`)
+    /// Not a failure of the order; defaults to `on_order_failed`
+    fn on_order_canceled(&mut self, cloid: Cloid, ctx: &mut StrategyContext) -> Result<()> {
+        self.on_order_failed(cloid, ctx)
+    }
+
     /// Returns high-level strategy metrics for summary display
     /// Called periodically by the engine (e.g., every 1-2 seconds)
     fn get_summary(&self, ctx: &StrategyContext) -> StrategySummary;
@@ -28,6 +36,31 @@ pub trait Strategy {
     /// Returns grid zone state for dashboard visualization
     /// Called after order fills when grid state changes
     fn get_grid_state(&self, ctx: &StrategyContext) -> GridState;
+
+    /// Moves the grid to a new price range (operator command)
+    /// Strategies without a range reject it
+    fn set_range(&mut self, _low: f64, _high: f64, _ctx: &mut StrategyContext) -> Result<()> {
+        Err(anyhow!("This strategy has no price range"))
+    }
+
+    /// Applies the live-safe fields of a reloaded config (see `config::reload`)
+    /// Leaves the strategy untouched when it returns an error
+    fn reconfigure(&mut self, _config: &StrategyConfig, _ctx: &mut StrategyContext) -> Result<()> {
+        Err(anyhow!(
+            "This strategy cannot be reconfigured while running"
+        ))
+    }
+}
+
+/// Copies a boxed strategy, so a reload can be previewed on the copy
+pub trait StrategyClone {
+    fn clone_box(&self) -> Box<dyn Strategy>;
+}
+
+impl<T: Strategy + Clone + 'static> StrategyClone for T {
+    fn clone_box(&self) -> Box<dyn Strategy> {
+        Box::new(self.clone())
+    }
 }
 
 /// Initialize a strategy from configuration
@@ -36,5 +69,10 @@ pub fn init_strategy(config: StrategyConfig) -> Result<Box<dyn Strategy>> {
     match config {
         StrategyConfig::SpotGrid(c) => Ok(Box::new(spot_grid::SpotGridStrategy::new(c))),
         StrategyConfig::PerpGrid(c) => Ok(Box::new(perp_grid::PerpGridStrategy::new(c))),
+        StrategyConfig::Twap(c) => Ok(Box::new(twap::TwapStrategy::new(c))),
+        StrategyConfig::Rebalance(c) => Ok(Box::new(rebalance::RebalanceStrategy::new(c))),
+        StrategyConfig::InfinityGrid(c) => {
+            Ok(Box::new(spot_grid::SpotGridStrategy::new_infinity(c)))
+        }
     }
 }
@@ -1,18 +1,20 @@
 use crate::broadcast::types::{GridState, StrategySummary};
-use crate::config::strategy::PerpGridConfig;
+use crate::config::notification::AlertKind;
+use crate::config::strategy::{PerpGridConfig, StrategyConfig};
 
 use crate::engine::context::{MarketInfo, StrategyContext, MIN_NOTIONAL_VALUE};
 use crate::model::{Cloid, OrderFill, OrderRequest, OrderSide};
+use crate::notify::Alert;
 use crate::strategy::Strategy;
 use anyhow::{anyhow, Result};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
 use super::common;
-use super::types::{GridBias, ZoneMode};
-use crate::constants::{ACQUISITION_SPREAD, INVESTMENT_BUFFER_PERP};
+use super::types::{GridBias, SpacingMode, ZoneMode};
+use crate::constants::{ACQUISITION_SPREAD, ATR_RESPACE_MIN_CHANGE, INVESTMENT_BUFFER_PERP};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum StrategyState {
@@ -39,9 +41,19 @@ struct GridZone {
     roundtrip_count: u32,
     /// Track order failures for retry logic
     retry_count: u32,
+    /// Compounded size for the next opening order, applied when that order is placed
+    pending_size: Option<f64>,
+}
+
+impl GridZone {
+    /// Size the zone's next opening order will be placed at.
+    fn next_size(&self) -> f64 {
+        self.pending_size.unwrap_or(self.size)
+    }
 }
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct PerpGridStrategy {
     pub config: PerpGridConfig,
 
@@ -68,11 +80,29 @@ pub struct PerpGridStrategy {
     // Cached price from on_tick
     current_price: f64,
 
+    // Spacing
+    /// Effective spread: `spread_bips` from config, or derived from ATR.
+    spread_bips: Option<f64>,
+    /// Open time of the newest candle the ATR spacing was computed from.
+    atr_candle_time: Option<u64>,
+    /// Orders replaced by a re-space or resize, cancelled but possibly still filling.
+    retired_orders: HashSet<Cloid>,
+    /// Roundtrips completed by zones that were replaced by a re-space.
+    retired_roundtrips: u32,
+
+    // Compounding
+    /// Realized profit waiting to be reinvested into zone sizes.
+    compound_pool: f64,
+    /// Profit reinvested into zone sizes so far.
+    compounded_profit: f64,
+    last_compound: Instant,
+
     market_info: Option<MarketInfo>,
 }
 
 impl PerpGridStrategy {
     pub fn new(config: PerpGridConfig) -> Self {
+        let spread_bips = config.spread_bips;
         Self {
             config,
             zones: Vec::new(),
@@ -90,6 +120,13 @@ impl PerpGridStrategy {
             avg_entry_price: 0.0,
             target_position_size: 0.0,
             current_price: 0.0,
+            spread_bips,
+            atr_candle_time: None,
+            retired_orders: HashSet::new(),
+            retired_roundtrips: 0,
+            compound_pool: 0.0,
+            compounded_profit: 0.0,
+            last_compound: Instant::now(),
             market_info: None,
         }
     }
@@ -99,12 +136,31 @@ impl PerpGridStrategy {
 
         let last_price = self.current_price;
 
-        let prices = if let Some(spread) = self.config.spread_bips {
+        if self.config.spacing_mode == SpacingMode::Atr {
+            let (bips, candle_time) = self.atr_spacing(ctx).ok_or_else(|| {
+                anyhow!(
+                    "Not enough {} candle history for ATR spacing ({} candles, need {}).",
+                    self.config.atr.candle_interval,
+                    ctx.get_candles(&self.config.symbol).len(),
+                    self.config.atr.lookback + 1
+                )
+            })?;
+            info!(
+                "[PERP_GRID] ATR spacing: {:.2} bips ({} x {} candles)",
+                bips, self.config.atr.candle_interval, self.config.atr.lookback
+            );
+            self.spread_bips = Some(bips);
+            self.atr_candle_time = Some(candle_time);
+        }
+
+        let prices = if let Some(spread) = self.spread_bips {
             common::calculate_grid_prices_by_spread(
                 self.config.grid_range_low,
                 self.config.grid_range_high,
                 spread,
             )
+        } else if let Some(levels) = self.config.custom_levels() {
+            levels
         } else {
             common::calculate_grid_prices(
                 self.config.grid_type,
@@ -127,44 +183,56 @@ impl PerpGridStrategy {
             return Err(anyhow!(msg));
         }
 
-        let adjusted_investment = INVESTMENT_BUFFER_PERP.markdown(self.config.total_investment);
-
-        let size_per_zone = adjusted_investment / zone_count as f64 / last_price;
-
         if self.market_info.is_none() {
             let info = ctx
                 .market_info(&self.config.symbol)
                 .ok_or(anyhow!("No market info"))?;
             self.market_info = Some(info.clone());
         }
-
         let market_info = self.market_info.as_ref().unwrap();
-        let size = market_info.round_size(size_per_zone);
 
-        if size * last_price < MIN_NOTIONAL_VALUE {
+        let initial_price = self.config.trigger_price.unwrap_or(last_price);
+        let adjusted_investment = INVESTMENT_BUFFER_PERP.markdown(self.config.total_investment);
+        let weights = match &self.config.ranges {
+            Some(ranges) => {
+                common::layered_zone_weights(&self.config.size_distribution, ranges, initial_price)?
+            }
+            None => common::zone_weights(&self.config.size_distribution, &prices, initial_price)?,
+        };
+
+        let min_notional_per_zone =
+            weights.iter().cloned().fold(f64::INFINITY, f64::min) * adjusted_investment;
+        if min_notional_per_zone < MIN_NOTIONAL_VALUE {
             warn!(
-                "[PERP_GRID] Zone size {:.4} is very small (value < {})",
-                size, MIN_NOTIONAL_VALUE
+                "[PERP_GRID] Smallest zone notional {:.2} is below {}; it will be raised to the minimum",
+                min_notional_per_zone, MIN_NOTIONAL_VALUE
             );
         }
 
-        let num_zones = if prices.len() > 1 {
-            prices.len() - 1
-        } else {
-            0
-        };
-        let adjusted_investment = INVESTMENT_BUFFER_PERP.markdown(self.config.total_investment);
-        let investment_per_zone = adjusted_investment / num_zones as f64;
-
-        let initial_price = self.config.trigger_price.unwrap_or(last_price);
+        let zone_sizes: Vec<f64> = prices
+            .windows(2)
+            .zip(&weights)
+            .map(|(level, weight)| {
+                let mid_price = (level[0] + level[1]) / 2.0;
+                let raw_size = adjusted_investment * weight / mid_price;
+                market_info.clamp_to_min_notional(raw_size, mid_price, MIN_NOTIONAL_VALUE)
+            })
+            .collect();
+        // Min-notional clamping can push the grid past total_investment
+        let planned_notional: f64 = prices
+            .windows(2)
+            .zip(&zone_sizes)
+            .map(|(level, size)| size * (level[0] + level[1]) / 2.0)
+            .sum();
+        let required_notional = self.config.total_investment.max(planned_notional);
 
         let wallet_balance = ctx.get_perp_available("USDC");
         let max_notional = wallet_balance * self.config.leverage as f64;
 
-        if max_notional < self.config.total_investment {
+        if max_notional < required_notional {
             let msg = format!(
                 "Insufficient Margin! Balance: {:.2}, Lev: {}, Max Notional: {:.2}, Required: {:.2}. Bailing out.",
-                wallet_balance, self.config.leverage, max_notional, self.config.total_investment
+                wallet_balance, self.config.leverage, max_notional, required_notional
             );
             error!("[PERP_GRID] {}", msg);
             return Err(anyhow!(msg));
@@ -175,15 +243,9 @@ impl PerpGridStrategy {
         self.zones.clear();
         let mut total_position_required = 0.0;
 
-        for i in 0..num_zones {
+        for (i, &size) in zone_sizes.iter().enumerate() {
             let lower = prices[i];
             let upper = prices[i + 1];
-            let mid_price = (lower + upper) / 2.0;
-
-            let size = {
-                let raw_size = investment_per_zone / mid_price;
-                market_info.clamp_to_min_notional(raw_size, mid_price, MIN_NOTIONAL_VALUE)
-            };
 
             let (order_side, mode) = match self.config.grid_bias {
                 GridBias::Long => {
@@ -220,6 +282,7 @@ impl PerpGridStrategy {
                 cloid: None,
                 roundtrip_count: 0,
                 retry_count: 0,
+                pending_size: None,
             });
         }
 
@@ -416,6 +479,253 @@ impl PerpGridStrategy {
         current_price
     }
 
+    /// ATR spacing in bips and the open time of the newest candle it was computed from.
+    fn atr_spacing(&self, ctx: &StrategyContext) -> Option<(f64, u64)> {
+        let candles = ctx.get_candles(&self.config.symbol);
+        let bips = common::atr_spread_bips(candles, &self.config.atr)?;
+        Some((bips, candles.last()?.open_time))
+    }
+
+    /// Rebuilds the idle zones (those waiting to open a position, on the far side of
+    /// the zones holding one) at the latest ATR spacing.
+    fn respace_idle_zones(&mut self, ctx: &mut StrategyContext) {
+        if self.config.spacing_mode != SpacingMode::Atr || self.config.atr.recompute_secs.is_none()
+        {
+            return;
+        }
+        let Some((bips, candle_time)) = self.atr_spacing(ctx) else {
+            return;
+        };
+        if self.atr_candle_time.is_some_and(|t| candle_time <= t) {
+            return;
+        }
+        self.atr_candle_time = Some(candle_time);
+
+        let current = self.spread_bips.unwrap_or(bips);
+        if ((bips - current) / current).abs() < ATR_RESPACE_MIN_CHANGE {
+            debug!(
+                "[PERP_GRID] ATR spacing {:.2} bips within {}% of current {:.2} bips",
+                bips,
+                ATR_RESPACE_MIN_CHANGE * 100.0,
+                current
+            );
+            return;
+        }
+        self.spread_bips = Some(bips);
+
+        // Long bias opens with buys below price, short bias with sells above it
+        let (opening_side, mode) = match self.config.grid_bias {
+            GridBias::Long => (OrderSide::Buy, ZoneMode::Long),
+            GridBias::Short => (OrderSide::Sell, ZoneMode::Short),
+        };
+        let idle_count = match self.config.grid_bias {
+            GridBias::Long => self
+                .zones
+                .iter()
+                .take_while(|z| z.order_side == opening_side)
+                .count(),
+            GridBias::Short => self
+                .zones
+                .iter()
+                .rev()
+                .take_while(|z| z.order_side == opening_side)
+                .count(),
+        };
+        if idle_count == 0 {
+            info!(
+                "[PERP_GRID] ATR spacing now {:.2} bips. No idle zones to rebuild.",
+                bips
+            );
+            return;
+        }
+        let idle = match self.config.grid_bias {
+            GridBias::Long => 0..idle_count,
+            GridBias::Short => self.zones.len() - idle_count..self.zones.len(),
+        };
+
+        let market_info = self
+            .market_info
+            .as_ref()
+            .expect("Market info should be initialized");
+        let lower = self.zones[idle.start].buy_price;
+        let upper = self.zones[idle.end - 1].sell_price;
+        let budget: f64 = self.zones[idle.clone()]
+            .iter()
+            .map(|z| z.size * (z.buy_price + z.sell_price) / 2.0)
+            .sum();
+        let max_zones = (budget / MIN_NOTIONAL_VALUE).floor() as usize;
+
+        let prices = common::calculate_fitted_grid_prices(lower, upper, bips, max_zones);
+        if prices.len() < 2 {
+            warn!(
+                "[PERP_GRID] ATR re-space skipped: {:.2} USDC budget is below min notional",
+                budget
+            );
+            return;
+        }
+
+        // Idle zones sit on one side of price; the shape grows away from the edge nearest it
+        let entry = match self.config.grid_bias {
+            GridBias::Long => upper,
+            GridBias::Short => lower,
+        };
+        let weights = match common::zone_weights(&self.config.size_distribution, &prices, entry) {
+            Ok(weights) => weights,
+            Err(e) => {
+                warn!("[PERP_GRID] ATR re-space skipped: {}", e);
+                return;
+            }
+        };
+        let new_zones: Vec<GridZone> = prices
+            .windows(2)
+            .zip(weights)
+            .map(|(level, weight)| {
+                let mid_price = (level[0] + level[1]) / 2.0;
+                GridZone {
+                    index: 0,
+                    buy_price: level[0],
+                    sell_price: level[1],
+                    size: market_info.clamp_to_min_notional(
+                        budget * weight / mid_price,
+                        mid_price,
+                        MIN_NOTIONAL_VALUE,
+                    ),
+                    order_side: opening_side,
+                    mode,
+                    entry_price: 0.0,
+                    cloid: None,
+                    roundtrip_count: 0,
+                    retry_count: 0,
+                    pending_size: None,
+                }
+            })
+            .collect();
+
+        info!(
+            "[PERP_GRID] ATR spacing now {:.2} bips. Rebuilt {} idle zones as {} between {} and {}",
+            bips,
+            idle_count,
+            new_zones.len(),
+            lower,
+            upper
+        );
+
+        let retired: Vec<GridZone> = self.zones.splice(idle, new_zones).collect();
+        for zone in retired {
+            self.retired_roundtrips += zone.roundtrip_count;
+            if let Some(cloid) = zone.cloid {
+                ctx.cancel_order(cloid);
+                self.retired_orders.insert(cloid);
+            }
+        }
+
+        self.active_orders.clear();
+        for (i, zone) in self.zones.iter_mut().enumerate() {
+            zone.index = i;
+            if let Some(cloid) = zone.cloid {
+                self.active_orders.insert(cloid, i);
+            }
+        }
+    }
+
+    /// Reinvests the compounding pool into zones waiting to open. Zones with a resting
+    /// order keep it and take the new size at their next opening order.
+    fn compound_profit(&mut self, ctx: &mut StrategyContext) {
+        let Some(compound) = self.config.compound.clone() else {
+            return;
+        };
+        self.last_compound = Instant::now();
+        if self.compound_pool <= 0.0 {
+            return;
+        }
+
+        let candidates: Vec<(usize, f64, f64)> = self
+            .zones
+            .iter()
+            .enumerate()
+            .filter_map(|(i, z)| match (z.mode, z.order_side) {
+                (ZoneMode::Long, OrderSide::Buy) => {
+                    Some((i, z.buy_price, z.next_size() * z.buy_price))
+                }
+                (ZoneMode::Short, OrderSide::Sell) => {
+                    Some((i, z.sell_price, z.next_size() * z.sell_price))
+                }
+                _ => None,
+            })
+            .collect();
+        let allocation = common::allocate_compound(
+            self.compound_pool,
+            &candidates,
+            &compound,
+            self.current_price,
+        );
+
+        let market_info = self
+            .market_info
+            .as_ref()
+            .expect("Market info should be initialized");
+        let mut spent = 0.0;
+        let mut resized = Vec::new();
+        for (idx, notional) in allocation {
+            let zone = &mut self.zones[idx];
+            let price = if zone.order_side.is_buy() {
+                zone.buy_price
+            } else {
+                zone.sell_price
+            };
+            let current = zone.next_size();
+            let size = market_info.clamp_to_min_notional(
+                current + notional / price,
+                price,
+                MIN_NOTIONAL_VALUE,
+            );
+            // Amounts below one size increment stay in the pool
+            if size <= current {
+                continue;
+            }
+            spent += (size - current) * price;
+            zone.pending_size = Some(size);
+            resized.push(idx);
+        }
+        if resized.is_empty() {
+            return;
+        }
+
+        self.compound_pool -= spent;
+        self.compounded_profit += spent;
+        info!(
+            "[PERP_GRID] Compounded {:.4} USDC into {} zones. Pool: {:.4}, total compounded: {:.4}",
+            spent,
+            resized.len(),
+            self.compound_pool,
+            self.compounded_profit
+        );
+        for idx in resized {
+            self.place_zone_order(idx, ctx);
+        }
+    }
+
+    /// A zone order was cancelled by a re-space or resize but filled (fully or partly) first.
+    fn handle_retired_fill(&mut self, fill: &OrderFill) {
+        warn!(
+            "[PERP_GRID] Fill for replaced zone order: {} {} @ {}. Held outside the grid.",
+            fill.side, fill.size, fill.price
+        );
+        self.total_fees += fill.fee;
+
+        // Replaced zones only ever had opening orders resting
+        let old_pos = self.position_size;
+        if fill.side.is_buy() {
+            self.position_size += fill.size;
+        } else {
+            self.position_size -= fill.size;
+        }
+        if self.position_size.abs() > 0.0 {
+            self.avg_entry_price = (old_pos.abs() * self.avg_entry_price + fill.size * fill.price)
+                / self.position_size.abs();
+        }
+    }
+
     fn refresh_orders(&mut self, ctx: &mut StrategyContext) -> Result<()> {
         let zones_needing_orders: Vec<usize> = (0..self.zones.len())
             .filter(|&i| self.zones[i].cloid.is_none())
@@ -429,7 +739,7 @@ impl PerpGridStrategy {
 
     /// Place an order for a zone based on its current state.
     fn place_zone_order(&mut self, zone_idx: usize, ctx: &mut StrategyContext) {
-        let zone = &self.zones[zone_idx];
+        let zone = &mut self.zones[zone_idx];
 
         if zone.cloid.is_some() {
             return;
@@ -451,6 +761,11 @@ impl PerpGridStrategy {
             ZoneMode::Long => side.is_sell(), // Sell closes long
         };
 
+        if !reduce_only {
+            if let Some(size) = zone.pending_size.take() {
+                zone.size = size;
+            }
+        }
         let size = zone.size;
 
         let (rounded_price, rounded_size) = {
@@ -595,6 +910,28 @@ impl PerpGridStrategy {
 
         Ok(())
     }
+
+    /// Swaps in `config` while nothing is placed yet; the grid is re-planned on
+    /// the next tick.
+    fn replan_before_start(&mut self, config: PerpGridConfig) -> Result<()> {
+        match self.state {
+            StrategyState::Initializing | StrategyState::WaitingForTrigger => {
+                config.validate()?;
+                info!(
+                    "[PERP_GRID] Range set to {} - {}, investment {}",
+                    config.grid_range_low, config.grid_range_high, config.total_investment
+                );
+                // Nothing placed yet: re-plan on the next tick
+                self.config = config;
+                self.zones.clear();
+                self.state = StrategyState::Initializing;
+                Ok(())
+            }
+            _ => Err(anyhow!(
+                "Perp grid range and investment can only change before the grid starts. Close the position and restart with new settings."
+            )),
+        }
+    }
 }
 
 impl Strategy for PerpGridStrategy {
@@ -629,6 +966,12 @@ impl Strategy for PerpGridStrategy {
                 // Maybe check for timeout? Feature for later.
             }
             StrategyState::Running => {
+                self.respace_idle_zones(ctx);
+                if let Some(interval) = self.config.compound.as_ref().and_then(|c| c.interval()) {
+                    if self.last_compound.elapsed() >= interval {
+                        self.compound_profit(ctx);
+                    }
+                }
                 self.refresh_orders(ctx)
                     .unwrap_or_else(|e| warn!("[PERP_GRID] Failed refresh: {}", e));
             }
@@ -780,13 +1123,24 @@ impl Strategy for PerpGridStrategy {
                     (next_px, next_side, pnl)
                 };
 
-                // Accumulate realized PnL from closing fills
                 // Accumulate realized PnL from closing fills
                 if let Some(pnl) = pnl {
                     self.matched_profit += pnl;
+
+                    if let Some(compound) = &self.config.compound {
+                        self.compound_pool += pnl - fill.fee;
+                        if compound.interval_secs.is_none() {
+                            self.compound_profit(ctx);
+                        }
+                    }
                 }
 
-                self.place_counter_order(zone_idx, next_px, next_side, ctx)?;
+                // Compounding may already have placed the resized opening order
+                if self.zones[zone_idx].cloid.is_none() {
+                    self.place_counter_order(zone_idx, next_px, next_side, ctx)?;
+                }
+            } else if self.retired_orders.remove(&cloid_val) {
+                self.handle_retired_fill(fill);
             } else {
                 debug!(
                     "[PERP_GRID] Fill received for unknown/inactive Perp CLOID: {}",
@@ -802,7 +1156,7 @@ impl Strategy for PerpGridStrategy {
         Ok(())
     }
 
-    fn on_order_failed(&mut self, cloid: Cloid, _ctx: &mut StrategyContext) -> Result<()> {
+    fn on_order_failed(&mut self, cloid: Cloid, ctx: &mut StrategyContext) -> Result<()> {
         if let Some(zone_idx) = self.active_orders.remove(&cloid) {
             if let Some(zone) = self.zones.get_mut(zone_idx) {
                 if zone.cloid == Some(cloid) {
@@ -816,8 +1170,77 @@ impl Strategy for PerpGridStrategy {
                         zone.retry_count,
                         crate::constants::MAX_ORDER_RETRIES
                     );
+
+                    if zone.retry_count == crate::constants::MAX_ORDER_RETRIES {
+                        ctx.raise_alert(Alert::new(
+                            AlertKind::CircuitBreak,
+                            "Grid zone stopped",
+                            format!(
+                                "Zone {} stopped after {} failed orders",
+                                zone_idx, zone.retry_count
+                            ),
+                        ));
+                    }
                 }
             }
+        } else if self.retired_orders.remove(&cloid) {
+            debug!("[PERP_GRID] Re-spaced zone order {} closed", cloid);
+        }
+        Ok(())
+    }
+
+    fn on_order_canceled(&mut self, cloid: Cloid, _ctx: &mut StrategyContext) -> Result<()> {
+        // Re-placed on the next tick after a resume, without counting as a retry
+        if let Some(zone_idx) = self.active_orders.remove(&cloid) {
+            if let Some(zone) = self.zones.get_mut(zone_idx) {
+                if zone.cloid == Some(cloid) {
+                    zone.cloid = None;
+                    info!(
+                        "[ORDER_CANCELED] [PERP_GRID] GRID_ZONE_{} cloid: {}",
+                        zone_idx, cloid
+                    );
+                }
+            }
+        } else if self.retired_orders.remove(&cloid) {
+            debug!("[PERP_GRID] Re-spaced zone order {} closed", cloid);
+        }
+        Ok(())
+    }
+
+    fn set_range(&mut self, low: f64, high: f64, _ctx: &mut StrategyContext) -> Result<()> {
+        if self.config.custom_levels().is_some() {
+            return Err(anyhow!(
+                "set_range is not supported for grids built from levels or ranges"
+            ));
+        }
+        let mut config = self.config.clone();
+        config.grid_range_low = low;
+        config.grid_range_high = high;
+        self.replan_before_start(config)
+    }
+
+    fn reconfigure(&mut self, config: &StrategyConfig, _ctx: &mut StrategyContext) -> Result<()> {
+        let StrategyConfig::PerpGrid(new) = config else {
+            return Err(anyhow!("The perp grid only reloads a perp_grid config"));
+        };
+        let range_changed = (new.grid_range_low, new.grid_range_high)
+            != (self.config.grid_range_low, self.config.grid_range_high);
+        if range_changed && self.config.custom_levels().is_some() {
+            return Err(anyhow!(
+                "The range of a grid built from levels or ranges cannot be reloaded"
+            ));
+        }
+        let mut config = self.config.clone();
+        config.grid_range_low = new.grid_range_low;
+        config.grid_range_high = new.grid_range_high;
+        config.total_investment = new.total_investment;
+        config.trigger_price = new.trigger_price;
+        let trigger_changed = config.trigger_price != self.config.trigger_price;
+        self.replan_before_start(config)?;
+        if trigger_changed {
+            // Measured again against the new trigger on the next tick
+            self.trigger_reference_price = None;
+            self.trigger_activated = false;
         }
         Ok(())
     }
@@ -828,7 +1251,8 @@ impl Strategy for PerpGridStrategy {
         let current_price = self.current_price;
 
         // Calculate total roundtrips from zones
-        let total_roundtrips: u32 = self.zones.iter().map(|z| z.roundtrip_count).sum();
+        let total_roundtrips: u32 =
+            self.retired_roundtrips + self.zones.iter().map(|z| z.roundtrip_count).sum::<u32>();
 
         // Determine position side and calculate unrealized PnL
         let (position_side, unrealized_pnl) = if self.position_size > 0.0 {
@@ -844,16 +1268,21 @@ impl Strategy for PerpGridStrategy {
         };
 
         // Calculate grid spacing percentage
-        let grid_spacing_pct = if let Some(spread_bips) = self.config.spread_bips {
+        let grid_spacing_pct = if let Some(spread_bips) = self.spread_bips {
             let spacing = spread_bips / 100.0;
             (spacing, spacing)
-        } else {
+        } else if let Some(levels) = self.config.custom_levels() {
+            common::calculate_ladder_spacing_pct(&levels)
+        } else if let Some(grid_count) = self.config.grid_count {
             common::calculate_grid_spacing_pct(
                 &self.config.grid_type,
                 self.config.grid_range_low,
                 self.config.grid_range_high,
-                self.config.grid_count.expect("validated grid_count"),
+                grid_count,
             )
+        } else {
+            // ATR spacing before the first tick
+            (0.0, 0.0)
         };
 
         // Calculate uptime
@@ -885,29 +1314,32 @@ impl Strategy for PerpGridStrategy {
     fn get_grid_state(&self, _ctx: &StrategyContext) -> GridState {
         use crate::broadcast::types::ZoneInfo;
 
-        let zones = self
-            .zones
-            .iter()
-            .map(|z| {
-                // Frontend will derive labels. We just need reduce_only for the struct.
-                let is_reduce_only = match z.mode {
-                    ZoneMode::Short => z.order_side.is_buy(),
-                    ZoneMode::Long => z.order_side.is_sell(),
-                };
+        let zones =
+            self.zones
+                .iter()
+                .map(|z| {
+                    // Frontend will derive labels. We just need reduce_only for the struct.
+                    let is_reduce_only = match z.mode {
+                        ZoneMode::Short => z.order_side.is_buy(),
+                        ZoneMode::Long => z.order_side.is_sell(),
+                    };
 
-                ZoneInfo {
-                    index: z.index,
-                    buy_price: z.buy_price,
-                    sell_price: z.sell_price,
-                    size: z.size,
-                    order_side: z.order_side.to_string(),
-                    has_order: z.cloid.is_some(),
-                    is_reduce_only,
-                    entry_price: z.entry_price,
-                    roundtrip_count: z.roundtrip_count,
-                }
-            })
-            .collect();
+                    ZoneInfo {
+                        index: z.index,
+                        buy_price: z.buy_price,
+                        sell_price: z.sell_price,
+                        size: z.size,
+                        order_side: z.order_side.to_string(),
+                        has_order: z.cloid.is_some(),
+                        is_reduce_only,
+                        entry_price: z.entry_price,
+                        roundtrip_count: z.roundtrip_count,
+                        range_index: self.config.ranges.as_deref().and_then(|ranges| {
+                            common::range_index(ranges, z.buy_price, z.sell_price)
+                        }),
+                    }
+                })
+                .collect();
 
         GridState {
             symbol: self.config.symbol.clone(),
@@ -921,8 +1353,12 @@ impl Strategy for PerpGridStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::strategy::PerpGridConfig;
+    use crate::config::strategy::{
+        AtrSpacingConfig, CompoundAllocation, CompoundConfig, DistributionShape, GridRangeConfig,
+        PerpGridConfig, SizeDistribution,
+    };
     use crate::engine::context::{MarketInfo, StrategyContext};
+    use crate::model::Candle;
     use crate::strategy::types::GridType;
     use std::collections::HashMap;
 
@@ -946,11 +1382,17 @@ mod tests {
             symbol: symbol.to_string(),
             leverage: 10,
             is_isolated: true,
-            grid_range_high: grid_range_high,
-            grid_range_low: grid_range_low,
+            grid_range_high,
+            grid_range_low,
             grid_type: GridType::Arithmetic,
             grid_count: Some(3),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             grid_bias,
             trigger_price,
@@ -966,8 +1408,8 @@ mod tests {
             symbol.to_string(),
             MarketInfo::new(symbol.to_string(), "HYPE".to_string(), 0, 2, 2),
         );
-        let ctx = StrategyContext::new(markets);
-        ctx
+
+        StrategyContext::new(markets)
     }
 
     #[test]
@@ -998,6 +1440,127 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_perp_grid_pyramid_size_distribution() {
+        let symbol = "HYPE".to_string();
+        let (mut strategy, mut ctx) =
+            create_test_setup(&symbol, GridBias::Long, None, 100.0, 90.0, 110.0);
+        strategy.config.grid_count = Some(5);
+        strategy.config.size_distribution = SizeDistribution::Shape(DistributionShape::Pyramid);
+
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+
+        // Zones [90-95] [95-100] [100-105] [105-110] around 100 weigh 4:1:1:4
+        let notional = |z: &GridZone| z.size * (z.buy_price + z.sell_price) / 2.0;
+        assert!((notional(&strategy.zones[0]) / notional(&strategy.zones[1]) - 4.0).abs() < 0.05);
+        assert!((notional(&strategy.zones[3]) / notional(&strategy.zones[2]) - 4.0).abs() < 0.05);
+        // Only [105-110] starts above price and needs an open long
+        assert_eq!(strategy.target_position_size, strategy.zones[3].size);
+    }
+
+    #[test]
+    fn test_perp_grid_margin_check_includes_min_notional_clamp() {
+        let symbol = "HYPE".to_string();
+        let (mut strategy, mut ctx) =
+            create_test_setup(&symbol, GridBias::Long, None, 100.0, 90.0, 110.0);
+        strategy.config.grid_count = Some(5);
+        strategy.config.total_investment = 60.0;
+        strategy.config.size_distribution = SizeDistribution::Shape(DistributionShape::Pyramid);
+        // 65 USDC of notional covers total_investment, but the inner zones are
+        // raised to the minimum order value and push the grid past it.
+        ctx.update_perp_balance("USDC".to_string(), 6.5, 6.5);
+
+        assert!(strategy.on_tick(100.0, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_perp_grid_set_range_before_start_only() {
+        let (mut strategy, mut ctx) =
+            create_test_setup("HYPE", GridBias::Long, Some(120.0), 100.0, 90.0, 130.0);
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::WaitingForTrigger));
+
+        strategy.set_range(95.0, 125.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::Initializing));
+        assert_eq!(strategy.config.grid_range_low, 95.0);
+        assert!(strategy.zones.is_empty());
+        // The trigger must stay inside the range
+        assert!(strategy.set_range(95.0, 110.0, &mut ctx).is_err());
+
+        strategy.state = StrategyState::Running;
+        assert!(strategy.set_range(90.0, 130.0, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_perp_grid_reconfigure_resets_trigger_before_start() {
+        let (mut strategy, mut ctx) =
+            create_test_setup("HYPE", GridBias::Long, Some(120.0), 100.0, 90.0, 130.0);
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::WaitingForTrigger));
+        assert_eq!(strategy.trigger_reference_price, Some(100.0));
+
+        let mut config = strategy.config.clone();
+        config.trigger_price = Some(110.0);
+        config.total_investment = 2000.0;
+        strategy
+            .reconfigure(&StrategyConfig::PerpGrid(config.clone()), &mut ctx)
+            .unwrap();
+        assert!(matches!(strategy.state, StrategyState::Initializing));
+        assert_eq!(strategy.config.trigger_price, Some(110.0));
+        assert_eq!(strategy.config.total_investment, 2000.0);
+        assert_eq!(strategy.trigger_reference_price, None);
+
+        strategy.state = StrategyState::Running;
+        config.total_investment = 3000.0;
+        assert!(strategy
+            .reconfigure(&StrategyConfig::PerpGrid(config), &mut ctx)
+            .is_err());
+        assert_eq!(strategy.config.total_investment, 2000.0);
+    }
+
+    #[test]
+    fn test_perp_grid_layered_ranges() {
+        let symbol = "HYPE".to_string();
+        let (strategy, mut ctx) =
+            create_test_setup(&symbol, GridBias::Long, None, 100.0, 90.0, 110.0);
+        let config = PerpGridConfig {
+            grid_count: None,
+            ranges: Some(vec![
+                GridRangeConfig {
+                    low: 90.0,
+                    high: 100.0,
+                    grid_type: GridType::Arithmetic,
+                    grid_count: Some(5),
+                    spread_bips: None,
+                    investment: 800.0,
+                },
+                GridRangeConfig {
+                    low: 100.0,
+                    high: 110.0,
+                    grid_type: GridType::Arithmetic,
+                    grid_count: Some(2),
+                    spread_bips: None,
+                    investment: 200.0,
+                },
+            ]),
+            ..strategy.config.clone()
+        };
+        let mut strategy = PerpGridStrategy::new(config);
+
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+
+        // Four dense zones below 100 share 800, one wide zone above gets 200
+        assert_eq!(strategy.zones.len(), 5);
+        let notional = |z: &GridZone| z.size * (z.buy_price + z.sell_price) / 2.0;
+        assert!((notional(&strategy.zones[0]) - 199.9).abs() < 1.0);
+        assert!((notional(&strategy.zones[4]) - 199.9).abs() < 1.0);
+        assert_eq!(strategy.zones[4].buy_price, 100.0);
+
+        let grid_state = strategy.get_grid_state(&ctx);
+        let ranges: Vec<Option<usize>> = grid_state.zones.iter().map(|z| z.range_index).collect();
+        assert_eq!(ranges, vec![Some(0), Some(0), Some(0), Some(0), Some(1)]);
+    }
+
     #[test]
     fn test_perp_trigger_buy_below_uses_market_price_not_trigger() {
         let symbol = "HYPE".to_string();
@@ -1120,6 +1683,12 @@ mod tests {
             grid_bias: GridBias::Long,
             trigger_price: None,
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
         };
 
         let mut strategy = PerpGridStrategy::new(config);
@@ -1221,6 +1790,12 @@ mod tests {
             grid_bias: GridBias::Long,
             trigger_price: None,
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
         };
 
         let mut strategy = PerpGridStrategy::new(config);
@@ -1638,6 +2213,12 @@ mod tests {
             grid_bias: GridBias::Long,
             trigger_price: None,
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
         };
 
         let mut strategy = PerpGridStrategy::new(config);
@@ -1713,6 +2294,12 @@ mod tests {
             grid_bias: GridBias::Short,
             trigger_price: None,
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
         };
 
         let mut strategy = PerpGridStrategy::new(config);
@@ -1752,4 +2339,136 @@ mod tests {
             "Zone [90-95] at price boundary should be Sell, not Buy"
         );
     }
+
+    #[test]
+    fn test_perp_grid_atr_respace_short_bias() {
+        let symbol = "HYPE";
+        let (mut strategy, mut ctx) =
+            create_test_setup(symbol, GridBias::Short, None, 95.0, 100.0, 120.0);
+        strategy.config.grid_type = GridType::Geometric;
+        strategy.config.grid_count = None;
+        strategy.config.spacing_mode = SpacingMode::Atr;
+        strategy.config.atr = AtrSpacingConfig {
+            lookback: 3,
+            recompute_secs: Some(3600),
+            ..AtrSpacingConfig::default()
+        };
+
+        let candles = |start: u64, range: f64| -> Vec<Candle> {
+            (start..start + 4)
+                .map(|i| Candle {
+                    open_time: i * 3_600_000,
+                    open: 100.0,
+                    high: 100.0 + range / 2.0,
+                    low: 100.0 - range / 2.0,
+                    close: 100.0,
+                })
+                .collect()
+        };
+
+        // 200 bips: every zone sits above price and opens a short
+        ctx.set_candles(symbol, candles(0, 2.0));
+        strategy.on_tick(95.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::Running));
+        assert_eq!(strategy.spread_bips, Some(200.0));
+        let old: Vec<Cloid> = strategy.zones.iter().filter_map(|z| z.cloid).collect();
+        assert_eq!(old.len(), strategy.zones.len());
+        assert!(strategy
+            .zones
+            .iter()
+            .all(|z| z.order_side == OrderSide::Sell && z.mode == ZoneMode::Short));
+        let zone_count = strategy.zones.len();
+        let upper = strategy.zones[zone_count - 1].sell_price;
+        ctx.order_queue.clear();
+
+        // 400 bips: the whole idle block is rebuilt with fewer, wider zones
+        ctx.set_candles(symbol, candles(4, 4.0));
+        strategy.on_tick(95.0, &mut ctx).unwrap();
+        assert_eq!(strategy.spread_bips, Some(400.0));
+        assert_eq!(ctx.cancellation_queue, old);
+        assert!(strategy.zones.len() < zone_count);
+        assert_eq!(strategy.zones[0].buy_price, 100.0);
+        assert_eq!(strategy.zones.last().unwrap().sell_price, upper);
+        assert!(strategy
+            .zones
+            .iter()
+            .all(|z| z.order_side == OrderSide::Sell && z.mode == ZoneMode::Short));
+        assert_eq!(ctx.order_queue.len(), strategy.zones.len());
+        for (i, zone) in strategy.zones.iter().enumerate() {
+            assert_eq!(strategy.active_orders.get(&zone.cloid.unwrap()), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_perp_grid_compounds_into_nearest_zone() {
+        let symbol = "HYPE";
+        let (mut strategy, mut ctx) =
+            create_test_setup(symbol, GridBias::Long, None, 115.0, 90.0, 110.0);
+        strategy.config.compound = Some(CompoundConfig {
+            allocation: CompoundAllocation::Nearest,
+            nearest_zones: 1,
+            interval_secs: None,
+        });
+
+        // Price above the range: both zones wait to open longs
+        strategy.on_tick(115.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::Running));
+        let size_0 = strategy.zones[0].size;
+        let size_1 = strategy.zones[1].size;
+        let zone_1_cloid = strategy.zones[1].cloid.unwrap();
+
+        let fill = |cloid, side, price, size| OrderFill {
+            side,
+            size,
+            price,
+            fee: 0.0,
+            cloid: Some(cloid),
+            reduce_only: None,
+            raw_dir: None,
+        };
+        let open = strategy.zones[0].cloid.unwrap();
+        strategy
+            .on_order_filled(&fill(open, OrderSide::Buy, 90.0, size_0), &mut ctx)
+            .unwrap();
+        let close = strategy.zones[0].cloid.unwrap();
+        strategy
+            .on_order_filled(&fill(close, OrderSide::Sell, 100.0, size_0), &mut ctx)
+            .unwrap();
+
+        // Profit goes to the opening zone closest to 115 (zone 1), whose resting order stays
+        let pnl = 10.0 * size_0;
+        let pending = strategy.zones[1].pending_size.unwrap();
+        assert_eq!(strategy.zones[1].size, size_1);
+        assert!(pending > size_1);
+        assert!((pending - (size_1 + pnl / 100.0)).abs() <= 0.005 + 1e-9);
+        assert!(ctx.cancellation_queue.is_empty());
+        assert!(strategy.retired_orders.is_empty());
+        assert_eq!(strategy.zones[1].cloid, Some(zone_1_cloid));
+        assert!((strategy.compounded_profit + strategy.compound_pool - pnl).abs() < 1e-9);
+
+        // Zone 0 reopens at its original size with exactly one order
+        assert_eq!(strategy.zones[0].size, size_0);
+        assert_eq!(strategy.zones[0].order_side, OrderSide::Buy);
+        let reopen = strategy.zones[0].cloid.unwrap();
+        assert_eq!(strategy.active_orders.get(&reopen), Some(&0));
+        assert_eq!(strategy.active_orders.get(&zone_1_cloid), Some(&1));
+        assert_eq!(strategy.active_orders.len(), 2);
+
+        // Zone 1 opens and closes at its old size, then reopens at the compounded one
+        strategy
+            .on_order_filled(&fill(zone_1_cloid, OrderSide::Buy, 100.0, size_1), &mut ctx)
+            .unwrap();
+        assert_eq!(strategy.zones[1].size, size_1);
+        let close = strategy.zones[1].cloid.unwrap();
+        strategy
+            .on_order_filled(&fill(close, OrderSide::Sell, 110.0, size_1), &mut ctx)
+            .unwrap();
+        let reopen = strategy.zones[1].cloid.unwrap();
+        assert!(strategy.zones[1].size >= pending);
+        let placed = ctx.order_queue.iter().any(|o| {
+            matches!(o, OrderRequest::Limit { cloid: Some(c), sz, reduce_only: false, .. }
+                if *c == reopen && *sz == strategy.zones[1].size)
+        });
+        assert!(placed);
+    }
 }
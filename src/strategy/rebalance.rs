@@ -0,0 +1,639 @@
+use super::common;
+
+use crate::broadcast::types::{GridState, StrategySummary};
+use crate::config::strategy::RebalanceConfig;
+
+use crate::constants::{FEE_BUFFER, REBALANCE_COOLDOWN};
+use crate::engine::context::{StrategyContext, MIN_NOTIONAL_VALUE};
+use crate::model::{Cloid, OrderFill, OrderRequest, OrderSide};
+use crate::strategy::Strategy;
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StrategyState {
+    Initializing,
+    /// Watching drift and the schedule.
+    Monitoring,
+    /// Reducing overweight assets. Buys wait for the proceeds.
+    Selling,
+    /// Topping up underweight assets.
+    Buying,
+    /// Waiting for balances to refresh after a rebalance.
+    Cooldown,
+}
+
+#[derive(Debug, Clone)]
+struct RebalanceOrder {
+    asset: String,
+    side: OrderSide,
+    size: f64,
+    placed_at: Instant,
+    cancel_requested: bool,
+}
+
+/// Holding of one target asset valued in the quote asset.
+#[derive(Debug, Clone)]
+struct Position {
+    asset: String,
+    balance: f64,
+    price: f64,
+}
+
+impl Position {
+    fn value(&self) -> f64 {
+        self.balance * self.price
+    }
+}
+
+#[derive(Clone)]
+pub struct RebalanceStrategy {
+    pub config: RebalanceConfig,
+    targets: BTreeMap<String, f64>,
+
+    state: StrategyState,
+    start_time: Instant,
+    order_timeout: Duration,
+    next_scheduled: Option<Instant>,
+    cooldown_until: Option<Instant>,
+
+    active_orders: HashMap<Cloid, RebalanceOrder>,
+    /// Balances captured when a rebalance starts, adjusted by fills until it ends.
+    holdings: HashMap<String, f64>,
+
+    rebalance_count: u32,
+    traded_notional: f64,
+    total_fees: f64,
+}
+
+impl RebalanceStrategy {
+    pub fn new(config: RebalanceConfig) -> Self {
+        let targets = config.normalized_targets();
+        let order_timeout = Duration::from_secs(config.order_timeout_secs);
+
+        Self {
+            config,
+            targets,
+            state: StrategyState::Initializing,
+            start_time: Instant::now(),
+            order_timeout,
+            next_scheduled: None,
+            cooldown_until: None,
+            active_orders: HashMap::new(),
+            holdings: HashMap::new(),
+            rebalance_count: 0,
+            traded_notional: 0.0,
+            total_fees: 0.0,
+        }
+    }
+
+    fn market_symbol(&self, asset: &str) -> String {
+        format!("{}/{}", asset, self.config.quote_asset)
+    }
+
+    fn target_weight(&self, asset: &str) -> f64 {
+        self.targets.get(asset).copied().unwrap_or(0.0)
+    }
+
+    /// Values every target asset (plus the quote asset) at live mids.
+    /// Returns `None` until a mid is known for every market.
+    fn positions(
+        &self,
+        ctx: &StrategyContext,
+        balance_of: impl Fn(&str) -> f64,
+    ) -> Option<Vec<Position>> {
+        let mut assets: Vec<String> = self.targets.keys().cloned().collect();
+        if !self.targets.contains_key(&self.config.quote_asset) {
+            assets.push(self.config.quote_asset.clone());
+        }
+
+        assets
+            .into_iter()
+            .map(|asset| {
+                let price = if asset == self.config.quote_asset {
+                    1.0
+                } else {
+                    ctx.get_mid(&self.market_symbol(&asset))?
+                };
+                Some(Position {
+                    balance: balance_of(&asset),
+                    asset,
+                    price,
+                })
+            })
+            .collect()
+    }
+
+    fn live_positions(&self, ctx: &StrategyContext) -> Option<Vec<Position>> {
+        self.positions(ctx, |asset| ctx.get_spot_total(asset))
+    }
+
+    fn snapshot_positions(&self, ctx: &StrategyContext) -> Option<Vec<Position>> {
+        self.positions(ctx, |asset| {
+            self.holdings.get(asset).copied().unwrap_or_default()
+        })
+    }
+
+    /// Largest absolute gap between current and target weight, in percentage points.
+    fn max_drift_pct(&self, positions: &[Position]) -> f64 {
+        let total: f64 = positions.iter().map(Position::value).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        positions
+            .iter()
+            .map(|p| ((p.value() / total) - self.target_weight(&p.asset)).abs() * 100.0)
+            .fold(0.0, f64::max)
+    }
+
+    fn post_only_price(&self, side: OrderSide, mid: f64) -> f64 {
+        let offset = self.config.post_only_offset_bips / 10_000.0;
+        match side {
+            OrderSide::Buy => mid * (1.0 - offset),
+            OrderSide::Sell => mid * (1.0 + offset),
+        }
+    }
+
+    fn start_rebalance(&mut self, reason: &str, now: Instant, ctx: &mut StrategyContext) {
+        self.holdings = self
+            .targets
+            .keys()
+            .chain(std::iter::once(&self.config.quote_asset))
+            .map(|asset| (asset.clone(), ctx.get_spot_total(asset)))
+            .collect();
+
+        if let Some(interval) = self.config.rebalance_interval_secs {
+            self.next_scheduled = Some(now + Duration::from_secs(interval));
+        }
+
+        if self.place_phase_orders(OrderSide::Sell, now, ctx) > 0 {
+            info!(
+                "[REBALANCE] Starting rebalance ({}): selling overweight assets",
+                reason
+            );
+            self.rebalance_count += 1;
+            self.state = StrategyState::Selling;
+        } else if self.place_phase_orders(OrderSide::Buy, now, ctx) > 0 {
+            info!(
+                "[REBALANCE] Starting rebalance ({}): buying underweight assets",
+                reason
+            );
+            self.rebalance_count += 1;
+            self.state = StrategyState::Buying;
+        } else {
+            debug!(
+                "[REBALANCE] Rebalance due ({}) but every adjustment is below min notional.",
+                reason
+            );
+        }
+    }
+
+    /// Queues the sells or buys needed to reach target weights. Returns the number placed.
+    fn place_phase_orders(
+        &mut self,
+        side: OrderSide,
+        now: Instant,
+        ctx: &mut StrategyContext,
+    ) -> usize {
+        let Some(positions) = self.snapshot_positions(ctx) else {
+            return 0;
+        };
+        let total: f64 = positions.iter().map(Position::value).sum();
+        if total <= 0.0 {
+            return 0;
+        }
+
+        // (asset, mid, value to trade in quote terms)
+        let mut legs: Vec<(String, f64, f64)> = positions
+            .iter()
+            .filter(|p| p.asset != self.config.quote_asset)
+            .filter_map(|p| {
+                let delta = self.target_weight(&p.asset) * total - p.value();
+                match side {
+                    OrderSide::Sell if delta < 0.0 => Some((p.asset.clone(), p.price, -delta)),
+                    OrderSide::Buy if delta > 0.0 => Some((p.asset.clone(), p.price, delta)),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        if side.is_buy() {
+            // Never spend more quote than the sells (or existing balance) provided
+            let available = FEE_BUFFER.markdown(
+                self.holdings
+                    .get(&self.config.quote_asset)
+                    .copied()
+                    .unwrap_or_default(),
+            );
+            let wanted: f64 = legs.iter().map(|(_, _, value)| value).sum();
+            if wanted > available && wanted > 0.0 {
+                let scale = (available / wanted).max(0.0);
+                warn!(
+                    "[REBALANCE] Buys need {:.2} {} but only {:.2} available. Scaling by {:.3}.",
+                    wanted, self.config.quote_asset, available, scale
+                );
+                for leg in legs.iter_mut() {
+                    leg.2 *= scale;
+                }
+            }
+        }
+
+        let mut placed = 0;
+        for (asset, mid, value) in legs {
+            let symbol = self.market_symbol(&asset);
+            let Some(info) = ctx.market_info(&symbol) else {
+                warn!("[REBALANCE] No market info for {}", symbol);
+                continue;
+            };
+
+            let price = info.round_price(self.post_only_price(side, mid));
+            let mut size = info.round_size(value / mid);
+            if side.is_sell() {
+                let held = self.holdings.get(&asset).copied().unwrap_or_default();
+                size = size.min(info.round_size(FEE_BUFFER.markdown(held)));
+            }
+            if size <= 0.0 || size * price < MIN_NOTIONAL_VALUE {
+                debug!(
+                    "[REBALANCE] Skipping {} {} {}: below min notional",
+                    side, size, symbol
+                );
+                continue;
+            }
+
+            let cloid = ctx.place_order(OrderRequest::Limit {
+                symbol: symbol.clone(),
+                side,
+                price,
+                sz: size,
+                reduce_only: false,
+                cloid: None,
+            });
+            info!("[REBALANCE] {} {} {} @ {}", side, size, symbol, price);
+
+            self.active_orders.insert(
+                cloid,
+                RebalanceOrder {
+                    asset,
+                    side,
+                    size,
+                    placed_at: now,
+                    cancel_requested: false,
+                },
+            );
+            placed += 1;
+        }
+        placed
+    }
+
+    fn cancel_stale_orders(&mut self, now: Instant, ctx: &mut StrategyContext) {
+        for (cloid, order) in self.active_orders.iter_mut() {
+            if !order.cancel_requested && now.duration_since(order.placed_at) >= self.order_timeout
+            {
+                info!(
+                    "[REBALANCE] {} {} {} unfilled after {:?}. Cancelling.",
+                    order.side, order.size, order.asset, self.order_timeout
+                );
+                ctx.cancel_order(*cloid);
+                order.cancel_requested = true;
+            }
+        }
+    }
+
+    /// Moves to the next phase once every order of the current one has resolved.
+    fn advance_phase(&mut self, ctx: &mut StrategyContext) {
+        if !self.active_orders.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+
+        if self.state == StrategyState::Selling
+            && self.place_phase_orders(OrderSide::Buy, now, ctx) > 0
+        {
+            self.state = StrategyState::Buying;
+            return;
+        }
+
+        info!("[REBALANCE] Rebalance #{} complete.", self.rebalance_count);
+        self.state = StrategyState::Cooldown;
+        self.cooldown_until = Some(now + REBALANCE_COOLDOWN);
+    }
+
+    fn tick_at(&mut self, now: Instant, ctx: &mut StrategyContext) -> Result<()> {
+        match self.state {
+            StrategyState::Initializing => {
+                self.config.validate().map_err(|e| anyhow!(e))?;
+                for symbol in self.config.markets() {
+                    if ctx.market_info(&symbol).is_none() {
+                        return Err(anyhow!("No market info for {}", symbol));
+                    }
+                }
+                if let Some(interval) = self.config.rebalance_interval_secs {
+                    self.next_scheduled = Some(now + Duration::from_secs(interval));
+                }
+                info!(
+                    "[REBALANCE] Monitoring {} assets against {} (band {:?}%, schedule {:?}s)",
+                    self.targets.len(),
+                    self.config.quote_asset,
+                    self.config.drift_band_pct,
+                    self.config.rebalance_interval_secs
+                );
+                self.state = StrategyState::Monitoring;
+                self.check_drift(now, ctx);
+            }
+            StrategyState::Monitoring => self.check_drift(now, ctx),
+            StrategyState::Selling | StrategyState::Buying => self.cancel_stale_orders(now, ctx),
+            StrategyState::Cooldown => {
+                if self.cooldown_until.is_none_or(|until| now >= until) {
+                    self.state = StrategyState::Monitoring;
+                    self.check_drift(now, ctx);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_drift(&mut self, now: Instant, ctx: &mut StrategyContext) {
+        let Some(positions) = self.live_positions(ctx) else {
+            return;
+        };
+        let drift = self.max_drift_pct(&positions);
+
+        if self.config.drift_band_pct.is_some_and(|band| drift >= band) {
+            self.start_rebalance(&format!("drift {:.2}%", drift), now, ctx);
+        } else if self.next_scheduled.is_some_and(|at| now >= at) {
+            self.start_rebalance("schedule", now, ctx);
+        }
+    }
+}
+
+impl Strategy for RebalanceStrategy {
+    fn on_tick(&mut self, _price: f64, ctx: &mut StrategyContext) -> Result<()> {
+        self.tick_at(Instant::now(), ctx)
+    }
+
+    fn on_order_filled(&mut self, fill: &OrderFill, ctx: &mut StrategyContext) -> Result<()> {
+        let Some(order) = fill.cloid.and_then(|c| self.active_orders.remove(&c)) else {
+            debug!("[REBALANCE] Fill for unknown CLOID: {:?}", fill.cloid);
+            return Ok(());
+        };
+
+        let notional = fill.size * fill.price;
+        let quote = self.config.quote_asset.clone();
+        let (asset_delta, quote_delta) = match order.side {
+            OrderSide::Buy => (fill.size, -notional),
+            OrderSide::Sell => (-fill.size, notional),
+        };
+        *self.holdings.entry(order.asset.clone()).or_default() += asset_delta;
+        *self.holdings.entry(quote).or_default() += quote_delta - fill.fee;
+
+        self.traded_notional += notional;
+        self.total_fees += fill.fee;
+        info!(
+            "[REBALANCE] Filled {} {} {} @ {}",
+            order.side, fill.size, order.asset, fill.price
+        );
+
+        self.advance_phase(ctx);
+        Ok(())
+    }
+
+    fn on_order_failed(&mut self, cloid: Cloid, ctx: &mut StrategyContext) -> Result<()> {
+        if let Some(order) = self.active_orders.remove(&cloid) {
+            warn!(
+                "[REBALANCE] {} {} {} did not fill ({}).",
+                order.side, order.size, order.asset, cloid
+            );
+            self.advance_phase(ctx);
+        }
+        Ok(())
+    }
+
+    fn get_summary(&self, ctx: &StrategyContext) -> StrategySummary {
+        use crate::broadcast::types::{AssetAllocation, RebalanceSummary};
+
+        let positions = self.live_positions(ctx).unwrap_or_default();
+        let total: f64 = positions.iter().map(Position::value).sum();
+        let allocations = positions
+            .iter()
+            .map(|p| AssetAllocation {
+                asset: p.asset.clone(),
+                balance: p.balance,
+                price: p.price,
+                value: p.value(),
+                current_weight_pct: if total > 0.0 {
+                    p.value() / total * 100.0
+                } else {
+                    0.0
+                },
+                target_weight_pct: self.target_weight(&p.asset) * 100.0,
+            })
+            .collect();
+
+        StrategySummary::Rebalance(RebalanceSummary {
+            quote_asset: self.config.quote_asset.clone(),
+            state: format!("{:?}", self.state),
+            uptime: common::format_uptime(self.start_time.elapsed()),
+            portfolio_value: total,
+            max_drift_pct: self.max_drift_pct(&positions),
+            rebalance_count: self.rebalance_count,
+            traded_notional: self.traded_notional,
+            total_fees: self.total_fees,
+            open_orders: self.active_orders.len() as u32,
+            allocations,
+        })
+    }
+
+    fn get_grid_state(&self, _ctx: &StrategyContext) -> GridState {
+        GridState {
+            symbol: self.config.quote_asset.clone(),
+            strategy_type: "rebalance".to_string(),
+            grid_bias: None,
+            zones: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::context::MarketInfo;
+
+    fn create_test_setup(
+        drift_band_pct: Option<f64>,
+        rebalance_interval_secs: Option<u64>,
+    ) -> (RebalanceStrategy, StrategyContext) {
+        let config = RebalanceConfig {
+            quote_asset: "USDC".to_string(),
+            targets: BTreeMap::from([
+                ("HYPE".to_string(), 50.0),
+                ("PURR".to_string(), 20.0),
+                ("USDC".to_string(), 30.0),
+            ]),
+            drift_band_pct,
+            rebalance_interval_secs,
+            post_only_offset_bips: 10.0,
+            order_timeout_secs: 60,
+        };
+
+        let mut markets = HashMap::new();
+        markets.insert(
+            "HYPE/USDC".to_string(),
+            MarketInfo::new("HYPE/USDC".to_string(), "@107".to_string(), 0, 2, 4),
+        );
+        markets.insert(
+            "PURR/USDC".to_string(),
+            MarketInfo::new("PURR/USDC".to_string(), "PURR/USDC".to_string(), 1, 0, 6),
+        );
+        let mut ctx = StrategyContext::new(markets);
+        ctx.update_mid("HYPE/USDC", 20.0);
+        ctx.update_mid("PURR/USDC", 0.2);
+
+        (RebalanceStrategy::new(config), ctx)
+    }
+
+    fn set_balances(ctx: &mut StrategyContext, hype: f64, purr: f64, usdc: f64) {
+        ctx.update_spot_balance("HYPE".to_string(), hype, hype);
+        ctx.update_spot_balance("PURR".to_string(), purr, purr);
+        ctx.update_spot_balance("USDC".to_string(), usdc, usdc);
+    }
+
+    fn drain_orders(ctx: &mut StrategyContext) -> Vec<(String, OrderSide, f64, f64, Cloid)> {
+        ctx.order_queue
+            .drain(..)
+            .map(|order| match order {
+                OrderRequest::Limit {
+                    symbol,
+                    side,
+                    price,
+                    sz,
+                    cloid,
+                    ..
+                } => (symbol, side, price, sz, cloid.unwrap()),
+                other => panic!("Expected limit order, got {:?}", other),
+            })
+            .collect()
+    }
+
+    fn fill(cloid: Cloid, side: OrderSide, size: f64, price: f64) -> OrderFill {
+        OrderFill {
+            side,
+            size,
+            price,
+            fee: 0.0,
+            cloid: Some(cloid),
+            reduce_only: None,
+            raw_dir: None,
+        }
+    }
+
+    #[test]
+    fn test_rebalance_within_band_places_nothing() {
+        let (mut strategy, mut ctx) = create_test_setup(Some(5.0), None);
+        // 1000 USDC portfolio: HYPE 510 (51%), PURR 190 (19%), USDC 300 (30%)
+        set_balances(&mut ctx, 25.5, 950.0, 300.0);
+
+        strategy.tick_at(Instant::now(), &mut ctx).unwrap();
+
+        assert_eq!(strategy.state, StrategyState::Monitoring);
+        assert!(ctx.order_queue.is_empty());
+    }
+
+    #[test]
+    fn test_rebalance_drift_sells_before_buying() {
+        let (mut strategy, mut ctx) = create_test_setup(Some(5.0), None);
+        // 1000 USDC portfolio: HYPE 700 (70%), PURR 100 (10%), USDC 200 (20%)
+        set_balances(&mut ctx, 35.0, 500.0, 200.0);
+
+        strategy.tick_at(Instant::now(), &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Selling);
+
+        let sells = drain_orders(&mut ctx);
+        assert_eq!(sells.len(), 1);
+        let (symbol, side, price, size, cloid) = sells[0].clone();
+        assert_eq!(symbol, "HYPE/USDC");
+        assert_eq!(side, OrderSide::Sell);
+        // Post-only: 10 bips above mid
+        assert_eq!(price, 20.02);
+        // 200 USDC overweight at mid 20
+        assert_eq!(size, 10.0);
+
+        strategy
+            .on_order_filled(&fill(cloid, OrderSide::Sell, 10.0, 20.02), &mut ctx)
+            .unwrap();
+        assert_eq!(strategy.state, StrategyState::Buying);
+
+        let buys = drain_orders(&mut ctx);
+        assert_eq!(buys.len(), 1);
+        let (symbol, side, price, size, cloid) = buys[0].clone();
+        assert_eq!(symbol, "PURR/USDC");
+        assert_eq!(side, OrderSide::Buy);
+        assert_eq!(price, 0.1998);
+        // ~100 USDC underweight at mid 0.2
+        assert_eq!(size, 500.0);
+
+        strategy
+            .on_order_filled(&fill(cloid, OrderSide::Buy, 500.0, 0.1998), &mut ctx)
+            .unwrap();
+        assert_eq!(strategy.state, StrategyState::Cooldown);
+        assert_eq!(strategy.rebalance_count, 1);
+    }
+
+    #[test]
+    fn test_rebalance_schedule_triggers_inside_band() {
+        let (mut strategy, mut ctx) = create_test_setup(None, Some(3600));
+        // HYPE 540 (54%), PURR 160 (16%), USDC 300 (30%)
+        set_balances(&mut ctx, 27.0, 800.0, 300.0);
+        let t0 = Instant::now();
+
+        strategy.tick_at(t0, &mut ctx).unwrap();
+        assert!(ctx.order_queue.is_empty());
+
+        strategy
+            .tick_at(t0 + Duration::from_secs(3600), &mut ctx)
+            .unwrap();
+        let orders = drain_orders(&mut ctx);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].0, "HYPE/USDC");
+        assert_eq!(orders[0].1, OrderSide::Sell);
+    }
+
+    #[test]
+    fn test_rebalance_cancels_stale_orders() {
+        let (mut strategy, mut ctx) = create_test_setup(Some(5.0), None);
+        set_balances(&mut ctx, 35.0, 500.0, 200.0);
+        let t0 = Instant::now();
+
+        strategy.tick_at(t0, &mut ctx).unwrap();
+        let (_, _, _, _, cloid) = drain_orders(&mut ctx)[0].clone();
+
+        strategy
+            .tick_at(t0 + Duration::from_secs(61), &mut ctx)
+            .unwrap();
+        assert_eq!(ctx.cancellation_queue, vec![cloid]);
+
+        // Cancel confirmed with no fill: the buy phase works from unchanged holdings
+        strategy.on_order_failed(cloid, &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Buying);
+        let buys = drain_orders(&mut ctx);
+        assert_eq!(buys[0].0, "PURR/USDC");
+    }
+
+    #[test]
+    fn test_rebalance_summary_reports_allocations() {
+        let (strategy, mut ctx) = create_test_setup(Some(5.0), None);
+        set_balances(&mut ctx, 35.0, 500.0, 200.0);
+
+        match strategy.get_summary(&ctx) {
+            StrategySummary::Rebalance(s) => {
+                assert!((s.portfolio_value - 1000.0).abs() < 1e-9);
+                assert!((s.max_drift_pct - 20.0).abs() < 1e-9);
+                let hype = s.allocations.iter().find(|a| a.asset == "HYPE").unwrap();
+                assert!((hype.current_weight_pct - 70.0).abs() < 1e-9);
+                assert!((hype.target_weight_pct - 50.0).abs() < 1e-9);
+            }
+            other => panic!("Expected rebalance summary, got {:?}", other),
+        }
+    }
+}
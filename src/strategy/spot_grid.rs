@@ -1,17 +1,22 @@
 use super::common;
+use super::types::SpacingMode;
 
 use crate::broadcast::types::{GridState, StrategySummary};
-use crate::config::strategy::SpotGridConfig;
+use crate::config::notification::AlertKind;
+use crate::config::strategy::{InfinityGridConfig, SpotGridConfig, StrategyConfig};
 
 use crate::engine::context::{MarketInfo, StrategyContext, MIN_NOTIONAL_VALUE};
 use crate::model::{Cloid, OrderFill, OrderRequest, OrderSide};
+use crate::notify::Alert;
 use crate::strategy::Strategy;
 use anyhow::{anyhow, Result};
 use log::{debug, error, info, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-use crate::constants::{ACQUISITION_SPREAD, FEE_BUFFER, INVESTMENT_BUFFER_SPOT};
+use crate::constants::{
+    ACQUISITION_SPREAD, ATR_RESPACE_MIN_CHANGE, FEE_BUFFER, INVESTMENT_BUFFER_SPOT,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -33,8 +38,18 @@ struct GridZone {
     cloid: Option<Cloid>,
     roundtrip_count: u32,
     retry_count: u32,
+    /// Compounded size for the next buy, applied when that order is placed
+    pending_size: Option<f64>,
 }
 
+impl GridZone {
+    /// Size the zone's next buy will be placed at.
+    fn next_size(&self) -> f64 {
+        self.pending_size.unwrap_or(self.size)
+    }
+}
+
+#[derive(Clone)]
 pub struct SpotGridStrategy {
     pub config: SpotGridConfig,
     base_asset: String,
@@ -65,6 +80,26 @@ pub struct SpotGridStrategy {
     grid_count: u32,
     grid_spacing_pct: (f64, f64),
 
+    /// Effective spread: `spread_bips` from config, or derived from ATR.
+    spread_bips: Option<f64>,
+    /// Open time of the newest candle the ATR spacing was computed from.
+    atr_candle_time: Option<u64>,
+    /// Orders replaced by a re-space or resize, cancelled but possibly still filling.
+    retired_orders: HashSet<Cloid>,
+    /// Roundtrips completed by zones that were replaced by a re-space.
+    retired_roundtrips: u32,
+
+    /// Realized profit waiting to be reinvested into zone sizes.
+    compound_pool: f64,
+    /// Profit reinvested into zone sizes so far.
+    compounded_profit: f64,
+    last_compound: Instant,
+
+    /// Set for an infinity grid: the ladder is built at startup and extended upward.
+    infinity: Option<InfinityGridConfig>,
+    /// A new high could not get a level; logged once until the ladder grows again.
+    ladder_blocked: bool,
+
     market_info: Option<MarketInfo>,
 }
 
@@ -81,24 +116,9 @@ impl SpotGridStrategy {
             (config.symbol.clone(), "USDC".to_string())
         };
 
-        let (grid_count, grid_spacing_pct) = if let Some(spread_bips) = config.spread_bips {
-            let prices = common::calculate_grid_prices_by_spread(
-                config.grid_range_low,
-                config.grid_range_high,
-                spread_bips,
-            );
-            let spacing = spread_bips / 100.0;
-            (prices.len() as u32, (spacing, spacing))
-        } else {
-            let count = config.grid_count.expect("validated grid_count");
-            let spacing = common::calculate_grid_spacing_pct(
-                &config.grid_type,
-                config.grid_range_low,
-                config.grid_range_high,
-                count,
-            );
-            (count, spacing)
-        };
+        let (grid_count, grid_spacing_pct) = Self::grid_shape(&config);
+
+        let spread_bips = config.spread_bips;
 
         Self {
             config,
@@ -124,11 +144,77 @@ impl SpotGridStrategy {
             current_price: 0.0,
             grid_count,
             grid_spacing_pct,
+            spread_bips,
+            atr_candle_time: None,
+            retired_orders: HashSet::new(),
+            retired_roundtrips: 0,
+            compound_pool: 0.0,
+            compounded_profit: 0.0,
+            last_compound: Instant::now(),
+            infinity: None,
+            ladder_blocked: false,
             market_info: None,
         }
     }
 
+    /// Level count and spacing of the configured ladder.
+    fn grid_shape(config: &SpotGridConfig) -> (u32, (f64, f64)) {
+        if config.spacing_mode == SpacingMode::Atr {
+            // Known once candles are available in initialize_zones
+            (0, (0.0, 0.0))
+        } else if let Some(spread_bips) = config.spread_bips {
+            let prices = common::calculate_grid_prices_by_spread(
+                config.grid_range_low,
+                config.grid_range_high,
+                spread_bips,
+            );
+            let spacing = spread_bips / 100.0;
+            (prices.len() as u32, (spacing, spacing))
+        } else if let Some(levels) = config.custom_levels() {
+            (
+                levels.len() as u32,
+                common::calculate_ladder_spacing_pct(&levels),
+            )
+        } else {
+            let count = config.grid_count.expect("validated grid_count");
+            let spacing = common::calculate_grid_spacing_pct(
+                &config.grid_type,
+                config.grid_range_low,
+                config.grid_range_high,
+                count,
+            );
+            (count, spacing)
+        }
+    }
+
+    /// Infinity grid over the bounded spot grid machinery. The placeholder ladder
+    /// is replaced by one around the entry price when zones are initialized.
+    pub fn new_infinity(config: InfinityGridConfig) -> Self {
+        let entry_price = config.trigger_price.unwrap_or(config.grid_range_low);
+        let mut strategy = Self::new(config.ladder_config(entry_price));
+        strategy.infinity = Some(config);
+        strategy
+    }
+
     fn initialize_zones(&mut self, ctx: &mut StrategyContext) -> Result<()> {
+        if let Some(infinity) = &self.infinity {
+            let entry_price = self.config.trigger_price.unwrap_or(self.current_price);
+            self.config = infinity.ladder_config(entry_price);
+            let levels = self.config.levels.as_deref().unwrap_or_default();
+            let zones = levels.len().saturating_sub(1);
+            if zones > infinity.level_cap() as usize {
+                return Err(anyhow!(
+                    "The ladder from {} to entry price {} needs {} zones, more than the {} that max_levels or total_investment allow.",
+                    infinity.grid_range_low,
+                    entry_price,
+                    zones,
+                    infinity.level_cap()
+                ));
+            }
+            self.grid_count = levels.len() as u32;
+            self.grid_spacing_pct = common::calculate_ladder_spacing_pct(levels);
+        }
+
         self.config.validate().map_err(|e| anyhow!(e))?;
 
         if self.market_info.is_none() {
@@ -138,6 +224,23 @@ impl SpotGridStrategy {
             self.market_info = Some(info.clone());
         }
 
+        if self.config.spacing_mode == SpacingMode::Atr {
+            let (bips, candle_time) = self.atr_spacing(ctx).ok_or_else(|| {
+                anyhow!(
+                    "Not enough {} candle history for ATR spacing ({} candles, need {}).",
+                    self.config.atr.candle_interval,
+                    ctx.get_candles(&self.config.symbol).len(),
+                    self.config.atr.lookback + 1
+                )
+            })?;
+            info!(
+                "[SPOT_GRID] ATR spacing: {:.2} bips ({} x {} candles)",
+                bips, self.config.atr.candle_interval, self.config.atr.lookback
+            );
+            self.spread_bips = Some(bips);
+            self.atr_candle_time = Some(candle_time);
+        }
+
         let (total_base_required, total_quote_required) = self.calculate_grid_plan()?;
 
         self.required_base = total_base_required;
@@ -188,7 +291,7 @@ impl SpotGridStrategy {
             .as_ref()
             .expect("Market info should be initialized");
 
-        let prices: Vec<f64> = if let Some(spread_bips) = self.config.spread_bips {
+        let prices: Vec<f64> = if let Some(spread_bips) = self.spread_bips {
             common::calculate_grid_prices_by_spread(
                 self.config.grid_range_low,
                 self.config.grid_range_high,
@@ -207,9 +310,14 @@ impl SpotGridStrategy {
             .into_iter()
             .map(|p| market_info.round_price(p))
             .collect()
+        } else if let Some(levels) = self.config.custom_levels() {
+            levels
+                .into_iter()
+                .map(|p| market_info.round_price(p))
+                .collect()
         } else {
             return Err(anyhow!(
-                "Either grid_count or spread_bips must be specified"
+                "Either grid_count, spread_bips, levels or ranges must be specified"
             ));
         };
 
@@ -217,21 +325,33 @@ impl SpotGridStrategy {
             return Err(anyhow!("Not enough price levels generated"));
         }
 
+        if let (SpacingMode::Atr, Some(spread_bips)) = (self.config.spacing_mode, self.spread_bips)
+        {
+            self.grid_count = prices.len() as u32;
+            self.grid_spacing_pct = (spread_bips / 100.0, spread_bips / 100.0);
+        }
+
         let num_zones = prices.len() - 1;
+        let initial_price = self.config.trigger_price.unwrap_or(self.current_price);
         let adjusted_investment = INVESTMENT_BUFFER_SPOT.markdown(self.config.total_investment);
-        let quote_per_zone = adjusted_investment / num_zones as f64;
+        let weights = match &self.config.ranges {
+            Some(ranges) => {
+                common::layered_zone_weights(&self.config.size_distribution, ranges, initial_price)?
+            }
+            None => common::zone_weights(&self.config.size_distribution, &prices, initial_price)?,
+        };
+        let min_quote_per_zone =
+            weights.iter().cloned().fold(f64::INFINITY, f64::min) * adjusted_investment;
 
-        if quote_per_zone < MIN_NOTIONAL_VALUE {
+        if min_quote_per_zone < MIN_NOTIONAL_VALUE {
             let msg = format!(
-                "Quote per zone ({:.2}) is less than minimum order value ({}). Increase total_investment or decrease grid_count.",
-                quote_per_zone, MIN_NOTIONAL_VALUE
+                "Quote per zone ({:.2}) is less than minimum order value ({}). Increase total_investment, decrease grid_count or use a flatter size_distribution.",
+                min_quote_per_zone, MIN_NOTIONAL_VALUE
             );
             error!("[SPOT_GRID] {}", msg);
             return Err(anyhow!(msg));
         }
 
-        let initial_price = self.config.trigger_price.unwrap_or(self.current_price);
-
         self.zones.clear();
         let mut total_base_required = 0.0;
         let mut total_quote_required = 0.0;
@@ -240,7 +360,7 @@ impl SpotGridStrategy {
             let zone_buy_price = prices[i];
             let zone_sell_price = prices[i + 1];
 
-            let raw_size = quote_per_zone / zone_buy_price;
+            let raw_size = adjusted_investment * weights[i] / zone_buy_price;
             let size = market_info.round_size(raw_size);
 
             let order_side = if zone_buy_price > initial_price {
@@ -269,6 +389,7 @@ impl SpotGridStrategy {
                 cloid: None,
                 roundtrip_count: 0,
                 retry_count: 0,
+                pending_size: None,
             });
         }
 
@@ -549,6 +670,494 @@ impl SpotGridStrategy {
         self.check_initial_acquisition(ctx, self.required_base, self.required_quote, false)
     }
 
+    /// ATR spacing in bips and the open time of the newest candle it was computed from.
+    fn atr_spacing(&self, ctx: &StrategyContext) -> Option<(f64, u64)> {
+        let candles = ctx.get_candles(&self.config.symbol);
+        let bips = common::atr_spread_bips(candles, &self.config.atr)?;
+        Some((bips, candles.last()?.open_time))
+    }
+
+    /// Rebuilds the idle zones (buy zones below the lowest held zone) at the latest ATR
+    /// spacing. Zones holding inventory keep their levels.
+    fn respace_idle_zones(&mut self, ctx: &mut StrategyContext) {
+        if self.config.spacing_mode != SpacingMode::Atr || self.config.atr.recompute_secs.is_none()
+        {
+            return;
+        }
+        let Some((bips, candle_time)) = self.atr_spacing(ctx) else {
+            return;
+        };
+        if self.atr_candle_time.is_some_and(|t| candle_time <= t) {
+            return;
+        }
+        self.atr_candle_time = Some(candle_time);
+
+        let current = self.spread_bips.unwrap_or(bips);
+        if ((bips - current) / current).abs() < ATR_RESPACE_MIN_CHANGE {
+            debug!(
+                "[SPOT_GRID] ATR spacing {:.2} bips within {}% of current {:.2} bips",
+                bips,
+                ATR_RESPACE_MIN_CHANGE * 100.0,
+                current
+            );
+            return;
+        }
+        self.spread_bips = Some(bips);
+        self.grid_spacing_pct = (bips / 100.0, bips / 100.0);
+
+        let idle = self
+            .zones
+            .iter()
+            .take_while(|z| z.order_side.is_buy())
+            .count();
+        if idle == 0 {
+            info!(
+                "[SPOT_GRID] ATR spacing now {:.2} bips. No idle zones to rebuild.",
+                bips
+            );
+            return;
+        }
+
+        let market_info = self
+            .market_info
+            .as_ref()
+            .expect("Market info should be initialized");
+        let lower = self.zones[0].buy_price;
+        let upper = self.zones[idle - 1].sell_price;
+        let budget: f64 = self.zones[..idle]
+            .iter()
+            .map(|z| z.size * z.buy_price)
+            .sum();
+        let max_zones = (budget / MIN_NOTIONAL_VALUE).floor() as usize;
+
+        let prices: Vec<f64> = common::calculate_fitted_grid_prices(lower, upper, bips, max_zones)
+            .into_iter()
+            .map(|p| market_info.round_price(p))
+            .collect();
+        if prices.len() < 2 {
+            warn!(
+                "[SPOT_GRID] ATR re-space skipped: {:.2} {} budget is below min notional",
+                budget, self.quote_asset
+            );
+            return;
+        }
+
+        // Idle zones all sit below price, so the shape grows down from the block's top
+        let weights = match common::zone_weights(&self.config.size_distribution, &prices, upper) {
+            Ok(weights) => weights,
+            Err(e) => {
+                warn!("[SPOT_GRID] ATR re-space skipped: {}", e);
+                return;
+            }
+        };
+        let new_zones: Vec<GridZone> = prices
+            .windows(2)
+            .zip(weights)
+            .map(|(level, weight)| GridZone {
+                index: 0,
+                buy_price: level[0],
+                sell_price: level[1],
+                size: market_info.clamp_to_min_notional(
+                    budget * weight / level[0],
+                    level[0],
+                    MIN_NOTIONAL_VALUE,
+                ),
+                order_side: OrderSide::Buy,
+                entry_price: 0.0,
+                cloid: None,
+                roundtrip_count: 0,
+                retry_count: 0,
+                pending_size: None,
+            })
+            .collect();
+
+        for zone in self.zones.drain(..idle).collect::<Vec<_>>() {
+            self.retired_roundtrips += zone.roundtrip_count;
+            if let Some(cloid) = zone.cloid {
+                ctx.cancel_order(cloid);
+                self.active_orders.remove(&cloid);
+                self.retired_orders.insert(cloid);
+            }
+        }
+
+        info!(
+            "[SPOT_GRID] ATR spacing now {:.2} bips. Rebuilt {} idle zones as {} between {} and {}",
+            bips,
+            idle,
+            new_zones.len(),
+            lower,
+            upper
+        );
+
+        self.zones.splice(0..0, new_zones);
+        self.reindex_zones();
+    }
+
+    /// Infinity grid: keeps `levels_above` zones above price by appending zones
+    /// on top as price makes new highs. Each holds `level_value` of quote: zones
+    /// above price hold base to sell, zones price already passed wait to buy.
+    /// A zone is funded from grid inventory no zone holds, and any shortfall is
+    /// drawn from spare account balances as in `replan`. Stops at the level cap
+    /// or when neither covers the zone.
+    fn extend_ladder(&mut self, ctx: &StrategyContext) {
+        let Some(infinity) = self.infinity.clone() else {
+            return;
+        };
+        let market_info = self
+            .market_info
+            .as_ref()
+            .expect("Market info should be initialized");
+        let ratio = 1.0 + infinity.spread_bips / 10000.0;
+        // Grid inventory no zone holds
+        let (held_base, held_quote) =
+            self.zones
+                .iter()
+                .fold((0.0, 0.0), |(base, quote), z| match z.order_side {
+                    OrderSide::Sell => (base + z.size, quote),
+                    OrderSide::Buy => (base, quote + z.size * z.buy_price),
+                });
+        let mut idle_base = (self.inventory_base - held_base).max(0.0);
+        let mut idle_quote = (self.inventory_quote - held_quote).max(0.0);
+        // Account balances the grid does not already hold
+        let spare = |asset: &str, held: f64| {
+            (ctx.get_spot_total(asset) - held)
+                .min(ctx.get_spot_available(asset))
+                .max(0.0)
+        };
+        let mut spare_base = spare(&self.base_asset, self.inventory_base);
+        let mut spare_quote = spare(&self.quote_asset, self.inventory_quote);
+        let (mut draw_base, mut draw_quote) = (0.0, 0.0);
+        let mut added = 0;
+
+        while let Some(top) = self.zones.last().map(|z| z.sell_price) {
+            let above = self
+                .zones
+                .iter()
+                .filter(|z| z.buy_price > self.current_price)
+                .count();
+            if above >= infinity.levels_above as usize {
+                break;
+            }
+            let sell_price = market_info.round_price(top * ratio);
+            if sell_price <= top {
+                break;
+            }
+            let size = market_info.clamp_to_min_notional(
+                infinity.level_value / top,
+                top,
+                MIN_NOTIONAL_VALUE,
+            );
+            let order_side = if top > self.current_price {
+                OrderSide::Sell
+            } else {
+                OrderSide::Buy
+            };
+
+            let (need_base, need_quote) = if order_side.is_sell() {
+                (size, 0.0)
+            } else {
+                (0.0, size * top)
+            };
+            let zone_base = (need_base - idle_base).max(0.0);
+            let zone_quote = (need_quote - idle_quote).max(0.0);
+
+            let blocked_by = if self.zones.len() >= infinity.level_cap() as usize {
+                Some("max_levels reached")
+            } else if zone_base - spare_base > 1e-9 {
+                Some("not enough free base")
+            } else if zone_quote - spare_quote > 1e-9 {
+                Some("not enough free quote")
+            } else {
+                None
+            };
+            if let Some(reason) = blocked_by {
+                if !self.ladder_blocked {
+                    warn!(
+                        "[SPOT_GRID] Price {} needs a level above the ladder top {} but none was added: {}",
+                        self.current_price, top, reason
+                    );
+                    self.ladder_blocked = true;
+                }
+                break;
+            }
+
+            idle_base = (idle_base - need_base).max(0.0);
+            idle_quote = (idle_quote - need_quote).max(0.0);
+            spare_base -= zone_base;
+            spare_quote -= zone_quote;
+            draw_base += zone_base;
+            draw_quote += zone_quote;
+            self.zones.push(GridZone {
+                index: self.zones.len(),
+                buy_price: top,
+                sell_price,
+                size,
+                order_side,
+                entry_price: if order_side.is_sell() { top } else { 0.0 },
+                cloid: None,
+                roundtrip_count: 0,
+                retry_count: 0,
+                pending_size: None,
+            });
+            if let Some(levels) = self.config.levels.as_mut() {
+                levels.push(sell_price);
+            }
+            self.config.grid_range_high = sell_price;
+            self.config.total_investment += size * top;
+            added += 1;
+        }
+
+        if draw_base > 0.0 || draw_quote > 0.0 {
+            // Added capital is not profit
+            self.inventory_base += draw_base;
+            self.inventory_quote += draw_quote;
+            self.initial_equity += draw_base * self.current_price + draw_quote;
+            info!(
+                "[SPOT_GRID] Topped up with {} {} and {:.2} {}",
+                draw_base, self.base_asset, draw_quote, self.quote_asset
+            );
+        }
+        if added > 0 {
+            self.ladder_blocked = false;
+            self.grid_count = self.zones.len() as u32 + 1;
+            info!(
+                "[SPOT_GRID] New high {}: added {} level(s), ladder top now {}",
+                self.current_price, added, self.config.grid_range_high
+            );
+        }
+    }
+
+    /// Moves the grid to `config`'s range and investment. A grid that has not
+    /// started is re-planned on the next tick; a running grid is rebuilt around
+    /// its inventory, and with `top_up` may draw a shortfall from spare account
+    /// balances. Zones whose levels, size and side do not change keep their
+    /// orders. Leaves the grid untouched on error.
+    fn replan(
+        &mut self,
+        mut config: SpotGridConfig,
+        ctx: &mut StrategyContext,
+        top_up: bool,
+    ) -> Result<()> {
+        let (low, high) = (config.grid_range_low, config.grid_range_high);
+        if self.state == StrategyState::Running {
+            // The trigger has done its job once the grid runs
+            config.trigger_price = None;
+        }
+        config.validate()?;
+
+        match self.state {
+            StrategyState::AcquiringAssets { .. } => {
+                Err(anyhow!("Cannot change the range while acquiring assets"))
+            }
+            StrategyState::Initializing | StrategyState::WaitingForTrigger => {
+                // Nothing placed yet: re-plan on the next tick
+                (self.grid_count, self.grid_spacing_pct) = Self::grid_shape(&config);
+                self.config = config;
+                self.zones.clear();
+                self.state = StrategyState::Initializing;
+                info!("[SPOT_GRID] Range set to {} - {}", low, high);
+                Ok(())
+            }
+            StrategyState::Running => {
+                let old_config = std::mem::replace(&mut self.config, config);
+                let old_zones = std::mem::take(&mut self.zones);
+                let old_shape = (self.grid_count, self.grid_spacing_pct);
+                if self.config.spacing_mode != SpacingMode::Atr {
+                    (self.grid_count, self.grid_spacing_pct) = Self::grid_shape(&self.config);
+                }
+
+                // Account balances the grid does not already hold
+                let (spare_base, spare_quote) = if top_up {
+                    let spare = |asset: &str, held: f64| {
+                        (ctx.get_spot_total(asset) - held)
+                            .min(ctx.get_spot_available(asset))
+                            .max(0.0)
+                    };
+                    (
+                        spare(&self.base_asset, self.inventory_base),
+                        spare(&self.quote_asset, self.inventory_quote),
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+
+                let planned = self.calculate_grid_plan().and_then(|(base, quote)| {
+                    let draw_base = (base - self.inventory_base).max(0.0);
+                    let draw_quote = (quote - self.inventory_quote).max(0.0);
+                    if draw_base - spare_base > 1e-9 || draw_quote - spare_quote > 1e-9 {
+                        Err(anyhow!(
+                            "Range {} - {} needs {} {} and {:.2} {}, the grid holds {} {} and {:.2} {}{}",
+                            low,
+                            high,
+                            base,
+                            self.base_asset,
+                            quote,
+                            self.quote_asset,
+                            self.inventory_base,
+                            self.base_asset,
+                            self.inventory_quote,
+                            self.quote_asset,
+                            if top_up {
+                                format!(
+                                    " with {} {} and {:.2} {} spare in the account",
+                                    spare_base, self.base_asset, spare_quote, self.quote_asset
+                                )
+                            } else {
+                                String::new()
+                            }
+                        ))
+                    } else {
+                        Ok((draw_base, draw_quote))
+                    }
+                });
+                let (draw_base, draw_quote) = match planned {
+                    Ok(draw) => draw,
+                    Err(e) => {
+                        self.config = old_config;
+                        self.zones = old_zones;
+                        (self.grid_count, self.grid_spacing_pct) = old_shape;
+                        return Err(e);
+                    }
+                };
+                if draw_base > 0.0 || draw_quote > 0.0 {
+                    // Added capital is not profit
+                    self.inventory_base += draw_base;
+                    self.inventory_quote += draw_quote;
+                    self.initial_equity += draw_base * self.current_price + draw_quote;
+                    info!(
+                        "[SPOT_GRID] Topped up with {} {} and {:.2} {}",
+                        draw_base, self.base_asset, draw_quote, self.quote_asset
+                    );
+                }
+
+                let mut kept = 0;
+                for zone in old_zones {
+                    let unchanged = self.zones.iter_mut().find(|z| {
+                        z.buy_price == zone.buy_price
+                            && z.sell_price == zone.sell_price
+                            && z.size == zone.size
+                            && z.order_side == zone.order_side
+                    });
+                    if let Some(new_zone) = unchanged {
+                        new_zone.cloid = zone.cloid;
+                        new_zone.entry_price = zone.entry_price;
+                        new_zone.roundtrip_count = zone.roundtrip_count;
+                        kept += 1;
+                        continue;
+                    }
+                    self.retired_roundtrips += zone.roundtrip_count;
+                    if let Some(cloid) = zone.cloid {
+                        ctx.cancel_order(cloid);
+                        self.retired_orders.insert(cloid);
+                    }
+                }
+                self.reindex_zones();
+                info!(
+                    "[SPOT_GRID] Range moved to {} - {}: {} zones, {} unchanged",
+                    low,
+                    high,
+                    self.zones.len(),
+                    kept
+                );
+                self.refresh_orders(ctx);
+                Ok(())
+            }
+        }
+    }
+
+    fn reindex_zones(&mut self) {
+        self.active_orders.clear();
+        for (i, zone) in self.zones.iter_mut().enumerate() {
+            zone.index = i;
+            if let Some(cloid) = zone.cloid {
+                self.active_orders.insert(cloid, i);
+            }
+        }
+        self.grid_count = self.zones.len() as u32 + 1;
+    }
+
+    /// Reinvests the compounding pool into buy zones. Zones with a resting buy keep
+    /// it and take the new size at their next placement.
+    fn compound_profit(&mut self, ctx: &mut StrategyContext) {
+        let Some(compound) = self.config.compound.clone() else {
+            return;
+        };
+        self.last_compound = Instant::now();
+        if self.compound_pool <= 0.0 {
+            return;
+        }
+
+        let candidates: Vec<(usize, f64, f64)> = self
+            .zones
+            .iter()
+            .enumerate()
+            .filter(|(_, z)| z.order_side.is_buy())
+            .map(|(i, z)| (i, z.buy_price, z.next_size() * z.buy_price))
+            .collect();
+        let allocation = common::allocate_compound(
+            self.compound_pool,
+            &candidates,
+            &compound,
+            self.current_price,
+        );
+
+        let market_info = self
+            .market_info
+            .as_ref()
+            .expect("Market info should be initialized");
+        let mut spent = 0.0;
+        let mut resized = Vec::new();
+        for (idx, quote) in allocation {
+            let zone = &mut self.zones[idx];
+            let current = zone.next_size();
+            let size = market_info.clamp_to_min_notional(
+                current + quote / zone.buy_price,
+                zone.buy_price,
+                MIN_NOTIONAL_VALUE,
+            );
+            // Amounts below one size increment stay in the pool
+            if size <= current {
+                continue;
+            }
+            spent += (size - current) * zone.buy_price;
+            zone.pending_size = Some(size);
+            resized.push(idx);
+        }
+        if resized.is_empty() {
+            return;
+        }
+
+        self.compound_pool -= spent;
+        self.compounded_profit += spent;
+        info!(
+            "[SPOT_GRID] Compounded {:.4} {} into {} zones. Pool: {:.4}, total compounded: {:.4}",
+            spent,
+            self.quote_asset,
+            resized.len(),
+            self.compound_pool,
+            self.compounded_profit
+        );
+        for idx in resized {
+            self.place_zone_order(idx, ctx);
+        }
+    }
+
+    /// A zone order was cancelled by a re-space or resize but filled (fully or partly) first.
+    fn handle_retired_fill(&mut self, fill: &OrderFill) {
+        warn!(
+            "[SPOT_GRID] Fill for replaced zone order: {} {} @ {}. Held outside the grid.",
+            fill.side, fill.size, fill.price
+        );
+        self.total_fees += fill.fee;
+        if fill.side.is_buy() {
+            self.inventory_base += fill.size;
+            self.inventory_quote -= fill.price * fill.size;
+        } else {
+            self.inventory_base = (self.inventory_base - fill.size).max(0.0);
+            self.inventory_quote += fill.price * fill.size;
+        }
+    }
+
     fn refresh_orders(&mut self, ctx: &mut StrategyContext) {
         let zones_needing_orders: Vec<usize> = (0..self.zones.len())
             .filter(|&i| self.zones[i].cloid.is_none())
@@ -560,7 +1169,7 @@ impl SpotGridStrategy {
     }
 
     fn place_zone_order(&mut self, zone_idx: usize, ctx: &mut StrategyContext) {
-        let zone = &self.zones[zone_idx];
+        let zone = &mut self.zones[zone_idx];
 
         if zone.cloid.is_some() {
             return;
@@ -571,6 +1180,11 @@ impl SpotGridStrategy {
         }
 
         let side = zone.order_side;
+        if side.is_buy() {
+            if let Some(size) = zone.pending_size.take() {
+                zone.size = size;
+            }
+        }
         let price = if side.is_buy() {
             zone.buy_price
         } else {
@@ -712,6 +1326,13 @@ impl SpotGridStrategy {
         self.zones[zone_idx].order_side = OrderSide::Buy;
         self.zones[zone_idx].entry_price = 0.0;
 
+        if let Some(compound) = &self.config.compound {
+            self.compound_pool += pnl - fill.fee;
+            if compound.interval_secs.is_none() {
+                self.compound_profit(ctx);
+            }
+        }
+
         self.place_zone_order(zone_idx, ctx);
         Ok(())
     }
@@ -771,6 +1392,13 @@ impl Strategy for SpotGridStrategy {
                 }
             }
             StrategyState::Running => {
+                self.respace_idle_zones(ctx);
+                self.extend_ladder(ctx);
+                if let Some(interval) = self.config.compound.as_ref().and_then(|c| c.interval()) {
+                    if self.last_compound.elapsed() >= interval {
+                        self.compound_profit(ctx);
+                    }
+                }
                 self.refresh_orders(ctx);
             }
         }
@@ -811,6 +1439,8 @@ impl Strategy for SpotGridStrategy {
                 } else {
                     self.handle_sell_fill(zone_idx, fill, ctx)?;
                 }
+            } else if self.retired_orders.remove(&cloid_val) {
+                self.handle_retired_fill(fill);
             } else {
                 debug!(
                     "[SPOT_GRID] Fill received for unknown/inactive CLOID: {}",
@@ -827,7 +1457,7 @@ impl Strategy for SpotGridStrategy {
         Ok(())
     }
 
-    fn on_order_failed(&mut self, cloid: Cloid, _ctx: &mut StrategyContext) -> Result<()> {
+    fn on_order_failed(&mut self, cloid: Cloid, ctx: &mut StrategyContext) -> Result<()> {
         if self.state == StrategyState::Initializing {
             return Ok(());
         }
@@ -845,8 +1475,94 @@ impl Strategy for SpotGridStrategy {
                         zone.retry_count,
                         crate::constants::MAX_ORDER_RETRIES
                     );
+
+                    if zone.retry_count == crate::constants::MAX_ORDER_RETRIES {
+                        ctx.raise_alert(Alert::new(
+                            AlertKind::CircuitBreak,
+                            "Grid zone stopped",
+                            format!(
+                                "Zone {} stopped after {} failed orders",
+                                zone_idx, zone.retry_count
+                            ),
+                        ));
+                    }
+                }
+            }
+        } else if self.retired_orders.remove(&cloid) {
+            debug!("[SPOT_GRID] Re-spaced zone order {} closed", cloid);
+        }
+        Ok(())
+    }
+
+    fn on_order_canceled(&mut self, cloid: Cloid, _ctx: &mut StrategyContext) -> Result<()> {
+        // Re-placed on the next tick after a resume, without counting as a retry
+        if let Some(zone_idx) = self.active_orders.remove(&cloid) {
+            if let Some(zone) = self.zones.get_mut(zone_idx) {
+                if zone.cloid == Some(cloid) {
+                    zone.cloid = None;
+                    info!(
+                        "[ORDER_CANCELED] [SPOT_GRID] GRID_ZONE_{} cloid: {}",
+                        zone_idx, cloid
+                    );
                 }
             }
+        } else if self.retired_orders.remove(&cloid) {
+            debug!("[SPOT_GRID] Re-spaced zone order {} closed", cloid);
+        }
+        Ok(())
+    }
+
+    fn set_range(&mut self, low: f64, high: f64, ctx: &mut StrategyContext) -> Result<()> {
+        if self.infinity.is_some() || self.config.custom_levels().is_some() {
+            return Err(anyhow!(
+                "set_range is not supported for grids built from levels, ranges or an infinity ladder"
+            ));
+        }
+        let mut config = self.config.clone();
+        config.grid_range_low = low;
+        config.grid_range_high = high;
+        self.replan(config, ctx, false)
+    }
+
+    fn reconfigure(&mut self, config: &StrategyConfig, ctx: &mut StrategyContext) -> Result<()> {
+        let StrategyConfig::SpotGrid(new) = config else {
+            return Err(anyhow!("The spot grid only reloads a spot_grid config"));
+        };
+        if self.infinity.is_some() {
+            return Err(anyhow!(
+                "An infinity grid cannot be reconfigured while running"
+            ));
+        }
+        let range_changed = (new.grid_range_low, new.grid_range_high)
+            != (self.config.grid_range_low, self.config.grid_range_high);
+        if range_changed && self.config.custom_levels().is_some() {
+            return Err(anyhow!(
+                "The range of a grid built from levels or ranges cannot be reloaded"
+            ));
+        }
+        let mut config = self.config.clone();
+        config.grid_range_low = new.grid_range_low;
+        config.grid_range_high = new.grid_range_high;
+        config.total_investment = new.total_investment;
+        // The trigger only gates the start, so a running grid ignores it
+        let started = !matches!(
+            self.state,
+            StrategyState::Initializing | StrategyState::WaitingForTrigger
+        );
+        let trigger_changed = config.trigger_price != new.trigger_price;
+        if !started {
+            config.trigger_price = new.trigger_price;
+        } else if trigger_changed {
+            info!(
+                "[SPOT_GRID] Ignoring trigger_price {:?}: the grid has already started",
+                new.trigger_price
+            );
+        }
+        self.replan(config, ctx, true)?;
+        if trigger_changed && self.state == StrategyState::Initializing {
+            // Measured again against the new trigger on the next tick
+            self.trigger_reference_price = None;
+            self.trigger_activated = false;
         }
         Ok(())
     }
@@ -870,7 +1586,8 @@ impl Strategy for SpotGridStrategy {
             0.0
         };
 
-        let total_roundtrips: u32 = self.zones.iter().map(|z| z.roundtrip_count).sum();
+        let total_roundtrips: u32 =
+            self.retired_roundtrips + self.zones.iter().map(|z| z.roundtrip_count).sum::<u32>();
 
         let uptime = common::format_uptime(self.start_time.elapsed());
 
@@ -909,6 +1626,11 @@ impl Strategy for SpotGridStrategy {
                 is_reduce_only: false,
                 entry_price: z.entry_price,
                 roundtrip_count: z.roundtrip_count,
+                range_index: self
+                    .config
+                    .ranges
+                    .as_deref()
+                    .and_then(|ranges| common::range_index(ranges, z.buy_price, z.sell_price)),
             })
             .collect();
 
@@ -924,8 +1646,12 @@ impl Strategy for SpotGridStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::strategy::SpotGridConfig;
+    use crate::config::strategy::{
+        AtrSpacingConfig, CompoundConfig, DistributionShape, InfinityGridConfig, SizeDistribution,
+        SpotGridConfig,
+    };
     use crate::engine::context::{MarketInfo, StrategyContext};
+    use crate::model::Candle;
     use crate::strategy::types::GridType;
 
     fn create_test_setup(
@@ -941,6 +1667,12 @@ mod tests {
             grid_type: GridType::Arithmetic,
             grid_count: Some(5),
             spread_bips: None,
+            spacing_mode: SpacingMode::Fixed,
+            atr: AtrSpacingConfig::default(),
+            levels: None,
+            ranges: None,
+            size_distribution: SizeDistribution::default(),
+            compound: None,
             total_investment: 1000.0,
             trigger_price,
         };
@@ -1388,6 +2120,106 @@ mod tests {
         assert_eq!(strategy.state, StrategyState::Running);
     }
 
+    #[test]
+    fn test_spot_grid_set_range_rebuilds_running_grid() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 100.0, 1000.0, 100.0);
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Running);
+        let old_cloids: Vec<Cloid> = strategy.zones.iter().filter_map(|z| z.cloid).collect();
+        assert_eq!(old_cloids.len(), 4);
+        ctx.order_queue.clear();
+
+        // All-sell range needs far more base than the grid holds
+        let err = strategy.set_range(50.0, 60.0, &mut ctx).unwrap_err();
+        assert!(err.to_string().contains("the grid holds"));
+        assert_eq!(strategy.config.grid_range_low, 90.0);
+        assert_eq!(strategy.zones.len(), 4);
+        assert!(ctx.cancellation_queue.is_empty());
+
+        strategy.set_range(80.0, 120.0, &mut ctx).unwrap();
+        assert_eq!(strategy.config.grid_range_low, 80.0);
+        assert_eq!(strategy.config.grid_range_high, 120.0);
+        assert_eq!(strategy.zones[0].buy_price, 80.0);
+        assert_eq!(ctx.cancellation_queue.len(), 4);
+        assert!(old_cloids
+            .iter()
+            .all(|c| strategy.retired_orders.contains(c)));
+        assert_eq!(ctx.order_queue.len(), 4);
+        assert_eq!(strategy.active_orders.len(), 4);
+    }
+
+    #[test]
+    fn test_spot_grid_reconfigure_tops_up_running_grid() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 100.0, 2000.0, 100.0);
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Running);
+        ctx.order_queue.clear();
+        let (base, quote) = (strategy.inventory_base, strategy.inventory_quote);
+        let equity = strategy.initial_equity;
+
+        // A larger investment over a wider range draws on the account's spare balances
+        let mut config = strategy.config.clone();
+        config.grid_range_low = 80.0;
+        config.grid_range_high = 120.0;
+        config.total_investment = 1500.0;
+        strategy
+            .reconfigure(&StrategyConfig::SpotGrid(config.clone()), &mut ctx)
+            .unwrap();
+        assert_eq!(strategy.config.total_investment, 1500.0);
+        assert_eq!(strategy.zones[0].buy_price, 80.0);
+        assert!(strategy.inventory_base > base);
+        assert!(strategy.inventory_quote > quote);
+        let added = (strategy.inventory_base - base) * 100.0 + strategy.inventory_quote - quote;
+        assert!((strategy.initial_equity - equity - added).abs() < 1e-6);
+        assert_eq!(ctx.cancellation_queue.len(), 4);
+        assert_eq!(ctx.order_queue.len(), 4);
+
+        // More than the account holds is rejected and changes nothing
+        ctx.cancellation_queue.clear();
+        config.total_investment = 50_000.0;
+        let err = strategy
+            .reconfigure(&StrategyConfig::SpotGrid(config), &mut ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("spare in the account"));
+        assert_eq!(strategy.config.total_investment, 1500.0);
+        assert!(ctx.cancellation_queue.is_empty());
+    }
+
+    #[test]
+    fn test_spot_grid_reconfigure_keeps_unchanged_zones() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 100.0, 2000.0, 100.0);
+        strategy.config.grid_count = None;
+        strategy.config.spread_bips = Some(500.0);
+        strategy.config.grid_type = GridType::Geometric;
+        strategy.spread_bips = strategy.config.spread_bips;
+        (strategy.grid_count, strategy.grid_spacing_pct) =
+            SpotGridStrategy::grid_shape(&strategy.config);
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Running);
+        let old_zones = strategy.zones.clone();
+        assert_eq!(old_zones.len(), 4);
+        ctx.order_queue.clear();
+
+        // One more level on top at the same quote per zone; a new trigger is ignored
+        let mut config = strategy.config.clone();
+        config.grid_range_high = 116.0;
+        config.total_investment = 1250.0;
+        config.trigger_price = Some(95.0);
+        strategy
+            .reconfigure(&StrategyConfig::SpotGrid(config), &mut ctx)
+            .unwrap();
+        assert_eq!(strategy.config.trigger_price, None);
+        assert_eq!(strategy.zones.len(), 5);
+        assert!(ctx.cancellation_queue.is_empty());
+        assert!(strategy.retired_orders.is_empty());
+        for (old, new) in old_zones.iter().zip(&strategy.zones) {
+            assert_eq!(new.cloid, old.cloid);
+            assert_eq!(new.entry_price, old.entry_price);
+        }
+        assert_eq!(ctx.order_queue.len(), 1);
+        assert_eq!(strategy.active_orders.len(), 5);
+    }
+
     #[test]
     fn test_spot_grid_order_failure_recovery() {
         // Scenario: Order Fails -> Zone State Cleared -> Retry on next Tick
@@ -1427,6 +2259,25 @@ mod tests {
         assert_ne!(new_cloid, original_cloid, "Should be a new cloid");
         assert!(strategy.active_orders.contains_key(&new_cloid));
     }
+    #[test]
+    fn test_spot_grid_engine_cancels_are_not_failures() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 100.0, 1000.0, 100.0);
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+
+        // More cancel_all rounds than MAX_ORDER_RETRIES leave every zone trading
+        for _ in 0..=crate::constants::MAX_ORDER_RETRIES {
+            let cloids: Vec<Cloid> = strategy.zones.iter().filter_map(|z| z.cloid).collect();
+            assert_eq!(cloids.len(), strategy.zones.len());
+            for cloid in cloids {
+                strategy.on_order_canceled(cloid, &mut ctx).unwrap();
+            }
+            assert!(strategy.active_orders.is_empty());
+            strategy.on_tick(100.0, &mut ctx).unwrap();
+        }
+        assert!(strategy.zones.iter().all(|z| z.retry_count == 0));
+        assert!(ctx.alerts.is_empty());
+    }
+
     #[test]
     fn test_spot_grid_avg_price_reset() {
         // Scenario: Buy -> Sell All -> Buy again. Avg Price should reset.
@@ -1516,4 +2367,372 @@ mod tests {
 
         assert_eq!(strategy.inventory_base, strategy.required_base);
     }
+
+    #[test]
+    fn test_spot_grid_linear_size_distribution() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 100.0, 1000.0, 100.0);
+        strategy.config.size_distribution = SizeDistribution::Shape(DistributionShape::Linear);
+
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+
+        // Zones [90-95] [95-100] [100-105] [105-110] around 100 weigh 2:1:1:2
+        let notionals: Vec<f64> = strategy
+            .zones
+            .iter()
+            .map(|z| z.size * z.buy_price)
+            .collect();
+        assert!((notionals[0] / notionals[1] - 2.0).abs() < 0.02);
+        assert!((notionals[3] / notionals[2] - 2.0).abs() < 0.02);
+        assert!((notionals.iter().sum::<f64>() - 999.0).abs() < 1.0);
+
+        assert_eq!(strategy.zones[3].order_side, OrderSide::Sell);
+        assert_eq!(strategy.required_base, strategy.zones[3].size);
+        let buy_quote: f64 = notionals[..3].iter().sum();
+        assert!((strategy.required_quote - buy_quote).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spot_grid_explicit_levels() {
+        let (strategy, mut ctx) = create_test_setup(None, 100.0, 1000.0, 100.0);
+        let config = SpotGridConfig {
+            grid_count: None,
+            levels: Some(vec![90.0, 98.0, 100.0, 102.0, 110.0]),
+            ..strategy.config.clone()
+        };
+        let mut strategy = SpotGridStrategy::new(config);
+
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+
+        let bounds: Vec<(f64, f64)> = strategy
+            .zones
+            .iter()
+            .map(|z| (z.buy_price, z.sell_price))
+            .collect();
+        assert_eq!(
+            bounds,
+            vec![(90.0, 98.0), (98.0, 100.0), (100.0, 102.0), (102.0, 110.0)]
+        );
+        assert_eq!(strategy.zones[3].order_side, OrderSide::Sell);
+
+        match strategy.get_summary(&ctx) {
+            StrategySummary::SpotGrid(summary) => {
+                assert_eq!(summary.grid_count, 5);
+                assert!((summary.grid_spacing_pct.0 - 2.0).abs() < 1e-9);
+                assert!((summary.grid_spacing_pct.1 - 8.0 / 90.0 * 100.0).abs() < 1e-9);
+            }
+            _ => panic!("Expected spot summary"),
+        }
+    }
+
+    #[test]
+    fn test_infinity_grid_extends_on_new_highs() {
+        let (_, mut ctx) = create_test_setup(None, 10.0, 1000.0, 100.0);
+        let config = InfinityGridConfig {
+            symbol: "HYPE/USDC".to_string(),
+            grid_range_low: 90.0,
+            spread_bips: 100.0,
+            level_value: 20.0,
+            levels_above: 2,
+            total_investment: 400.0,
+            max_levels: Some(16),
+            trigger_price: None,
+        };
+
+        // The startup ladder must fit under the cap
+        let mut capped = SpotGridStrategy::new_infinity(InfinityGridConfig {
+            max_levels: Some(12),
+            ..config.clone()
+        });
+        let err = capped.on_tick(100.0, &mut ctx).unwrap_err();
+        assert!(err.to_string().contains("needs 13 zones"));
+
+        let mut strategy = SpotGridStrategy::new_infinity(config);
+
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Running);
+
+        // 90 * 1.01^k: 11 levels up to 100, then two zones above the entry
+        assert_eq!(strategy.zones.len(), 13);
+        let above: Vec<&GridZone> = strategy
+            .zones
+            .iter()
+            .filter(|z| z.buy_price > 100.0)
+            .collect();
+        assert_eq!(above.len(), 2);
+        assert!(above.iter().all(|z| z.order_side.is_sell()));
+        for zone in &strategy.zones {
+            // Sizes round to 0.01 base, about 1 USDC at these prices
+            assert!((zone.size * zone.buy_price - 19.98).abs() < 1.0);
+        }
+
+        // Passing the first level above the entry adds a Sell zone on top,
+        // holding the same quote value
+        let top = strategy.zones.last().unwrap().sell_price;
+        let first_above = strategy.zones[11].buy_price;
+        strategy.on_tick(first_above + 0.01, &mut ctx).unwrap();
+        assert_eq!(strategy.zones.len(), 14);
+        let added = &strategy.zones[13];
+        assert_eq!(added.buy_price, top);
+        assert!(added.order_side.is_sell());
+        assert_eq!(added.entry_price, top);
+        assert!(added.cloid.is_some());
+        assert!((added.size * added.buy_price - 20.0).abs() < 1.0);
+
+        // A new high above the top adds Buy zones below price until max_levels
+        let top = strategy.zones.last().unwrap().sell_price;
+        strategy.on_tick(top * 1.10, &mut ctx).unwrap();
+        assert_eq!(strategy.zones.len(), 16);
+        let new_zones = &strategy.zones[14..];
+        assert_eq!(new_zones[0].buy_price, top);
+        assert!(new_zones
+            .iter()
+            .all(|z| z.order_side.is_buy() && z.cloid.is_some()));
+        assert!(new_zones
+            .windows(2)
+            .all(|pair| pair[0].sell_price == pair[1].buy_price));
+
+        match strategy.get_summary(&ctx) {
+            StrategySummary::SpotGrid(summary) => {
+                assert_eq!(summary.grid_range_high, new_zones[1].sell_price);
+                assert_eq!(summary.grid_count, 17);
+            }
+            _ => panic!("Expected spot summary"),
+        }
+    }
+
+    #[test]
+    fn test_infinity_grid_extension_draws_capital_without_profit() {
+        // Just enough base for the two zones above the entry
+        let (_, mut ctx) = create_test_setup(None, 0.45, 1000.0, 100.0);
+        let mut strategy = SpotGridStrategy::new_infinity(InfinityGridConfig {
+            symbol: "HYPE/USDC".to_string(),
+            grid_range_low: 90.0,
+            spread_bips: 100.0,
+            level_value: 20.0,
+            levels_above: 2,
+            total_investment: 400.0,
+            max_levels: None,
+            trigger_price: None,
+        });
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert_eq!(strategy.state, StrategyState::Running);
+        let profit =
+            |strategy: &SpotGridStrategy, ctx: &StrategyContext| match strategy.get_summary(ctx) {
+                StrategySummary::SpotGrid(summary) => summary.total_profit,
+                _ => panic!("Expected spot summary"),
+            };
+
+        // The new Sell zone on top needs base the grid does not have
+        let price = strategy.zones[11].buy_price + 0.01;
+        strategy.on_tick(price, &mut ctx).unwrap();
+        assert_eq!(strategy.zones.len(), 13);
+        let before = profit(&strategy, &ctx);
+
+        // A deposit funds it; the drawn base is capital, not profit
+        let base = strategy.inventory_base;
+        ctx.update_spot_balance("HYPE".to_string(), 1.0, 1.0);
+        strategy.on_tick(price, &mut ctx).unwrap();
+        assert_eq!(strategy.zones.len(), 14);
+        assert!(strategy.zones[13].order_side.is_sell());
+        assert!(strategy.inventory_base > base);
+        assert!((profit(&strategy, &ctx) - before).abs() < 1e-9);
+    }
+
+    fn atr_candles(start: u64, count: usize, range: f64) -> Vec<Candle> {
+        (0..count)
+            .map(|i| Candle {
+                open_time: (start + i as u64) * 3_600_000,
+                open: 100.0,
+                high: 100.0 + range / 2.0,
+                low: 100.0 - range / 2.0,
+                close: 100.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_spot_grid_atr_requires_candles() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 10.0, 1000.0, 100.0);
+        strategy.config.grid_type = GridType::Geometric;
+        strategy.spread_bips = strategy.config.spread_bips;
+        strategy.config.grid_count = None;
+        strategy.config.spacing_mode = SpacingMode::Atr;
+
+        let err = strategy.on_tick(100.0, &mut ctx).unwrap_err();
+        assert!(err.to_string().contains("candle history"));
+    }
+
+    #[test]
+    fn test_spot_grid_atr_respace_rebuilds_idle_buy_zones() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 10.0, 1000.0, 100.0);
+        strategy.config.grid_type = GridType::Geometric;
+        strategy.spread_bips = strategy.config.spread_bips;
+        strategy.config.grid_count = None;
+        strategy.config.spacing_mode = SpacingMode::Atr;
+        strategy.config.atr = AtrSpacingConfig {
+            lookback: 3,
+            recompute_secs: Some(3600),
+            ..AtrSpacingConfig::default()
+        };
+
+        // ATR of 2.0 on a close of 100 -> 200 bips
+        ctx.set_candles("HYPE/USDC", atr_candles(0, 4, 2.0));
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::Running));
+        assert_eq!(strategy.spread_bips, Some(200.0));
+        assert_eq!(strategy.grid_count as usize, strategy.zones.len() + 1);
+
+        let idle: Vec<Cloid> = strategy
+            .zones
+            .iter()
+            .take_while(|z| z.order_side.is_buy())
+            .filter_map(|z| z.cloid)
+            .collect();
+        let sell_zones: Vec<(f64, f64)> = strategy
+            .zones
+            .iter()
+            .skip(idle.len())
+            .map(|z| (z.buy_price, z.sell_price))
+            .collect();
+        let idle_upper = strategy.zones[idle.len() - 1].sell_price;
+        assert!(idle.len() > 2);
+        assert!(!sell_zones.is_empty());
+        ctx.order_queue.clear();
+
+        // Same candle again: nothing changes
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert!(ctx.cancellation_queue.is_empty());
+
+        // Volatility doubles -> 400 bips, idle zones are rebuilt wider
+        ctx.set_candles("HYPE/USDC", atr_candles(4, 4, 4.0));
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert_eq!(strategy.spread_bips, Some(400.0));
+        assert_eq!(ctx.cancellation_queue, idle);
+        assert!(idle.iter().all(|c| strategy.retired_orders.contains(c)));
+
+        let rebuilt = strategy.zones.len() - sell_zones.len();
+        assert!(rebuilt < idle.len());
+        assert_eq!(strategy.zones[0].buy_price, 90.0);
+        assert_eq!(strategy.zones[rebuilt - 1].sell_price, idle_upper);
+        let kept: Vec<(f64, f64)> = strategy.zones[rebuilt..]
+            .iter()
+            .map(|z| (z.buy_price, z.sell_price))
+            .collect();
+        assert_eq!(kept, sell_zones);
+        assert_eq!(strategy.grid_count as usize, strategy.zones.len() + 1);
+        assert_eq!(
+            ctx.order_queue
+                .iter()
+                .filter(|o| matches!(o, OrderRequest::Limit { side, .. } if side.is_buy()))
+                .count(),
+            rebuilt
+        );
+
+        // A retired order filling before its cancel landed still counts as inventory
+        let before = strategy.inventory_base;
+        let size = 0.5;
+        strategy
+            .on_order_filled(
+                &OrderFill {
+                    side: OrderSide::Buy,
+                    size,
+                    price: 95.0,
+                    fee: 0.0,
+                    cloid: Some(idle[0]),
+                    reduce_only: None,
+                    raw_dir: None,
+                },
+                &mut ctx,
+            )
+            .unwrap();
+        assert!((strategy.inventory_base - (before + size)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spot_grid_compounds_profit_into_buy_zones() {
+        let (mut strategy, mut ctx) = create_test_setup(None, 10.0, 1000.0, 100.0);
+        strategy.config.compound = Some(CompoundConfig::default());
+
+        // Zones: [90-95] [95-100] [100-105] buy, [105-110] sell
+        strategy.on_tick(100.0, &mut ctx).unwrap();
+        assert!(matches!(strategy.state, StrategyState::Running));
+        let sizes: Vec<f64> = strategy.zones.iter().map(|z| z.size).collect();
+        let buy_cloids: Vec<Cloid> = strategy.zones[..3]
+            .iter()
+            .map(|z| z.cloid.unwrap())
+            .collect();
+
+        let sell_cloid = strategy.zones[3].cloid.unwrap();
+        let entry = strategy.zones[3].entry_price;
+        let sold = 2.5;
+        let fee = 0.05;
+        strategy
+            .on_order_filled(
+                &OrderFill {
+                    side: OrderSide::Sell,
+                    size: sold,
+                    price: 110.0,
+                    fee,
+                    cloid: Some(sell_cloid),
+                    reduce_only: None,
+                    raw_dir: None,
+                },
+                &mut ctx,
+            )
+            .unwrap();
+
+        // Net profit is spread over all four buy zones, including the one just sold
+        let net = (110.0 - entry) * sold - fee;
+        assert!(net > 0.0);
+        assert!((strategy.compounded_profit + strategy.compound_pool - net).abs() < 1e-9);
+        assert!(strategy.compounded_profit > 0.0);
+        for (i, (zone, old_size)) in strategy.zones.iter().zip(&sizes).enumerate() {
+            assert!(zone.next_size() > *old_size, "zone {} not resized", i);
+            assert!(zone.order_side.is_buy());
+        }
+
+        // Resting buys keep their place in the book; the sold zone buys at the new size
+        assert!(ctx.cancellation_queue.is_empty());
+        for i in 0..3 {
+            assert_eq!(strategy.zones[i].cloid, Some(buy_cloids[i]));
+            assert_eq!(strategy.zones[i].size, sizes[i]);
+        }
+        let rebuy = strategy.zones[3].cloid.unwrap();
+        assert_eq!(strategy.active_orders.get(&rebuy), Some(&3));
+        assert!(strategy.zones[3].size > sizes[3]);
+        assert!(strategy.zones[3].pending_size.is_none());
+        let placed = |ctx: &StrategyContext, cloid: Cloid, size: f64| {
+            ctx.order_queue.iter().any(|o| {
+                matches!(o, OrderRequest::Limit { cloid: Some(c), sz, .. }
+                    if *c == cloid && *sz == size)
+            })
+        };
+        assert!(placed(&ctx, rebuy, strategy.zones[3].size));
+
+        // A resting buy fills at its old size and takes the new one on its next buy
+        let pending = strategy.zones[2].pending_size.unwrap();
+        let fill = |cloid, side, price, size| OrderFill {
+            side,
+            size,
+            price,
+            fee: 0.0,
+            cloid: Some(cloid),
+            reduce_only: None,
+            raw_dir: None,
+        };
+        strategy
+            .on_order_filled(
+                &fill(buy_cloids[2], OrderSide::Buy, 100.0, sizes[2]),
+                &mut ctx,
+            )
+            .unwrap();
+        assert_eq!(strategy.zones[2].size, sizes[2]);
+        let sell = strategy.zones[2].cloid.unwrap();
+        strategy
+            .on_order_filled(&fill(sell, OrderSide::Sell, 105.0, sizes[2]), &mut ctx)
+            .unwrap();
+        let rebuy = strategy.zones[2].cloid.unwrap();
+        assert!(strategy.zones[2].size >= pending);
+        assert!(placed(&ctx, rebuy, strategy.zones[2].size));
+    }
 }
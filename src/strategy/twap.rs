@@ -0,0 +1,713 @@
+use super::common;
+
+use crate::broadcast::types::{GridState, StrategySummary};
+use crate::config::strategy::{StrategyConfig, TwapConfig};
+
+use crate::constants::TWAP_IOC_SLIPPAGE;
+use crate::engine::context::{MarketInfo, StrategyContext, MIN_NOTIONAL_VALUE};
+use crate::model::{Cloid, OrderFill, OrderRequest, OrderSide};
+use crate::strategy::Strategy;
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StrategyState {
+    Initializing,
+    Running,
+    Completed,
+}
+
+/// Execution leg of the slice currently being worked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SlicePhase {
+    /// Resting limit order at (or better than) mid.
+    Passive,
+    /// IOC sweep for whatever the passive leg did not fill.
+    Aggressive,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveSlice {
+    cloid: Cloid,
+    phase: SlicePhase,
+    /// Slice size still to execute (both legs combined).
+    remaining: f64,
+    placed_at: Instant,
+    cancel_requested: bool,
+}
+
+#[derive(Clone)]
+pub struct TwapStrategy {
+    pub config: TwapConfig,
+
+    state: StrategyState,
+    start_time: Instant,
+    market_info: Option<MarketInfo>,
+
+    slice_count: u32,
+    slice_interval: Duration,
+    slice_timeout: Duration,
+    slices_completed: u32,
+    next_slice_at: Option<Instant>,
+    active_slice: Option<ActiveSlice>,
+
+    /// Traded volume counter at the start of the current participation window.
+    volume_mark: f64,
+
+    filled_size: f64,
+    filled_notional: f64,
+    total_fees: f64,
+    arrival_price: Option<f64>,
+    current_price: f64,
+}
+
+impl TwapStrategy {
+    pub fn new(config: TwapConfig) -> Self {
+        let slice_count = config.effective_slice_count();
+        let slice_interval = config.slice_interval();
+        let slice_timeout = config.slice_timeout();
+
+        Self {
+            config,
+            state: StrategyState::Initializing,
+            start_time: Instant::now(),
+            market_info: None,
+            slice_count,
+            slice_interval,
+            slice_timeout,
+            slices_completed: 0,
+            next_slice_at: None,
+            active_slice: None,
+            volume_mark: 0.0,
+            filled_size: 0.0,
+            filled_notional: 0.0,
+            total_fees: 0.0,
+            arrival_price: None,
+            current_price: 0.0,
+        }
+    }
+
+    fn remaining_size(&self) -> f64 {
+        (self.config.target_size - self.filled_size).max(0.0)
+    }
+
+    fn avg_fill_price(&self) -> Option<f64> {
+        if self.filled_size > 0.0 {
+            Some(self.filled_notional / self.filled_size)
+        } else {
+            None
+        }
+    }
+
+    /// Execution shortfall vs arrival mid in basis points. Positive means worse.
+    fn slippage_bps(&self) -> Option<f64> {
+        let avg = self.avg_fill_price()?;
+        let arrival = self.arrival_price.filter(|p| *p > 0.0)?;
+        let diff = match self.config.side {
+            OrderSide::Buy => avg - arrival,
+            OrderSide::Sell => arrival - avg,
+        };
+        Some(diff / arrival * 10_000.0)
+    }
+
+    fn price_within_limit(&self, price: f64) -> bool {
+        match (self.config.price_limit, self.config.side) {
+            (Some(limit), OrderSide::Buy) => price <= limit,
+            (Some(limit), OrderSide::Sell) => price >= limit,
+            (None, _) => true,
+        }
+    }
+
+    /// Clamp a price so it never crosses the configured limit.
+    fn bound_by_limit(&self, price: f64) -> f64 {
+        match (self.config.price_limit, self.config.side) {
+            (Some(limit), OrderSide::Buy) => price.min(limit),
+            (Some(limit), OrderSide::Sell) => price.max(limit),
+            (None, _) => price,
+        }
+    }
+
+    fn jittered_interval(&self) -> Duration {
+        if self.config.randomize_pct <= 0.0 {
+            return self.slice_interval;
+        }
+        let jitter = self.config.randomize_pct / 100.0;
+        let factor = 1.0 + rand::thread_rng().gen_range(-jitter..=jitter);
+        self.slice_interval.mul_f64(factor)
+    }
+
+    fn initialize(&mut self, price: f64, now: Instant, ctx: &mut StrategyContext) -> Result<()> {
+        self.config.validate().map_err(|e| anyhow!(e))?;
+
+        let info = ctx
+            .market_info(&self.config.symbol)
+            .ok_or_else(|| anyhow!("No market info for {}", self.config.symbol))?
+            .clone();
+
+        if let Some((base, quote)) = self.config.symbol.split_once('/') {
+            let (asset, needed, available) = match self.config.side {
+                OrderSide::Buy => (
+                    quote,
+                    self.config.target_size * self.bound_by_limit(price),
+                    ctx.get_spot_available(quote),
+                ),
+                OrderSide::Sell => (base, self.config.target_size, ctx.get_spot_available(base)),
+            };
+            if available < needed {
+                warn!(
+                    "[TWAP] Available {} {:.6} is below the {:.6} needed to complete the target.",
+                    asset, available, needed
+                );
+            }
+        }
+
+        info!(
+            "[TWAP] {} {} {} over {} slices every {:.1}s (arrival mid {})",
+            self.config.side,
+            self.config.target_size,
+            self.config.symbol,
+            self.slice_count,
+            self.slice_interval.as_secs_f64(),
+            price
+        );
+
+        self.market_info = Some(info);
+        self.arrival_price = Some(price);
+        self.volume_mark = ctx.get_traded_volume(&self.config.symbol);
+        self.next_slice_at = Some(now);
+        self.state = StrategyState::Running;
+        Ok(())
+    }
+
+    fn advance(&mut self, price: f64, now: Instant, ctx: &mut StrategyContext) {
+        if let Some(slice) = self.active_slice.as_mut() {
+            if slice.phase == SlicePhase::Passive
+                && !slice.cancel_requested
+                && now.duration_since(slice.placed_at) >= self.slice_timeout
+            {
+                info!(
+                    "[TWAP] Slice {} passive leg timed out. Cancelling {}.",
+                    self.slices_completed + 1,
+                    slice.cloid
+                );
+                ctx.cancel_order(slice.cloid);
+                slice.cancel_requested = true;
+            }
+            return;
+        }
+
+        let info = match &self.market_info {
+            Some(info) => info.clone(),
+            None => return,
+        };
+
+        let remaining = info.round_size(self.remaining_size());
+        if remaining <= 0.0 || remaining * price < MIN_NOTIONAL_VALUE {
+            self.complete(remaining);
+            return;
+        }
+
+        if self.next_slice_at.is_some_and(|at| now < at) {
+            return;
+        }
+
+        if !self.price_within_limit(price) {
+            debug!(
+                "[TWAP] Mid {} is beyond price limit {:?}. Holding slice.",
+                price, self.config.price_limit
+            );
+            return;
+        }
+
+        let slices_left = self
+            .slice_count
+            .saturating_sub(self.slices_completed)
+            .max(1);
+        let mut size = remaining / slices_left as f64;
+
+        let min_size = info.clamp_to_min_notional(0.0, price, MIN_NOTIONAL_VALUE);
+        if let Some(pct) = self.config.max_participation_pct {
+            let observed = ctx.get_traded_volume(&self.config.symbol) - self.volume_mark;
+            let cap = info.round_size(observed * pct / 100.0);
+            if cap < min_size {
+                debug!(
+                    "[TWAP] Participation cap {} below minimum slice {} (observed volume {}). Waiting.",
+                    cap, min_size, observed
+                );
+                return;
+            }
+            size = size.min(cap);
+        }
+
+        size = info.round_size(size.max(min_size)).min(remaining);
+        // Don't strand a remainder that would be too small to trade on its own
+        if (remaining - size) * price < MIN_NOTIONAL_VALUE {
+            size = remaining;
+        }
+
+        let limit_px = info.round_price(self.bound_by_limit(price));
+        let cloid = ctx.place_order(OrderRequest::Limit {
+            symbol: self.config.symbol.clone(),
+            side: self.config.side,
+            price: limit_px,
+            sz: size,
+            reduce_only: self.config.reduce_only,
+            cloid: None,
+        });
+
+        info!(
+            "[TWAP] Slice {}/{}: {} {} @ {} (remaining {})",
+            self.slices_completed + 1,
+            self.slice_count,
+            self.config.side,
+            size,
+            limit_px,
+            remaining
+        );
+
+        self.active_slice = Some(ActiveSlice {
+            cloid,
+            phase: SlicePhase::Passive,
+            remaining: size,
+            placed_at: now,
+            cancel_requested: false,
+        });
+        self.volume_mark = ctx.get_traded_volume(&self.config.symbol);
+        self.next_slice_at = Some(now + self.jittered_interval());
+    }
+
+    /// Send the unfilled part of the passive leg as an IOC bounded by the price limit.
+    fn escalate(&mut self, ctx: &mut StrategyContext) {
+        let (info, slice_remaining) = match (&self.market_info, &self.active_slice) {
+            (Some(info), Some(slice)) => (info.clone(), slice.remaining),
+            _ => return,
+        };
+
+        let price = self.current_price;
+        let size = info.round_size(slice_remaining.min(self.remaining_size()));
+        if size * price < MIN_NOTIONAL_VALUE || !self.price_within_limit(price) {
+            self.finish_slice();
+            return;
+        }
+
+        let crossing_px = match self.config.side {
+            OrderSide::Buy => TWAP_IOC_SLIPPAGE.markup(price),
+            OrderSide::Sell => TWAP_IOC_SLIPPAGE.markdown(price),
+        };
+        let limit_px = info.round_price(self.bound_by_limit(crossing_px));
+
+        let cloid = ctx.place_order(OrderRequest::Market {
+            symbol: self.config.symbol.clone(),
+            side: self.config.side,
+            sz: size,
+            reduce_only: self.config.reduce_only,
+            limit_px: Some(limit_px),
+            cloid: None,
+        });
+
+        info!(
+            "[TWAP] Slice {} IOC: {} {} limit {}",
+            self.slices_completed + 1,
+            self.config.side,
+            size,
+            limit_px
+        );
+
+        if let Some(slice) = self.active_slice.as_mut() {
+            slice.cloid = cloid;
+            slice.phase = SlicePhase::Aggressive;
+        }
+    }
+
+    fn finish_slice(&mut self) {
+        self.active_slice = None;
+        self.slices_completed += 1;
+
+        let remaining = match &self.market_info {
+            Some(info) => info.round_size(self.remaining_size()),
+            None => self.remaining_size(),
+        };
+        if remaining <= 0.0 || remaining * self.current_price < MIN_NOTIONAL_VALUE {
+            self.complete(remaining);
+        }
+    }
+
+    fn complete(&mut self, remaining: f64) {
+        if self.state == StrategyState::Completed {
+            return;
+        }
+        self.state = StrategyState::Completed;
+        info!(
+            "[TWAP] Completed: filled {} of {} (avg {:?}, slippage {:?} bps, unfilled {})",
+            self.filled_size,
+            self.config.target_size,
+            self.avg_fill_price(),
+            self.slippage_bps(),
+            remaining
+        );
+    }
+
+    fn tick_at(&mut self, price: f64, now: Instant, ctx: &mut StrategyContext) -> Result<()> {
+        self.current_price = price;
+
+        match self.state {
+            StrategyState::Initializing => {
+                self.initialize(price, now, ctx)?;
+                self.advance(price, now, ctx);
+            }
+            StrategyState::Running => self.advance(price, now, ctx),
+            StrategyState::Completed => {}
+        }
+        Ok(())
+    }
+}
+
+impl Strategy for TwapStrategy {
+    fn on_tick(&mut self, price: f64, ctx: &mut StrategyContext) -> Result<()> {
+        self.tick_at(price, Instant::now(), ctx)
+    }
+
+    fn on_order_filled(&mut self, fill: &OrderFill, ctx: &mut StrategyContext) -> Result<()> {
+        let Some(slice) = self.active_slice.as_mut() else {
+            debug!(
+                "[TWAP] Fill received with no active slice: {:?}",
+                fill.cloid
+            );
+            return Ok(());
+        };
+        if fill.cloid != Some(slice.cloid) {
+            debug!("[TWAP] Fill received for unknown CLOID: {:?}", fill.cloid);
+            return Ok(());
+        }
+
+        slice.remaining = (slice.remaining - fill.size).max(0.0);
+        let phase = slice.phase;
+        let cancel_requested = slice.cancel_requested;
+
+        self.filled_size += fill.size;
+        self.filled_notional += fill.size * fill.price;
+        self.total_fees += fill.fee;
+
+        info!(
+            "[TWAP] Filled {} @ {} ({:.2}% of target)",
+            fill.size,
+            fill.price,
+            self.filled_size / self.config.target_size * 100.0
+        );
+
+        // A fill on a cancelled passive leg is the partial amount; sweep the rest.
+        if phase == SlicePhase::Passive && cancel_requested {
+            self.escalate(ctx);
+        } else {
+            self.finish_slice();
+        }
+        Ok(())
+    }
+
+    fn on_order_failed(&mut self, cloid: Cloid, ctx: &mut StrategyContext) -> Result<()> {
+        let phase = match &self.active_slice {
+            Some(slice) if slice.cloid == cloid => slice.phase,
+            _ => return Ok(()),
+        };
+
+        match phase {
+            SlicePhase::Passive => self.escalate(ctx),
+            SlicePhase::Aggressive => {
+                warn!(
+                    "[TWAP] IOC leg {} did not fill. Moving to next slice.",
+                    cloid
+                );
+                self.finish_slice();
+            }
+        }
+        Ok(())
+    }
+
+    fn reconfigure(&mut self, config: &StrategyConfig, _ctx: &mut StrategyContext) -> Result<()> {
+        let StrategyConfig::Twap(new) = config else {
+            return Err(anyhow!("TWAP only reloads a twap config"));
+        };
+        let mut config = self.config.clone();
+        config.price_limit = new.price_limit;
+        config.max_participation_pct = new.max_participation_pct;
+        config.validate().map_err(|e| anyhow!(e))?;
+        info!(
+            "[TWAP] Limits updated: price_limit={:?} max_participation_pct={:?}",
+            config.price_limit, config.max_participation_pct
+        );
+        // Takes effect from the next slice
+        self.config = config;
+        Ok(())
+    }
+
+    fn get_summary(&self, _ctx: &StrategyContext) -> StrategySummary {
+        use crate::broadcast::types::TwapSummary;
+
+        StrategySummary::Twap(TwapSummary {
+            symbol: self.config.symbol.clone(),
+            state: format!("{:?}", self.state),
+            uptime: common::format_uptime(self.start_time.elapsed()),
+            side: self.config.side.to_string(),
+            target_size: self.config.target_size,
+            filled_size: self.filled_size,
+            remaining_size: self.remaining_size(),
+            progress_pct: self.filled_size / self.config.target_size * 100.0,
+            slices_completed: self.slices_completed,
+            slice_count: self.slice_count,
+            avg_fill_price: self.avg_fill_price(),
+            arrival_price: self.arrival_price,
+            slippage_bps: self.slippage_bps(),
+            total_fees: self.total_fees,
+        })
+    }
+
+    fn get_grid_state(&self, _ctx: &StrategyContext) -> GridState {
+        GridState {
+            symbol: self.config.symbol.clone(),
+            strategy_type: "twap".to_string(),
+            grid_bias: None,
+            zones: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_test_setup(config: TwapConfig) -> (TwapStrategy, StrategyContext) {
+        let strategy = TwapStrategy::new(config);
+        let mut markets = HashMap::new();
+        markets.insert(
+            "HYPE/USDC".to_string(),
+            MarketInfo::new("HYPE/USDC".to_string(), "@107".to_string(), 0, 2, 2),
+        );
+        let mut ctx = StrategyContext::new(markets);
+        ctx.update_spot_balance("HYPE".to_string(), 1000.0, 1000.0);
+        ctx.update_spot_balance("USDC".to_string(), 100_000.0, 100_000.0);
+        (strategy, ctx)
+    }
+
+    fn base_config() -> TwapConfig {
+        TwapConfig {
+            symbol: "HYPE/USDC".to_string(),
+            side: OrderSide::Buy,
+            target_size: 100.0,
+            duration_secs: Some(400),
+            slice_count: Some(4),
+            randomize_pct: 0.0,
+            price_limit: None,
+            max_participation_pct: None,
+            slice_timeout_secs: Some(30),
+            reduce_only: false,
+        }
+    }
+
+    fn fill(cloid: Cloid, size: f64, price: f64) -> OrderFill {
+        OrderFill {
+            side: OrderSide::Buy,
+            size,
+            price,
+            fee: 0.01,
+            cloid: Some(cloid),
+            reduce_only: None,
+            raw_dir: None,
+        }
+    }
+
+    fn last_order(ctx: &mut StrategyContext) -> OrderRequest {
+        ctx.order_queue.pop().expect("expected an order")
+    }
+
+    #[test]
+    fn test_twap_places_slices_on_schedule() {
+        let (mut strategy, mut ctx) = create_test_setup(base_config());
+        let t0 = Instant::now();
+
+        strategy.tick_at(20.0, t0, &mut ctx).unwrap();
+        let cloid = match last_order(&mut ctx) {
+            OrderRequest::Limit {
+                price, sz, cloid, ..
+            } => {
+                assert_eq!(price, 20.0);
+                assert_eq!(sz, 25.0);
+                cloid.unwrap()
+            }
+            other => panic!("Expected limit slice, got {:?}", other),
+        };
+
+        strategy
+            .on_order_filled(&fill(cloid, 25.0, 20.0), &mut ctx)
+            .unwrap();
+        assert_eq!(strategy.slices_completed, 1);
+
+        // Next slice is not due until the interval elapses
+        strategy
+            .tick_at(20.0, t0 + Duration::from_secs(50), &mut ctx)
+            .unwrap();
+        assert!(ctx.order_queue.is_empty());
+
+        strategy
+            .tick_at(20.0, t0 + Duration::from_secs(100), &mut ctx)
+            .unwrap();
+        match last_order(&mut ctx) {
+            OrderRequest::Limit { sz, .. } => assert_eq!(sz, 25.0),
+            other => panic!("Expected limit slice, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_twap_escalates_unfilled_remainder_to_ioc() {
+        let config = TwapConfig {
+            price_limit: Some(20.05),
+            ..base_config()
+        };
+        let (mut strategy, mut ctx) = create_test_setup(config);
+        let t0 = Instant::now();
+
+        strategy.tick_at(20.0, t0, &mut ctx).unwrap();
+        let passive = match last_order(&mut ctx) {
+            OrderRequest::Limit { cloid, .. } => cloid.unwrap(),
+            other => panic!("Expected limit slice, got {:?}", other),
+        };
+
+        // Timeout: passive leg is cancelled
+        strategy
+            .tick_at(20.0, t0 + Duration::from_secs(31), &mut ctx)
+            .unwrap();
+        assert_eq!(ctx.cancellation_queue, vec![passive]);
+
+        // Engine reports the partial fill of the cancelled order
+        strategy
+            .on_order_filled(&fill(passive, 10.0, 20.0), &mut ctx)
+            .unwrap();
+        match last_order(&mut ctx) {
+            OrderRequest::Market { sz, limit_px, .. } => {
+                assert_eq!(sz, 15.0);
+                // 0.5% crossing allowance capped by the price limit
+                assert_eq!(limit_px, Some(20.05));
+            }
+            other => panic!("Expected IOC remainder, got {:?}", other),
+        }
+        assert_eq!(strategy.slices_completed, 0);
+    }
+
+    #[test]
+    fn test_twap_holds_slice_beyond_price_limit() {
+        let config = TwapConfig {
+            price_limit: Some(19.5),
+            ..base_config()
+        };
+        let (mut strategy, mut ctx) = create_test_setup(config);
+        let t0 = Instant::now();
+
+        strategy.tick_at(20.0, t0, &mut ctx).unwrap();
+        assert!(ctx.order_queue.is_empty());
+
+        strategy
+            .tick_at(19.4, t0 + Duration::from_secs(1), &mut ctx)
+            .unwrap();
+        match last_order(&mut ctx) {
+            OrderRequest::Limit { price, .. } => assert_eq!(price, 19.4),
+            other => panic!("Expected limit slice, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_twap_reconfigure_moves_price_limit() {
+        let config = TwapConfig {
+            price_limit: Some(19.5),
+            ..base_config()
+        };
+        let (mut strategy, mut ctx) = create_test_setup(config.clone());
+        let t0 = Instant::now();
+
+        strategy.tick_at(20.0, t0, &mut ctx).unwrap();
+        assert!(ctx.order_queue.is_empty());
+
+        let raised = TwapConfig {
+            price_limit: Some(20.5),
+            ..config
+        };
+        strategy
+            .reconfigure(&StrategyConfig::Twap(raised), &mut ctx)
+            .unwrap();
+        strategy
+            .tick_at(20.0, t0 + Duration::from_secs(1), &mut ctx)
+            .unwrap();
+        match last_order(&mut ctx) {
+            OrderRequest::Limit { price, .. } => assert_eq!(price, 20.0),
+            other => panic!("Expected limit slice, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_twap_participation_cap_limits_slice_size() {
+        let config = TwapConfig {
+            max_participation_pct: Some(10.0),
+            ..base_config()
+        };
+        let (mut strategy, mut ctx) = create_test_setup(config);
+        let t0 = Instant::now();
+
+        // No observed volume yet: nothing is placed
+        strategy.tick_at(20.0, t0, &mut ctx).unwrap();
+        assert!(ctx.order_queue.is_empty());
+
+        ctx.record_trade_volume("HYPE/USDC", 80.0);
+        strategy
+            .tick_at(20.0, t0 + Duration::from_secs(1), &mut ctx)
+            .unwrap();
+        match last_order(&mut ctx) {
+            OrderRequest::Limit { sz, .. } => assert_eq!(sz, 8.0),
+            other => panic!("Expected limit slice, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_twap_summary_reports_progress_and_slippage() {
+        let config = TwapConfig {
+            slice_count: Some(2),
+            target_size: 10.0,
+            ..base_config()
+        };
+        let (mut strategy, mut ctx) = create_test_setup(config);
+        let t0 = Instant::now();
+
+        strategy.tick_at(20.0, t0, &mut ctx).unwrap();
+        let first = match last_order(&mut ctx) {
+            OrderRequest::Limit { cloid, .. } => cloid.unwrap(),
+            other => panic!("Expected limit slice, got {:?}", other),
+        };
+        strategy
+            .on_order_filled(&fill(first, 5.0, 20.0), &mut ctx)
+            .unwrap();
+
+        strategy
+            .tick_at(20.4, t0 + Duration::from_secs(200), &mut ctx)
+            .unwrap();
+        let second = match last_order(&mut ctx) {
+            OrderRequest::Limit { cloid, .. } => cloid.unwrap(),
+            other => panic!("Expected limit slice, got {:?}", other),
+        };
+        strategy
+            .on_order_filled(&fill(second, 5.0, 20.4), &mut ctx)
+            .unwrap();
+
+        assert_eq!(strategy.state, StrategyState::Completed);
+        match strategy.get_summary(&ctx) {
+            StrategySummary::Twap(s) => {
+                assert_eq!(s.filled_size, 10.0);
+                assert!((s.progress_pct - 100.0).abs() < 1e-9);
+                assert!((s.avg_fill_price.unwrap() - 20.2).abs() < 1e-9);
+                assert_eq!(s.arrival_price, Some(20.0));
+                assert!((s.slippage_bps.unwrap() - 100.0).abs() < 1e-6);
+                assert_eq!(s.slices_completed, 2);
+            }
+            other => panic!("Expected TWAP summary, got {:?}", other),
+        }
+    }
+}
@@ -8,6 +8,76 @@ pub enum GridType {
     Geometric,
 }
 
+/// How level spacing is chosen.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SpacingMode {
+    /// Spacing from `grid_count` or `spread_bips`.
+    #[default]
+    Fixed,
+    /// Spacing from the average true range of recent candles.
+    Atr,
+}
+
+/// Which zones receive reinvested profit.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CompoundAllocation {
+    /// Every waiting zone grows in proportion to its current notional.
+    #[default]
+    Proportional,
+    /// Only the waiting zones closest to price grow.
+    Nearest,
+}
+
+/// Preset shapes for per-zone order size.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DistributionShape {
+    /// Every zone gets the same notional.
+    #[default]
+    Flat,
+    /// Weight grows by one step per level away from the entry price.
+    Linear,
+    /// Weight grows by a constant ratio per level away from the entry price.
+    Geometric,
+    /// Weight grows with the square of the level distance from the entry price.
+    Pyramid,
+}
+
+/// How `total_investment` is split across zones: a preset shape or one weight per zone.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum SizeDistribution {
+    Shape(DistributionShape),
+    /// Relative weights, one per zone from the lowest zone up.
+    Weights(Vec<f64>),
+}
+
+impl Default for SizeDistribution {
+    fn default() -> Self {
+        SizeDistribution::Shape(DistributionShape::Flat)
+    }
+}
+
+impl SizeDistribution {
+    pub fn is_flat(&self) -> bool {
+        matches!(self, SizeDistribution::Shape(DistributionShape::Flat))
+    }
+}
+
+impl std::fmt::Display for SizeDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizeDistribution::Shape(DistributionShape::Flat) => write!(f, "flat"),
+            SizeDistribution::Shape(DistributionShape::Linear) => write!(f, "linear"),
+            SizeDistribution::Shape(DistributionShape::Geometric) => write!(f, "geometric"),
+            SizeDistribution::Shape(DistributionShape::Pyramid) => write!(f, "pyramid"),
+            SizeDistribution::Weights(w) => write!(f, "weights ({} zones)", w.len()),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum GridBias {
@@ -1,8 +1,17 @@
 //! Console renderer for simulation dry-run output.
 
-use crate::broadcast::types::{GridState, PerpGridSummary, SpotGridSummary, StrategySummary};
-use crate::config::strategy::StrategyConfig;
-use crate::model::OrderRequest;
+use crate::broadcast::types::{
+    GridState, PerpGridSummary, RebalanceSummary, SpotGridSummary, StrategySummary, TwapSummary,
+};
+use crate::config::accounts::AccountSummary;
+use crate::config::strategy::{
+    CompoundAllocation, CompoundConfig, GridRangeConfig, SpacingMode, StrategyConfig,
+};
+use crate::constants::MAX_FEE_SHARE_OF_SPACING;
+use crate::engine::preflight::{CheckStatus, PreflightReport};
+use crate::journal::JournalEntry;
+use crate::model::{FeeRates, OrderRequest};
+use crate::strategy::common::GridRecommendation;
 
 /// Console renderer for simulation dry-run reports.
 pub struct ConsoleRenderer;
@@ -54,6 +63,205 @@ impl ConsoleRenderer {
         println!();
     }
 
+    /// Render a grid spacing recommendation for `--recommend`.
+    pub fn render_recommendation(
+        config: &StrategyConfig,
+        fees: &FeeRates,
+        rec: &GridRecommendation,
+    ) {
+        println!();
+        println!("{}", "=".repeat(60));
+        println!(" GRID RECOMMENDATION: {}", config.symbol());
+        println!("{}", "=".repeat(60));
+        println!();
+        println!(
+            "Maker Fee:   {:.4}% ({:.4}% per roundtrip)",
+            fees.maker * 100.0,
+            fees.roundtrip_maker_pct()
+        );
+        if let Some(check) = config.fee_check(fees) {
+            println!(
+                "Current:     {:.4}% spacing, {:.4}% net per roundtrip{}",
+                check.spacing_pct,
+                check.net_pct(),
+                if check.net_pct() <= 0.0 {
+                    " (LOSES MONEY)"
+                } else if check.is_marginal() {
+                    " (marginal)"
+                } else {
+                    ""
+                }
+            );
+        }
+        println!(
+            "Max Zones:   {} by min notional, {} by fees (<= {:.0}% of spacing)",
+            rec.max_zones_by_notional,
+            rec.max_zones_by_fees,
+            MAX_FEE_SHARE_OF_SPACING * 100.0
+        );
+        println!();
+        println!("{}", "-".repeat(60));
+        println!("grid_count = {}", rec.grid_count);
+        if let Some(bips) = rec.spread_bips {
+            println!("# or: spread_bips = {:.1}", bips);
+        }
+        println!("{}", "-".repeat(60));
+        println!();
+        println!(
+            "Spacing:     {:.4}% - {:.4}%",
+            rec.spacing_pct.0, rec.spacing_pct.1
+        );
+        println!("Per Zone:    {:.2}", rec.investment_per_zone);
+        println!("Net/RT:      {:.4}% (tightest zone)", rec.net_profit_pct);
+        println!();
+        println!("{}", "=".repeat(60));
+        println!();
+    }
+
+    /// Render the preflight report of `--check` and live startup.
+    pub fn render_preflight(config: &StrategyConfig, account: &str, report: &PreflightReport) {
+        Self::render_checks(
+            &format!(
+                "PREFLIGHT CHECK: {} on account '{}'",
+                config.markets().join(", "),
+                account
+            ),
+            report,
+        );
+    }
+
+    /// Render the report of `accounts validate`.
+    pub fn render_account_check(account: &str, report: &PreflightReport) {
+        Self::render_checks(&format!("ACCOUNT CHECK: '{}'", account), report);
+    }
+
+    fn render_checks(title: &str, report: &PreflightReport) {
+        println!();
+        println!("{}", "=".repeat(60));
+        println!(" {}", title);
+        println!("{}", "=".repeat(60));
+        println!();
+        for check in &report.checks {
+            println!(
+                "[{}] {:<12} {}",
+                check.status.label(),
+                check.name,
+                check.detail
+            );
+        }
+        println!();
+        println!("{}", "-".repeat(60));
+        let warnings = report.count(CheckStatus::Warn);
+        if report.passed() {
+            println!("Result:      PASS ({} warnings)", warnings);
+        } else {
+            println!(
+                "Result:      FAIL ({} failed, {} warnings)",
+                report.count(CheckStatus::Fail),
+                warnings
+            );
+        }
+        println!("{}", "=".repeat(60));
+        println!();
+    }
+
+    /// Render the profiles of `accounts list`.
+    pub fn render_accounts(path: &str, accounts: &[AccountSummary]) {
+        println!("Accounts file: {}", path);
+        for a in accounts {
+            println!();
+            println!("[{}] {}", a.name, a.network);
+            println!("  Master:      {}", a.master_account_address);
+            println!(
+                "  Sub-account: {}",
+                a.sub_account_address.as_deref().unwrap_or("-")
+            );
+            println!("  Key:         {}", a.key_source);
+            println!(
+                "  API wallet:  {}",
+                a.api_wallet
+                    .as_deref()
+                    .unwrap_or("unknown until `accounts validate`")
+            );
+            if let Some(problem) = &a.problem {
+                println!("  PROBLEM:     {}", problem);
+            }
+        }
+        println!();
+        println!("{} accounts", accounts.len());
+    }
+
+    /// Render trade journal entries for the `journal` subcommand.
+    pub fn render_journal(entries: &[JournalEntry]) {
+        println!(
+            "{:<6} {:<14} {:<16} {:<18} {:<12} {:<5} {:>12} {:>14} {:>10} {:<34} {:>12}  DETAILS",
+            "ID",
+            "TIME_MS",
+            "BOT",
+            "EVENT",
+            "SYMBOL",
+            "SIDE",
+            "SIZE",
+            "PRICE",
+            "FEE",
+            "CLOID",
+            "OID"
+        );
+        let opt = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+        for e in entries {
+            println!(
+                "{:<6} {:<14} {:<16} {:<18} {:<12} {:<5} {:>12} {:>14} {:>10} {:<34} {:>12}  {}",
+                e.id,
+                e.timestamp_ms,
+                e.bot,
+                e.event,
+                e.symbol,
+                e.side
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                opt(e.size),
+                opt(e.price),
+                opt(e.fee),
+                e.cloid.as_deref().unwrap_or("-"),
+                e.oid
+                    .map(|o| o.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                [e.raw_dir.as_deref(), e.details.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        println!("{} entries", entries.len());
+    }
+
+    fn render_ranges(ranges: &[GridRangeConfig]) {
+        println!("Grid Mode:   ranges ({})", ranges.len());
+        for r in ranges {
+            let spacing = match (r.grid_count, r.spread_bips) {
+                (Some(count), _) => format!("{} levels {:?}", count, r.grid_type),
+                (None, Some(bips)) => format!("{} bips", bips),
+                (None, None) => "-".to_string(),
+            };
+            println!(
+                "  Range:     {:.6} - {:.6} | {} | {:.2}",
+                r.low, r.high, spacing, r.investment
+            );
+        }
+    }
+
+    fn render_compound(compound: &CompoundConfig) {
+        let allocation = match compound.allocation {
+            CompoundAllocation::Proportional => "proportional".to_string(),
+            CompoundAllocation::Nearest => format!("nearest {} zones", compound.nearest_zones),
+        };
+        match compound.interval_secs {
+            Some(secs) => println!("Compound:    {} every {}s", allocation, secs),
+            None => println!("Compound:    {} every roundtrip", allocation),
+        }
+    }
+
     /// Render strategy configuration.
     fn render_config(config: &StrategyConfig, grid_len: Option<usize>) {
         println!("CONFIGURATION");
@@ -64,7 +272,19 @@ impl ConsoleRenderer {
                 println!("Type:        spot_grid");
                 println!("Total Inv:   {:.3}", c.total_investment);
 
-                if let Some(spread) = c.spread_bips {
+                if c.spacing_mode == SpacingMode::Atr {
+                    println!(
+                        "Grid Mode:   atr ({} x {} candles, {}x ATR)",
+                        c.atr.candle_interval, c.atr.lookback, c.atr.multiplier
+                    );
+                    if let Some(secs) = c.atr.recompute_secs {
+                        println!("Recompute:   every {}s", secs);
+                    }
+                } else if let Some(levels) = &c.levels {
+                    println!("Grid Mode:   levels ({} explicit)", levels.len());
+                } else if let Some(ranges) = &c.ranges {
+                    Self::render_ranges(ranges);
+                } else if let Some(spread) = c.spread_bips {
                     println!("Grid Mode:   spread_bips (geometric)");
                     println!("Spread:      {} bips", spread);
                 } else {
@@ -85,6 +305,14 @@ impl ConsoleRenderer {
                 if let Some(trigger) = c.trigger_price {
                     println!("Trigger:     {:.6}", trigger);
                 }
+
+                if !c.size_distribution.is_flat() {
+                    println!("Sizing:      {}", c.size_distribution);
+                }
+
+                if let Some(compound) = &c.compound {
+                    Self::render_compound(compound);
+                }
             }
             StrategyConfig::PerpGrid(c) => {
                 println!("Symbol:      {}", c.symbol);
@@ -93,7 +321,19 @@ impl ConsoleRenderer {
                 println!("Total Inv:   {:.3}", c.total_investment);
                 println!("Leverage:    {}x", c.leverage);
 
-                if let Some(spread) = c.spread_bips {
+                if c.spacing_mode == SpacingMode::Atr {
+                    println!(
+                        "Grid Mode:   atr ({} x {} candles, {}x ATR)",
+                        c.atr.candle_interval, c.atr.lookback, c.atr.multiplier
+                    );
+                    if let Some(secs) = c.atr.recompute_secs {
+                        println!("Recompute:   every {}s", secs);
+                    }
+                } else if let Some(levels) = &c.levels {
+                    println!("Grid Mode:   levels ({} explicit)", levels.len());
+                } else if let Some(ranges) = &c.ranges {
+                    Self::render_ranges(ranges);
+                } else if let Some(spread) = c.spread_bips {
                     println!("Grid Mode:   spread_bips (geometric)");
                     println!("Spread:      {} bips", spread);
                 } else {
@@ -114,6 +354,67 @@ impl ConsoleRenderer {
                 if let Some(trigger) = c.trigger_price {
                     println!("Trigger:     {:.6}", trigger);
                 }
+
+                if !c.size_distribution.is_flat() {
+                    println!("Sizing:      {}", c.size_distribution);
+                }
+
+                if let Some(compound) = &c.compound {
+                    Self::render_compound(compound);
+                }
+            }
+            StrategyConfig::Twap(c) => {
+                println!("Symbol:      {}", c.symbol);
+                println!("Type:        twap");
+                println!("Side:        {}", c.side);
+                println!("Target Size: {:.6}", c.target_size);
+                println!(
+                    "Slices:      {} every {:.1}s",
+                    c.effective_slice_count(),
+                    c.slice_interval().as_secs_f64()
+                );
+                if c.randomize_pct > 0.0 {
+                    println!("Jitter:      ±{}%", c.randomize_pct);
+                }
+                if let Some(limit) = c.price_limit {
+                    println!("Price Limit: {:.6}", limit);
+                }
+                if let Some(pct) = c.max_participation_pct {
+                    println!("Max Part.:   {}%", pct);
+                }
+                if c.reduce_only {
+                    println!("Reduce Only: true");
+                }
+            }
+            StrategyConfig::Rebalance(c) => {
+                println!("Quote:       {}", c.quote_asset);
+                println!("Type:        rebalance");
+                for (asset, weight) in c.normalized_targets() {
+                    println!("Target:      {:<8} {:.2}%", asset, weight * 100.0);
+                }
+                if let Some(band) = c.drift_band_pct {
+                    println!("Drift Band:  {}%", band);
+                }
+                if let Some(interval) = c.rebalance_interval_secs {
+                    println!("Schedule:    every {}s", interval);
+                }
+                println!("Post Offset: {} bips", c.post_only_offset_bips);
+            }
+            StrategyConfig::InfinityGrid(c) => {
+                println!("Symbol:      {}", c.symbol);
+                println!("Type:        infinity_grid");
+                println!("Lower Bound: {:.6}", c.grid_range_low);
+                println!("Spread:      {} bips", c.spread_bips);
+                println!("Level Value: {:.2}", c.level_value);
+                println!("Above Price: {} zones", c.levels_above);
+                println!("Investment:  {:.2}", c.total_investment);
+                println!("Max Zones:   {}", c.level_cap());
+                if let Some(len) = grid_len {
+                    println!("Grid Count:  {}", len);
+                }
+                if let Some(trigger) = c.trigger_price {
+                    println!("Trigger:     {:.6}", trigger);
+                }
             }
         }
     }
@@ -123,6 +424,8 @@ impl ConsoleRenderer {
         match summary {
             StrategySummary::SpotGrid(s) => Self::render_spot_summary(s),
             StrategySummary::PerpGrid(s) => Self::render_perp_summary(s),
+            StrategySummary::Twap(s) => Self::render_twap_summary(s),
+            StrategySummary::Rebalance(s) => Self::render_rebalance_summary(s),
         }
     }
 
@@ -170,6 +473,47 @@ impl ConsoleRenderer {
         println!("Leverage: {}x", s.leverage);
     }
 
+    fn render_twap_summary(s: &TwapSummary) {
+        println!("STRATEGY: {}", s.symbol);
+        println!("State:    {}", s.state);
+        println!("Type:     TWAP ({})", s.side);
+        println!(
+            "Progress: {:.6} / {:.6} ({:.2}%)",
+            s.filled_size, s.target_size, s.progress_pct
+        );
+        println!("Slices:   {} / {}", s.slices_completed, s.slice_count);
+        if let Some(arrival) = s.arrival_price {
+            println!("Arrival:  {:.6}", arrival);
+        }
+        if let Some(avg) = s.avg_fill_price {
+            println!("Avg Fill: {:.6}", avg);
+        }
+        if let Some(slippage) = s.slippage_bps {
+            println!("Slippage: {:.2} bps", slippage);
+        }
+    }
+
+    fn render_rebalance_summary(s: &RebalanceSummary) {
+        println!("STRATEGY: Rebalance ({})", s.quote_asset);
+        println!("State:    {}", s.state);
+        println!("Value:    {:.2} {}", s.portfolio_value, s.quote_asset);
+        println!("Drift:    {:.2}%", s.max_drift_pct);
+        println!(
+            "Rebal.:   {} (traded {:.2}, fees {:.4})",
+            s.rebalance_count, s.traded_notional, s.total_fees
+        );
+        println!(
+            "{:<8} | {:<14} | {:<12} | {:<14} | {:<8} | TARGET",
+            "ASSET", "BALANCE", "PRICE", "VALUE", "WEIGHT"
+        );
+        for a in &s.allocations {
+            println!(
+                "{:<8} | {:<14.6} | {:<12.6} | {:<14.2} | {:<7.2}% | {:.2}%",
+                a.asset, a.balance, a.price, a.value, a.current_weight_pct, a.target_weight_pct
+            );
+        }
+    }
+
     /// Render grid state.
     fn render_grid(g: &GridState) {
         println!("GRID STATE ({} Zones)", g.zones.len());
@@ -242,8 +586,20 @@ impl ConsoleRenderer {
                     let ro_tag = if *reduce_only { " [ReduceOnly]" } else { "" };
                     println!("  [ORDER] {:?} {:.6} @ {:.6}{}", side, sz, price, ro_tag);
                 }
-                OrderRequest::Market { side, sz, .. } => {
-                    println!("  [ORDER] {:?} {:.6} @ MARKET", side, sz);
+                OrderRequest::Market {
+                    side,
+                    sz,
+                    reduce_only,
+                    limit_px,
+                    ..
+                } => {
+                    let ro_tag = if *reduce_only { " [ReduceOnly]" } else { "" };
+                    match limit_px {
+                        Some(px) => {
+                            println!("  [ORDER] {:?} {:.6} @ IOC {:.6}{}", side, sz, px, ro_tag)
+                        }
+                        None => println!("  [ORDER] {:?} {:.6} @ MARKET{}", side, sz, ro_tag),
+                    }
                 }
                 OrderRequest::Cancel { cloid } => {
                     println!("  [CANCEL] CLOID {}", cloid);